@@ -1,3 +1,11 @@
 fn main() {
+    // A build with the release key refuses to start without the manifest signature,
+    // so catch a missing one here (see BUNDLED_EXECUTABLES.md)
+    println!("cargo:rerun-if-env-changed=VORTEX_MANIFEST_PUBLIC_KEY");
+    if std::env::var_os("VORTEX_MANIFEST_PUBLIC_KEY").is_some()
+        && !std::path::Path::new("resources/bin/CHECKSUMS.txt.sig").exists()
+    {
+        panic!("VORTEX_MANIFEST_PUBLIC_KEY is set but resources/bin/CHECKSUMS.txt.sig is missing; sign CHECKSUMS.txt first");
+    }
     tauri_build::build()
 }
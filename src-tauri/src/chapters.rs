@@ -0,0 +1,435 @@
+//! Chapter markers of completed files, read and rewritten with ffmpeg metadata files
+//!
+//! Chapters are edited as a list of start times and titles; each one ends
+//! where the next starts and the last at the end of the file. Writing remuxes
+//! the file with `-c copy`, so nothing is re-encoded. New chapter lists can be
+//! seeded from a YouTube video's SponsorBlock segments.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::error::{DownloadError, Result};
+use crate::platform::{CommandRequest, CommandRunner, SystemCommandRunner};
+
+/// SponsorBlock segments endpoint
+const SPONSORBLOCK_API: &str = "https://sponsor.ajay.app/api/skipSegments";
+
+/// Segment categories and action types requested from SponsorBlock
+const SPONSORBLOCK_QUERY: &str = r#"categories=["sponsor","selfpromo","interaction","intro","outro","preview","music_offtopic","filler","chapter"]&actionTypes=["skip","mute","chapter"]"#;
+
+/// Gaps between segments shorter than this (seconds) don't get their own chapter
+const MIN_GAP_SECONDS: f64 = 1.0;
+
+/// A chapter marker: where it starts and what it's called
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Chapter {
+    /// Seconds from the start of the file
+    pub start: f64,
+    pub title: String,
+}
+
+/// Chapters of a file and its length
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChapterList {
+    /// Seconds; 0 when ffmpeg didn't report one
+    pub duration: f64,
+    pub chapters: Vec<Chapter>,
+}
+
+/// A SponsorBlock segment of a YouTube video
+#[derive(Clone, Debug, PartialEq)]
+pub struct SponsorSegment {
+    pub start: f64,
+    pub end: f64,
+    pub category: String,
+    /// Chapter name, for `chapter` segments
+    pub description: Option<String>,
+}
+
+/// Sort chapters by start, trimming titles; fails on blank titles, repeated
+/// starts or starts outside `0..duration`
+pub fn normalize_chapters(chapters: &[Chapter], duration: f64) -> Result<Vec<Chapter>> {
+    let mut chapters: Vec<Chapter> = chapters
+        .iter()
+        .map(|c| Chapter { start: c.start, title: c.title.trim().to_string() })
+        .collect();
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+    
+    for chapter in &chapters {
+        if chapter.title.is_empty() {
+            return Err(DownloadError::InvalidOption(format!("Chapter at {}s has no title", chapter.start)));
+        }
+        if !chapter.start.is_finite() || chapter.start < 0.0 || chapter.start >= duration {
+            return Err(DownloadError::InvalidOption(format!(
+                "Chapter '{}' starts at {}s, outside the file's {}s",
+                chapter.title, chapter.start, duration
+            )));
+        }
+    }
+    if let Some(pair) = chapters.windows(2).find(|w| w[0].start == w[1].start) {
+        return Err(DownloadError::InvalidOption(format!(
+            "Chapters '{}' and '{}' start at the same time",
+            pair[0].title, pair[1].title
+        )));
+    }
+    Ok(chapters)
+}
+
+/// ffmpeg metadata file with one `[CHAPTER]` per chapter, in milliseconds
+///
+/// Expects chapters from `normalize_chapters`.
+pub fn to_ffmetadata(chapters: &[Chapter], duration: f64) -> String {
+    let millis = |seconds: f64| (seconds * 1000.0).round() as u64;
+    let mut out = String::from(";FFMETADATA1\n");
+    for (i, chapter) in chapters.iter().enumerate() {
+        let end = chapters.get(i + 1).map_or(duration, |next| next.start);
+        out.push_str("[CHAPTER]\nTIMEBASE=1/1000\n");
+        out.push_str(&format!("START={}\nEND={}\n", millis(chapter.start), millis(end)));
+        out.push_str(&format!("title={}\n", escape(&chapter.title)));
+    }
+    out
+}
+
+/// Chapters from an ffmpeg metadata file
+pub fn parse_ffmetadata(metadata: &str) -> Vec<Chapter> {
+    let mut chapters = Vec::new();
+    let mut current: Option<(f64, i64, String)> = None;
+    let finish = |current: Option<(f64, i64, String)>, chapters: &mut Vec<Chapter>| {
+        if let Some((timebase, start, title)) = current {
+            chapters.push(Chapter { start: start as f64 * timebase, title });
+        }
+    };
+    
+    for line in metadata.lines() {
+        if line.starts_with('[') {
+            finish(current.take(), &mut chapters);
+            if line == "[CHAPTER]" {
+                current = Some((0.001, 0, String::new()));
+            }
+            continue;
+        }
+        let (Some((timebase, start, title)), Some((key, value))) = (current.as_mut(), line.split_once('=')) else {
+            continue;
+        };
+        match key {
+            "TIMEBASE" => {
+                if let Some((num, den)) = value.split_once('/') {
+                    if let (Ok(num), Ok(den)) = (num.parse::<f64>(), den.parse::<f64>()) {
+                        if den > 0.0 {
+                            *timebase = num / den;
+                        }
+                    }
+                }
+            }
+            "START" => *start = value.parse().unwrap_or(0),
+            "title" => *title = unescape(value),
+            _ => {}
+        }
+    }
+    finish(current, &mut chapters);
+    chapters
+}
+
+/// Duration from ffmpeg's input summary (`Duration: 01:02:03.45, ...`) on stderr
+pub fn parse_duration(stderr: &str) -> Option<f64> {
+    let value = stderr.lines().find_map(|l| l.trim_start().strip_prefix("Duration: "))?;
+    let mut parts = value.split(',').next()?.trim().split(':');
+    let (hours, minutes, seconds) = (parts.next()?, parts.next()?, parts.next()?);
+    Some(hours.parse::<f64>().ok()? * 3600.0 + minutes.parse::<f64>().ok()? * 60.0 + seconds.parse::<f64>().ok()?)
+}
+
+/// Segments from a SponsorBlock `skipSegments` response
+pub fn parse_segments(response: &Value) -> Vec<SponsorSegment> {
+    response
+        .as_array()
+        .map(|segments| {
+            segments
+                .iter()
+                .filter_map(|s| {
+                    Some(SponsorSegment {
+                        start: s["segment"][0].as_f64()?,
+                        end: s["segment"][1].as_f64()?,
+                        category: s["category"].as_str()?.to_string(),
+                        description: s["description"].as_str().filter(|d| !d.is_empty()).map(str::to_string),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Chapters seeded from SponsorBlock segments
+///
+/// Each segment becomes a chapter named after its category (or its name, for
+/// chapter segments); the stretches between them become "Part 1", "Part 2", ...
+/// Segments overlapping an earlier one are skipped.
+pub fn chapters_from_segments(segments: &[SponsorSegment], duration: f64) -> Vec<Chapter> {
+    let mut segments = segments.to_vec();
+    segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+    
+    let mut chapters = Vec::new();
+    let mut parts = 0;
+    let mut cursor = 0.0;
+    let mut add_part = |chapters: &mut Vec<Chapter>, start: f64| {
+        parts += 1;
+        chapters.push(Chapter { start, title: format!("Part {}", parts) });
+    };
+    
+    for segment in segments.iter().filter(|s| s.end > s.start && s.start < duration) {
+        if segment.start < cursor {
+            continue;
+        }
+        if segment.start - cursor >= MIN_GAP_SECONDS {
+            add_part(&mut chapters, cursor);
+        }
+        let title = segment.description.clone().unwrap_or_else(|| category_title(&segment.category));
+        chapters.push(Chapter { start: segment.start, title });
+        cursor = segment.end;
+    }
+    if !chapters.is_empty() && duration - cursor >= MIN_GAP_SECONDS {
+        add_part(&mut chapters, cursor);
+    }
+    chapters
+}
+
+/// Fetch a YouTube video's SponsorBlock segments; none when the video has no submissions
+pub async fn fetch_sponsor_segments(video_id: &str) -> Result<Vec<SponsorSegment>> {
+    if video_id.is_empty() || !video_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(DownloadError::InvalidOption(format!("'{}' is not a YouTube video ID", video_id)));
+    }
+    let client = reqwest::Client::builder()
+        .user_agent("Vortex-Downloader")
+        .timeout(Duration::from_secs(15))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+    let url = format!("{}?videoID={}&{}", SPONSORBLOCK_API, video_id, SPONSORBLOCK_QUERY);
+    let response = client.get(&url).send().await.map_err(|e| {
+        if e.is_timeout() {
+            DownloadError::Timeout
+        } else {
+            DownloadError::Network(e.to_string())
+        }
+    })?;
+    
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !status.is_success() {
+        return Err(DownloadError::Network(format!("SponsorBlock returned {}", status)));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| DownloadError::DownloadFailed(format!("Invalid SponsorBlock response: {}", e)))?;
+    Ok(parse_segments(&body))
+}
+
+fn category_title(category: &str) -> String {
+    match category {
+        "sponsor" => "Sponsor",
+        "selfpromo" => "Self-promotion",
+        "interaction" => "Interaction reminder",
+        "intro" => "Intro",
+        "outro" => "Outro",
+        "preview" => "Preview",
+        "music_offtopic" => "Non-music",
+        "filler" => "Filler",
+        other => other,
+    }
+    .to_string()
+}
+
+/// Backslash-escape the characters ffmetadata treats specially
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reads and rewrites chapter markers with ffmpeg
+pub struct ChapterEditor {
+    ffmpeg_path: PathBuf,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl ChapterEditor {
+    pub fn new(ffmpeg_path: PathBuf) -> Self {
+        Self {
+            ffmpeg_path,
+            runner: Arc::new(SystemCommandRunner),
+        }
+    }
+    
+    /// Replace the process runner (used by tests)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.runner = runner;
+        self
+    }
+    
+    /// Chapters and duration of `media`
+    pub async fn read(&self, media: &Path) -> Result<ChapterList> {
+        let args = vec![
+            "-hide_banner".to_string(),
+            "-i".to_string(),
+            media.to_string_lossy().to_string(),
+            "-f".to_string(),
+            "ffmetadata".to_string(),
+            "-".to_string(),
+        ];
+        let output = self.run(CommandRequest::new(&self.ffmpeg_path, args).confine_to(media), media).await?;
+        Ok(ChapterList {
+            duration: parse_duration(&output.1).unwrap_or(0.0),
+            chapters: parse_ffmetadata(&output.0),
+        })
+    }
+    
+    /// Replace the chapters of `media`, returning them as written
+    ///
+    /// The file is remuxed next to itself and swapped in only once ffmpeg
+    /// succeeds. An empty list removes all chapters.
+    pub async fn write(&self, media: &Path, chapters: &[Chapter]) -> Result<Vec<Chapter>> {
+        let duration = self.read(media).await?.duration;
+        if duration <= 0.0 {
+            return Err(DownloadError::DownloadFailed(format!("Could not read the length of {}", media.display())));
+        }
+        let chapters = normalize_chapters(chapters, duration)?;
+        
+        let extension = media.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        let remuxed = media.with_extension(format!("chapters.{}", extension));
+        let metadata = media.with_extension("chapters.txt");
+        tokio::fs::write(&metadata, to_ffmetadata(&chapters, duration)).await?;
+        
+        let mut args: Vec<String> = ["-y", "-loglevel", "error", "-i"].iter().map(|s| s.to_string()).collect();
+        args.push(media.to_string_lossy().to_string());
+        args.push("-i".to_string());
+        args.push(metadata.to_string_lossy().to_string());
+        let chapter_source = if chapters.is_empty() { "-1" } else { "1" };
+        args.extend(["-map", "0", "-map_metadata", "0", "-map_chapters", chapter_source, "-c", "copy"].iter().map(|s| s.to_string()));
+        args.push(remuxed.to_string_lossy().to_string());
+        
+        let result = self.run(CommandRequest::new(&self.ffmpeg_path, args).confine_to(media), media).await;
+        let _ = tokio::fs::remove_file(&metadata).await;
+        if let Err(e) = result {
+            let _ = tokio::fs::remove_file(&remuxed).await;
+            return Err(e);
+        }
+        tokio::fs::rename(&remuxed, media).await?;
+        Ok(chapters)
+    }
+    
+    /// Run ffmpeg, returning its (stdout, stderr)
+    async fn run(&self, request: CommandRequest, media: &Path) -> Result<(String, String)> {
+        let output = self.runner.output(&request).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => DownloadError::DependencyMissing("ffmpeg".to_string()),
+            _ => DownloadError::Io(e),
+        })?;
+        if !output.success() {
+            return Err(DownloadError::DownloadFailed(format!(
+                "ffmpeg failed on {}: {}",
+                media.display(),
+                output.stderr.lines().last().unwrap_or("unknown error")
+            )));
+        }
+        Ok((output.stdout, output.stderr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{MockCommandRunner, MockResponse};
+    
+    fn chapter(start: f64, title: &str) -> Chapter {
+        Chapter { start, title: title.to_string() }
+    }
+
+    #[test]
+    fn test_ffmetadata_round_trip() {
+        let chapters = vec![chapter(0.0, "Intro"), chapter(65.5, "Q&A; part=1 #2")];
+        let metadata = to_ffmetadata(&chapters, 120.0);
+        assert!(metadata.starts_with(";FFMETADATA1\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=65500\n"));
+        assert!(metadata.contains("START=65500\nEND=120000\ntitle=Q&A\\; part\\=1 \\#2\n"));
+        assert_eq!(parse_ffmetadata(&metadata), chapters);
+        
+        let from_ffmpeg = ";FFMETADATA1\nmajor_brand=isom\n[CHAPTER]\nTIMEBASE=1/1000000\nSTART=2500000\nEND=5000000\ntitle=Verse\n[STREAM]\ntitle=ignored\n";
+        assert_eq!(parse_ffmetadata(from_ffmpeg), vec![chapter(2.5, "Verse")]);
+    }
+
+    #[test]
+    fn test_normalize_chapters() {
+        let sorted = normalize_chapters(&[chapter(30.0, " Outro "), chapter(0.0, "Intro")], 60.0).unwrap();
+        assert_eq!(sorted, vec![chapter(0.0, "Intro"), chapter(30.0, "Outro")]);
+        
+        assert!(normalize_chapters(&[chapter(0.0, " ")], 60.0).is_err());
+        assert!(normalize_chapters(&[chapter(60.0, "Past the end")], 60.0).is_err());
+        assert!(normalize_chapters(&[chapter(5.0, "A"), chapter(5.0, "B")], 60.0).is_err());
+    }
+
+    #[test]
+    fn test_chapters_from_sponsorblock_segments() {
+        let response = serde_json::json!([
+            {"segment": [300.0, 360.0], "category": "sponsor", "actionType": "skip"},
+            {"segment": [0.0, 15.2], "category": "intro", "actionType": "skip"},
+            {"segment": [900.0, 1500.0], "category": "chapter", "actionType": "chapter", "description": "Interview"},
+            {"segment": [1000.0, 1010.0], "category": "selfpromo", "actionType": "skip"},
+        ]);
+        let chapters = chapters_from_segments(&parse_segments(&response), 1800.0);
+        assert_eq!(chapters, vec![
+            chapter(0.0, "Intro"),
+            chapter(15.2, "Part 1"),
+            chapter(300.0, "Sponsor"),
+            chapter(360.0, "Part 2"),
+            chapter(900.0, "Interview"),
+            chapter(1500.0, "Part 3"),
+        ]);
+        assert!(chapters_from_segments(&[], 1800.0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_remuxes_with_metadata_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let media = dir.path().join("mix.m4a");
+        std::fs::write(&media, b"audio").unwrap();
+        // The mock doesn't run ffmpeg, so the remuxed file it would write is already there
+        std::fs::write(dir.path().join("mix.chapters.m4a"), b"remuxed").unwrap();
+        
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![
+            MockResponse {
+                stderr_lines: vec!["  Duration: 00:10:00.00, start: 0.000000, bitrate: 128 kb/s".to_string()],
+                ..MockResponse::success(";FFMETADATA1")
+            },
+            MockResponse::success(""),
+        ]));
+        let editor = ChapterEditor::new(PathBuf::from("ffmpeg")).with_command_runner(runner.clone());
+        
+        let written = editor.write(&media, &[chapter(120.0, "Track 2"), chapter(0.0, "Track 1")]).await.unwrap();
+        assert_eq!(written[0].title, "Track 1");
+        assert_eq!(std::fs::read(&media).unwrap(), b"remuxed");
+        assert!(!dir.path().join("mix.chapters.txt").exists());
+        
+        let args = &runner.calls()[1].args;
+        assert!(args.windows(2).any(|w| w[0] == "-map_chapters" && w[1] == "1"));
+        assert!(args.windows(2).any(|w| w[0] == "-c" && w[1] == "copy"));
+        assert_eq!(args.last().unwrap(), &dir.path().join("mix.chapters.m4a").to_string_lossy());
+    }
+}
@@ -0,0 +1,174 @@
+//! Checksum manifests for the bundled executables
+//!
+//! `CHECKSUMS.txt` is generated when the app is built and signed with the
+//! release ed25519 key; the signature sits next to it in `CHECKSUMS.txt.sig`
+//! as hex. The public key is compiled in from `VORTEX_MANIFEST_PUBLIC_KEY`.
+//! Builds without a key (local development) skip the signature check.
+//!
+//! The in-app yt-dlp updater never writes the signed manifest. It records the
+//! checksum of the binary it installed in `CHECKSUMS.user.txt`, which is not
+//! signed and is only trusted for yt-dlp.
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::error::{DownloadError, Result};
+use crate::log_warn;
+
+/// Signed manifest bundled with the app
+pub const SIGNED_MANIFEST: &str = "CHECKSUMS.txt";
+/// Hex ed25519 signature of `SIGNED_MANIFEST`
+pub const SIGNATURE_FILE: &str = "CHECKSUMS.txt.sig";
+/// Unsigned manifest written by the updater
+pub const USER_MANIFEST: &str = "CHECKSUMS.user.txt";
+
+const USER_MANIFEST_HEADER: &str = "\
+# USER MANIFEST - not signed.
+# Checksums of executables installed by the in-app updater; only used for yt-dlp.
+# The signed checksums of the bundled executables are in CHECKSUMS.txt.
+";
+
+/// Release public key (hex), set when building release bundles
+pub const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("VORTEX_MANIFEST_PUBLIC_KEY");
+
+/// SHA-256 checksums by path relative to `bin/`, e.g. `aarch64/yt-dlp`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChecksumManifest {
+    entries: BTreeMap<String, String>,
+}
+
+impl ChecksumManifest {
+    /// Parse `sha256sum` output; comments and malformed lines are skipped
+    pub fn parse(content: &str) -> Self {
+        let entries = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                match parts.as_slice() {
+                    [checksum, path] => Some((path.to_string(), checksum.to_ascii_lowercase())),
+                    _ => None,
+                }
+            })
+            .collect();
+        Self { entries }
+    }
+    
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.entries.get(path).map(String::as_str)
+    }
+    
+    pub fn set(&mut self, path: &str, checksum: &str) {
+        self.entries.insert(path.to_string(), checksum.to_ascii_lowercase());
+    }
+    
+    /// `sha256sum`-style lines, sorted by path
+    pub fn to_text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|(path, checksum)| format!("{}  {}\n", checksum, path))
+            .collect()
+    }
+    
+    /// Read the signed manifest from `bin_dir`, checking its signature against
+    /// `public_key` (normally `RELEASE_PUBLIC_KEY`) when there is one
+    pub fn load_signed(bin_dir: &Path, public_key: Option<&str>) -> Result<Self> {
+        let content = fs::read(bin_dir.join(SIGNED_MANIFEST))
+            .map_err(|e| DownloadError::DownloadFailed(format!("Failed to read checksums file: {}", e)))?;
+        
+        match public_key {
+            Some(public_key) => {
+                let signature = fs::read_to_string(bin_dir.join(SIGNATURE_FILE))
+                    .map_err(|e| DownloadError::DownloadFailed(format!("Checksums file is not signed: {}", e)))?;
+                verify_signature(&content, &signature, public_key)?;
+            }
+            None => log_warn!("[Checksums] No signing key in this build; {} signature not checked", SIGNED_MANIFEST),
+        }
+        Ok(Self::parse(&String::from_utf8_lossy(&content)))
+    }
+    
+    /// Read the user manifest from `bin_dir`; empty when the updater hasn't written one
+    pub fn load_user(bin_dir: &Path) -> Result<Self> {
+        match fs::read_to_string(bin_dir.join(USER_MANIFEST)) {
+            Ok(content) => Ok(Self::parse(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(DownloadError::DownloadFailed(format!("Failed to read user checksums file: {}", e))),
+        }
+    }
+    
+    /// Write this manifest to `bin_dir` as the user manifest
+    pub fn save_user(&self, bin_dir: &Path) -> Result<()> {
+        fs::write(bin_dir.join(USER_MANIFEST), format!("{}{}", USER_MANIFEST_HEADER, self.to_text()))
+            .map_err(|e| DownloadError::DownloadFailed(format!("Failed to write user checksums file: {}", e)))
+    }
+}
+
+/// Check a hex ed25519 `signature` of `content` against a hex `public_key`
+pub fn verify_signature(content: &[u8], signature: &str, public_key: &str) -> Result<()> {
+    let invalid = |what: &str| DownloadError::DownloadFailed(format!("Checksums file signature check failed: {}", what));
+    
+    let key: [u8; 32] = decode_hex(public_key)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid("malformed public key"))?;
+    let key = VerifyingKey::from_bytes(&key).map_err(|_| invalid("malformed public key"))?;
+    let signature: [u8; 64] = decode_hex(signature)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid("malformed signature"))?;
+    
+    key.verify_strict(content, &Signature::from_bytes(&signature))
+        .map_err(|_| invalid("signature does not match"))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u32> = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16))
+        .collect::<Option<_>>()?;
+    digits
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => Some((high * 16 + low) as u8),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_signature_verification() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = to_hex(signing_key.verifying_key().as_bytes());
+        let manifest = b"abc123  aarch64/yt-dlp\n";
+        let signature = to_hex(&signing_key.sign(manifest).to_bytes());
+        
+        assert!(verify_signature(manifest, &format!("{}\n", signature), &public_key).is_ok());
+        assert!(verify_signature(b"def456  aarch64/yt-dlp\n", &signature, &public_key).is_err());
+        assert!(verify_signature(manifest, "not hex", &public_key).is_err());
+    }
+
+    #[test]
+    fn test_user_manifest_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(ChecksumManifest::load_user(dir.path()).unwrap(), ChecksumManifest::default());
+        
+        let mut manifest = ChecksumManifest::parse("# comment\nAAA111  x86_64/yt-dlp\nbroken line here\n");
+        assert_eq!(manifest.get("x86_64/yt-dlp"), Some("aaa111"));
+        manifest.set("aarch64/yt-dlp", "bbb222");
+        manifest.save_user(dir.path()).unwrap();
+        
+        let written = fs::read_to_string(dir.path().join(USER_MANIFEST)).unwrap();
+        assert!(written.starts_with("# USER MANIFEST"));
+        assert_eq!(ChecksumManifest::load_user(dir.path()).unwrap(), manifest);
+    }
+}
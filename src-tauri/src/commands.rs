@@ -1,24 +1,32 @@
+use std::collections::HashMap;
 use tauri::{State, Manager};
 use crate::AppState;
-use youtube_downloader_gui::platform::{VideoInfo, PlaylistInfo, ChannelInfo, Dependency};
-use youtube_downloader_gui::download::DownloadItem;
-use youtube_downloader_gui::storage::AppSettings;
+use youtube_downloader_gui::platform::{validate_cookies_file, BrowserInfo, ChannelTab, VideoInfo, PlaylistInfo, PlaylistItems, ChannelInfo, Dependency, DirectUrlProvider, PlatformSetting, GenericProvider, YouTubeProvider};
+use youtube_downloader_gui::download::{variants, DashboardSnapshot, DownloadItem, DownloadStatus, QualityVariant, VariantGroupProgress};
+use youtube_downloader_gui::storage::{archive, benchmark, m3u, playlist_sync, AppSettings, CompletedDownload, ErrorStats, PlatformStats, PlaylistDiff, PlaylistSnapshot, ReviewStatus, SmartCollection, WriteBenchmark};
 use youtube_downloader_gui::error::{DownloadError, ErrorResponse};
-use youtube_downloader_gui::error_handler::{UrlValidator, retry_with_backoff, RetryConfig};
-use youtube_downloader_gui::update_service::UpdateService;
+use youtube_downloader_gui::events::{emit_serialized, EventSink};
+use youtube_downloader_gui::diagnostics::{default_bundle_path, DiagnosticsBundle};
+use youtube_downloader_gui::formatting;
+use youtube_downloader_gui::logging;
+use youtube_downloader_gui::{log_error, log_info};
+use youtube_downloader_gui::error_handler::{retry_with_backoff, retry_with_cancellation, RetryConfig};
+use youtube_downloader_gui::update_service::{UpdateOutcome, UpdateService};
 use youtube_downloader_gui::executable_manager::ExecutableManager;
+use youtube_downloader_gui::chapters::{self, Chapter, ChapterEditor, ChapterList};
+use youtube_downloader_gui::preview::{PreviewFrame, PreviewService};
+use youtube_downloader_gui::media_dedupe::{DuplicateGroup, MediaDedupeService, DEFAULT_SIMILARITY_THRESHOLD};
 
 #[tauri::command]
 pub async fn detect_platform(url: String, state: State<'_, AppState>) -> Result<String, ErrorResponse> {
-    // Validate URL first
-    let validator = UrlValidator::new();
-    let validated_url = validator.validate_and_normalize(&url)
+    // Validate URL and find the provider that handles it
+    let (provider, _) = state
+        .platform_registry
+        .resolve_url(&url)
+        .await
         .map_err(|e| e.to_response())?;
     
-    match state.platform_registry.detect_provider(&validated_url) {
-        Some(provider) => Ok(provider.name().to_string()),
-        None => Err(DownloadError::PlatformNotSupported(validated_url).to_response()),
-    }
+    Ok(provider.name().to_string())
 }
 
 #[tauri::command]
@@ -35,117 +43,482 @@ pub async fn get_supported_platforms(state: State<'_, AppState>) -> Result<Vec<P
 }
 
 #[tauri::command]
-pub async fn get_video_info(url: String, state: State<'_, AppState>) -> Result<VideoInfo, ErrorResponse> {
-    // Validate URL first
-    let validator = UrlValidator::new();
-    let validated_url = validator.validate_and_normalize(&url)
+pub async fn get_video_info(
+    url: String,
+    request_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<VideoInfo, ErrorResponse> {
+    // Validate URL and verify platform is supported
+    let (provider, validated_url) = state
+        .platform_registry
+        .resolve_url(&url)
+        .await
         .map_err(|e| e.to_response())?;
     
-    // Verify platform is supported
-    let _provider = state
-        .platform_registry
-        .detect_provider(&validated_url)
-        .ok_or_else(|| DownloadError::PlatformNotSupported(validated_url.clone()).to_response())?;
+    // Retry with exponential backoff for network errors, until the request is cancelled
+    let request = state.info_requests.register(request_id.as_deref()).await;
     
-    // Retry with exponential backoff for network errors
-    let state_clone = state.inner().clone();
-    let url_clone = validated_url.clone();
+    let result = retry_with_cancellation(
+        || provider.get_video_info(&validated_url),
+        RetryConfig::default(),
+        request.token.clone(),
+    )
+    .await;
     
-    retry_with_backoff(
+    state.info_requests.finish(&request).await;
+    result.map_err(|e| e.to_response())
+}
+
+/// Maximum number of metadata fetches running at once in a batch
+const BATCH_METADATA_CONCURRENCY: usize = 4;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BatchVideoInfoEntry {
+    pub url: String,
+    pub info: Option<VideoInfo>,
+    pub error: Option<ErrorResponse>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchVideoInfoResult {
+    pub entries: Vec<BatchVideoInfoEntry>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+#[tauri::command]
+pub async fn get_videos_info(
+    urls: Vec<String>,
+    request_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<BatchVideoInfoResult, ErrorResponse> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::Semaphore;
+
+    let total = urls.len();
+    log_info!("[get_videos_info] Fetching metadata for {} URLs", total);
+
+    let semaphore = Arc::new(Semaphore::new(BATCH_METADATA_CONCURRENCY));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let request = state.info_requests.register(request_id.as_deref()).await;
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for (index, url) in urls.into_iter().enumerate() {
+        let state = state.inner().clone();
+        let semaphore = Arc::clone(&semaphore);
+        let completed = Arc::clone(&completed);
+        let cancel_token = request.token.clone();
+
+        tasks.spawn(async move {
+            // Semaphore is never closed, so acquiring can't fail
+            let _permit = semaphore.acquire_owned().await.ok();
+
+            let result = fetch_video_info_for_batch(&url, &state, cancel_token).await;
+
+            let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+            state.events.emit("metadata:batch_progress", serde_json::json!({
+                "url": url,
+                "completed": done,
+                "total": total,
+                "success": result.is_ok(),
+            }));
+
+            let entry = match result {
+                Ok(info) => BatchVideoInfoEntry { url, info: Some(info), error: None },
+                Err(e) => BatchVideoInfoEntry { url, info: None, error: Some(e.to_response()) },
+            };
+            (index, entry)
+        });
+    }
+
+    // Collect results, preserving the order of the input URLs
+    let mut slots: Vec<Option<BatchVideoInfoEntry>> = vec![None; total];
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, entry)) => slots[index] = Some(entry),
+            Err(e) => log_error!("[get_videos_info] Metadata task failed: {}", e),
+        }
+    }
+
+    state.info_requests.finish(&request).await;
+    
+    let entries: Vec<BatchVideoInfoEntry> = slots.into_iter().flatten().collect();
+    let succeeded = entries.iter().filter(|e| e.info.is_some()).count();
+    let failed = total - succeeded;
+
+    log_info!("[get_videos_info] Done: {} succeeded, {} failed", succeeded, failed);
+
+    Ok(BatchVideoInfoResult {
+        entries,
+        succeeded,
+        failed,
+    })
+}
+
+/// Fetch a single video's metadata for a batch request (cache, rate limit, retry)
+async fn fetch_video_info_for_batch(
+    url: &str,
+    state: &AppState,
+    cancel_token: tokio_util::sync::CancellationToken,
+) -> Result<VideoInfo, DownloadError> {
+    let (provider, validated_url) = state.platform_registry.resolve_url(url).await?;
+
+    if let Some(info) = state.metadata_cache.get_video(&validated_url).await {
+        return Ok(info);
+    }
+
+    let info = retry_with_cancellation(
         || async {
-            let provider = state_clone
-                .platform_registry
-                .detect_provider(&url_clone)
-                .ok_or_else(|| DownloadError::PlatformNotSupported(url_clone.clone()))?;
-            provider.get_video_info(&url_clone).await
+            state.metadata_rate_limiter.acquire(provider.name()).await;
+            provider.get_video_info(&validated_url).await
         },
         RetryConfig::default(),
+        cancel_token,
     )
-    .await
-    .map_err(|e| e.to_response())
+    .await?;
+
+    state.metadata_cache.put_video(validated_url.clone(), info.clone()).await;
+
+    Ok(info)
 }
 
 #[tauri::command]
-pub async fn get_playlist_info(url: String, state: State<'_, AppState>) -> Result<PlaylistInfo, ErrorResponse> {
-    // Validate URL first
-    let validator = UrlValidator::new();
-    let validated_url = validator.validate_and_normalize(&url)
+pub async fn get_playlist_info(
+    url: String,
+    request_id: Option<String>,
+    playlist_items: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<PlaylistInfo, ErrorResponse> {
+    // Validate URL and verify platform is supported
+    let (provider, validated_url) = state
+        .platform_registry
+        .resolve_url(&url)
+        .await
         .map_err(|e| e.to_response())?;
     
-    // Verify platform is supported
-    let _provider = state
+    // Only the selected videos (e.g. "1-10,15") are returned for queueing
+    let selection = playlist_items
+        .as_deref()
+        .map(PlaylistItems::parse)
+        .transpose()
+        .map_err(|e| e.to_response())?;
+    
+    // Retry with exponential backoff for network errors, until the request is cancelled
+    let request = state.info_requests.register(request_id.as_deref()).await;
+    
+    let result = retry_with_cancellation(
+        || provider.get_playlist_info(&validated_url),
+        RetryConfig::default(),
+        request.token.clone(),
+    )
+    .await;
+    
+    state.info_requests.finish(&request).await;
+    let mut info = result.map_err(|e| e.to_response())?;
+    if let Some(selection) = selection {
+        info.videos = selection.select(&info.videos);
+    }
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn diff_playlist(
+    url: String,
+    state: State<'_, AppState>,
+) -> Result<PlaylistDiff, ErrorResponse> {
+    let (provider, validated_url) = state
         .platform_registry
-        .detect_provider(&validated_url)
-        .ok_or_else(|| DownloadError::PlatformNotSupported(validated_url.clone()).to_response())?;
+        .resolve_url(&url)
+        .await
+        .map_err(|e| e.to_response())?;
+    
+    let playlist = retry_with_backoff(
+        || provider.get_playlist_info(&validated_url),
+        RetryConfig::default(),
+    )
+    .await
+    .map_err(|e| e.to_response())?;
     
-    // Retry with exponential backoff for network errors
-    let state_clone = state.inner().clone();
-    let url_clone = validated_url.clone();
+    sync_listing(&playlist, &state).await
+}
+
+#[tauri::command]
+pub async fn diff_channel(
+    url: String,
+    state: State<'_, AppState>,
+) -> Result<PlaylistDiff, ErrorResponse> {
+    let (provider, validated_url) = state
+        .platform_registry
+        .resolve_url(&url)
+        .await
+        .map_err(|e| e.to_response())?;
     
-    retry_with_backoff(
-        || async {
-            let provider = state_clone
-                .platform_registry
-                .detect_provider(&url_clone)
-                .ok_or_else(|| DownloadError::PlatformNotSupported(url_clone.clone()))?;
-            provider.get_playlist_info(&url_clone).await
-        },
+    let channel = retry_with_backoff(
+        || provider.get_channel_info(&validated_url),
         RetryConfig::default(),
     )
     .await
-    .map_err(|e| e.to_response())
+    .map_err(|e| e.to_response())?;
+    
+    sync_listing(&playlist_sync::listing_from_channel(&channel), &state).await
+}
+
+/// Diff a fresh listing against local history, tombstone vanished downloads and store the new snapshot
+async fn sync_listing(playlist: &PlaylistInfo, state: &State<'_, AppState>) -> Result<PlaylistDiff, ErrorResponse> {
+    // A video counts as downloaded if it is in history or completed in the current queue
+    let history = state
+        .storage_service
+        .load_download_history()
+        .map_err(|e| e.to_response())?;
+    let mut downloaded_ids: std::collections::HashSet<String> = history
+        .downloads
+        .into_iter()
+        .filter(|d| d.platform == playlist.platform)
+        .map(|d| d.video_id)
+        .collect();
+    downloaded_ids.extend(
+        state
+            .download_manager
+            .get_queue_status()
+            .await
+            .into_iter()
+            .filter(|item| item.status == DownloadStatus::Completed && item.platform == playlist.platform)
+            .map(|item| item.video_id),
+    );
+    
+    let previous = state
+        .storage_service
+        .load_playlist_snapshot(&playlist.platform, &playlist.id)
+        .map_err(|e| e.to_response())?;
+    
+    let diff = playlist_sync::diff_playlist(playlist, &downloaded_ids, previous.as_ref());
+    log_info!(
+        "[sync_listing] {}: {} new, {} downloaded, {} removed, {} tombstoned",
+        playlist.id,
+        diff.new_videos.len(),
+        diff.already_downloaded.len(),
+        diff.removed.len(),
+        diff.tombstoned.len()
+    );
+    
+    let tombstoned_ids: Vec<String> = diff.tombstoned.iter().map(|e| e.video_id.clone()).collect();
+    state
+        .storage_service
+        .mark_tombstoned(&playlist.platform, &tombstoned_ids)
+        .map_err(|e| e.to_response())?;
+    
+    state
+        .storage_service
+        .save_playlist_snapshot(&PlaylistSnapshot::from_playlist(playlist))
+        .map_err(|e| e.to_response())?;
+    
+    Ok(diff)
 }
 
 #[tauri::command]
-pub async fn get_channel_info(url: String, state: State<'_, AppState>) -> Result<ChannelInfo, ErrorResponse> {
-    // Validate URL first
-    let validator = UrlValidator::new();
-    let validated_url = validator.validate_and_normalize(&url)
+pub async fn export_m3u(
+    ids: Vec<String>,
+    output_path: String,
+    state: State<'_, AppState>,
+) -> Result<String, ErrorResponse> {
+    let mut output_path = std::path::PathBuf::from(output_path);
+    if output_path.extension().is_none() {
+        output_path.set_extension("m3u8");
+    }
+    state
+        .storage_service
+        .validate_path(&output_path)
         .map_err(|e| e.to_response())?;
     
-    // Verify platform is supported
-    let _provider = state
+    // IDs may refer to history entries or to completed items still in the queue
+    let history = state
+        .storage_service
+        .load_download_history()
+        .map_err(|e| e.to_response())?;
+    let queue = state.download_manager.get_queue_status().await;
+    
+    let entries: Vec<m3u::M3uEntry> = ids
+        .iter()
+        .filter_map(|id| {
+            let entry = history
+                .downloads
+                .iter()
+                .find(|d| &d.id == id)
+                .map(|d| m3u::M3uEntry {
+                    title: d.title.clone(),
+                    path: d.save_path.clone(),
+                    duration: None,
+                })
+                .or_else(|| {
+                    queue
+                        .iter()
+                        .find(|item| &item.id == id && item.status == DownloadStatus::Completed)
+                        .map(|item| m3u::M3uEntry {
+                            title: item.title.clone(),
+                            path: item.save_path.clone(),
+                            duration: None,
+                        })
+                });
+            if entry.is_none() {
+                log_info!("[export_m3u] Skipping unknown or incomplete download: {}", id);
+            }
+            entry
+        })
+        .collect();
+    
+    if entries.is_empty() {
+        return Err(DownloadError::DownloadFailed("No completed downloads to export".to_string()).to_response());
+    }
+    
+    let contents = m3u::render_m3u(&entries, &output_path);
+    tokio::fs::write(&output_path, contents)
+        .await
+        .map_err(|e| DownloadError::Io(e).to_response())?;
+    
+    log_info!("[export_m3u] Wrote {} entries to {}", entries.len(), output_path.display());
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn get_channel_info(
+    url: String,
+    request_id: Option<String>,
+    tab: Option<ChannelTab>,
+    state: State<'_, AppState>,
+) -> Result<ChannelInfo, ErrorResponse> {
+    // Validate URL and verify platform is supported
+    let (provider, validated_url) = state
         .platform_registry
-        .detect_provider(&validated_url)
-        .ok_or_else(|| DownloadError::PlatformNotSupported(validated_url.clone()).to_response())?;
+        .resolve_url(&url)
+        .await
+        .map_err(|e| e.to_response())?;
+    
+    // Only list one tab (Videos, Shorts, Live) instead of the whole channel
+    let validated_url = match tab {
+        Some(tab) if provider.name() == "YouTube" => tab.apply(&validated_url),
+        Some(_) => {
+            return Err(DownloadError::InvalidOption(
+                format!("Channel tabs are not supported for {}", provider.name())
+            ).to_response());
+        }
+        None => validated_url,
+    };
     
-    // Retry with exponential backoff for network errors
-    let state_clone = state.inner().clone();
-    let url_clone = validated_url.clone();
+    // Retry with exponential backoff for network errors, until the request is cancelled
+    let request = state.info_requests.register(request_id.as_deref()).await;
     
-    retry_with_backoff(
-        || async {
-            let provider = state_clone
-                .platform_registry
-                .detect_provider(&url_clone)
-                .ok_or_else(|| DownloadError::PlatformNotSupported(url_clone.clone()))?;
-            provider.get_channel_info(&url_clone).await
-        },
+    let result = retry_with_cancellation(
+        || provider.get_channel_info(&validated_url),
         RetryConfig::default(),
+        request.token.clone(),
     )
-    .await
-    .map_err(|e| e.to_response())
+    .await;
+    
+    state.info_requests.finish(&request).await;
+    result.map_err(|e| e.to_response())
+}
+
+#[tauri::command]
+pub async fn cancel_info_request(request_id: String, state: State<'_, AppState>) -> Result<bool, ErrorResponse> {
+    let cancelled = state.info_requests.cancel(&request_id).await;
+    if cancelled {
+        log_info!("[cancel_info_request] Cancelled request: {}", request_id);
+    }
+    Ok(cancelled)
 }
 
 #[tauri::command]
 pub async fn add_to_download_queue(
-    items: Vec<DownloadItem>,
+    mut items: Vec<DownloadItem>,
+    start_at: Option<String>,
+    start_in_seconds: Option<u64>,
+    request_token: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), ErrorResponse> {
-    println!("[add_to_download_queue] Received {} items", items.len());
-    for (idx, item) in items.iter().enumerate() {
-        println!("[add_to_download_queue] Item {}: id={}, title={}, status={:?}", 
-                 idx, item.id, item.title, item.status);
-    }
+) -> Result<Vec<String>, ErrorResponse> {
+    // Everything logged for these items, through to their completion, carries this request's ID
+    logging::in_request(&logging::new_request_id(), "add_to_download_queue", async move {
+        log_info!("[add_to_download_queue] Received {} items", items.len());
+        
+        // A countdown or start time applies to items without their own schedule
+        let scheduled_at = match start_in_seconds {
+            Some(secs) => Some((chrono::Utc::now() + chrono::Duration::seconds(secs as i64)).to_rfc3339()),
+            None => start_at,
+        };
+        if let Some(at) = scheduled_at {
+            for item in items.iter_mut().filter(|i| i.scheduled_at.is_none()) {
+                item.scheduled_at = Some(at.clone());
+            }
+        }
+        
+        // Items without an explicit subtitle choice follow the platform settings;
+        // YouTube Music links download as audio
+        let settings = state.storage_service.load_settings().unwrap_or_default();
+        for item in items.iter_mut().filter(|i| i.platform == "YouTube") {
+            if YouTubeProvider::is_music_url(&item.url) {
+                item.audio_only = true;
+            }
+            if item.subtitle_languages.is_none() {
+                item.subtitle_languages = Some(settings.youtube_subtitle_languages());
+            }
+            if item.auto_subtitles.is_none() {
+                item.auto_subtitles = Some(settings.youtube_auto_subtitles());
+            }
+        }
+        
+        for (idx, item) in items.iter().enumerate() {
+            log_info!("[add_to_download_queue] Item {}: id={}, title={}, status={:?}", 
+                     idx, item.id, item.title, item.status);
+        }
+        
+        let result = match request_token.as_deref() {
+            Some(token) => state.download_manager.add_to_queue_once(token, items).await,
+            None => state.download_manager.add_to_queue(items).await,
+        };
+        result.map_err(|e| {
+            log_info!("[add_to_download_queue] Error: {:?}", e);
+            e.to_response()
+        })
+    })
+    .await
+}
+
+/// Queue one video at several qualities as a group, from a single metadata fetch
+///
+/// Each variant is its own queue item saved next to the others in `save_path`;
+/// the returned group's progress is also emitted as `download:group_progress`.
+#[tauri::command]
+pub async fn add_quality_variants(
+    url: String,
+    variants: Vec<QualityVariant>,
+    save_path: String,
+    state: State<'_, AppState>,
+) -> Result<VariantGroupProgress, ErrorResponse> {
+    let (_, validated_url) = state
+        .platform_registry
+        .resolve_url(&url)
+        .await
+        .map_err(|e| e.to_response())?;
+    let info = fetch_video_info_for_batch(&validated_url, &state, tokio_util::sync::CancellationToken::new())
+        .await
+        .map_err(|e| e.to_response())?;
+    let items = variants::variant_items(&info, &validated_url, &variants, &save_path)
+        .map_err(|e| e.to_response())?;
+    let group = items[0].variant_group.clone().unwrap_or_default();
+    log_info!("[add_quality_variants] Queueing {} at {} qualities as group {}", info.title, items.len(), group);
     
+    add_to_download_queue(items, None, None, None, state.clone()).await?;
     state
         .download_manager
-        .add_to_queue(items)
+        .variant_group_progress(&group)
         .await
-        .map_err(|e| {
-            println!("[add_to_download_queue] Error: {:?}", e);
-            e.to_response()
-        })
+        .ok_or_else(|| DownloadError::DownloadFailed(format!("Group {} was not queued", group)).to_response())
+}
+
+/// Combined progress of a group queued with `add_quality_variants`
+#[tauri::command]
+pub async fn get_variant_group_progress(group: String, state: State<'_, AppState>) -> Result<Option<VariantGroupProgress>, ErrorResponse> {
+    Ok(state.download_manager.variant_group_progress(&group).await)
 }
 
 #[tauri::command]
@@ -175,6 +548,79 @@ pub async fn cancel_download(id: String, state: State<'_, AppState>) -> Result<(
         .map_err(|e| e.to_response())
 }
 
+/// Pause every unfinished download, returning how many were paused
+#[tauri::command]
+pub async fn pause_all(state: State<'_, AppState>) -> Result<usize, ErrorResponse> {
+    Ok(state.download_manager.pause_all().await)
+}
+
+/// Resume every paused download, returning how many were resumed
+#[tauri::command]
+pub async fn resume_all(state: State<'_, AppState>) -> Result<usize, ErrorResponse> {
+    Ok(state.download_manager.resume_all().await)
+}
+
+/// Cancel every unfinished download, returning how many were cancelled
+#[tauri::command]
+pub async fn cancel_all(state: State<'_, AppState>) -> Result<usize, ErrorResponse> {
+    Ok(state.download_manager.cancel_all().await)
+}
+
+/// Queue failed item `id` again, or every failed item when `id` is omitted; returns how many were retried
+#[tauri::command]
+pub async fn retry_download(id: Option<String>, state: State<'_, AppState>) -> Result<usize, ErrorResponse> {
+    match id {
+        Some(id) => state
+            .download_manager
+            .retry_download(&id)
+            .await
+            .map(|()| 1)
+            .map_err(|e| e.to_response()),
+        None => Ok(state.download_manager.retry_failed().await),
+    }
+}
+
+/// Remove an item from the queue, cancelling it if running; `delete_partial` also deletes its `.part` files
+#[tauri::command]
+pub async fn remove_from_queue(id: String, delete_partial: Option<bool>, state: State<'_, AppState>) -> Result<(), ErrorResponse> {
+    state
+        .download_manager
+        .remove_from_queue(&id, delete_partial.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_response())
+}
+
+/// Remove completed items from the queue, returning how many were removed
+#[tauri::command]
+pub async fn clear_completed(state: State<'_, AppState>) -> Result<usize, ErrorResponse> {
+    Ok(state.download_manager.clear_completed().await)
+}
+
+/// Remove failed and cancelled items from the queue, returning how many were removed
+#[tauri::command]
+pub async fn clear_failed(state: State<'_, AppState>) -> Result<usize, ErrorResponse> {
+    Ok(state.download_manager.clear_failed().await)
+}
+
+/// Empty the queue, cancelling running downloads; returns how many items were removed
+#[tauri::command]
+pub async fn clear_queue(state: State<'_, AppState>) -> Result<usize, ErrorResponse> {
+    Ok(state.download_manager.clear_queue().await)
+}
+
+/// Full-rate progress events for the items visible in the UI; others get coarse updates
+#[tauri::command]
+pub async fn subscribe_progress(ids: Vec<String>, state: State<'_, AppState>) -> Result<(), ErrorResponse> {
+    state.download_manager.subscribe_progress(ids).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unsubscribe_progress(ids: Vec<String>, state: State<'_, AppState>) -> Result<(), ErrorResponse> {
+    state.download_manager.unsubscribe_progress(&ids).await;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn reorder_queue(
     from_index: usize,
@@ -188,6 +634,29 @@ pub async fn reorder_queue(
         .map_err(|e| e.to_response())
 }
 
+#[tauri::command]
+pub async fn get_dashboard_snapshot(state: State<'_, AppState>) -> Result<DashboardSnapshot, ErrorResponse> {
+    Ok(state.download_manager.dashboard_snapshot().await)
+}
+
+/// Failure counts by error code, platform and hour of day, optionally limited to the last `days` days
+#[tauri::command]
+pub async fn get_error_stats(
+    days: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<ErrorStats, ErrorResponse> {
+    Ok(state.download_manager.error_stats(days).await)
+}
+
+/// Lifetime download count, bytes and success rate for one platform
+#[tauri::command]
+pub async fn get_platform_stats(
+    platform: String,
+    state: State<'_, AppState>,
+) -> Result<PlatformStats, ErrorResponse> {
+    Ok(state.download_manager.platform_stats(&platform).await)
+}
+
 #[tauri::command]
 pub async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, ErrorResponse> {
     state
@@ -201,12 +670,89 @@ pub async fn save_settings(
     settings: AppSettings,
     state: State<'_, AppState>,
 ) -> Result<(), ErrorResponse> {
+    settings.validate().map_err(|e| e.to_response())?;
+    
     state
         .storage_service
         .save_settings(&settings)
+        .map_err(|e| e.to_response())?;
+    
+    GenericProvider::apply_setting(&state.platform_registry, settings.allow_generic_sites);
+    DirectUrlProvider::apply_dns_setting(&state.platform_registry, &settings.dns_over_https);
+    state.platform_registry.apply_metadata_concurrency(&settings.metadata_concurrency);
+    state.platform_registry.apply_network_settings(&settings.proxy, &settings.http_headers);
+    YouTubeProvider::apply_cookies_setting(
+        &state.platform_registry,
+        settings.cookies_file_path.as_deref(),
+        settings.cookies_from_browser.as_deref(),
+    );
+    YouTubeProvider::apply_po_token_setting(&state.platform_registry, &settings.youtube_po_token);
+    state.download_manager.set_mp4_faststart(settings.mp4_faststart).await;
+    state.download_manager.set_concurrent_fragments(settings.concurrent_fragments).await;
+    state.download_manager.set_ytdlp_retries(settings.ytdlp_retries).await;
+    state.download_manager.set_embed_chapters(settings.embed_chapters).await;
+    state.download_manager.set_download_archive(settings.use_download_archive).await;
+    state.download_manager.set_min_free_space(settings.min_free_space_mb * 1024 * 1024).await;
+    state.download_manager.set_mixing_policy(settings.queue_mixing_policy).await;
+    state.download_manager.set_quick_wins(settings.queue_quick_wins).await;
+    state.download_manager.set_global_speed_limit(settings.global_speed_limit_bytes()).await;
+    state.download_manager.set_helper_priority(settings.helper_process_priority).await;
+    state.download_manager.set_postprocess_limits(settings.postprocess_limits);
+    state.download_manager.set_proxy_settings(settings.proxy.with_system_proxy().await).await;
+    state.download_manager.set_http_headers(settings.http_headers).await;
+    state.download_manager.set_audio_settings(settings.audio).await;
+    state.download_manager.set_transcription_settings(settings.transcription).await;
+    state.download_manager.set_inbox_settings(settings.inbox).await;
+    formatting::set_locale(settings.locale.as_deref());
+    state.sounds.set_settings(settings.sounds);
+    logging::set_settings(settings.logging);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_platform_settings(
+    platform: String,
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, serde_json::Value>, ErrorResponse> {
+    state
+        .storage_service
+        .load_platform_settings(&platform)
         .map_err(|e| e.to_response())
 }
 
+/// Setting definitions of `platform`, labelled in the display language
+#[tauri::command]
+pub async fn get_platform_setting_definitions(
+    platform: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<PlatformSetting>, ErrorResponse> {
+    let provider = state
+        .platform_registry
+        .get_provider(&platform)
+        .ok_or_else(|| DownloadError::PlatformNotSupported(platform.clone()).to_response())?;
+    let language = formatting::current_locale().language();
+    Ok(provider
+        .get_platform_settings()
+        .into_iter()
+        .map(|setting| setting.localized(language))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn save_platform_settings(
+    platform: String,
+    settings: HashMap<String, serde_json::Value>,
+    state: State<'_, AppState>,
+) -> Result<(), ErrorResponse> {
+    state
+        .storage_service
+        .save_platform_settings(&platform, &settings)
+        .map_err(|e| e.to_response())?;
+    
+    state.download_manager.set_platform_settings(&platform, settings).await;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn select_directory() -> Result<Option<String>, String> {
     use tauri::api::dialog::blocking::FileDialogBuilder;
@@ -229,9 +775,8 @@ pub async fn check_homebrew_installed() -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub async fn install_ytdlp_via_homebrew(app_handle: tauri::AppHandle) -> Result<(), String> {
+pub async fn install_ytdlp_via_homebrew(state: State<'_, AppState>) -> Result<(), String> {
     use std::process::Command;
-    use tauri::Manager;
     
     // Check if homebrew is installed
     let has_brew = check_homebrew_installed().await?;
@@ -240,7 +785,7 @@ pub async fn install_ytdlp_via_homebrew(app_handle: tauri::AppHandle) -> Result<
     }
     
     // Emit progress event
-    let _ = app_handle.emit_all("install:progress", "Installing yt-dlp via Homebrew...");
+    state.events.emit("install:progress", "Installing yt-dlp via Homebrew...".into());
     
     // Run brew install yt-dlp
     let output = Command::new("brew")
@@ -249,7 +794,7 @@ pub async fn install_ytdlp_via_homebrew(app_handle: tauri::AppHandle) -> Result<
         .map_err(|e| format!("Failed to execute brew command: {}", e))?;
     
     if output.status.success() {
-        let _ = app_handle.emit_all("install:progress", "yt-dlp installed successfully!");
+        state.events.emit("install:progress", "yt-dlp installed successfully!".into());
         Ok(())
     } else {
         let error_msg = String::from_utf8_lossy(&output.stderr);
@@ -257,6 +802,78 @@ pub async fn install_ytdlp_via_homebrew(app_handle: tauri::AppHandle) -> Result<
     }
 }
 
+/// Measure sequential write speed of a download folder (e.g. to explain slow NAS downloads)
+#[tauri::command]
+pub async fn benchmark_destination(path: String) -> Result<WriteBenchmark, ErrorResponse> {
+    log_info!("[benchmark_destination] Benchmarking {}", path);
+    
+    let result = tokio::task::spawn_blocking(move || {
+        benchmark::benchmark_destination(std::path::Path::new(&path), benchmark::DEFAULT_BENCHMARK_BYTES)
+    })
+    .await
+    .map_err(|e| DownloadError::DownloadFailed(format!("Benchmark task failed: {}", e)).to_response())?
+    .map_err(|e| e.to_response())?;
+    
+    log_info!("[benchmark_destination] {:.1} MB/s", result.bytes_per_second / (1024.0 * 1024.0));
+    Ok(result)
+}
+
+/// App download archive, or an error when there is no app data directory
+fn download_archive_path(state: &AppState) -> Result<std::path::PathBuf, ErrorResponse> {
+    state.download_manager.download_archive_path().ok_or_else(|| {
+        DownloadError::DownloadFailed("Could not find app data directory".to_string()).to_response()
+    })
+}
+
+/// Forget every video in the download archive so it can be downloaded again, returning how many were removed
+#[tauri::command]
+pub async fn reset_download_archive(state: State<'_, AppState>) -> Result<usize, ErrorResponse> {
+    let removed = archive::reset_archive(&download_archive_path(&state)?).map_err(|e| e.to_response())?;
+    log_info!("[reset_download_archive] Removed {} entries", removed);
+    Ok(removed)
+}
+
+/// Copy the download archive to `destination`, returning how many videos it lists
+#[tauri::command]
+pub async fn export_download_archive(
+    destination: String,
+    state: State<'_, AppState>,
+) -> Result<usize, ErrorResponse> {
+    archive::export_archive(&download_archive_path(&state)?, std::path::Path::new(&destination))
+        .map_err(|e| e.to_response())
+}
+
+/// Let the user pick a cookies file and check it before it goes into settings
+#[tauri::command]
+pub async fn select_cookies_file() -> Result<Option<String>, ErrorResponse> {
+    use tauri::api::dialog::blocking::FileDialogBuilder;
+    
+    let path = FileDialogBuilder::new()
+        .set_title("Select Cookies File")
+        .add_filter("Cookies", &["txt"])
+        .pick_file();
+    
+    match path {
+        Some(path) => {
+            validate_cookies_file_path(path.to_string_lossy().to_string()).await?;
+            Ok(Some(path.to_string_lossy().to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Installed browsers that cookies can be imported from
+#[tauri::command]
+pub async fn list_available_browsers() -> Result<Vec<BrowserInfo>, ErrorResponse> {
+    Ok(youtube_downloader_gui::platform::list_available_browsers())
+}
+
+/// Validate a cookies file, returning how many cookies it holds
+#[tauri::command]
+pub async fn validate_cookies_file_path(path: String) -> Result<usize, ErrorResponse> {
+    validate_cookies_file(std::path::Path::new(&path)).map_err(|e| e.to_response())
+}
+
 #[tauri::command]
 pub async fn check_dependencies(
     platform_name: Option<String>,
@@ -283,7 +900,7 @@ pub async fn check_dependencies(
                 Ok(mut deps) => all_dependencies.append(&mut deps),
                 Err(e) => {
                     // Log error but continue checking other platforms
-                    eprintln!("Failed to check dependencies for {}: {}", provider.name(), e);
+                    log_error!("Failed to check dependencies for {}: {}", provider.name(), e);
                 }
             }
         }
@@ -292,6 +909,53 @@ pub async fn check_dependencies(
     }
 }
 
+/// Failures included in a diagnostics bundle
+const DIAGNOSTICS_ERROR_LIMIT: usize = 200;
+
+/// Zip recent failures, sanitized settings, dependency versions and system info for a bug report
+///
+/// With `item_id`, the item and its yt-dlp output are included too. Written to
+/// `destination`, or the Downloads folder; returns the archive's path.
+#[tauri::command]
+pub async fn export_diagnostics(
+    item_id: Option<String>,
+    destination: Option<String>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, ErrorResponse> {
+    let settings = state.storage_service.load_settings().unwrap_or_default();
+    let errors = state.download_manager.recent_errors(DIAGNOSTICS_ERROR_LIMIT).await;
+    let item = match item_id {
+        Some(id) => Some(
+            state.download_manager.get_queue_status().await
+                .into_iter()
+                .find(|i| i.id == id)
+                .ok_or_else(|| DownloadError::InvalidOption(format!("No download with ID {}", id)).to_response())?,
+        ),
+        None => None,
+    };
+    
+    let mut dependencies = Vec::new();
+    for provider in state.platform_registry.get_all_providers() {
+        match provider.check_dependencies().await {
+            Ok(mut deps) => dependencies.append(&mut deps),
+            Err(e) => log_error!("[Diagnostics] Failed to check dependencies for {}: {}", provider.name(), e),
+        }
+    }
+    
+    let version = app_handle.package_info().version.to_string();
+    let log_file = logging::log_file_path();
+    let bundle = DiagnosticsBundle::collect(&version, &settings, &dependencies, &errors, item.as_ref(), log_file.as_deref())
+        .map_err(|e| e.to_response())?;
+    
+    let path = destination
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(default_bundle_path);
+    bundle.write_zip(&path).map_err(|e| e.to_response())?;
+    log_info!("[Diagnostics] Wrote {}", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -305,6 +969,9 @@ pub struct UpdateInfo {
     pub current_version: String,
     pub latest_version: Option<String>,
     pub update_available: bool,
+    /// GitHub release notes (markdown) for the latest version, if an update is available
+    pub release_notes: Option<String>,
+    pub release_url: Option<String>,
 }
 
 #[tauri::command]
@@ -321,7 +988,10 @@ pub async fn verify_bundled_executables(app_handle: tauri::AppHandle) -> Result<
 }
 
 #[tauri::command]
-pub async fn check_ytdlp_update(app_handle: tauri::AppHandle) -> Result<UpdateInfo, ErrorResponse> {
+pub async fn check_ytdlp_update(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<UpdateInfo, ErrorResponse> {
     let package_info = app_handle.package_info();
     let exec_manager = ExecutableManager::new(package_info)
         .map_err(|e| e.to_response())?;
@@ -329,27 +999,41 @@ pub async fn check_ytdlp_update(app_handle: tauri::AppHandle) -> Result<UpdateIn
     let ytdlp_path = exec_manager.get_ytdlp_path();
     let arch = exec_manager.architecture();
     
-    let update_service = UpdateService::new(ytdlp_path, arch);
+    let settings = state.storage_service.load_settings().unwrap_or_default();
+    let proxy = settings.proxy.with_system_proxy().await;
+    let update_service = UpdateService::new(ytdlp_path, arch)
+        .with_proxy(proxy.global().map(str::to_string))
+        .with_dns_over_https(settings.dns_over_https);
     
     let current_version = update_service.get_current_version()
         .await
         .map_err(|e| e.to_response())?;
     
-    let latest_version = update_service.check_for_update()
+    let latest_release = update_service.check_for_update_release()
         .await
         .map_err(|e| e.to_response())?;
     
-    let update_available = latest_version.is_some();
+    let update_available = latest_release.is_some();
+    let (latest_version, release_notes, release_url) = match latest_release {
+        Some(release) => (Some(release.version), release.notes, release.url),
+        None => (None, None, None),
+    };
     
     Ok(UpdateInfo {
         current_version,
         latest_version,
         update_available,
+        release_notes,
+        release_url,
     })
 }
 
 #[tauri::command]
-pub async fn update_ytdlp(app_handle: tauri::AppHandle) -> Result<String, ErrorResponse> {
+pub async fn update_ytdlp(
+    force: Option<bool>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<UpdateOutcome, ErrorResponse> {
     let package_info = app_handle.package_info();
     let exec_manager = ExecutableManager::new(package_info)
         .map_err(|e| e.to_response())?;
@@ -357,21 +1041,82 @@ pub async fn update_ytdlp(app_handle: tauri::AppHandle) -> Result<String, ErrorR
     let ytdlp_path = exec_manager.get_ytdlp_path();
     let arch = exec_manager.architecture();
     
-    let update_service = UpdateService::new(ytdlp_path, arch);
+    let settings = state.storage_service.load_settings().unwrap_or_default();
+    let proxy = settings.proxy.with_system_proxy().await;
+    let update_service = UpdateService::new(ytdlp_path, arch)
+        .with_proxy(proxy.global().map(str::to_string))
+        .with_dns_over_https(settings.dns_over_https);
     
     // Emit progress event
-    let _ = app_handle.emit_all("ytdlp:update:progress", "Checking for updates...");
+    state.events.emit("ytdlp:update:progress", "Checking for updates...".into());
+    
+    // Don't hold the queue when there is nothing to install
+    if update_service.check_for_update().await.map_err(|e| e.to_response())?.is_none() {
+        let result = UpdateOutcome {
+            message: "Already up to date".to_string(),
+            verification: None,
+        };
+        emit_serialized(state.events.as_ref(), "ytdlp:update:complete", &result);
+        return Ok(result);
+    }
     
-    let result = update_service.update()
+    // Replacing the binary under a running download can corrupt it: wait for
+    // active downloads to finish, or pause them when forced
+    let force = force.unwrap_or(false);
+    let active = state.download_manager.active_download_count().await;
+    if active > 0 {
+        let message = if force {
+            format!("Pausing {} active downloads to update...", active)
+        } else {
+            format!("Waiting for {} active downloads to finish...", active)
+        };
+        state.events.emit("ytdlp:update:progress", message.into());
+    }
+    let paused = state.download_manager
+        .begin_binary_update(force)
         .await
         .map_err(|e| e.to_response())?;
     
-    // Emit completion event
-    let _ = app_handle.emit_all("ytdlp:update:complete", &result);
+    // Installs the update, then test-downloads the reference video with it
+    let result = update_service.update_verified(&exec_manager.get_ffmpeg_path()).await;
+    state.download_manager.end_binary_update(&paused).await;
+    let result = result.map_err(|e| e.to_response())?;
+    
+    // Emit completion event (the UI offers rollback when verification failed)
+    emit_serialized(state.events.as_ref(), "ytdlp:update:complete", &result);
     
     Ok(result)
 }
 
+#[tauri::command]
+pub async fn rollback_ytdlp(
+    force: Option<bool>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, ErrorResponse> {
+    let package_info = app_handle.package_info();
+    let exec_manager = ExecutableManager::new(package_info)
+        .map_err(|e| e.to_response())?;
+    
+    let update_service = UpdateService::new(exec_manager.get_ytdlp_path(), exec_manager.architecture());
+    
+    // Same as updating: don't swap the binary under a running download
+    let paused = state.download_manager
+        .begin_binary_update(force.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_response())?;
+    let result = update_service.rollback();
+    state.download_manager.end_binary_update(&paused).await;
+    result.map_err(|e| e.to_response())?;
+    
+    let version = update_service.get_current_version()
+        .await
+        .map_err(|e| e.to_response())?;
+    log_info!("[rollback_ytdlp] Restored yt-dlp {}", version);
+    
+    Ok(version)
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct DiagnosticInfo {
     pub ytdlp_version: Option<String>,
@@ -414,6 +1159,195 @@ pub async fn test_ytdlp(url: String, state: State<'_, AppState>) -> Result<Diagn
     })
 }
 
+#[tauri::command]
+pub async fn find_duplicate_media(
+    paths: Option<Vec<String>>,
+    threshold: Option<f64>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<DuplicateGroup>, ErrorResponse> {
+    let package_info = app_handle.package_info();
+    let exec_manager = ExecutableManager::new(package_info)
+        .map_err(|e| e.to_response())?;
+    
+    // Default to every file in download history that is still on disk
+    let paths: Vec<std::path::PathBuf> = match paths {
+        Some(paths) => paths.into_iter().map(std::path::PathBuf::from).collect(),
+        None => state
+            .storage_service
+            .load_download_history()
+            .map_err(|e| e.to_response())?
+            .downloads
+            .into_iter()
+            .map(|d| std::path::PathBuf::from(d.save_path))
+            .filter(|p| p.is_file())
+            .collect(),
+    };
+    
+    log_info!("[find_duplicate_media] Analysing {} files", paths.len());
+    let service = MediaDedupeService::new(exec_manager.get_ffmpeg_path());
+    let groups = service
+        .find_duplicates(&paths, threshold.unwrap_or(DEFAULT_SIMILARITY_THRESHOLD))
+        .await;
+    log_info!("[find_duplicate_media] Found {} duplicate groups", groups.len());
+    
+    Ok(groups)
+}
+
+/// Chapter markers and length of a downloaded file
+#[tauri::command]
+pub async fn get_chapters(path: String, app_handle: tauri::AppHandle) -> Result<ChapterList, ErrorResponse> {
+    let exec_manager = ExecutableManager::new(app_handle.package_info())
+        .map_err(|e| e.to_response())?;
+    ChapterEditor::new(exec_manager.get_ffmpeg_path())
+        .read(&existing_file(&path)?)
+        .await
+        .map_err(|e| e.to_response())
+}
+
+/// Replace the chapter markers of a downloaded file, returning them sorted as written
+#[tauri::command]
+pub async fn save_chapters(path: String, chapters: Vec<Chapter>, app_handle: tauri::AppHandle) -> Result<Vec<Chapter>, ErrorResponse> {
+    log_info!("[save_chapters] Writing {} chapters to {}", chapters.len(), path);
+    let exec_manager = ExecutableManager::new(app_handle.package_info())
+        .map_err(|e| e.to_response())?;
+    ChapterEditor::new(exec_manager.get_ffmpeg_path())
+        .write(&existing_file(&path)?, &chapters)
+        .await
+        .map_err(|e| e.to_response())
+}
+
+/// Chapters for a downloaded YouTube video seeded from its SponsorBlock segments; nothing is written
+#[tauri::command]
+pub async fn suggest_sponsorblock_chapters(
+    video_id: String,
+    path: String,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<Chapter>, ErrorResponse> {
+    let exec_manager = ExecutableManager::new(app_handle.package_info())
+        .map_err(|e| e.to_response())?;
+    let duration = ChapterEditor::new(exec_manager.get_ffmpeg_path())
+        .read(&existing_file(&path)?)
+        .await
+        .map_err(|e| e.to_response())?
+        .duration;
+    let segments = chapters::fetch_sponsor_segments(&video_id)
+        .await
+        .map_err(|e| e.to_response())?;
+    log_info!("[suggest_sponsorblock_chapters] {} SponsorBlock segments for {}", segments.len(), video_id);
+    Ok(chapters::chapters_from_segments(&segments, duration))
+}
+
+/// Low-res frames at `timestamps` (seconds) of a video that hasn't been downloaded, to confirm it before queueing
+#[tauri::command]
+pub async fn get_preview_frames(
+    url: String,
+    timestamps: Vec<f64>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<PreviewFrame>, ErrorResponse> {
+    let (_, validated_url) = state
+        .platform_registry
+        .resolve_url(&url)
+        .await
+        .map_err(|e| e.to_response())?;
+    let exec_manager = ExecutableManager::new(app_handle.package_info())
+        .map_err(|e| e.to_response())?;
+    let frames = PreviewService::new(exec_manager.get_ytdlp_path(), exec_manager.get_ffmpeg_path())
+        .frames(&validated_url, &timestamps)
+        .await
+        .map_err(|e| e.to_response())?;
+    log_info!("[get_preview_frames] {} of {} frames for {}", frames.len(), timestamps.len(), validated_url);
+    Ok(frames)
+}
+
+fn existing_file(path: &str) -> Result<std::path::PathBuf, ErrorResponse> {
+    let path = std::path::PathBuf::from(path);
+    if !path.is_file() {
+        return Err(DownloadError::InvalidOption(format!("{} is not a file", path.display())).to_response());
+    }
+    Ok(path)
+}
+
+/// Downloads in the inbox waiting for review, oldest first
+#[tauri::command]
+pub async fn get_inbox(state: State<'_, AppState>) -> Result<Vec<CompletedDownload>, ErrorResponse> {
+    let history = state
+        .storage_service
+        .load_download_history()
+        .map_err(|e| e.to_response())?;
+    Ok(history
+        .downloads
+        .into_iter()
+        .filter(|d| d.review == Some(ReviewStatus::Unreviewed))
+        .collect())
+}
+
+/// Move inbox downloads to their library folders, returning the approved entries
+#[tauri::command]
+pub async fn approve_inbox_items(ids: Vec<String>, state: State<'_, AppState>) -> Result<Vec<CompletedDownload>, ErrorResponse> {
+    log_info!("[approve_inbox_items] Approving {} downloads", ids.len());
+    state
+        .storage_service
+        .review_downloads(&ids, true)
+        .map_err(|e| e.to_response())
+}
+
+/// Delete inbox downloads and their sidecar files, returning the rejected entries
+#[tauri::command]
+pub async fn reject_inbox_items(ids: Vec<String>, state: State<'_, AppState>) -> Result<Vec<CompletedDownload>, ErrorResponse> {
+    log_info!("[reject_inbox_items] Rejecting {} downloads", ids.len());
+    state
+        .storage_service
+        .review_downloads(&ids, false)
+        .map_err(|e| e.to_response())
+}
+
+/// Star or unstar a history entry
+#[tauri::command]
+pub async fn set_download_starred(id: String, starred: bool, state: State<'_, AppState>) -> Result<CompletedDownload, ErrorResponse> {
+    state
+        .storage_service
+        .set_starred(&id, starred)
+        .map_err(|e| e.to_response())
+}
+
+#[tauri::command]
+pub async fn get_collections(state: State<'_, AppState>) -> Result<Vec<SmartCollection>, ErrorResponse> {
+    state
+        .storage_service
+        .load_collections()
+        .map(|c| c.collections)
+        .map_err(|e| e.to_response())
+}
+
+/// Create a smart collection, or update one with an ID, returning the saved collection
+#[tauri::command]
+pub async fn save_collection(collection: SmartCollection, state: State<'_, AppState>) -> Result<SmartCollection, ErrorResponse> {
+    log_info!("[save_collection] Saving collection '{}'", collection.name);
+    state
+        .storage_service
+        .save_collection(collection)
+        .map_err(|e| e.to_response())
+}
+
+#[tauri::command]
+pub async fn delete_collection(id: String, state: State<'_, AppState>) -> Result<(), ErrorResponse> {
+    state
+        .storage_service
+        .delete_collection(&id)
+        .map_err(|e| e.to_response())
+}
+
+/// Downloads currently matching a smart collection's filter, most recent first
+#[tauri::command]
+pub async fn get_collection_downloads(id: String, state: State<'_, AppState>) -> Result<Vec<CompletedDownload>, ErrorResponse> {
+    state
+        .storage_service
+        .collection_downloads(&id)
+        .map_err(|e| e.to_response())
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct VersionInfo {
     pub ytdlp_version: Option<String>,
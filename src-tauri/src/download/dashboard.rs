@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use super::task::{DownloadItem, DownloadStatus};
+
+/// Downloads finished or failed on one local calendar day
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyTotals {
+    /// Local date, `YYYY-MM-DD`
+    pub date: String,
+    pub completed: usize,
+    pub failed: usize,
+}
+
+impl DailyTotals {
+    pub fn today() -> Self {
+        Self::for_date(chrono::Local::now().format("%Y-%m-%d").to_string())
+    }
+    
+    fn for_date(date: String) -> Self {
+        Self { date, completed: 0, failed: 0 }
+    }
+    
+    /// Count a finished download, starting over when the day has changed
+    pub fn record(&mut self, status: &DownloadStatus, date: &str) {
+        if self.date != date {
+            *self = Self::for_date(date.to_string());
+        }
+        match status {
+            DownloadStatus::Completed => self.completed += 1,
+            DownloadStatus::Failed => self.failed += 1,
+            _ => {}
+        }
+    }
+}
+
+/// One queue entry as shown in the tray menu or a widget
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardItem {
+    pub id: String,
+    pub title: String,
+    pub platform: String,
+    pub thumbnail: String,
+    pub progress: f64,
+    pub speed: f64,
+    pub eta: u64,
+    /// Delayed start time for items waiting on a countdown
+    pub scheduled_at: Option<String>,
+}
+
+impl From<&DownloadItem> for DashboardItem {
+    fn from(item: &DownloadItem) -> Self {
+        Self {
+            id: item.id.clone(),
+            title: item.title.clone(),
+            platform: item.platform.clone(),
+            thumbnail: item.thumbnail.clone(),
+            progress: item.progress,
+            speed: item.speed,
+            eta: item.eta,
+            scheduled_at: item.scheduled_at.clone(),
+        }
+    }
+}
+
+/// Compact queue summary for the tray menu, menubar widget and similar surfaces
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardSnapshot {
+    /// Items downloading right now
+    pub active: Vec<DashboardItem>,
+    /// Item that starts next: the first one ready to go, else the earliest scheduled
+    pub next_up: Option<DashboardItem>,
+    pub queued_count: usize,
+    pub paused_count: usize,
+    /// Combined speed of active downloads in bytes per second
+    pub total_speed: f64,
+    /// New downloads are held, e.g. while yt-dlp is being updated
+    pub held: bool,
+    pub today: DailyTotals,
+}
+
+impl DashboardSnapshot {
+    /// Summarize `queue` (in queue order) with the day's totals
+    pub fn from_queue(queue: &[DownloadItem], held: bool, today: DailyTotals) -> Self {
+        let active: Vec<DashboardItem> = queue
+            .iter()
+            .filter(|i| i.status == DownloadStatus::Downloading)
+            .map(DashboardItem::from)
+            .collect();
+        let count = |status: DownloadStatus| queue.iter().filter(|i| i.status == status).count();
+        
+        let now = chrono::Utc::now();
+        let queued = || queue.iter().filter(|i| i.status == DownloadStatus::Queued);
+        let next_up = queued()
+            .find(|i| i.is_due(now))
+            .or_else(|| queued().min_by_key(|i| i.scheduled_time()));
+        
+        Self {
+            total_speed: active.iter().map(|i| i.speed).sum(),
+            active,
+            next_up: next_up.map(DashboardItem::from),
+            queued_count: count(DownloadStatus::Queued),
+            paused_count: count(DownloadStatus::Paused),
+            held,
+            today,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn item(id: &str, status: DownloadStatus, speed: f64) -> DownloadItem {
+        DownloadItem {
+            id: id.to_string(),
+            video_id: id.to_string(),
+            title: format!("Video {}", id),
+            thumbnail: String::new(),
+            status,
+            progress: 10.0,
+            speed,
+            eta: 60,
+            save_path: "/tmp".to_string(),
+            error: None,
+            url: format!("https://www.youtube.com/watch?v={}", id),
+            platform: "YouTube".to_string(),
+            postprocessor_args: None,
+            subtitle_languages: None,
+            auto_subtitles: None,
+            embed_chapters: None,
+            scheduled_at: None,
+            playlist_id: None,
+            record_live: false,
+            premiere_at: None,
+            section_start: None,
+            section_end: None,
+            audio_only: false,
+            format_id: None,
+            album: None,
+            track_number: None,
+            uploader: None,
+            quality: None,
+            variant_group: None,
+            estimated_size: None,
+            request_id: None,
+            liveness: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_summarizes_queue() {
+        let queue = vec![
+            item("done", DownloadStatus::Completed, 0.0),
+            item("a", DownloadStatus::Downloading, 1000.0),
+            item("p", DownloadStatus::Paused, 0.0),
+            item("next", DownloadStatus::Queued, 0.0),
+            item("b", DownloadStatus::Downloading, 500.0),
+            item("later", DownloadStatus::Queued, 0.0),
+        ];
+        
+        let snapshot = DashboardSnapshot::from_queue(&queue, false, DailyTotals::today());
+        assert_eq!(snapshot.active.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(snapshot.next_up.map(|i| i.id), Some("next".to_string()));
+        assert_eq!(snapshot.queued_count, 2);
+        assert_eq!(snapshot.paused_count, 1);
+        assert_eq!(snapshot.total_speed, 1500.0);
+    }
+
+    #[test]
+    fn test_next_up_prefers_ready_items_over_scheduled_ones() {
+        let later = |id: &str, hours: i64| DownloadItem {
+            scheduled_at: Some((chrono::Utc::now() + chrono::Duration::hours(hours)).to_rfc3339()),
+            ..item(id, DownloadStatus::Queued, 0.0)
+        };
+        
+        let queue = vec![later("in3h", 3), later("in1h", 1), item("ready", DownloadStatus::Queued, 0.0)];
+        let snapshot = DashboardSnapshot::from_queue(&queue, false, DailyTotals::today());
+        assert_eq!(snapshot.next_up.map(|i| i.id), Some("ready".to_string()));
+        
+        let snapshot = DashboardSnapshot::from_queue(&queue[..2], false, DailyTotals::today());
+        assert_eq!(snapshot.next_up.map(|i| i.id), Some("in1h".to_string()));
+    }
+
+    #[test]
+    fn test_daily_totals_reset_on_new_day() {
+        let mut totals = DailyTotals::for_date("2024-05-01".to_string());
+        totals.record(&DownloadStatus::Completed, "2024-05-01");
+        totals.record(&DownloadStatus::Failed, "2024-05-01");
+        totals.record(&DownloadStatus::Cancelled, "2024-05-01");
+        assert_eq!((totals.completed, totals.failed), (1, 1));
+        
+        totals.record(&DownloadStatus::Completed, "2024-05-02");
+        assert_eq!(totals, DailyTotals { date: "2024-05-02".to_string(), completed: 1, failed: 0 });
+    }
+}
@@ -1,33 +1,157 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use tokio::time::{sleep, Duration};
-use tauri::{AppHandle, Manager};
-use super::task::{DownloadItem, DownloadTask, DownloadStatus};
+use super::dashboard::{DailyTotals, DashboardSnapshot};
+use super::outage::{is_network_failure, probe_connectivity, OutageBreaker};
+use super::recovery::{corrected_target, find_downloaded_file, find_partial_files, recover_item, target_location, RecoveryOutcome};
+use super::request_tokens::RequestTokens;
+use super::scheduling::{select_next, MixingPolicy, QuickWins};
+use super::task::{DownloadItem, DownloadTask, DownloadStatus, TaskLiveness};
 use super::throttle::ProgressThrottler;
-use crate::platform::{PlatformRegistry, DownloadOptions, DownloadProgress};
+use super::variants::{validate_quality, VariantGroupProgress};
+use crate::platform::{PlatformRegistry, DownloadOptions, DownloadProgress, PostprocessLimits, PostprocessPool, ProcessPriority, ProxySettings, HttpHeaderSettings, AudioSettings, AudioTags, YtDlpRetries, validate_format_id, validate_postprocessor_args};
 use crate::error::{Result, DownloadError};
+use crate::events::{emit_serialized, EventSink};
+use crate::formatting::{current_locale, ProgressDisplay};
+use crate::logging;
+use crate::paths::PathProvider;
+use crate::storage::archive::{self, ARCHIVE_FILE_NAME};
+use crate::storage::inbox;
+use crate::storage::{CompletedDownload, ErrorLog, ErrorRecord, ErrorStats, HistoryStore, InboxSettings, PlatformStats, PlatformStatsLog, ReviewStatus, StorageService};
+use crate::transcription::{subtitle_files, TranscriptionService, TranscriptionSettings};
+use crate::{log_error, log_info};
 
 /// Download manager for handling queue and concurrent downloads
 pub struct DownloadManager {
     queue: Arc<RwLock<Vec<DownloadItem>>>,
     active_downloads: Arc<Mutex<HashMap<String, Arc<DownloadTask>>>>,
     max_concurrent: Arc<RwLock<usize>>,
-    app_handle: AppHandle,
+    /// Where the saved queue and error log live
+    paths: Arc<dyn PathProvider>,
+    /// Where queue and download events go
+    events: Arc<dyn EventSink>,
     platform_registry: Arc<PlatformRegistry>,
     processing: Arc<Mutex<bool>>,
+    mp4_faststart: Arc<RwLock<bool>>,
+    /// DASH/HLS fragments fetched in parallel per download
+    concurrent_fragments: Arc<RwLock<u32>>,
+    ytdlp_retries: Arc<RwLock<YtDlpRetries>>,
+    embed_chapters: Arc<RwLock<bool>>,
+    /// Skip videos already recorded in the app's download archive
+    download_archive: Arc<RwLock<bool>>,
+    helper_priority: Arc<RwLock<ProcessPriority>>,
+    /// Saved provider settings by platform name, passed to each download
+    platform_settings: Arc<RwLock<HashMap<String, HashMap<String, serde_json::Value>>>>,
+    /// ffmpeg thread cap and transcode scheduling shared by all downloads
+    postprocess_pool: Arc<PostprocessPool>,
+    /// Downloads completed and failed today, for the dashboard snapshot
+    daily_totals: Arc<RwLock<DailyTotals>>,
+    /// Set while a dependency binary is being replaced; no downloads start meanwhile
+    binary_update: Arc<RwLock<bool>>,
+    /// Free bytes to keep at download destinations; 0 disables the check
+    min_free_space: Arc<RwLock<u64>>,
+    /// Set while a destination is below `min_free_space`; no downloads start meanwhile
+    low_space: Arc<RwLock<bool>>,
+    /// How playlist items interleave with standalone items
+    mixing_policy: Arc<RwLock<MixingPolicy>>,
+    /// Playlist (or "" for standalone), or platform under fair share, of the most recently started item
+    last_started_group: Arc<RwLock<Option<String>>>,
+    /// Extra slots for small items once the concurrency limit is reached
+    quick_wins: Arc<RwLock<QuickWins>>,
+    /// Total speed budget in bytes per second, divided among active downloads
+    global_speed_limit: Arc<RwLock<Option<u64>>>,
+    proxy_settings: Arc<RwLock<ProxySettings>>,
+    http_headers: Arc<RwLock<HttpHeaderSettings>>,
+    audio_settings: Arc<RwLock<AudioSettings>>,
+    /// whisper.cpp runner for completed videos without subtitles; unset until configured
+    transcription: Arc<RwLock<Option<Arc<TranscriptionService>>>>,
+    transcription_settings: Arc<RwLock<TranscriptionSettings>>,
+    /// Review folder completed downloads are moved to, when on
+    inbox: Arc<RwLock<InboxSettings>>,
+    /// Where completed downloads are recorded; unset until configured
+    history: Arc<RwLock<Option<Arc<dyn HistoryStore>>>>,
+    /// Holds the queue after a burst of network failures until connectivity returns
+    outage: Arc<RwLock<OutageBreaker>>,
+    /// Failure history behind `error_stats`, loaded on first use
+    error_log: Arc<Mutex<Option<ErrorLog>>>,
+    /// Per-platform totals behind `platform_stats`, loaded on first use
+    platform_stats: Arc<Mutex<Option<PlatformStatsLog>>>,
+    /// Item IDs from recent add requests, so repeated requests don't queue twice
+    request_tokens: Arc<Mutex<RequestTokens>>,
+    /// Items shown in the UI, which get progress events at the full rate
+    progress_subscriptions: Arc<RwLock<HashSet<String>>>,
+}
+
+/// How often free space at active destinations is checked
+const SPACE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Longest wait for active downloads to finish before a binary update gives up
+const BINARY_UPDATE_WAIT: Duration = Duration::from_secs(30 * 60);
+
+/// Longest a live recording may run; streams end on their own long before this
+const LIVE_RECORDING_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Minimum gap between progress events for items the UI hasn't subscribed to
+const COARSE_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often `download:waiting` countdown events are sent while a premiere hasn't started
+const PREMIERE_COUNTDOWN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a download whose task has died may go without a heartbeat before the watchdog fails it
+const STALE_DOWNLOAD_GRACE: Duration = Duration::from_secs(10);
+
+/// How long a running download may go without progress before the watchdog fails it as hung
+const STALLED_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Longest a removal waits for a cancelled download to stop before cleaning up
+const REMOVE_WAIT: Duration = Duration::from_secs(5);
+
+/// Equal share of `limit` for each of `active` downloads; uncapped without a limit
+fn bandwidth_share(limit: Option<u64>, active: usize) -> Option<u64> {
+    limit.map(|limit| (limit / active.max(1) as u64).max(1))
 }
 
 impl DownloadManager {
-    pub fn new(app_handle: AppHandle, platform_registry: Arc<PlatformRegistry>) -> Self {
+    pub fn new(paths: Arc<dyn PathProvider>, events: Arc<dyn EventSink>, platform_registry: Arc<PlatformRegistry>) -> Self {
         Self {
             queue: Arc::new(RwLock::new(Vec::new())),
             active_downloads: Arc::new(Mutex::new(HashMap::new())),
             max_concurrent: Arc::new(RwLock::new(3)),
-            app_handle,
+            paths,
+            events,
             platform_registry,
             processing: Arc::new(Mutex::new(false)),
+            mp4_faststart: Arc::new(RwLock::new(true)),
+            concurrent_fragments: Arc::new(RwLock::new(1)),
+            ytdlp_retries: Arc::new(RwLock::new(YtDlpRetries::default())),
+            embed_chapters: Arc::new(RwLock::new(false)),
+            download_archive: Arc::new(RwLock::new(false)),
+            helper_priority: Arc::new(RwLock::new(ProcessPriority::Normal)),
+            platform_settings: Arc::new(RwLock::new(HashMap::new())),
+            postprocess_pool: Arc::new(PostprocessPool::default()),
+            daily_totals: Arc::new(RwLock::new(DailyTotals::today())),
+            binary_update: Arc::new(RwLock::new(false)),
+            min_free_space: Arc::new(RwLock::new(0)),
+            low_space: Arc::new(RwLock::new(false)),
+            mixing_policy: Arc::new(RwLock::new(MixingPolicy::default())),
+            quick_wins: Arc::new(RwLock::new(QuickWins::default())),
+            global_speed_limit: Arc::new(RwLock::new(None)),
+            last_started_group: Arc::new(RwLock::new(None)),
+            proxy_settings: Arc::new(RwLock::new(ProxySettings::default())),
+            http_headers: Arc::new(RwLock::new(HttpHeaderSettings::default())),
+            audio_settings: Arc::new(RwLock::new(AudioSettings::default())),
+            transcription: Arc::new(RwLock::new(None)),
+            transcription_settings: Arc::new(RwLock::new(TranscriptionSettings::default())),
+            inbox: Arc::new(RwLock::new(InboxSettings::default())),
+            history: Arc::new(RwLock::new(None)),
+            outage: Arc::new(RwLock::new(OutageBreaker::default())),
+            error_log: Arc::new(Mutex::new(None)),
+            platform_stats: Arc::new(Mutex::new(None)),
+            request_tokens: Arc::new(Mutex::new(RequestTokens::default())),
+            progress_subscriptions: Arc::new(RwLock::new(HashSet::new())),
         }
     }
     
@@ -37,64 +161,314 @@ impl DownloadManager {
         *max_concurrent = max.max(1).min(5);
     }
     
+    /// Apply the `mp4_faststart` setting to downloads started from now on
+    pub async fn set_mp4_faststart(&self, enabled: bool) {
+        *self.mp4_faststart.write().await = enabled;
+    }
+    
+    /// Apply the `concurrent_fragments` setting to downloads started from now on
+    pub async fn set_concurrent_fragments(&self, fragments: u32) {
+        *self.concurrent_fragments.write().await = fragments;
+    }
+    
+    /// Apply the `ytdlp_retries` setting to downloads started from now on
+    pub async fn set_ytdlp_retries(&self, retries: YtDlpRetries) {
+        *self.ytdlp_retries.write().await = retries;
+    }
+    
+    /// Apply the `queue_mixing_policy` setting
+    pub async fn set_mixing_policy(&self, policy: MixingPolicy) {
+        *self.mixing_policy.write().await = policy;
+    }
+    
+    /// Apply the `queue_quick_wins` setting
+    pub async fn set_quick_wins(&self, quick_wins: QuickWins) {
+        *self.quick_wins.write().await = quick_wins;
+    }
+    
+    /// Apply the `global_speed_limit` setting, re-dividing it among running downloads
+    pub async fn set_global_speed_limit(&self, bytes_per_second: Option<u64>) {
+        *self.global_speed_limit.write().await = bytes_per_second;
+        self.rebalance_bandwidth().await;
+    }
+    
+    /// Give every active download an equal share of the global speed limit
+    ///
+    /// Runs whenever a download starts or stops. yt-dlp can't change its rate
+    /// while running, so a download whose share changed restarts in place.
+    async fn rebalance_bandwidth(&self) {
+        let limit = *self.global_speed_limit.read().await;
+        let active = self.active_downloads.lock().await;
+        let share = bandwidth_share(limit, active.len());
+        for (id, task) in active.iter() {
+            if task.set_rate_limit(share) {
+                self.events.emit("download:rate_limit", serde_json::json!({
+                    "id": id,
+                    "bytesPerSecond": share,
+                }));
+            }
+        }
+    }
+    
+    /// Apply the `proxy` setting to downloads started from now on
+    pub async fn set_proxy_settings(&self, proxy: ProxySettings) {
+        *self.proxy_settings.write().await = proxy;
+    }
+    
+    /// Apply the `http_headers` setting to downloads started from now on
+    pub async fn set_http_headers(&self, headers: HttpHeaderSettings) {
+        *self.http_headers.write().await = headers;
+    }
+    
+    /// Apply the `audio` setting to audio-only downloads started from now on
+    pub async fn set_audio_settings(&self, audio: AudioSettings) {
+        *self.audio_settings.write().await = audio;
+    }
+    
+    /// Use `service` to transcribe completed videos
+    pub async fn set_transcription_service(&self, service: TranscriptionService) {
+        *self.transcription.write().await = Some(Arc::new(service));
+    }
+    
+    /// Apply the `transcription` setting to downloads completing from now on
+    pub async fn set_transcription_settings(&self, settings: TranscriptionSettings) {
+        *self.transcription_settings.write().await = settings;
+    }
+    
+    /// Apply the `inbox` setting to downloads completing from now on
+    pub async fn set_inbox_settings(&self, inbox: InboxSettings) {
+        *self.inbox.write().await = inbox;
+    }
+    
+    /// Record completed downloads in `history`
+    pub async fn set_history_store(&self, history: Arc<dyn HistoryStore>) {
+        *self.history.write().await = Some(history);
+    }
+    
+    /// Apply the `min_free_space_mb` setting
+    pub async fn set_min_free_space(&self, bytes: u64) {
+        *self.min_free_space.write().await = bytes;
+    }
+    
+    /// Apply the `embed_chapters` setting to items without their own choice
+    pub async fn set_embed_chapters(&self, enabled: bool) {
+        *self.embed_chapters.write().await = enabled;
+    }
+    
+    /// Apply the `use_download_archive` setting to downloads started from now on
+    pub async fn set_download_archive(&self, enabled: bool) {
+        *self.download_archive.write().await = enabled;
+    }
+    
+    /// The app-maintained yt-dlp download archive
+    pub fn download_archive_path(&self) -> Option<PathBuf> {
+        self.paths.app_data_dir().map(|dir| dir.join(ARCHIVE_FILE_NAME))
+    }
+    
+    /// Apply the `helper_process_priority` setting to downloads started from now on
+    pub async fn set_helper_priority(&self, priority: ProcessPriority) {
+        *self.helper_priority.write().await = priority;
+    }
+    
+    /// Apply the `postprocess_limits` setting; queued transcodes re-check the new cap
+    pub fn set_postprocess_limits(&self, limits: PostprocessLimits) {
+        self.postprocess_pool.set_limits(limits);
+    }
+    
+    /// Apply a platform's saved settings to its downloads started from now on
+    pub async fn set_platform_settings(&self, platform: &str, settings: HashMap<String, serde_json::Value>) {
+        self.platform_settings.write().await.insert(platform.to_string(), settings);
+    }
+    
+    /// Hold new downloads and wait until none are running, so yt-dlp can be replaced safely
+    ///
+    /// Without `force` this waits for active downloads to finish. With `force`
+    /// they are paused instead and their ids returned, to be resumed by
+    /// `end_binary_update`. Fails with `Timeout` if downloads are still
+    /// running after `BINARY_UPDATE_WAIT`; the hold is released in that case.
+    pub async fn begin_binary_update(&self, force: bool) -> Result<Vec<String>> {
+        *self.binary_update.write().await = true;
+        
+        let mut paused = Vec::new();
+        if force {
+            let active_ids: Vec<String> = self.active_downloads.lock().await.keys().cloned().collect();
+            for id in active_ids {
+                self.pause_download(&id).await?;
+                paused.push(id);
+            }
+            log_info!("[DownloadManager] Paused {} downloads for binary update", paused.len());
+        }
+        
+        let started = std::time::Instant::now();
+        loop {
+            let active = self.active_downloads.lock().await.len();
+            if active == 0 {
+                return Ok(paused);
+            }
+            if started.elapsed() >= BINARY_UPDATE_WAIT {
+                self.end_binary_update(&paused).await;
+                return Err(DownloadError::Timeout);
+            }
+            log_info!("[DownloadManager] Waiting for {} active downloads before binary update", active);
+            sleep(Duration::from_secs(1)).await;
+        }
+    }
+    
+    /// Release the hold taken by `begin_binary_update` and resume downloads it paused
+    pub async fn end_binary_update(&self, paused: &[String]) {
+        *self.binary_update.write().await = false;
+        for id in paused {
+            let _ = self.resume_download(id).await;
+        }
+        self.start_processing().await;
+    }
+    
+    /// Number of downloads currently running
+    pub async fn active_download_count(&self) -> usize {
+        self.active_downloads.lock().await.len()
+    }
+    
     /// Add download tasks to queue
-    pub async fn add_to_queue(&self, items: Vec<DownloadItem>) -> Result<()> {
-        println!("[DownloadManager::add_to_queue] Adding {} items to queue", items.len());
+    ///
+    /// Items without an ID get a generated UUID. Returns the IDs of the added
+    /// items, in order; IDs already in the queue or repeated in the batch are rejected.
+    pub async fn add_to_queue(&self, mut items: Vec<DownloadItem>) -> Result<Vec<String>> {
+        log_info!("[DownloadManager::add_to_queue] Adding {} items to queue", items.len());
+        
+        // Reject invalid advanced options up front rather than failing mid-queue
+        for item in &items {
+            if let Some(args) = item.postprocessor_args.as_deref() {
+                validate_postprocessor_args(args)?;
+            }
+            if let Some(format_id) = item.format_id.as_deref() {
+                validate_format_id(format_id)?;
+            }
+            if let Some(quality) = item.quality.as_deref() {
+                validate_quality(quality)?;
+            }
+            if let Some(at) = item.scheduled_at.as_deref() {
+                if item.scheduled_time().is_none() {
+                    return Err(DownloadError::InvalidOption(format!("Invalid start time: {}", at)));
+                }
+            }
+            let start = item.section_start.unwrap_or(0.0);
+            if start < 0.0 || item.section_end.is_some_and(|end| end <= start) {
+                return Err(DownloadError::InvalidOption(format!(
+                    "Invalid clip range for {}: the end must come after the start",
+                    item.title
+                )));
+            }
+        }
+        
+        let mut queue = self.queue.write().await;
+        
+        // IDs key the active-downloads map, so they must be present and unique
+        let mut ids: HashSet<String> = queue.iter().map(|i| i.id.clone()).collect();
+        let request_id = logging::current_trace().map(|trace| trace.request_id);
+        for (idx, item) in items.iter_mut().enumerate() {
+            if item.id.trim().is_empty() {
+                item.id = uuid::Uuid::new_v4().to_string();
+            }
+            // `<request>.<n>` keeps every item of one request findable by the request ID
+            if item.request_id.is_none() {
+                item.request_id = request_id.as_ref().map(|id| format!("{}.{}", id, idx + 1));
+            }
+            if !ids.insert(item.id.clone()) {
+                return Err(DownloadError::InvalidOption(format!("Duplicate download ID: {}", item.id)));
+            }
+        }
         
         for (idx, item) in items.iter().enumerate() {
-            println!("[DownloadManager::add_to_queue] Item {}: id={}, title={}, status={:?}, url={}", 
+            log_info!("[DownloadManager::add_to_queue] Item {}: id={}, title={}, status={:?}, url={}", 
                      idx, item.id, item.title, item.status, item.url);
         }
         
-        let mut queue = self.queue.write().await;
+        let assigned: Vec<String> = items.iter().map(|i| i.id.clone()).collect();
         queue.extend(items);
-        println!("[DownloadManager::add_to_queue] Queue now has {} items", queue.len());
+        log_info!("[DownloadManager::add_to_queue] Queue now has {} items", queue.len());
         drop(queue); // Release lock before emitting events
         
         // Emit queue update event
         self.emit_queue_update().await;
         
         // Start processing if not already running
-        println!("[DownloadManager::add_to_queue] Starting processing...");
+        log_info!("[DownloadManager::add_to_queue] Starting processing...");
         self.start_processing().await;
-        println!("[DownloadManager::add_to_queue] Processing started");
+        log_info!("[DownloadManager::add_to_queue] Processing started");
         
-        Ok(())
+        Ok(assigned)
+    }
+    
+    /// Add items once per request `token`
+    ///
+    /// Repeating a request with the same token returns the IDs from the first
+    /// time instead of queueing the items again.
+    pub async fn add_to_queue_once(&self, token: &str, items: Vec<DownloadItem>) -> Result<Vec<String>> {
+        // Held across the add so a concurrent repeat waits and then sees this result
+        let mut tokens = self.request_tokens.lock().await;
+        if let Some(ids) = tokens.get(token, std::time::Instant::now()) {
+            log_info!("[DownloadManager::add_to_queue] Ignoring repeated request {}", token);
+            return Ok(ids);
+        }
+        
+        let ids = self.add_to_queue(items).await?;
+        tokens.insert(token, ids.clone(), std::time::Instant::now());
+        Ok(ids)
     }
     
     /// Start queue processing loop
     async fn start_processing(&self) {
         let mut processing = self.processing.lock().await;
         if *processing {
-            println!("[start_processing] Already processing, skipping");
+            log_info!("[start_processing] Already processing, skipping");
             return;
         }
-        println!("[start_processing] Starting new processing loop");
+        log_info!("[start_processing] Starting new processing loop");
         *processing = true;
         drop(processing);
         
         let manager = self.clone_arc();
         tokio::spawn(async move {
-            println!("[process_queue_loop] Spawned processing task");
+            log_info!("[process_queue_loop] Spawned processing task");
             manager.process_queue_loop().await;
-            println!("[process_queue_loop] Processing task completed");
+            log_info!("[process_queue_loop] Processing task completed");
         });
     }
     
     /// Process download queue in a loop
     async fn process_queue_loop(&self) {
-        println!("[process_queue_loop] Starting queue processing loop");
+        log_info!("[process_queue_loop] Starting queue processing loop");
+        let mut last_space_check: Option<tokio::time::Instant> = None;
         loop {
+            if last_space_check.is_none_or(|at| at.elapsed() >= SPACE_CHECK_INTERVAL) {
+                last_space_check = Some(tokio::time::Instant::now());
+                self.check_free_space().await;
+            }
+            self.probe_outage().await;
+            self.reap_stale_downloads().await;
+            
             // Check if there are items to process
             let has_work = {
                 let queue = self.queue.read().await;
                 let active = self.active_downloads.lock().await;
                 let max_concurrent = *self.max_concurrent.read().await;
                 
-                let queued_count = queue.iter().filter(|item| item.status == DownloadStatus::Queued).count();
-                let has_work = queued_count > 0 && active.len() < max_concurrent;
+                let updating = *self.binary_update.read().await
+                    || *self.low_space.read().await
+                    || self.outage.read().await.is_open();
+                
+                // Items scheduled for later wait here until their start time passes
+                let now = chrono::Utc::now();
+                let queued_count = queue.iter()
+                    .filter(|item| item.status == DownloadStatus::Queued && item.is_due(now))
+                    .count();
+                // Past the limit, only a small item can take one of the quick-win slots
+                let quick_wins = *self.quick_wins.read().await;
+                let has_slot = active.len() < max_concurrent
+                    || (active.len() < max_concurrent + quick_wins.extra_slots() && quick_wins.select(&queue, now).is_some());
+                let has_work = queued_count > 0 && has_slot && !updating;
                 
-                println!("[process_queue_loop] Queue check: {} queued, {} active, {} max, has_work={}", 
+                log_info!("[process_queue_loop] Queue check: {} due, {} active, {} max, has_work={}", 
                          queued_count, active.len(), max_concurrent, has_work);
                 
                 has_work
@@ -105,32 +479,32 @@ impl DownloadManager {
                 let queue = self.queue.read().await;
                 let active = self.active_downloads.lock().await;
                 
-                println!("[process_queue_loop] No work: queue.len()={}, active.len()={}", 
+                log_info!("[process_queue_loop] No work: queue.len()={}, active.len()={}", 
                          queue.len(), active.len());
                 
                 if queue.is_empty() && active.is_empty() {
-                    println!("[process_queue_loop] Queue and active both empty, stopping");
+                    log_info!("[process_queue_loop] Queue and active both empty, stopping");
                     let mut processing = self.processing.lock().await;
                     *processing = false;
                     break;
                 }
                 
                 // Wait before checking again
-                println!("[process_queue_loop] Waiting 500ms before next check");
+                log_info!("[process_queue_loop] Waiting 500ms before next check");
                 sleep(Duration::from_millis(500)).await;
                 continue;
             }
             
             // Process next item
-            println!("[process_queue_loop] Processing next item");
+            log_info!("[process_queue_loop] Processing next item");
             if let Err(e) = self.process_next_item().await {
-                eprintln!("[process_queue_loop] Error processing queue item: {}", e);
+                log_error!("[process_queue_loop] Error processing queue item: {}", e);
             }
             
             // Small delay to prevent tight loop
             sleep(Duration::from_millis(100)).await;
         }
-        println!("[process_queue_loop] Exiting processing loop");
+        log_info!("[process_queue_loop] Exiting processing loop");
     }
     
     /// Process next queued item
@@ -140,22 +514,61 @@ impl DownloadManager {
             let mut queue = self.queue.write().await;
             let active = self.active_downloads.lock().await;
             let max_concurrent = *self.max_concurrent.read().await;
+            let quick_wins = *self.quick_wins.read().await;
+            
+            log_info!("[process_next_item] Active downloads: {}/{}", active.len(), max_concurrent);
+            
+            if active.len() >= max_concurrent + quick_wins.extra_slots() {
+                log_info!("[process_next_item] Max concurrent downloads reached");
+                return Ok(());
+            }
             
-            println!("[process_next_item] Active downloads: {}/{}", active.len(), max_concurrent);
+            if *self.binary_update.read().await {
+                log_info!("[process_next_item] Binary update in progress, holding queue");
+                return Ok(());
+            }
+            
+            if *self.low_space.read().await {
+                log_info!("[process_next_item] Low disk space, holding queue");
+                return Ok(());
+            }
             
-            if active.len() >= max_concurrent {
-                println!("[process_next_item] Max concurrent downloads reached");
+            if self.outage.read().await.is_open() {
+                log_info!("[process_next_item] Waiting out a network outage, holding queue");
                 return Ok(());
             }
             
-            let queued_count = queue.iter().filter(|item| item.status == DownloadStatus::Queued).count();
-            println!("[process_next_item] Found {} queued items", queued_count);
+            let now = chrono::Utc::now();
+            let queued_count = queue.iter()
+                .filter(|item| item.status == DownloadStatus::Queued && item.is_due(now))
+                .count();
+            log_info!("[process_next_item] Found {} due queued items", queued_count);
             
-            queue.iter_mut()
-                .find(|item| item.status == DownloadStatus::Queued)
+            let policy = *self.mixing_policy.read().await;
+            let mut last_group = self.last_started_group.write().await;
+            let next = if active.len() < max_concurrent {
+                select_next(&queue, policy, last_group.as_deref(), now).inspect(|&idx| {
+                    *last_group = Some(policy.group_of(&queue[idx]).to_string());
+                })
+            } else {
+                // A quick win doesn't take a turn from its playlist under round-robin
+                quick_wins.select(&queue, now).inspect(|&idx| {
+                    log_info!("[process_next_item] Starting small item {} in a quick-win slot", queue[idx].id);
+                })
+            };
+            next.and_then(|idx| queue.get_mut(idx))
                 .map(|item| {
-                    println!("[process_next_item] Starting download for: {} ({})", item.title, item.id);
-                    item.status = DownloadStatus::Downloading;
+                    log_info!("[process_next_item] Starting download for: {} ({})", item.title, item.id);
+                    // Premieres hold their slot in Waiting until yt-dlp starts receiving data
+                    item.status = if item.premiere_time().is_some_and(|at| at > now) {
+                        DownloadStatus::Waiting
+                    } else {
+                        DownloadStatus::Downloading
+                    };
+                    item.liveness = Some(match item.liveness.take() {
+                        Some(previous) => TaskLiveness { restart_count: previous.restart_count + 1, ..previous },
+                        None => TaskLiveness::default(),
+                    });
                     item.clone()
                 })
         };
@@ -168,144 +581,538 @@ impl DownloadManager {
                 let mut active = self.active_downloads.lock().await;
                 active.insert(item.id.clone(), Arc::clone(&task));
             }
+            self.rebalance_bandwidth().await;
             
             // Emit status change
-            self.emit_status_change(&item.id, DownloadStatus::Downloading).await;
+            self.emit_status_change(&item.id, item.status.clone()).await;
             
             // Start download in background
             let manager = self.clone_arc();
             let item_id = item.id.clone();
-            tokio::spawn(async move {
-                if let Err(e) = manager.execute_download(task).await {
-                    eprintln!("[execute_download] Download failed for {}: {}", item_id, e);
+            let running = Arc::clone(&task);
+            let request_id = item.request_id.clone().unwrap_or_else(logging::new_request_id);
+            let handle = tokio::spawn(logging::in_request(&request_id, "download", async move {
+                if let Err(e) = manager.execute_download(running).await {
+                    log_error!("[execute_download] Download failed for {}: {}", item_id, e);
                 }
-            });
+            }));
+            task.set_handle(handle);
         } else {
-            println!("[process_next_item] No queued items found to process");
+            log_info!("[process_next_item] No queued items found to process");
         }
         
         Ok(())
     }
     
+    /// Fail downloads whose task died without cleaning up or stopped making progress, freeing their slots
+    ///
+    /// A panic or early return in `execute_download` leaves the item in
+    /// `active_downloads`, where it would hold a concurrency slot forever; so does
+    /// a provider that hangs without returning, whose task is aborted. Premieres
+    /// in Waiting report no progress until the stream starts, so they can't stall.
+    async fn reap_stale_downloads(&self) {
+        let waiting: HashSet<String> = self.queue.read().await
+            .iter()
+            .filter(|i| i.status == DownloadStatus::Waiting)
+            .map(|i| i.id.clone())
+            .collect();
+        // (item ID, whether its task is hung rather than gone)
+        let stale: Vec<(String, bool)> = {
+            let mut active = self.active_downloads.lock().await;
+            let stale: Vec<(String, bool)> = active.iter()
+                .filter_map(|(id, task)| {
+                    let quiet = task.since_heartbeat();
+                    if !task.is_running() {
+                        (quiet >= STALE_DOWNLOAD_GRACE).then(|| (id.clone(), false))
+                    } else {
+                        (quiet >= STALLED_DOWNLOAD_TIMEOUT && !waiting.contains(id)).then(|| (id.clone(), true))
+                    }
+                })
+                .collect();
+            for (id, hung) in &stale {
+                if let Some(task) = active.remove(id) {
+                    if *hung {
+                        task.abort();
+                    }
+                }
+            }
+            stale
+        };
+        if !stale.is_empty() {
+            self.rebalance_bandwidth().await;
+        }
+        
+        for (id, hung) in stale {
+            let unfinished = self.queue.read().await
+                .iter()
+                .any(|i| i.id == id && matches!(i.status, DownloadStatus::Downloading | DownloadStatus::Waiting));
+            let reason = if hung {
+                log_error!("[DownloadManager] Download {} made no progress for {}s, stopping it", id, STALLED_DOWNLOAD_TIMEOUT.as_secs());
+                format!(
+                    "Download stalled: no progress for {} minutes. Retry to start it again.",
+                    STALLED_DOWNLOAD_TIMEOUT.as_secs() / 60
+                )
+            } else {
+                log_error!("[DownloadManager] Download task for {} stopped unexpectedly, freeing its slot", id);
+                "Download stopped unexpectedly: its download task exited without finishing. Retry to start it again.".to_string()
+            };
+            if unfinished {
+                self.update_item_status(&id, DownloadStatus::Failed, Some(reason.clone())).await;
+                self.emit_error(&id, &reason).await;
+            }
+        }
+    }
+    
+    /// Hold the queue and pause downloads whose destination is low on space
+    ///
+    /// Checks destinations of running and queued items against `min_free_space`.
+    /// The hold lifts by itself once space is freed; paused items stay paused
+    /// until the user resumes them.
+    async fn check_free_space(&self) {
+        let floor = *self.min_free_space.read().await;
+        let destinations: Vec<(String, PathBuf)> = self.queue.read().await
+            .iter()
+            .filter(|i| matches!(i.status, DownloadStatus::Downloading | DownloadStatus::Queued))
+            .map(|i| (i.id.clone(), target_location(&i.save_path).0))
+            .collect();
+        
+        let mut low: Option<(PathBuf, u64)> = None;
+        if floor > 0 {
+            for (_, dir) in &destinations {
+                if let Ok(available) = StorageService::available_space(dir) {
+                    if available < floor {
+                        low = Some((dir.clone(), available));
+                        break;
+                    }
+                }
+            }
+        }
+        
+        let held = *self.low_space.read().await;
+        let Some((dir, available)) = low else {
+            if held {
+                log_info!("[DownloadManager] Disk space recovered, releasing queue hold");
+                *self.low_space.write().await = false;
+            }
+            return;
+        };
+        if held {
+            return;
+        }
+        
+        *self.low_space.write().await = true;
+        let running: Vec<String> = self.active_downloads.lock().await.keys().cloned().collect();
+        let mut paused = Vec::new();
+        for (id, item_dir) in &destinations {
+            if *item_dir == dir && running.contains(id) {
+                let _ = self.pause_download(id).await;
+                paused.push(id.clone());
+            }
+        }
+        
+        log_info!("[DownloadManager] Low disk space at {} ({} bytes free), paused {} downloads",
+                 dir.display(), available, paused.len());
+        self.events.emit("storage:low_space", serde_json::json!({
+            "path": dir.to_string_lossy(),
+            "available": available,
+            "required": floor,
+            "paused": paused,
+        }));
+    }
+    
+    /// Count a network failure towards the outage breaker
+    ///
+    /// Returns true when the item went back to the queue instead of failing:
+    /// either this failure tripped the breaker, or it was already open. Items
+    /// that failed inside the window before the trip are re-queued too.
+    async fn handle_network_failure(&self, item_id: &str, url: &str) -> bool {
+        let (requeue, tripped) = {
+            let mut breaker = self.outage.write().await;
+            let was_open = breaker.is_open();
+            match breaker.record_failure(item_id, url, std::time::Instant::now()) {
+                Some(ids) => (ids, true),
+                None if was_open => (vec![item_id.to_string()], false),
+                None => return false,
+            }
+        };
+        
+        {
+            let mut queue = self.queue.write().await;
+            for item in queue.iter_mut().filter(|i| requeue.contains(&i.id)) {
+                item.status = DownloadStatus::Queued;
+                item.error = None;
+                item.progress = 0.0;
+                item.speed = 0.0;
+            }
+        }
+        for id in &requeue {
+            self.emit_status_change(id, DownloadStatus::Queued).await;
+        }
+        self.emit_queue_update().await;
+        
+        if tripped {
+            log_info!("[DownloadManager] {} network failures in a row, holding queue until connectivity returns", requeue.len());
+            self.events.emit("queue:outage", serde_json::json!({
+                "requeued": requeue,
+                "probeUrl": url,
+            }));
+        }
+        true
+    }
+    
+    /// Probe connectivity while the outage breaker is open, releasing the queue once it's back
+    async fn probe_outage(&self) {
+        let Some(url) = self.outage.read().await.probe_due(std::time::Instant::now()).map(str::to_string) else {
+            return;
+        };
+        
+        let proxy = match self.platform_registry.detect_provider(&url) {
+            Some(provider) => self.proxy_settings.read().await.for_platform(provider.name()).map(str::to_string),
+            None => self.proxy_settings.read().await.url.clone(),
+        };
+        let reachable = probe_connectivity(&url, proxy.as_deref()).await;
+        
+        let mut breaker = self.outage.write().await;
+        if !reachable {
+            breaker.probe_failed(std::time::Instant::now());
+            return;
+        }
+        let downtime = breaker.open_for(std::time::Instant::now()).unwrap_or_default();
+        breaker.close();
+        drop(breaker);
+        
+        log_info!("[DownloadManager] Connectivity restored after {}s, resuming queue", downtime.as_secs());
+        self.events.emit("queue:outage_recovered", serde_json::json!({
+            "downtimeSeconds": downtime.as_secs(),
+        }));
+    }
+    
     /// Execute a download task
     async fn execute_download(&self, task: Arc<DownloadTask>) -> Result<()> {
         let item = &task.item;
         let item_id = item.id.clone();
         
-        println!("[execute_download] Starting download for: {} ({})", item.title, item_id);
-        println!("[execute_download] URL: {}", item.url);
-        println!("[execute_download] Save path: {}", item.save_path);
+        log_info!("[execute_download] Starting download for: {} ({})", item.title, item_id);
+        log_info!("[execute_download] URL: {}", item.url);
+        log_info!("[execute_download] Save path: {}", item.save_path);
         
         // Get platform provider
         let url = &item.url;
         
         let provider = self.platform_registry.detect_provider(url)
             .ok_or_else(|| {
-                println!("[execute_download] Failed to detect platform for URL: {}", url);
+                log_error!("[execute_download] Failed to detect platform for URL: {}", url);
                 DownloadError::InvalidUrl("Unsupported platform".to_string())
             })?;
         
-        println!("[execute_download] Detected platform: {}", provider.name());
+        log_info!("[execute_download] Detected platform: {}", provider.name());
+        
+        // yt-dlp appends to its own copy of the archive, merged back when it exits,
+        // so concurrent downloads never write the shared file at the same time
+        let archive_enabled = *self.download_archive.read().await;
+        let archive_copy = match self.download_archive_path().filter(|_| archive_enabled) {
+            Some(shared) => match archive::private_archive_copy(&shared) {
+                Ok(copy) => Some((shared, copy)),
+                Err(e) => {
+                    log_error!("[execute_download] Could not copy the download archive, continuing without it: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
         
         // Prepare download options
+        let audio = self.audio_settings.read().await.clone();
         let options = DownloadOptions {
-            quality: "best".to_string(),
-            format: "mp4".to_string(),
-            audio_only: false,
+            quality: item.quality.clone().unwrap_or_else(|| "best".to_string()),
+            format: if item.audio_only { audio.format.as_str() } else { "mp4" }.to_string(),
+            audio_only: item.audio_only,
+            postprocessor_args: item.postprocessor_args.clone(),
+            faststart: *self.mp4_faststart.read().await,
+            embed_chapters: match item.embed_chapters {
+                Some(enabled) => enabled,
+                None => *self.embed_chapters.read().await,
+            },
+            subtitle_languages: item.subtitle_languages.clone().unwrap_or_default(),
+            auto_subtitles: item.auto_subtitles.unwrap_or(false),
+            priority: *self.helper_priority.read().await,
+            platform_settings: self.platform_settings.read().await
+                .get(provider.name())
+                .cloned()
+                .unwrap_or_default(),
+            proxy: self.proxy_settings.read().await.for_platform(provider.name()).map(str::to_string),
+            headers: self.http_headers.read().await.for_platform(provider.name()),
+            download_archive: archive_copy.as_ref().map(|(_, copy)| copy.path().to_path_buf()),
+            record_live: item.record_live,
+            wait_for_video: item.premiere_at.is_some(),
+            section_start: item.section_start,
+            section_end: item.section_end,
+            audio_quality: audio.quality,
+            format_id: item.format_id.clone(),
+            concurrent_fragments: Some(*self.concurrent_fragments.read().await).filter(|n| *n > 1),
+            retries: Some(*self.ytdlp_retries.read().await),
+            // Set per attempt from the task's bandwidth share
+            rate_limit: None,
+            rate_limit_updates: None,
+            tags: AudioTags { album: item.album.clone(), track_number: item.track_number },
+            postprocess: Some(Arc::clone(&self.postprocess_pool)),
+            process: Some(task.process.clone()),
         };
         
-        let save_path = PathBuf::from(&item.save_path);
+        // A file-path target takes the extension of what is actually written,
+        // e.g. `song.m4a` rather than `song.mp4` for an audio-only download
+        let save_path = match corrected_target(&item.save_path, options.output_extension()) {
+            Some(corrected) => {
+                log_info!("[execute_download] Saving {} as {}", item.save_path, corrected);
+                self.set_item_save_path(&item_id, &corrected).await;
+                PathBuf::from(corrected)
+            }
+            None => PathBuf::from(&item.save_path),
+        };
         
         // Create progress callback with throttling (500ms)
         let manager = self.clone_arc();
         let item_id_clone = item_id.clone();
         let throttler = Arc::new(ProgressThrottler::with_default_interval());
-        let progress_callback = Box::new(move |progress: DownloadProgress| {
+        let downloaded_bytes = Arc::new(AtomicU64::new(0));
+        let downloaded = Arc::clone(&downloaded_bytes);
+        let heartbeat = Arc::clone(&task);
+        // Log every 10% so a trace shows where a stalled download stopped
+        let logged_tenths = AtomicU64::new(0);
+        let progress_callback = Arc::new(move |progress: DownloadProgress| {
+            heartbeat.beat();
+            downloaded.fetch_max(progress.downloaded_bytes.max(progress.total_bytes), Ordering::Relaxed);
+            let tenths = (progress.percentage / 10.0).clamp(0.0, 10.0) as u64;
+            if tenths > logged_tenths.fetch_max(tenths, Ordering::Relaxed) {
+                log_info!("[execute_download] {} at {:.0}% ({} bytes)", item_id_clone, progress.percentage, progress.downloaded_bytes);
+            }
             let manager = manager.clone();
             let item_id = item_id_clone.clone();
             let throttler = Arc::clone(&throttler);
             tokio::spawn(async move {
                 // Only update if throttle allows or if download is complete
-                if throttler.should_update().await || progress.percentage >= 100.0 {
+                let due = match manager.progress_interval(&item_id).await {
+                    Some(interval) => throttler.should_update_after(interval).await,
+                    None => throttler.should_update().await,
+                };
+                if due || progress.percentage >= 100.0 {
                     manager.update_progress(&item_id, progress).await;
                 }
             });
         });
         
-        println!("[execute_download] Starting download with provider: {}", provider.name());
+        log_info!("[execute_download] Starting download with provider: {}", provider.name());
         
         // Execute download with timeout (30 minutes for large videos)
-        let timeout_duration = Duration::from_secs(30 * 60); // 30 minutes
-        let download_future = provider.download_video(
-            url,
-            options,
-            &save_path,
-            progress_callback,
-        );
+        let timeout_duration = if item.record_live || item.premiere_at.is_some() {
+            LIVE_RECORDING_TIMEOUT
+        } else {
+            Duration::from_secs(30 * 60) // 30 minutes
+        };
         
-        println!("[execute_download] Download timeout set to {} seconds", timeout_duration.as_secs());
+        // Time spent waiting for a premiere doesn't count against the timeout
+        let premiere_wait = item.premiere_time()
+            .and_then(|at| (at - chrono::Utc::now()).to_std().ok())
+            .unwrap_or_default();
+        let timeout_duration = timeout_duration + premiere_wait;
+        if item.status == DownloadStatus::Waiting {
+            self.start_premiere_countdown(&item_id);
+        }
         
+        log_info!("[execute_download] Download timeout set to {} seconds", timeout_duration.as_secs());
+        
+        // Pausing or cancelling drops the download, which stops its helper processes.
+        // A new bandwidth share restarts providers that resume from their part file
+        // (yt-dlp takes its rate once, at startup). Providers that would start over
+        // follow the new rate in-process instead, and live recordings keep their first rate.
+        let mut cancel_rx = task.cancel_rx.clone();
+        let mut rate_rx = task.subscribe_rate_limit();
+        let restartable = provider.supports_resume() && !item.record_live && item.premiere_at.is_none();
+        let download_future = async {
+            loop {
+                let rate_limit = *rate_rx.borrow_and_update();
+                let callback = Arc::clone(&progress_callback);
+                let attempt = logging::in_span("provider", provider.download_video(
+                    url,
+                    DownloadOptions {
+                        rate_limit,
+                        rate_limit_updates: Some(rate_rx.clone()),
+                        ..options.clone()
+                    },
+                    &save_path,
+                    Box::new(move |progress| callback(progress)),
+                ));
+                tokio::select! {
+                    result = attempt => return result,
+                    _ = cancel_rx.wait_for(|cancelled| *cancelled) => return Err(DownloadError::Cancelled),
+                    Ok(()) = rate_rx.changed(), if restartable => {
+                        log_info!("[execute_download] Restarting {} at its new speed limit: {:?} B/s", item_id, *rate_rx.borrow());
+                    }
+                }
+            }
+        };
         let result = tokio::time::timeout(timeout_duration, download_future).await;
         
         // Update status based on result
         match result {
+            // pause_download/cancel_download already set the status
+            Ok(_) if task.is_cancelled() => {
+                log_info!("[execute_download] Download was stopped: {}", item_id);
+            }
             Ok(Ok(_)) => {
-                println!("[execute_download] Download completed successfully: {}", item_id);
-                if task.is_cancelled() {
-                    println!("[execute_download] Download was cancelled: {}", item_id);
-                    self.update_item_status(&item_id, DownloadStatus::Cancelled, None).await;
-                } else {
-                    self.update_item_status(&item_id, DownloadStatus::Completed, None).await;
-                    self.emit_download_complete(&item_id).await;
-                }
+                log_info!("[execute_download] Download completed successfully: {}", item_id);
+                self.outage.write().await.record_success();
+                self.record_platform_result(provider.name(), Some(downloaded_bytes.load(Ordering::Relaxed))).await;
+                self.update_item_status(&item_id, DownloadStatus::Completed, None).await;
+                self.record_completion(&item_id).await;
+                self.emit_download_complete(&item_id).await;
+                self.queue_transcription(&item_id).await;
             }
             Ok(Err(e)) => {
-                println!("[execute_download] Download failed for {}: {}", item_id, e);
-                self.update_item_status(&item_id, DownloadStatus::Failed, Some(e.to_string())).await;
-                self.emit_error(&item_id, &e.to_string()).await;
+                if is_network_failure(&e) && self.handle_network_failure(&item_id, url).await {
+                    log_info!("[execute_download] Network failure for {}, re-queued: {}", item_id, e);
+                } else {
+                    log_error!("[execute_download] Download failed for {}: {}", item_id, e);
+                    self.record_error(&e, provider.name()).await;
+                    self.record_platform_result(provider.name(), None).await;
+                    self.update_item_status(&item_id, DownloadStatus::Failed, Some(e.to_string())).await;
+                    self.emit_error(&item_id, &e.to_string()).await;
+                }
             }
             Err(_) => {
                 let timeout_msg = format!(
                     "Download timed out after {} minutes. The video may be too large or the connection too slow. Please try again or check your network connection.",
                     timeout_duration.as_secs() / 60
                 );
-                println!("[execute_download] Download timed out for {}: {}", item_id, timeout_msg);
+                log_info!("[execute_download] Download timed out for {}: {}", item_id, timeout_msg);
+                self.record_error(&DownloadError::Timeout, provider.name()).await;
+                self.record_platform_result(provider.name(), None).await;
                 self.update_item_status(&item_id, DownloadStatus::Failed, Some(timeout_msg.clone())).await;
                 self.emit_error(&item_id, &timeout_msg).await;
             }
         }
         
+        if let Some((shared, copy)) = archive_copy {
+            if let Err(e) = archive::merge_archive_copy(&copy, &shared) {
+                log_error!("[execute_download] Failed to update the download archive: {}", e);
+            }
+        }
+        
         // Remove from active downloads
         {
             let mut active = self.active_downloads.lock().await;
             active.remove(&item_id);
-            println!("[execute_download] Removed from active downloads: {}", item_id);
+            log_info!("[execute_download] Removed from active downloads: {}", item_id);
         }
+        self.rebalance_bandwidth().await;
         
         Ok(())
     }
     
+    /// Emit `download:waiting` countdowns until item `id` leaves the Waiting status
+    fn start_premiere_countdown(&self, id: &str) {
+        let manager = self.clone_arc();
+        let id = id.to_string();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(PREMIERE_COUNTDOWN_INTERVAL);
+            loop {
+                ticker.tick().await;
+                let starts_at = {
+                    let queue = manager.queue.read().await;
+                    match queue.iter().find(|i| i.id == id) {
+                        Some(item) if item.status == DownloadStatus::Waiting => item.premiere_time(),
+                        _ => break,
+                    }
+                };
+                let Some(starts_at) = starts_at else {
+                    break;
+                };
+                manager.events.emit("download:waiting", serde_json::json!({
+                    "id": id,
+                    "startsAt": starts_at.to_rfc3339(),
+                    "secondsRemaining": (starts_at - chrono::Utc::now()).num_seconds().max(0),
+                }));
+            }
+        });
+    }
+    
+    /// Send full-rate progress events for `ids`
+    pub async fn subscribe_progress(&self, ids: Vec<String>) {
+        self.progress_subscriptions.write().await.extend(ids);
+    }
+    
+    /// Back to coarse progress events for `ids`
+    pub async fn unsubscribe_progress(&self, ids: &[String]) {
+        let mut subscriptions = self.progress_subscriptions.write().await;
+        for id in ids {
+            subscriptions.remove(id);
+        }
+    }
+    
+    /// Minimum gap between progress events for item `id`
+    ///
+    /// Without any subscriptions every item gets the full rate, as before the UI subscribed.
+    async fn progress_interval(&self, id: &str) -> Option<Duration> {
+        let subscriptions = self.progress_subscriptions.read().await;
+        if subscriptions.is_empty() || subscriptions.contains(id) {
+            None
+        } else {
+            Some(COARSE_PROGRESS_INTERVAL)
+        }
+    }
+    
     /// Update download progress
-    async fn update_progress(&self, id: &str, progress: DownloadProgress) {
+    async fn update_progress(&self, id: &str, mut progress: DownloadProgress) {
         let mut queue = self.queue.write().await;
+        let mut started = false;
+        let mut group = None;
         if let Some(item) = queue.iter_mut().find(|i| i.id == id) {
+            group = item.variant_group.clone();
             item.progress = progress.percentage;
             item.speed = progress.speed;
             item.eta = progress.eta;
+            if let Some(liveness) = item.liveness.as_mut() {
+                liveness.last_progress_at = Some(chrono::Utc::now().to_rfc3339());
+            }
+            
+            // Data is flowing, so the premiere has begun
+            if item.status == DownloadStatus::Waiting && (progress.downloaded_bytes > 0 || progress.percentage > 0.0) {
+                item.status = DownloadStatus::Downloading;
+                started = true;
+            }
         }
         drop(queue);
         
+        if started {
+            self.emit_status_change(id, DownloadStatus::Downloading).await;
+            self.emit_queue_update().await;
+        }
+        
         // Emit progress event
-        let _ = self.app_handle.emit_all("download:progress", serde_json::json!({
+        progress.display = Some(ProgressDisplay::new(&progress, current_locale()));
+        self.events.emit("download:progress", serde_json::json!({
             "id": id,
             "progress": progress,
         }));
+        if let Some(group) = group {
+            self.emit_variant_group_progress(&group).await;
+        }
+    }
+    
+    /// Point an item at the file it is actually saved to
+    async fn set_item_save_path(&self, id: &str, save_path: &str) {
+        if let Some(item) = self.queue.write().await.iter_mut().find(|i| i.id == id) {
+            item.save_path = save_path.to_string();
+        }
+        self.emit_queue_update().await;
     }
     
     /// Update item status
     async fn update_item_status(&self, id: &str, status: DownloadStatus, error: Option<String>) {
         let mut queue = self.queue.write().await;
+        let mut group = None;
         if let Some(item) = queue.iter_mut().find(|i| i.id == id) {
+            group = item.variant_group.clone();
             item.status = status.clone();
             if let Some(err) = error {
                 item.error = Some(err);
@@ -313,8 +1120,27 @@ impl DownloadManager {
         }
         drop(queue);
         
+        if matches!(status, DownloadStatus::Completed | DownloadStatus::Failed) {
+            let today = DailyTotals::today();
+            self.daily_totals.write().await.record(&status, &today.date);
+        }
+        
         self.emit_status_change(id, status).await;
         self.emit_queue_update().await;
+        if let Some(group) = group {
+            self.emit_variant_group_progress(&group).await;
+        }
+    }
+    
+    /// Combined progress of the items queued together at several qualities
+    pub async fn variant_group_progress(&self, group: &str) -> Option<VariantGroupProgress> {
+        VariantGroupProgress::from_queue(group, &self.queue.read().await)
+    }
+    
+    async fn emit_variant_group_progress(&self, group: &str) {
+        if let Some(progress) = self.variant_group_progress(group).await {
+            emit_serialized(self.events.as_ref(), "download:group_progress", &progress);
+        }
     }
     
     /// Pause download
@@ -356,6 +1182,128 @@ impl DownloadManager {
         Ok(())
     }
     
+    /// Pause every queued, waiting or running download, returning how many were paused
+    ///
+    /// Like the other bulk operations, this sends a single queue update rather
+    /// than one status change per item.
+    pub async fn pause_all(&self) -> usize {
+        self.set_status_where(
+            |status| matches!(status, DownloadStatus::Queued | DownloadStatus::Waiting | DownloadStatus::Downloading),
+            DownloadStatus::Paused,
+        )
+        .await
+    }
+    
+    /// Queue every paused download again, returning how many were resumed
+    pub async fn resume_all(&self) -> usize {
+        let resumed = self.set_status_where(|status| *status == DownloadStatus::Paused, DownloadStatus::Queued).await;
+        if resumed > 0 {
+            self.start_processing().await;
+        }
+        resumed
+    }
+    
+    /// Cancel every download that hasn't finished, returning how many were cancelled
+    pub async fn cancel_all(&self) -> usize {
+        self.set_status_where(
+            |status| !matches!(status, DownloadStatus::Completed | DownloadStatus::Failed | DownloadStatus::Cancelled),
+            DownloadStatus::Cancelled,
+        )
+        .await
+    }
+    
+    /// Move items whose status matches `from` to `to`, stopping any that are running
+    async fn set_status_where(&self, from: impl Fn(&DownloadStatus) -> bool, to: DownloadStatus) -> usize {
+        let mut groups = HashSet::new();
+        let changed: Vec<String> = {
+            let mut queue = self.queue.write().await;
+            queue
+                .iter_mut()
+                .filter(|i| from(&i.status))
+                .map(|item| {
+                    item.status = to.clone();
+                    if to == DownloadStatus::Queued {
+                        item.progress = 0.0;
+                        item.speed = 0.0;
+                        item.eta = 0;
+                    }
+                    groups.extend(item.variant_group.clone());
+                    item.id.clone()
+                })
+                .collect()
+        };
+        if changed.is_empty() {
+            return 0;
+        }
+        
+        if to != DownloadStatus::Queued {
+            let active = self.active_downloads.lock().await;
+            for task in changed.iter().filter_map(|id| active.get(id)) {
+                task.cancel();
+            }
+        }
+        
+        log_info!("[DownloadManager] Set {} items to {:?}", changed.len(), to);
+        self.emit_queue_update().await;
+        for group in groups {
+            self.emit_variant_group_progress(&group).await;
+        }
+        changed.len()
+    }
+    
+    /// Queue a failed download again, clearing its error
+    ///
+    /// yt-dlp picks up any partial file the failed attempt left behind.
+    pub async fn retry_download(&self, id: &str) -> Result<()> {
+        let status = self.queue.read().await.iter().find(|i| i.id == id).map(|i| i.status.clone());
+        match status {
+            None => return Err(DownloadError::InvalidOption(format!("No queue item with ID {}", id))),
+            Some(DownloadStatus::Failed) => {}
+            Some(status) => {
+                return Err(DownloadError::InvalidOption(format!(
+                    "Only failed downloads can be retried; {} is {:?}",
+                    id, status
+                )))
+            }
+        }
+        self.requeue_failed(|item| item.id == id).await;
+        Ok(())
+    }
+    
+    /// Queue every failed download again, returning how many were retried
+    pub async fn retry_failed(&self) -> usize {
+        self.requeue_failed(|_| true).await
+    }
+    
+    async fn requeue_failed(&self, retry: impl Fn(&DownloadItem) -> bool) -> usize {
+        let retried: Vec<String> = {
+            let mut queue = self.queue.write().await;
+            queue
+                .iter_mut()
+                .filter(|i| i.status == DownloadStatus::Failed && retry(i))
+                .map(|item| {
+                    item.status = DownloadStatus::Queued;
+                    item.error = None;
+                    item.progress = 0.0;
+                    item.speed = 0.0;
+                    item.eta = 0;
+                    item.id.clone()
+                })
+                .collect()
+        };
+        if retried.is_empty() {
+            return 0;
+        }
+        
+        log_info!("[DownloadManager] Retrying {} failed downloads", retried.len());
+        for id in &retried {
+            self.emit_status_change(id, DownloadStatus::Queued).await;
+        }
+        self.emit_queue_update().await;
+        self.start_processing().await;
+        retried.len()
+    }
+    
     /// Cancel download
     pub async fn cancel_download(&self, id: &str) -> Result<()> {
         // Cancel the active download
@@ -372,6 +1320,85 @@ impl DownloadManager {
         Ok(())
     }
     
+    /// Remove an item from the queue, cancelling its download first if it is running
+    ///
+    /// With `delete_partial`, the item's `.part` files are deleted once its
+    /// download has stopped. Finished files are left alone.
+    pub async fn remove_from_queue(&self, id: &str, delete_partial: bool) -> Result<()> {
+        let task = self.active_downloads.lock().await.get(id).cloned();
+        if let Some(task) = &task {
+            task.cancel();
+        }
+        
+        let item = {
+            let mut queue = self.queue.write().await;
+            let position = queue
+                .iter()
+                .position(|i| i.id == id)
+                .ok_or_else(|| DownloadError::InvalidOption(format!("No queue item with ID {}", id)))?;
+            queue.remove(position)
+        };
+        self.unsubscribe_progress(&[id.to_string()]).await;
+        log_info!("[DownloadManager] Removed {} from the queue", id);
+        
+        // The task stops its helper processes as it winds down; wait so no part file is still open
+        if let Some(task) = task {
+            let deadline = tokio::time::Instant::now() + REMOVE_WAIT;
+            while task.is_running() && tokio::time::Instant::now() < deadline {
+                sleep(Duration::from_millis(50)).await;
+            }
+        }
+        
+        if delete_partial {
+            for (part, _) in find_partial_files(&item) {
+                match std::fs::remove_file(&part) {
+                    Ok(()) => log_info!("[DownloadManager] Deleted partial file {}", part.display()),
+                    Err(e) => log_error!("[DownloadManager] Could not delete partial file {}: {}", part.display(), e),
+                }
+            }
+        }
+        
+        self.emit_queue_update().await;
+        Ok(())
+    }
+    
+    /// Remove completed items, returning how many were removed
+    pub async fn clear_completed(&self) -> usize {
+        self.clear_where(|item| item.status == DownloadStatus::Completed).await
+    }
+    
+    /// Remove failed and cancelled items, returning how many were removed
+    pub async fn clear_failed(&self) -> usize {
+        self.clear_where(|item| matches!(item.status, DownloadStatus::Failed | DownloadStatus::Cancelled)).await
+    }
+    
+    /// Remove every item, cancelling running downloads; returns how many were removed
+    ///
+    /// Partial files are kept.
+    pub async fn clear_queue(&self) -> usize {
+        for task in self.active_downloads.lock().await.values() {
+            task.cancel();
+        }
+        self.clear_where(|_| true).await
+    }
+    
+    async fn clear_where(&self, remove: impl Fn(&DownloadItem) -> bool) -> usize {
+        let removed: Vec<String> = {
+            let mut queue = self.queue.write().await;
+            let removed = queue.iter().filter(|i| remove(i)).map(|i| i.id.clone()).collect();
+            queue.retain(|i| !remove(i));
+            removed
+        };
+        if removed.is_empty() {
+            return 0;
+        }
+        
+        self.unsubscribe_progress(&removed).await;
+        log_info!("[DownloadManager] Cleared {} items from the queue", removed.len());
+        self.emit_queue_update().await;
+        removed.len()
+    }
+    
     /// Reorder queue
     pub async fn reorder_queue(&self, from_index: usize, to_index: usize) -> Result<()> {
         let mut queue = self.queue.write().await;
@@ -386,14 +1413,106 @@ impl DownloadManager {
     
     /// Get queue status
     pub async fn get_queue_status(&self) -> Vec<DownloadItem> {
+        let mut queue = self.queue.read().await.clone();
+        
+        // PIDs live on the running tasks
+        let active = self.active_downloads.lock().await;
+        for item in queue.iter_mut() {
+            if let Some(liveness) = item.liveness.as_mut() {
+                liveness.pid = active.get(&item.id).and_then(|task| task.process.pid());
+            }
+        }
+        queue
+    }
+    
+    /// Compact summary of the queue for the tray menu and widgets
+    pub async fn dashboard_snapshot(&self) -> DashboardSnapshot {
         let queue = self.queue.read().await;
-        queue.clone()
+        let held = *self.binary_update.read().await
+            || *self.low_space.read().await
+            || self.outage.read().await.is_open();
+        
+        // Roll the totals over if nothing has finished since midnight
+        let mut totals = self.daily_totals.write().await;
+        let today = DailyTotals::today();
+        if totals.date != today.date {
+            *totals = today;
+        }
+        
+        DashboardSnapshot::from_queue(&queue, held, totals.clone())
+    }
+    
+    /// File holding the failure history, next to the saved queue
+    fn error_log_path(&self) -> Option<PathBuf> {
+        self.paths.app_data_dir().map(|dir| dir.join("error_log.json"))
+    }
+    
+    /// Add a failed download to the error history
+    async fn record_error(&self, error: &DownloadError, platform: &str) {
+        let Some(path) = self.error_log_path() else {
+            return;
+        };
+        let mut log = self.error_log.lock().await;
+        let log = log.get_or_insert_with(|| ErrorLog::load(&path));
+        log.record(ErrorRecord::new(error, platform));
+        if let Err(e) = log.save(&path) {
+            log_error!("[DownloadManager] Failed to save error log: {}", e);
+        }
+    }
+    
+    /// The `limit` most recent failures, oldest first
+    pub async fn recent_errors(&self, limit: usize) -> Vec<ErrorRecord> {
+        let mut log = self.error_log.lock().await;
+        let records = match self.error_log_path() {
+            Some(path) => &log.get_or_insert_with(|| ErrorLog::load(&path)).records,
+            None => return Vec::new(),
+        };
+        records[records.len().saturating_sub(limit)..].to_vec()
+    }
+    
+    /// Failure counts by error code, platform and hour of day over the last `days` days
+    pub async fn error_stats(&self, days: Option<u32>) -> ErrorStats {
+        let mut log = self.error_log.lock().await;
+        match self.error_log_path() {
+            Some(path) => log.get_or_insert_with(|| ErrorLog::load(&path)).stats(days),
+            None => ErrorLog::default().stats(days),
+        }
+    }
+    
+    /// File holding per-platform download totals
+    fn platform_stats_path(&self) -> Option<PathBuf> {
+        self.paths.app_data_dir().map(|dir| dir.join("platform_stats.json"))
+    }
+    
+    /// Count a finished download for `platform`: its size when completed, `None` when it failed
+    async fn record_platform_result(&self, platform: &str, completed_bytes: Option<u64>) {
+        let Some(path) = self.platform_stats_path() else {
+            return;
+        };
+        let mut stats = self.platform_stats.lock().await;
+        let stats = stats.get_or_insert_with(|| PlatformStatsLog::load(&path));
+        match completed_bytes {
+            Some(bytes) => stats.record_completed(platform, bytes),
+            None => stats.record_failed(platform),
+        }
+        if let Err(e) = stats.save(&path) {
+            log_error!("[DownloadManager] Failed to save platform stats: {}", e);
+        }
+    }
+    
+    /// Lifetime downloads, bytes and success rate for `platform`
+    pub async fn platform_stats(&self, platform: &str) -> PlatformStats {
+        let mut stats = self.platform_stats.lock().await;
+        match self.platform_stats_path() {
+            Some(path) => stats.get_or_insert_with(|| PlatformStatsLog::load(&path)).stats(platform),
+            None => PlatformStatsLog::default().stats(platform),
+        }
     }
     
     /// Save queue state to disk
     pub async fn save_queue_state(&self) -> Result<()> {
         let queue = self.queue.read().await;
-        let app_dir = self.app_handle.path_resolver()
+        let app_dir = self.paths
             .app_data_dir()
             .ok_or_else(|| DownloadError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
@@ -411,7 +1530,7 @@ impl DownloadManager {
     
     /// Restore queue state from disk
     pub async fn restore_queue_state(&self) -> Result<()> {
-        let app_dir = self.app_handle.path_resolver()
+        let app_dir = self.paths
             .app_data_dir()
             .ok_or_else(|| DownloadError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
@@ -427,13 +1546,27 @@ impl DownloadManager {
         let json = tokio::fs::read_to_string(queue_file).await?;
         let mut items: Vec<DownloadItem> = serde_json::from_str(&json)?;
         
-        // Reset downloading items to queued
-        for item in &mut items {
-            if item.status == DownloadStatus::Downloading {
-                item.status = DownloadStatus::Queued;
-                item.progress = 0.0;
-                item.speed = 0.0;
-                item.eta = 0;
+        // Premieres that were waiting start waiting again
+        for item in items.iter_mut().filter(|i| i.status == DownloadStatus::Waiting) {
+            item.status = DownloadStatus::Queued;
+        }
+        
+        // Items still marked downloading were interrupted (crash or forced quit):
+        // resume from their partial files where possible instead of restarting
+        for item in items.iter_mut().filter(|i| i.status == DownloadStatus::Downloading) {
+            match recover_item(item) {
+                RecoveryOutcome::Resumable { part_files, bytes } => log_info!(
+                    "[restore_queue_state] Resuming {} from {} partial files ({} bytes, {:.1}%)",
+                    item.id, part_files.len(), bytes, item.progress
+                ),
+                RecoveryOutcome::Completed(path) => log_info!(
+                    "[restore_queue_state] {} finished before shutdown: {}",
+                    item.id, path.display()
+                ),
+                RecoveryOutcome::Restart => log_info!(
+                    "[restore_queue_state] No partial data for {}, restarting",
+                    item.id
+                ),
             }
         }
         
@@ -452,27 +1585,138 @@ impl DownloadManager {
     /// Emit queue update event
     async fn emit_queue_update(&self) {
         let queue = self.get_queue_status().await;
-        let _ = self.app_handle.emit_all("queue:update", queue);
+        emit_serialized(self.events.as_ref(), "queue:update", &queue);
     }
     
     /// Emit status change event
     async fn emit_status_change(&self, id: &str, status: DownloadStatus) {
-        let _ = self.app_handle.emit_all("download:status_change", serde_json::json!({
+        self.events.emit("download:status_change", serde_json::json!({
             "id": id,
             "status": status,
         }));
     }
     
+    /// Record a completed item in history, first moving it to the inbox when that is on
+    async fn record_completion(&self, id: &str) {
+        let Some(item) = self.queue.read().await.iter().find(|i| i.id == id).cloned() else {
+            return;
+        };
+        let file = find_downloaded_file(&item);
+        let mut save_path = file.as_ref().map_or(item.save_path.clone(), |f| f.to_string_lossy().to_string());
+        let mut review = None;
+        let mut library_path = None;
+        
+        if let (Some(folder), Some(file)) = (self.inbox.read().await.folder(), &file) {
+            match inbox::move_with_sidecars(file, &folder) {
+                Ok(moved) => {
+                    log_info!("[Inbox] Moved {} to {}", file.display(), moved.display());
+                    save_path = moved.to_string_lossy().to_string();
+                    review = Some(ReviewStatus::Unreviewed);
+                    library_path = file.parent().map(|dir| dir.to_string_lossy().to_string());
+                    self.set_item_save_path(id, &save_path).await;
+                }
+                Err(e) => log_error!("[Inbox] Could not move {} to the inbox: {}", file.display(), e),
+            }
+        }
+        
+        let Some(history) = self.history.read().await.clone() else {
+            return;
+        };
+        let subtitles = subtitle_files(Path::new(&save_path))
+            .into_iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        let download = CompletedDownload {
+            id: item.id,
+            video_id: item.video_id,
+            title: item.title,
+            completed_at: chrono::Utc::now().to_rfc3339(),
+            file_size: std::fs::metadata(&save_path).map(|m| m.len()).unwrap_or(0),
+            save_path,
+            platform: item.platform,
+            tombstoned_at: None,
+            review,
+            library_path,
+            uploader: item.uploader,
+            audio_only: item.audio_only,
+            starred: false,
+            subtitles,
+        };
+        if let Err(e) = history.add_to_history(download) {
+            log_error!("[execute_download] Could not record {} in history: {}", id, e);
+        }
+    }
+    
+    /// Generate subtitles for a completed video in the background, if enabled and it has none
+    ///
+    /// Runs after the download's own slot is freed, so it holds a transcode
+    /// slot instead and never delays the queue.
+    async fn queue_transcription(&self, id: &str) {
+        let settings = self.transcription_settings.read().await.clone();
+        if !settings.enabled {
+            return;
+        }
+        let Some(item) = self.queue.read().await.iter().find(|i| i.id == id).cloned() else {
+            return;
+        };
+        if item.audio_only {
+            return;
+        }
+        let Some(service) = self.transcription.read().await.clone() else {
+            return;
+        };
+        if !service.is_available() {
+            log_info!("[transcription] whisper is not installed, skipping {}", item.id);
+            return;
+        }
+        let Some(media) = find_downloaded_file(&item) else {
+            log_info!("[transcription] No video file found for {}", item.id);
+            return;
+        };
+        
+        let manager = self.clone_arc();
+        let item_id = item.id.clone();
+        let request_id = logging::current_trace()
+            .map(|trace| trace.request_id)
+            .unwrap_or_else(logging::new_request_id);
+        tokio::spawn(logging::in_request(&request_id, "transcribe", async move {
+            if service.has_subtitles(&media).await {
+                log_info!("[transcription] {} already has subtitles", media.display());
+                return;
+            }
+            
+            let _slot = manager.postprocess_pool.acquire().await;
+            let priority = *manager.helper_priority.read().await;
+            log_info!("[transcription] Transcribing {}", media.display());
+            match service.transcribe(&media, &settings, priority, manager.postprocess_pool.ffmpeg_threads()).await {
+                Ok(path) => {
+                    log_info!("[transcription] Wrote {}", path.display());
+                    manager.events.emit("download:transcribed", serde_json::json!({
+                        "id": item_id,
+                        "path": path,
+                    }));
+                }
+                Err(e) => {
+                    log_error!("[transcription] Failed for {}: {}", item_id, e);
+                    manager.events.emit("download:transcription_failed", serde_json::json!({
+                        "id": item_id,
+                        "error": e.to_string(),
+                    }));
+                }
+            }
+        }));
+    }
+    
     /// Emit download complete event
     async fn emit_download_complete(&self, id: &str) {
-        let _ = self.app_handle.emit_all("download:complete", serde_json::json!({
+        self.events.emit("download:complete", serde_json::json!({
             "id": id,
         }));
     }
     
     /// Emit error event
     async fn emit_error(&self, id: &str, error: &str) {
-        let _ = self.app_handle.emit_all("download:error", serde_json::json!({
+        self.events.emit("download:error", serde_json::json!({
             "id": id,
             "error": error,
         }));
@@ -484,9 +1728,38 @@ impl DownloadManager {
             queue: Arc::clone(&self.queue),
             active_downloads: Arc::clone(&self.active_downloads),
             max_concurrent: Arc::clone(&self.max_concurrent),
-            app_handle: self.app_handle.clone(),
+            paths: Arc::clone(&self.paths),
+            events: Arc::clone(&self.events),
             platform_registry: Arc::clone(&self.platform_registry),
             processing: Arc::clone(&self.processing),
+            mp4_faststart: Arc::clone(&self.mp4_faststart),
+            concurrent_fragments: Arc::clone(&self.concurrent_fragments),
+            ytdlp_retries: Arc::clone(&self.ytdlp_retries),
+            embed_chapters: Arc::clone(&self.embed_chapters),
+            download_archive: Arc::clone(&self.download_archive),
+            helper_priority: Arc::clone(&self.helper_priority),
+            platform_settings: Arc::clone(&self.platform_settings),
+            postprocess_pool: Arc::clone(&self.postprocess_pool),
+            daily_totals: Arc::clone(&self.daily_totals),
+            min_free_space: Arc::clone(&self.min_free_space),
+            low_space: Arc::clone(&self.low_space),
+            mixing_policy: Arc::clone(&self.mixing_policy),
+            last_started_group: Arc::clone(&self.last_started_group),
+            quick_wins: Arc::clone(&self.quick_wins),
+            global_speed_limit: Arc::clone(&self.global_speed_limit),
+            proxy_settings: Arc::clone(&self.proxy_settings),
+            http_headers: Arc::clone(&self.http_headers),
+            audio_settings: Arc::clone(&self.audio_settings),
+            transcription: Arc::clone(&self.transcription),
+            transcription_settings: Arc::clone(&self.transcription_settings),
+            inbox: Arc::clone(&self.inbox),
+            history: Arc::clone(&self.history),
+            outage: Arc::clone(&self.outage),
+            error_log: Arc::clone(&self.error_log),
+            platform_stats: Arc::clone(&self.platform_stats),
+            request_tokens: Arc::clone(&self.request_tokens),
+            progress_subscriptions: Arc::clone(&self.progress_subscriptions),
+            binary_update: Arc::clone(&self.binary_update),
         })
     }
 }
@@ -1,7 +1,17 @@
+pub mod dashboard;
 pub mod manager;
+pub mod outage;
+pub mod recovery;
+pub mod request_tokens;
+pub mod scheduling;
 pub mod task;
 pub mod throttle;
+pub mod variants;
 
 pub use manager::DownloadManager;
-pub use task::{DownloadTask, DownloadItem, DownloadStatus};
+pub use task::{DownloadTask, DownloadItem, DownloadStatus, TaskLiveness};
 pub use throttle::ProgressThrottler;
+pub use recovery::RecoveryOutcome;
+pub use scheduling::{MixingPolicy, QuickWins};
+pub use dashboard::{DailyTotals, DashboardItem, DashboardSnapshot};
+pub use variants::{QualityVariant, VariantGroupProgress};
@@ -0,0 +1,206 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use crate::error::DownloadError;
+use crate::log_warn;
+
+/// Network failures within `OUTAGE_WINDOW` that trip the breaker
+pub const OUTAGE_FAILURE_THRESHOLD: usize = 4;
+
+/// How far back failures count towards the threshold
+pub const OUTAGE_WINDOW: Duration = Duration::from_secs(2 * 60);
+
+/// First wait between connectivity probes while the breaker is open
+pub const OUTAGE_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Longest wait between probes during a long outage
+const OUTAGE_MAX_PROBE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// yt-dlp and HTTP messages that point at the network or the platform rather than the video
+const NETWORK_FAILURE_MARKERS: &[&str] = &[
+    "Unable to download webpage",
+    "Unable to download API page",
+    "Connection reset",
+    "Connection refused",
+    "Connection aborted",
+    "Remote end closed connection",
+    "timed out",
+    "Temporary failure in name resolution",
+    "nodename nor servname",
+    "Network is unreachable",
+    "HTTP Error 500",
+    "HTTP Error 502",
+    "HTTP Error 503",
+    "HTTP Error 504",
+];
+
+/// Whether `error` looks like a connectivity or platform outage rather than a problem with the item
+pub fn is_network_failure(error: &DownloadError) -> bool {
+    match error {
+        DownloadError::Network(_) | DownloadError::Timeout => true,
+        DownloadError::DownloadFailed(msg) => NETWORK_FAILURE_MARKERS.iter().any(|m| msg.contains(m)),
+        _ => false,
+    }
+}
+
+/// Circuit breaker that holds the whole queue after a burst of network failures
+///
+/// While open, items that fail with network errors go back to the queue instead
+/// of being marked failed, and connectivity is probed with a growing interval
+/// until it comes back.
+#[derive(Debug, Default)]
+pub struct OutageBreaker {
+    /// Recent network failures: when, and which item
+    failures: VecDeque<(Instant, String)>,
+    opened_at: Option<Instant>,
+    next_probe: Option<Instant>,
+    probe_interval: Duration,
+    /// URL of the latest failure, probed to detect recovery
+    probe_url: Option<String>,
+}
+
+impl OutageBreaker {
+    pub fn is_open(&self) -> bool {
+        self.opened_at.is_some()
+    }
+    
+    /// How long the queue has been held
+    pub fn open_for(&self, now: Instant) -> Option<Duration> {
+        self.opened_at.map(|at| now.duration_since(at))
+    }
+    
+    /// Record a network failure for `item_id` at `url`
+    ///
+    /// Returns the items that failed inside the window when this failure trips
+    /// the breaker, so they can be put back in the queue.
+    pub fn record_failure(&mut self, item_id: &str, url: &str, now: Instant) -> Option<Vec<String>> {
+        self.probe_url = Some(url.to_string());
+        if self.is_open() {
+            return None;
+        }
+        
+        while self.failures.front().is_some_and(|(at, _)| now.duration_since(*at) > OUTAGE_WINDOW) {
+            self.failures.pop_front();
+        }
+        self.failures.push_back((now, item_id.to_string()));
+        if self.failures.len() < OUTAGE_FAILURE_THRESHOLD {
+            return None;
+        }
+        
+        self.opened_at = Some(now);
+        self.probe_interval = OUTAGE_PROBE_INTERVAL;
+        self.next_probe = Some(now + OUTAGE_PROBE_INTERVAL);
+        Some(self.failures.drain(..).map(|(_, id)| id).collect())
+    }
+    
+    /// A download finished, so the network is fine
+    pub fn record_success(&mut self) {
+        self.failures.clear();
+    }
+    
+    /// URL to probe if a probe is due at `now`
+    pub fn probe_due(&self, now: Instant) -> Option<&str> {
+        match self.next_probe {
+            Some(at) if self.is_open() && now >= at => self.probe_url.as_deref(),
+            _ => None,
+        }
+    }
+    
+    /// Record a failed probe, doubling the wait before the next one
+    pub fn probe_failed(&mut self, now: Instant) {
+        self.probe_interval = (self.probe_interval * 2).min(OUTAGE_MAX_PROBE_INTERVAL);
+        self.next_probe = Some(now + self.probe_interval);
+    }
+    
+    /// Connectivity is back: release the queue
+    pub fn close(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Whether the host serving `url` answers again; any non-5xx response counts
+pub async fn probe_connectivity(url: &str, proxy: Option<&str>) -> bool {
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(10));
+    if let Some(proxy) = proxy.and_then(|p| reqwest::Proxy::all(p).ok()) {
+        builder = builder.proxy(proxy);
+    }
+    let Ok(client) = builder.build() else {
+        return false;
+    };
+    
+    match client.head(url).send().await {
+        Ok(response) => !response.status().is_server_error(),
+        Err(e) => {
+            log_warn!("[OutageBreaker] Probe of {} failed: {}", url, e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const URL: &str = "https://www.youtube.com/watch?v=abc";
+    
+    #[test]
+    fn test_breaker_trips_on_failure_burst() {
+        let mut breaker = OutageBreaker::default();
+        let start = Instant::now();
+        
+        for i in 0..OUTAGE_FAILURE_THRESHOLD - 1 {
+            assert!(breaker.record_failure(&format!("item{}", i), URL, start).is_none());
+        }
+        let requeue = breaker.record_failure("last", URL, start + Duration::from_secs(1)).unwrap();
+        assert_eq!(requeue.len(), OUTAGE_FAILURE_THRESHOLD);
+        assert!(breaker.is_open());
+        
+        // Further failures while open are requeued by the caller, not counted
+        assert!(breaker.record_failure("more", URL, start + Duration::from_secs(2)).is_none());
+    }
+
+    #[test]
+    fn test_failures_outside_window_do_not_count() {
+        let mut breaker = OutageBreaker::default();
+        let start = Instant::now();
+        
+        for i in 0..OUTAGE_FAILURE_THRESHOLD - 1 {
+            breaker.record_failure(&format!("old{}", i), URL, start);
+        }
+        assert!(breaker.record_failure("new", URL, start + OUTAGE_WINDOW + Duration::from_secs(1)).is_none());
+        
+        breaker.record_success();
+        assert!(breaker.record_failure("after", URL, start + OUTAGE_WINDOW).is_none());
+    }
+
+    #[test]
+    fn test_probe_backs_off_until_closed() {
+        let mut breaker = OutageBreaker::default();
+        let start = Instant::now();
+        for i in 0..OUTAGE_FAILURE_THRESHOLD {
+            breaker.record_failure(&format!("item{}", i), URL, start);
+        }
+        
+        assert_eq!(breaker.probe_due(start), None);
+        let first = start + OUTAGE_PROBE_INTERVAL;
+        assert_eq!(breaker.probe_due(first), Some(URL));
+        
+        breaker.probe_failed(first);
+        assert_eq!(breaker.probe_due(first + OUTAGE_PROBE_INTERVAL), None);
+        assert_eq!(breaker.probe_due(first + OUTAGE_PROBE_INTERVAL * 2), Some(URL));
+        
+        breaker.close();
+        assert!(!breaker.is_open());
+        assert_eq!(breaker.probe_due(first + OUTAGE_MAX_PROBE_INTERVAL), None);
+    }
+
+    #[test]
+    fn test_network_failure_classification() {
+        assert!(is_network_failure(&DownloadError::Timeout));
+        assert!(is_network_failure(&DownloadError::DownloadFailed(
+            "ERROR: [youtube] abc: Unable to download webpage: <urlopen error [Errno 8] nodename nor servname provided>".to_string()
+        )));
+        assert!(is_network_failure(&DownloadError::DownloadFailed("ERROR: HTTP Error 503: Service Unavailable".to_string())));
+        assert!(!is_network_failure(&DownloadError::DownloadFailed("ERROR: Private video".to_string())));
+        assert!(!is_network_failure(&DownloadError::VideoUnavailable("gone".to_string())));
+    }
+}
@@ -0,0 +1,294 @@
+use std::path::{Path, PathBuf};
+use super::task::{DownloadItem, DownloadStatus};
+use crate::platform::AudioFormat;
+
+/// Suffix yt-dlp and the direct provider give to incomplete files
+const PART_SUFFIX: &str = ".part";
+
+/// Extensions of finished video files
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mkv", "webm", "mov", "m4v", "avi", "flv", "ts"];
+
+/// Whether `extension` is a finished video or audio file's
+fn is_media_extension(extension: &str) -> bool {
+    let extension = extension.to_lowercase();
+    VIDEO_EXTENSIONS.contains(&extension.as_str()) || AudioFormat::parse(&extension).is_some()
+}
+
+/// What startup recovery found for an item interrupted mid-download
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecoveryOutcome {
+    /// Partial data is on disk; the item is re-queued keeping its progress
+    Resumable { part_files: Vec<PathBuf>, bytes: u64 },
+    /// The finished file exists, so the download completed before the crash
+    Completed(PathBuf),
+    /// Nothing usable on disk; the item restarts from zero
+    Restart,
+}
+
+/// Directory downloads for `save_path` land in, and the file stem if it names a file
+///
+/// `save_path` may be a directory, a yt-dlp output template or a file path.
+pub(crate) fn target_location(save_path: &str) -> (PathBuf, Option<String>) {
+    let path = Path::new(save_path);
+    if path.is_dir() {
+        return (path.to_path_buf(), None);
+    }
+    
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let stem = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .filter(|n| !n.contains("%("))
+        .map(|n| match n.rsplit_once('.') {
+            Some((stem, _)) if !stem.is_empty() => stem.to_string(),
+            _ => n,
+        });
+    (dir, stem)
+}
+
+/// `save_path` with the extension `extension`, when it names a media file with a different one
+///
+/// Directories, output templates and names whose "extension" isn't a media
+/// type (`Mr. Smith`) are left alone.
+pub(crate) fn corrected_target(save_path: &str, extension: &str) -> Option<String> {
+    let path = Path::new(save_path);
+    if path.is_dir() || save_path.contains("%(") {
+        return None;
+    }
+    
+    let current = path.extension()?.to_string_lossy().to_lowercase();
+    if !is_media_extension(&current) || current == extension.to_lowercase() {
+        return None;
+    }
+    Some(path.with_extension(extension).to_string_lossy().to_string())
+}
+
+/// Whether a partial file name belongs to `item`
+fn belongs_to(name: &str, item: &DownloadItem, stem: Option<&str>) -> bool {
+    match stem {
+        Some(stem) => name.strip_prefix(stem).is_some_and(|rest| rest.starts_with('.')),
+        None => {
+            (!item.video_id.is_empty() && name.contains(&item.video_id))
+                || (!item.title.is_empty() && name.starts_with(&crate::storage::StorageService::sanitize_filename(&item.title)))
+        }
+    }
+}
+
+/// Find non-empty partial files left behind by `item`, removing empty ones
+pub fn find_partial_files(item: &DownloadItem) -> Vec<(PathBuf, u64)> {
+    let (dir, stem) = target_location(&item.save_path);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    
+    let mut parts = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.ends_with(PART_SUFFIX) || !belongs_to(&name, item, stem.as_deref()) {
+            continue;
+        }
+        
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if size == 0 {
+            // Nothing to resume from, and an empty part can confuse the resume request
+            let _ = std::fs::remove_file(entry.path());
+            continue;
+        }
+        parts.push((entry.path(), size));
+    }
+    
+    parts.sort();
+    parts
+}
+
+/// The finished media file for a completed `item`, if one can be found
+///
+/// A file-path target is used as is; otherwise the newest video or audio file
+/// in the target directory that belongs to the item.
+pub fn find_downloaded_file(item: &DownloadItem) -> Option<PathBuf> {
+    let target = Path::new(&item.save_path);
+    if target.is_file() {
+        return Some(target.to_path_buf());
+    }
+    
+    let (dir, stem) = target_location(&item.save_path);
+    std::fs::read_dir(&dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_media = Path::new(&name)
+                .extension()
+                .is_some_and(|ext| is_media_extension(&ext.to_string_lossy()));
+            is_media && belongs_to(&name, item, stem.as_deref())
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max()
+        .map(|(_, path)| path)
+}
+
+/// Inspect the disk for an item that was `Downloading` when the app stopped
+pub fn inspect_interrupted(item: &DownloadItem) -> RecoveryOutcome {
+    let parts = find_partial_files(item);
+    if parts.is_empty() {
+        // A file-path target that exists in full means only the status update was lost
+        let (_, stem) = target_location(&item.save_path);
+        let target = Path::new(&item.save_path);
+        if stem.is_some() && target.metadata().map(|m| m.is_file() && m.len() > 0).unwrap_or(false) {
+            return RecoveryOutcome::Completed(target.to_path_buf());
+        }
+        return RecoveryOutcome::Restart;
+    }
+    
+    RecoveryOutcome::Resumable {
+        bytes: parts.iter().map(|(_, size)| size).sum(),
+        part_files: parts.into_iter().map(|(path, _)| path).collect(),
+    }
+}
+
+/// Apply startup recovery to an interrupted item, returning what was found
+///
+/// Resumable items go back to `Queued` with their last reported progress, so
+/// yt-dlp (or the direct provider) continues from the `.part` file.
+pub fn recover_item(item: &mut DownloadItem) -> RecoveryOutcome {
+    let outcome = inspect_interrupted(item);
+    match &outcome {
+        RecoveryOutcome::Resumable { .. } => {
+            item.status = DownloadStatus::Queued;
+        }
+        RecoveryOutcome::Completed(_) => {
+            item.status = DownloadStatus::Completed;
+            item.progress = 100.0;
+        }
+        RecoveryOutcome::Restart => {
+            item.status = DownloadStatus::Queued;
+            item.progress = 0.0;
+        }
+    }
+    item.speed = 0.0;
+    item.eta = 0;
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn interrupted_item(save_path: &Path) -> DownloadItem {
+        DownloadItem {
+            id: "1".to_string(),
+            video_id: "dQw4w9WgXcQ".to_string(),
+            title: "Never Gonna Give You Up".to_string(),
+            thumbnail: String::new(),
+            status: DownloadStatus::Downloading,
+            progress: 42.0,
+            speed: 1024.0,
+            eta: 30,
+            save_path: save_path.to_string_lossy().to_string(),
+            error: None,
+            url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
+            platform: "YouTube".to_string(),
+            postprocessor_args: None,
+            subtitle_languages: None,
+            auto_subtitles: None,
+            embed_chapters: None,
+            scheduled_at: None,
+            playlist_id: None,
+            record_live: false,
+            premiere_at: None,
+            section_start: None,
+            section_end: None,
+            audio_only: false,
+            format_id: None,
+            album: None,
+            track_number: None,
+            uploader: None,
+            quality: None,
+            variant_group: None,
+            estimated_size: None,
+            request_id: None,
+            liveness: None,
+        }
+    }
+
+    #[test]
+    fn test_part_files_make_item_resumable_with_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("song.f137.mp4.part"), vec![0u8; 2048]).unwrap();
+        std::fs::write(dir.path().join("song.f140.m4a.part"), Vec::<u8>::new()).unwrap();
+        std::fs::write(dir.path().join("other.mp4.part"), vec![0u8; 10]).unwrap();
+        
+        let mut item = interrupted_item(&dir.path().join("song.mp4"));
+        let outcome = recover_item(&mut item);
+        
+        assert_eq!(
+            outcome,
+            RecoveryOutcome::Resumable {
+                part_files: vec![dir.path().join("song.f137.mp4.part")],
+                bytes: 2048,
+            }
+        );
+        assert_eq!(item.status, DownloadStatus::Queued);
+        assert_eq!(item.progress, 42.0);
+        assert_eq!(item.speed, 0.0);
+        // Empty partial files are discarded
+        assert!(!dir.path().join("song.f140.m4a.part").exists());
+    }
+
+    #[test]
+    fn test_template_targets_match_by_video_id_or_title() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Never Gonna Give You Up [dQw4w9WgXcQ].webm.part"), vec![0u8; 64]).unwrap();
+        
+        let item = interrupted_item(&dir.path().join("%(title)s [%(id)s].%(ext)s"));
+        assert!(matches!(inspect_interrupted(&item), RecoveryOutcome::Resumable { bytes: 64, .. }));
+    }
+
+    #[test]
+    fn test_finished_file_is_marked_completed_and_missing_data_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("done.mp4");
+        std::fs::write(&target, vec![0u8; 16]).unwrap();
+        
+        let mut item = interrupted_item(&target);
+        assert_eq!(recover_item(&mut item), RecoveryOutcome::Completed(target));
+        assert_eq!(item.status, DownloadStatus::Completed);
+        
+        let mut item = interrupted_item(&dir.path().join("missing.mp4"));
+        assert_eq!(recover_item(&mut item), RecoveryOutcome::Restart);
+        assert_eq!(item.progress, 0.0);
+    }
+
+    #[test]
+    fn test_downloaded_file_is_found_for_template_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Never Gonna Give You Up [dQw4w9WgXcQ].mkv"), vec![0u8; 16]).unwrap();
+        std::fs::write(dir.path().join("Never Gonna Give You Up [dQw4w9WgXcQ].jpg"), vec![0u8; 16]).unwrap();
+        std::fs::write(dir.path().join("Something Else [aaaaaaaaaaa].mp4"), vec![0u8; 16]).unwrap();
+        
+        let item = interrupted_item(&dir.path().join("%(title)s [%(id)s].%(ext)s"));
+        assert_eq!(
+            find_downloaded_file(&item),
+            Some(dir.path().join("Never Gonna Give You Up [dQw4w9WgXcQ].mkv"))
+        );
+        
+        let item = interrupted_item(&dir.path().join("missing.mp4"));
+        assert_eq!(find_downloaded_file(&item), None);
+    }
+
+    #[test]
+    fn test_file_targets_get_the_output_extension() {
+        assert_eq!(corrected_target("/music/song.mp4", "m4a").as_deref(), Some("/music/song.m4a"));
+        assert_eq!(corrected_target("/music/song.MP3", "mp3"), None);
+        // Direct HLS downloads are saved as MPEG-TS
+        assert_eq!(corrected_target("/videos/stream.ts", "mp4").as_deref(), Some("/videos/stream.mp4"));
+        assert_eq!(corrected_target("/music/%(title)s.%(ext)s", "mp3"), None);
+        assert_eq!(corrected_target("/music/Mr. Smith", "mp3"), None);
+        
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("clips.mp4");
+        std::fs::create_dir(&folder).unwrap();
+        assert_eq!(corrected_target(&folder.to_string_lossy(), "mkv"), None);
+    }
+}
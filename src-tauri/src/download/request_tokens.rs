@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long a request token is remembered; repeats after this are treated as new requests
+pub const REQUEST_TOKEN_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Results of recent add requests by idempotency token
+///
+/// The frontend sends one token per click of "Download"; a repeated command
+/// with the same token (double click, retried IPC call) gets the item IDs of
+/// the first one instead of queueing the items again.
+#[derive(Debug, Default)]
+pub struct RequestTokens {
+    entries: HashMap<String, (Instant, Vec<String>)>,
+}
+
+impl RequestTokens {
+    /// IDs assigned by an earlier request with `token`, if it's still remembered
+    pub fn get(&self, token: &str, now: Instant) -> Option<Vec<String>> {
+        self.entries
+            .get(token)
+            .filter(|(at, _)| now.duration_since(*at) < REQUEST_TOKEN_TTL)
+            .map(|(_, ids)| ids.clone())
+    }
+    
+    /// Remember the IDs assigned for `token`, forgetting expired tokens
+    pub fn insert(&mut self, token: &str, ids: Vec<String>, now: Instant) {
+        self.entries.retain(|_, (at, _)| now.duration_since(*at) < REQUEST_TOKEN_TTL);
+        self.entries.insert(token.to_string(), (now, ids));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_tokens_remembered_until_expiry() {
+        let mut tokens = RequestTokens::default();
+        let start = Instant::now();
+        tokens.insert("click-1", vec!["a".to_string(), "b".to_string()], start);
+        
+        assert_eq!(tokens.get("click-1", start + Duration::from_secs(1)), Some(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(tokens.get("click-2", start), None);
+        assert_eq!(tokens.get("click-1", start + REQUEST_TOKEN_TTL), None);
+        
+        // Expired tokens are dropped on the next insert
+        tokens.insert("click-2", Vec::new(), start + REQUEST_TOKEN_TTL);
+        assert_eq!(tokens.entries.len(), 1);
+    }
+}
@@ -0,0 +1,290 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use super::task::{DownloadItem, DownloadStatus};
+use crate::error::{DownloadError, Result};
+
+/// How queued items share download slots: by playlist, by platform or in strict order
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MixingPolicy {
+    /// Strict queue order
+    #[default]
+    Fifo,
+    /// Take turns between each playlist and the standalone items
+    RoundRobin,
+    /// Keep going with a playlist that has started before picking anything else
+    FinishPlaylistFirst,
+    /// Take turns between platforms, favouring those with the fewest running downloads
+    ///
+    /// Downloads from a slow site hold their slots longer, so freed slots go
+    /// to the other sites instead of to more of the slow one.
+    FairShare,
+}
+
+impl MixingPolicy {
+    /// Group `item` takes turns in under this policy: its platform for fair share, else its playlist
+    pub fn group_of(self, item: &DownloadItem) -> &str {
+        match self {
+            MixingPolicy::FairShare => &item.platform,
+            _ => group_key(item),
+        }
+    }
+}
+
+/// Most extra slots the quick-wins lane can add
+pub const MAX_QUICK_WIN_SLOTS: usize = 4;
+
+/// Extra download slots for very small items, so they don't wait behind large ones
+///
+/// When the concurrency limit is reached, due items whose expected size is at
+/// most `max_size_mb` may still start in up to `extra_slots` additional
+/// slots. Items of unknown size never count as small.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuickWins {
+    pub enabled: bool,
+    pub max_size_mb: u64,
+    pub extra_slots: usize,
+}
+
+impl Default for QuickWins {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_size_mb: 50,
+            extra_slots: 1,
+        }
+    }
+}
+
+impl QuickWins {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_size_mb == 0 {
+            return Err(DownloadError::InvalidOption("Quick-win size limit must be at least 1 MB".to_string()));
+        }
+        if !(1..=MAX_QUICK_WIN_SLOTS).contains(&self.extra_slots) {
+            return Err(DownloadError::InvalidOption(format!(
+                "Quick-win slots must be between 1 and {}, got {}",
+                MAX_QUICK_WIN_SLOTS, self.extra_slots
+            )));
+        }
+        Ok(())
+    }
+    
+    /// Slots beyond the concurrency limit, zero when the mode is off
+    pub fn extra_slots(&self) -> usize {
+        if self.enabled {
+            self.extra_slots
+        } else {
+            0
+        }
+    }
+    
+    pub fn is_quick(&self, item: &DownloadItem) -> bool {
+        item.estimated_size.is_some_and(|size| size <= self.max_size_mb * 1024 * 1024)
+    }
+    
+    /// Index of the first due small item, to start in an extra slot
+    pub fn select(&self, queue: &[DownloadItem], now: DateTime<Utc>) -> Option<usize> {
+        if !self.enabled {
+            return None;
+        }
+        queue
+            .iter()
+            .position(|i| i.status == DownloadStatus::Queued && i.is_due(now) && self.is_quick(i))
+    }
+}
+
+/// Group an item is scheduled in: its playlist, or the shared standalone group
+pub fn group_key(item: &DownloadItem) -> &str {
+    item.playlist_id.as_deref().unwrap_or("")
+}
+
+/// Groups of the `ready` items in turn order: first-appearance order, starting after `last_group`
+fn turn_order<'a>(queue: &'a [DownloadItem], ready: &[usize], key: impl Fn(&'a DownloadItem) -> &'a str, last_group: Option<&str>) -> Vec<&'a str> {
+    let mut groups: Vec<&str> = Vec::new();
+    for &idx in ready {
+        let group = key(&queue[idx]);
+        if !groups.contains(&group) {
+            groups.push(group);
+        }
+    }
+    // When the last group has nothing left to start, begin again from the front
+    if let Some(pos) = last_group.and_then(|last| groups.iter().position(|g| *g == last)) {
+        let len = groups.len();
+        groups.rotate_left((pos + 1) % len);
+    }
+    groups
+}
+
+/// Index of the next item to start under `policy`
+///
+/// `last_group` is the group (`MixingPolicy::group_of`) of the most recently
+/// started item, used by round-robin and fair share to move on to the next group.
+pub fn select_next(
+    queue: &[DownloadItem],
+    policy: MixingPolicy,
+    last_group: Option<&str>,
+    now: DateTime<Utc>,
+) -> Option<usize> {
+    let ready: Vec<usize> = queue
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| i.status == DownloadStatus::Queued && i.is_due(now))
+        .map(|(idx, _)| idx)
+        .collect();
+    let first = *ready.first()?;
+    
+    match policy {
+        MixingPolicy::Fifo => Some(first),
+        MixingPolicy::RoundRobin => {
+            let next_group = turn_order(queue, &ready, group_key, last_group)[0];
+            ready.into_iter().find(|&idx| group_key(&queue[idx]) == next_group)
+        }
+        MixingPolicy::FairShare => {
+            let running = |platform: &str| {
+                queue
+                    .iter()
+                    .filter(|i| i.platform == platform && matches!(i.status, DownloadStatus::Downloading | DownloadStatus::Waiting))
+                    .count()
+            };
+            // min_by_key keeps the first of equals, so ties go to whoever's turn it is
+            let next_platform = turn_order(queue, &ready, |i| i.platform.as_str(), last_group)
+                .into_iter()
+                .min_by_key(|platform| running(platform))?;
+            ready.into_iter().find(|&idx| queue[idx].platform == next_platform)
+        }
+        MixingPolicy::FinishPlaylistFirst => {
+            let started: Vec<&str> = queue
+                .iter()
+                .filter(|i| i.status == DownloadStatus::Downloading && i.playlist_id.is_some())
+                .map(group_key)
+                .chain(last_group.filter(|g| !g.is_empty()))
+                .collect();
+            ready
+                .iter()
+                .copied()
+                .find(|&idx| queue[idx].playlist_id.is_some() && started.contains(&group_key(&queue[idx])))
+                .or(Some(first))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn item(id: &str, playlist: Option<&str>, status: DownloadStatus) -> DownloadItem {
+        DownloadItem {
+            id: id.to_string(),
+            video_id: id.to_string(),
+            title: id.to_string(),
+            thumbnail: String::new(),
+            status,
+            progress: 0.0,
+            speed: 0.0,
+            eta: 0,
+            save_path: "/tmp".to_string(),
+            error: None,
+            url: format!("https://www.youtube.com/watch?v={}", id),
+            platform: "YouTube".to_string(),
+            postprocessor_args: None,
+            subtitle_languages: None,
+            auto_subtitles: None,
+            embed_chapters: None,
+            scheduled_at: None,
+            playlist_id: playlist.map(|p| p.to_string()),
+            record_live: false,
+            premiere_at: None,
+            section_start: None,
+            section_end: None,
+            audio_only: false,
+            format_id: None,
+            album: None,
+            track_number: None,
+            uploader: None,
+            quality: None,
+            variant_group: None,
+            estimated_size: None,
+            request_id: None,
+            liveness: None,
+        }
+    }
+    
+    /// Start items one at a time, returning the order they were picked in
+    fn run(mut queue: Vec<DownloadItem>, policy: MixingPolicy) -> Vec<String> {
+        let mut order = Vec::new();
+        let mut last: Option<String> = None;
+        while let Some(idx) = select_next(&queue, policy, last.as_deref(), Utc::now()) {
+            queue[idx].status = DownloadStatus::Completed;
+            last = Some(policy.group_of(&queue[idx]).to_string());
+            order.push(queue[idx].id.clone());
+        }
+        order
+    }
+    
+    fn mixed_queue() -> Vec<DownloadItem> {
+        vec![
+            item("p1", Some("PL"), DownloadStatus::Queued),
+            item("p2", Some("PL"), DownloadStatus::Queued),
+            item("p3", Some("PL"), DownloadStatus::Queued),
+            item("s1", None, DownloadStatus::Queued),
+            item("s2", None, DownloadStatus::Queued),
+        ]
+    }
+
+    #[test]
+    fn test_fifo_keeps_queue_order() {
+        assert_eq!(run(mixed_queue(), MixingPolicy::Fifo), vec!["p1", "p2", "p3", "s1", "s2"]);
+    }
+
+    #[test]
+    fn test_round_robin_alternates_groups() {
+        assert_eq!(run(mixed_queue(), MixingPolicy::RoundRobin), vec!["p1", "s1", "p2", "s2", "p3"]);
+    }
+
+    #[test]
+    fn test_fair_share_alternates_platforms_and_favours_idle_ones() {
+        let mut queue = mixed_queue();
+        for item in &mut queue[..3] {
+            item.platform = "Vimeo".to_string();
+        }
+        assert_eq!(run(queue.clone(), MixingPolicy::FairShare), vec!["p1", "s1", "p2", "s2", "p3"]);
+        
+        // Vimeo already has a download running, so YouTube goes next even though it went last
+        queue[0].status = DownloadStatus::Downloading;
+        assert_eq!(select_next(&queue, MixingPolicy::FairShare, Some("YouTube"), Utc::now()), Some(3));
+        assert_eq!(select_next(&queue, MixingPolicy::Fifo, Some("YouTube"), Utc::now()), Some(1));
+    }
+
+    #[test]
+    fn test_quick_wins_pick_only_small_items() {
+        let mut queue = mixed_queue();
+        queue[1].estimated_size = Some(400 * 1024 * 1024);
+        queue[3].estimated_size = Some(12 * 1024 * 1024);
+        
+        let quick_wins = QuickWins { enabled: true, ..Default::default() };
+        assert_eq!(quick_wins.select(&queue, Utc::now()), Some(3));
+        assert_eq!(QuickWins::default().select(&queue, Utc::now()), None);
+        assert_eq!(QuickWins::default().extra_slots(), 0);
+        
+        queue[3].status = DownloadStatus::Downloading;
+        assert_eq!(quick_wins.select(&queue, Utc::now()), None);
+        assert!(QuickWins { extra_slots: 0, ..quick_wins }.validate().is_err());
+    }
+
+    #[test]
+    fn test_finish_playlist_first_continues_started_playlist() {
+        let mut queue = vec![
+            item("s1", None, DownloadStatus::Queued),
+            item("p1", Some("PL"), DownloadStatus::Downloading),
+            item("p2", Some("PL"), DownloadStatus::Queued),
+            item("q1", Some("OTHER"), DownloadStatus::Queued),
+        ];
+        assert_eq!(select_next(&queue, MixingPolicy::FinishPlaylistFirst, None, Utc::now()), Some(2));
+        
+        // With nothing running and no previous pick, queue order decides
+        queue[1].status = DownloadStatus::Completed;
+        assert_eq!(select_next(&queue, MixingPolicy::FinishPlaylistFirst, None, Utc::now()), Some(0));
+        assert_eq!(select_next(&queue, MixingPolicy::FinishPlaylistFirst, Some("OTHER"), Utc::now()), Some(3));
+    }
+}
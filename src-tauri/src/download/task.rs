@@ -1,5 +1,10 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
 use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+use crate::platform::ProcessSlot;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -16,12 +21,107 @@ pub struct DownloadItem {
     pub error: Option<String>,
     pub url: String,
     pub platform: String,
+    /// Advanced ffmpeg output arguments for this item (e.g. "-movflags +faststart")
+    #[serde(default)]
+    pub postprocessor_args: Option<String>,
+    /// Subtitle languages to fetch; `None` uses the platform's subtitle setting
+    #[serde(default)]
+    pub subtitle_languages: Option<Vec<String>>,
+    /// Fall back to auto-generated captions; `None` uses the platform setting
+    #[serde(default)]
+    pub auto_subtitles: Option<bool>,
+    /// Embed chapter markers; `None` uses the `embed_chapters` setting
+    #[serde(default)]
+    pub embed_chapters: Option<bool>,
+    /// RFC 3339 time before which the item stays queued ("start in 2 hours")
+    #[serde(default)]
+    pub scheduled_at: Option<String>,
+    /// Playlist this item was added from, used by the queue mixing policy
+    #[serde(default)]
+    pub playlist_id: Option<String>,
+    /// Live stream to record from its beginning (`--live-from-start`)
+    #[serde(default)]
+    pub record_live: bool,
+    /// RFC 3339 start of a premiere or upcoming stream; the download waits for it (`--wait-for-video`)
+    #[serde(default)]
+    pub premiere_at: Option<String>,
+    /// Download only from this many seconds into the video
+    #[serde(default)]
+    pub section_start: Option<f64>,
+    /// Download only up to this many seconds into the video
+    #[serde(default)]
+    pub section_end: Option<f64>,
+    /// Download only the audio, in the format and quality of the `audio` setting
+    #[serde(default)]
+    pub audio_only: bool,
+    /// yt-dlp format to download instead of picking by quality, e.g. `137+140`
+    #[serde(default)]
+    pub format_id: Option<String>,
+    /// Album tag for audio downloads, usually the playlist the item came from
+    #[serde(default)]
+    pub album: Option<String>,
+    /// Track number tag for audio downloads, the item's position in its playlist
+    #[serde(default)]
+    pub track_number: Option<u32>,
+    /// Channel or account that published the item, kept in history for collections
+    #[serde(default)]
+    pub uploader: Option<String>,
+    /// Video quality to pick formats by (`1080p`, `4k`, `best`); `None` takes the best
+    #[serde(default)]
+    pub quality: Option<String>,
+    /// Shared by the items of one job queued at several qualities, for combined progress
+    #[serde(default)]
+    pub variant_group: Option<String>,
+    /// Expected size in bytes, when the chosen format reports one
+    #[serde(default)]
+    pub estimated_size: Option<u64>,
+    /// ID of the request that queued the item, carried by its log records
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Progress and process details, once the item has started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liveness: Option<TaskLiveness>,
+}
+
+/// Whether a started download is making progress, so "slow" can be told from "dead"
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskLiveness {
+    /// RFC 3339 time of the last progress report
+    pub last_progress_at: Option<String>,
+    /// PID of the running yt-dlp or ffmpeg process; `None` while no process is running
+    pub pid: Option<u32>,
+    /// Times the item was started again after its first start (resume, retry, re-queue)
+    pub restart_count: u32,
+}
+
+impl DownloadItem {
+    /// Scheduled start time, if set and valid
+    pub fn scheduled_time(&self) -> Option<DateTime<Utc>> {
+        parse_time(self.scheduled_at.as_deref()?)
+    }
+    
+    /// Premiere start time, if set and valid
+    pub fn premiere_time(&self) -> Option<DateTime<Utc>> {
+        parse_time(self.premiere_at.as_deref()?)
+    }
+    
+    /// Whether the item may start at `now`; unscheduled items always may
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.scheduled_time().is_none_or(|at| at <= now)
+    }
+}
+
+fn parse_time(at: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(at).ok().map(|t| t.with_timezone(&Utc))
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum DownloadStatus {
     Queued,
+    /// Holding a download slot until a premiere or scheduled stream starts
+    Waiting,
     Downloading,
     Paused,
     Completed,
@@ -33,6 +133,14 @@ pub struct DownloadTask {
     pub item: DownloadItem,
     pub cancel_tx: watch::Sender<bool>,
     pub cancel_rx: watch::Receiver<bool>,
+    /// The spawned `execute_download` task, once started
+    handle: Mutex<Option<JoinHandle<()>>>,
+    /// Last sign of life: start or progress
+    heartbeat: Mutex<Instant>,
+    /// The helper process currently running for this download
+    pub process: ProcessSlot,
+    /// This download's share of the global speed limit, in bytes per second
+    rate_limit: watch::Sender<Option<u64>>,
 }
 
 impl DownloadTask {
@@ -42,9 +150,35 @@ impl DownloadTask {
             item,
             cancel_tx,
             cancel_rx,
+            handle: Mutex::new(None),
+            heartbeat: Mutex::new(Instant::now()),
+            process: ProcessSlot::new(),
+            rate_limit: watch::channel(None).0,
+        }
+    }
+    
+    pub fn set_handle(&self, handle: JoinHandle<()>) {
+        if let Ok(mut current) = self.handle.lock() {
+            *current = Some(handle);
         }
     }
     
+    /// Whether the download task is still running; true until it has been spawned
+    pub fn is_running(&self) -> bool {
+        self.handle.lock().map_or(true, |h| h.as_ref().is_none_or(|h| !h.is_finished()))
+    }
+    
+    pub fn beat(&self) {
+        if let Ok(mut heartbeat) = self.heartbeat.lock() {
+            *heartbeat = Instant::now();
+        }
+    }
+    
+    /// Time since the last heartbeat
+    pub fn since_heartbeat(&self) -> Duration {
+        self.heartbeat.lock().map_or(Duration::ZERO, |h| h.elapsed())
+    }
+    
     pub fn is_cancelled(&self) -> bool {
         *self.cancel_rx.borrow()
     }
@@ -52,4 +186,30 @@ impl DownloadTask {
     pub fn cancel(&self) {
         let _ = self.cancel_tx.send(true);
     }
+    
+    /// Cancel and drop the download task outright, for one that no longer makes progress
+    ///
+    /// Dropping the task kills its helper process; the item's status is left to the caller.
+    pub fn abort(&self) {
+        self.cancel();
+        if let Ok(handle) = self.handle.lock() {
+            if let Some(handle) = handle.as_ref() {
+                handle.abort();
+            }
+        }
+    }
+    
+    /// Change the download's speed cap, returning whether it changed
+    pub fn set_rate_limit(&self, rate: Option<u64>) -> bool {
+        self.rate_limit.send_if_modified(|current| {
+            let changed = *current != rate;
+            *current = rate;
+            changed
+        })
+    }
+    
+    /// Receiver that sees every later speed cap change
+    pub fn subscribe_rate_limit(&self) -> watch::Receiver<Option<u64>> {
+        self.rate_limit.subscribe()
+    }
 }
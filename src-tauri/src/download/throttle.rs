@@ -26,10 +26,15 @@ impl ProgressThrottler {
     /// Check if enough time has passed to send an update
     /// Returns true if the update should be sent
     pub async fn should_update(&self) -> bool {
+        self.should_update_after(self.min_interval).await
+    }
+    
+    /// Like `should_update`, with `interval` in place of the throttler's own
+    pub async fn should_update_after(&self, interval: Duration) -> bool {
         let mut last = self.last_update.lock().await;
         let now = Instant::now();
         
-        if now.duration_since(*last) >= self.min_interval {
+        if now.duration_since(*last) >= interval {
             *last = now;
             true
         } else {
@@ -81,6 +86,16 @@ mod tests {
         assert!(throttler.should_update().await);
     }
     
+    #[tokio::test]
+    async fn test_custom_interval() {
+        let throttler = ProgressThrottler::new(Duration::from_millis(10));
+        assert!(throttler.should_update().await);
+        
+        sleep(Duration::from_millis(50)).await;
+        assert!(!throttler.should_update_after(Duration::from_secs(60)).await);
+        assert!(throttler.should_update().await);
+    }
+
     #[tokio::test]
     async fn test_force_update() {
         let throttler = ProgressThrottler::new(Duration::from_millis(100));
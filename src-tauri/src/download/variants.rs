@@ -0,0 +1,247 @@
+//! One video queued at several qualities as a single job
+//!
+//! "1080p for the TV and audio-only for the phone" becomes one queue item per
+//! variant, built from a single metadata fetch and tied together by a shared
+//! `variant_group`. Each item downloads on its own; the group reports their
+//! combined progress.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use super::task::{DownloadItem, DownloadStatus};
+use crate::error::{DownloadError, Result};
+use crate::platform::VideoInfo;
+use crate::storage::StorageService;
+
+/// One quality a job is downloaded at
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityVariant {
+    /// `1080p`, `4k` or `best`; ignored for audio-only
+    #[serde(default = "default_quality")]
+    pub quality: String,
+    #[serde(default)]
+    pub audio_only: bool,
+}
+
+fn default_quality() -> String {
+    "best".to_string()
+}
+
+impl QualityVariant {
+    /// Name the variant's file and queue entry are tagged with
+    pub fn label(&self) -> &str {
+        if self.audio_only {
+            "audio"
+        } else {
+            &self.quality
+        }
+    }
+}
+
+/// Check a video quality before it's turned into a format selector
+pub fn validate_quality(quality: &str) -> Result<()> {
+    let valid = match quality {
+        "best" | "4k" => true,
+        _ => quality
+            .strip_suffix('p')
+            .is_some_and(|height| !height.is_empty() && height.chars().all(|c| c.is_ascii_digit())),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(DownloadError::InvalidOption(format!("Invalid quality: {}", quality)))
+    }
+}
+
+/// Queue items for `info` at each of `variants`, saved side by side in `save_dir`
+///
+/// Files are named `<title> [<label>]` so the variants don't overwrite each
+/// other; the extension is corrected to the real one when the download starts.
+pub fn variant_items(
+    info: &VideoInfo,
+    url: &str,
+    variants: &[QualityVariant],
+    save_dir: &str,
+) -> Result<Vec<DownloadItem>> {
+    if variants.is_empty() {
+        return Err(DownloadError::InvalidOption("At least one quality is required".to_string()));
+    }
+    let mut labels = HashSet::new();
+    for variant in variants {
+        if !variant.audio_only {
+            validate_quality(&variant.quality)?;
+        }
+        if !labels.insert(variant.label()) {
+            return Err(DownloadError::InvalidOption(format!("Quality {} is listed twice", variant.label())));
+        }
+    }
+    
+    let group = uuid::Uuid::new_v4().to_string();
+    let title = StorageService::sanitize_filename(&info.title);
+    Ok(variants
+        .iter()
+        .map(|variant| {
+            let extension = if variant.audio_only { "m4a" } else { "mp4" };
+            let file_name = format!("{} [{}].{}", title, variant.label(), extension);
+            DownloadItem {
+                id: uuid::Uuid::new_v4().to_string(),
+                video_id: info.id.clone(),
+                title: format!("{} [{}]", info.title, variant.label()),
+                thumbnail: info.thumbnail.clone(),
+                status: DownloadStatus::Queued,
+                progress: 0.0,
+                speed: 0.0,
+                eta: 0,
+                save_path: Path::new(save_dir).join(file_name).to_string_lossy().to_string(),
+                error: None,
+                url: url.to_string(),
+                platform: info.platform.clone(),
+                postprocessor_args: None,
+                subtitle_languages: None,
+                auto_subtitles: None,
+                embed_chapters: None,
+                scheduled_at: None,
+                playlist_id: None,
+                record_live: false,
+                premiere_at: info.premiere_at.clone(),
+                section_start: None,
+                section_end: None,
+                audio_only: variant.audio_only,
+                format_id: None,
+                album: None,
+                track_number: None,
+                uploader: Some(info.uploader.clone()).filter(|u| !u.is_empty()),
+                quality: Some(variant.quality.clone()).filter(|_| !variant.audio_only),
+                variant_group: Some(group.clone()),
+                estimated_size: info.estimated_size(None),
+                request_id: None,
+                liveness: None,
+            }
+        })
+        .collect())
+}
+
+/// Combined progress of the items in one variant group
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantGroupProgress {
+    pub group: String,
+    pub item_ids: Vec<String>,
+    /// Mean progress of the items, 0-100; a completed item counts as 100
+    pub progress: f64,
+    pub completed: usize,
+    /// Failed or cancelled items
+    pub failed: usize,
+    /// Every item has completed, failed or been cancelled
+    pub finished: bool,
+}
+
+impl VariantGroupProgress {
+    /// Progress of `group` over the queue, `None` when no item is in it
+    pub fn from_queue(group: &str, queue: &[DownloadItem]) -> Option<Self> {
+        let items: Vec<&DownloadItem> = queue
+            .iter()
+            .filter(|i| i.variant_group.as_deref() == Some(group))
+            .collect();
+        if items.is_empty() {
+            return None;
+        }
+        
+        let completed = items.iter().filter(|i| i.status == DownloadStatus::Completed).count();
+        let failed = items
+            .iter()
+            .filter(|i| matches!(i.status, DownloadStatus::Failed | DownloadStatus::Cancelled))
+            .count();
+        let total: f64 = items
+            .iter()
+            .map(|i| if i.status == DownloadStatus::Completed { 100.0 } else { i.progress.clamp(0.0, 100.0) })
+            .sum();
+        Some(Self {
+            group: group.to_string(),
+            item_ids: items.iter().map(|i| i.id.clone()).collect(),
+            progress: total / items.len() as f64,
+            completed,
+            failed,
+            finished: completed + failed == items.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::FormatInfo;
+    
+    fn info() -> VideoInfo {
+        VideoInfo {
+            id: "abc".to_string(),
+            title: "Concert: Live".to_string(),
+            description: String::new(),
+            thumbnail: String::new(),
+            duration: 600,
+            uploader: "Band".to_string(),
+            upload_date: String::new(),
+            view_count: 0,
+            available_formats: Vec::new(),
+            platform: "YouTube".to_string(),
+            url: "https://www.youtube.com/watch?v=abc".to_string(),
+            is_live: false,
+            premiere_at: None,
+        }
+    }
+    
+    fn variant(quality: &str, audio_only: bool) -> QualityVariant {
+        QualityVariant { quality: quality.to_string(), audio_only }
+    }
+
+    #[test]
+    fn test_variant_items_share_a_group_and_get_distinct_files() {
+        let variants = [variant("1080p", false), variant("best", true)];
+        let items = variant_items(&info(), "https://www.youtube.com/watch?v=abc", &variants, "/videos").unwrap();
+        
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].variant_group, items[1].variant_group);
+        assert_eq!(items[0].save_path, Path::new("/videos").join("Concert_ Live [1080p].mp4").to_string_lossy());
+        assert_eq!(items[0].quality.as_deref(), Some("1080p"));
+        assert_eq!(items[1].title, "Concert: Live [audio]");
+        assert!(items[1].audio_only && items[1].quality.is_none());
+        
+        assert!(variant_items(&info(), "u", &[variant("720p", false), variant("720p", false)], "/videos").is_err());
+        assert!(variant_items(&info(), "u", &[variant("720", false)], "/videos").is_err());
+        assert!(variant_items(&info(), "u", &[], "/videos").is_err());
+    }
+
+    #[test]
+    fn test_estimated_size_from_formats() {
+        let format = |id: &str, filesize| FormatInfo { format_id: id.to_string(), ext: "mp4".to_string(), resolution: None, filesize };
+        let mut info = info();
+        assert_eq!(info.estimated_size(None), None);
+        
+        info.available_formats = vec![format("137", Some(300)), format("140", Some(50)), format("22", None)];
+        assert_eq!(info.estimated_size(None), Some(300));
+        assert_eq!(info.estimated_size(Some("137+140")), Some(350));
+        assert_eq!(info.estimated_size(Some("22")), None);
+        assert_eq!(info.estimated_size(Some("18")), None);
+        
+        let items = variant_items(&info, "u", &[variant("1080p", false)], "/videos").unwrap();
+        assert_eq!(items[0].estimated_size, Some(300));
+    }
+
+    #[test]
+    fn test_group_progress_combines_items() {
+        let mut items = variant_items(&info(), "u", &[variant("4k", false), variant("best", true)], "/videos").unwrap();
+        let group = items[0].variant_group.clone().unwrap();
+        items[0].progress = 50.0;
+        items[1].status = DownloadStatus::Completed;
+        
+        let progress = VariantGroupProgress::from_queue(&group, &items).unwrap();
+        assert_eq!(progress.progress, 75.0);
+        assert_eq!(progress.completed, 1);
+        assert!(!progress.finished);
+        
+        items[0].status = DownloadStatus::Failed;
+        assert!(VariantGroupProgress::from_queue(&group, &items).unwrap().finished);
+        assert!(VariantGroupProgress::from_queue("other", &items).is_none());
+    }
+}
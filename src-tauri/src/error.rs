@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use crate::formatting::{current_locale, format_size};
 
 /// Main error type for download operations
 #[derive(Debug, Error)]
@@ -42,6 +43,15 @@ pub enum DownloadError {
     
     #[error("Timeout: operation took too long")]
     Timeout,
+    
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+    
+    #[error("Live stream in progress: {0}")]
+    LiveStream(String),
+    
+    #[error("Blocked by a bot check: {0}")]
+    BotCheck(String),
 }
 
 /// Error type enum for categorization (serializable for frontend)
@@ -58,6 +68,9 @@ pub enum ErrorType {
     DependencyMissing,
     Cancelled,
     Timeout,
+    InvalidOption,
+    LiveStream,
+    BotCheck,
     Unknown,
 }
 
@@ -91,6 +104,9 @@ impl DownloadError {
             DownloadError::DependencyMissing(_) => ErrorType::DependencyMissing,
             DownloadError::Cancelled => ErrorType::Cancelled,
             DownloadError::Timeout => ErrorType::Timeout,
+            DownloadError::InvalidOption(_) => ErrorType::InvalidOption,
+            DownloadError::LiveStream(_) => ErrorType::LiveStream,
+            DownloadError::BotCheck(_) => ErrorType::BotCheck,
             DownloadError::Io(_) | DownloadError::Serialization(_) => ErrorType::Unknown,
         }
     }
@@ -115,6 +131,22 @@ impl DownloadError {
             DownloadError::PlatformNotSupported(_) => Some("This platform is not yet supported.".to_string()),
             DownloadError::DependencyMissing(dep) => Some(format!("Install the required dependency: {}", dep)),
             DownloadError::Timeout => Some("The operation took too long. Try again later.".to_string()),
+            DownloadError::InvalidOption(_) => Some("Check the advanced download options and try again.".to_string()),
+            DownloadError::LiveStream(_) => Some("Live recording is not supported yet. Download the VOD once the stream has ended.".to_string()),
+            DownloadError::BotCheck(_) => Some("YouTube wants to confirm you're not a bot. Add browser cookies from a signed-in session, or a PO token and visitor data, in Settings, then try again.".to_string()),
+            _ => None,
+        }
+    }
+    
+    /// Localized figures behind the error, e.g. the sizes for insufficient space
+    fn display_details(&self) -> Option<String> {
+        let locale = current_locale();
+        match self {
+            DownloadError::InsufficientSpace { required, available } => Some(format!(
+                "Required: {}, available: {}",
+                format_size(*required, locale),
+                format_size(*available, locale)
+            )),
             _ => None,
         }
     }
@@ -124,7 +156,7 @@ impl DownloadError {
         ErrorResponse {
             error_type: self.error_type(),
             message: self.to_string(),
-            details: None,
+            details: self.display_details(),
             retryable: self.is_retryable(),
             suggested_action: self.suggested_action(),
         }
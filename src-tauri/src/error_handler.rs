@@ -3,6 +3,7 @@ use regex::Regex;
 use std::path::Path;
 use std::time::Duration;
 use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
 
 /// Configuration for retry behavior
 #[derive(Debug, Clone)]
@@ -33,6 +34,21 @@ pub async fn retry_with_backoff<F, Fut, T>(
     operation: F,
     config: RetryConfig,
 ) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    retry_with_cancellation(operation, config, CancellationToken::new()).await
+}
+
+/// Retry a fallible async operation with exponential backoff, stopping as soon as
+/// the token is cancelled. An in-flight attempt is dropped on cancellation, so
+/// operations should make sure their child processes are killed on drop.
+pub async fn retry_with_cancellation<F, Fut, T>(
+    operation: F,
+    config: RetryConfig,
+    cancel_token: CancellationToken,
+) -> Result<T>
 where
     F: Fn() -> Fut,
     Fut: std::future::Future<Output = Result<T>>,
@@ -41,9 +57,18 @@ where
     let mut delay = config.initial_delay;
     
     loop {
+        if cancel_token.is_cancelled() {
+            return Err(DownloadError::Cancelled);
+        }
+        
         attempt += 1;
         
-        match operation().await {
+        let result = tokio::select! {
+            result = operation() => result,
+            _ = cancel_token.cancelled() => return Err(DownloadError::Cancelled),
+        };
+        
+        match result {
             Ok(result) => return Ok(result),
             Err(e) if attempt >= config.max_attempts => {
                 return Err(e);
@@ -52,8 +77,11 @@ where
                 return Err(e);
             }
             Err(_) => {
-                // Wait before retrying
-                sleep(delay).await;
+                // Wait before retrying (or bail out early if cancelled)
+                tokio::select! {
+                    _ = sleep(delay) => {}
+                    _ = cancel_token.cancelled() => return Err(DownloadError::Cancelled),
+                }
                 
                 // Calculate next delay with exponential backoff
                 delay = Duration::from_secs_f64(
@@ -64,6 +92,9 @@ where
     }
 }
 
+/// Maximum accepted URL length
+const MAX_URL_LENGTH: usize = 2048;
+
 /// URL validator for YouTube URLs
 pub struct UrlValidator {
     youtube_patterns: Vec<Regex>,
@@ -74,6 +105,8 @@ impl UrlValidator {
         let youtube_patterns = vec![
             Regex::new(r"^https?://(www\.)?youtube\.com/watch\?v=[\w-]+").unwrap(),
             Regex::new(r"^https?://youtu\.be/[\w-]+").unwrap(),
+            Regex::new(r"^https?://(www\.|m\.)?youtube\.com/(shorts|live)/[\w-]+").unwrap(),
+            Regex::new(r"^https?://music\.youtube\.com/(watch\?v=|playlist\?list=|browse/)[\w-]+").unwrap(),
             Regex::new(r"^https?://(www\.)?youtube\.com/playlist\?list=[\w-]+").unwrap(),
             Regex::new(r"^https?://(www\.)?youtube\.com/@[\w-]+").unwrap(),
             Regex::new(r"^https?://(www\.)?youtube\.com/channel/[\w-]+").unwrap(),
@@ -117,13 +150,77 @@ impl UrlValidator {
         let validated = self.validate_youtube_url(url)?;
         
         // Remove tracking parameters
-        let cleaned = self.remove_tracking_params(&validated);
+        let cleaned = Self::remove_tracking_params(&Self::normalize_video_path(&validated));
         
         Ok(cleaned)
     }
     
+    /// Platform-independent sanity checks for any http(s) URL
+    /// Platform-specific matching is left to the providers in the registry
+    pub fn validate_generic_url(url: &str) -> Result<String> {
+        let trimmed = url.trim();
+        
+        // Check if empty
+        if trimmed.is_empty() {
+            return Err(DownloadError::InvalidUrl("URL cannot be empty".to_string()));
+        }
+        
+        if trimmed.len() > MAX_URL_LENGTH {
+            return Err(DownloadError::InvalidUrl(
+                format!("URL is too long (maximum {} characters)", MAX_URL_LENGTH)
+            ));
+        }
+        
+        // Check scheme
+        let rest = trimmed
+            .strip_prefix("https://")
+            .or_else(|| trimmed.strip_prefix("http://"))
+            .ok_or_else(|| DownloadError::InvalidUrl(
+                "URL must start with http:// or https://".to_string()
+            ))?;
+        
+        // Reject whitespace and control characters anywhere in the URL
+        if trimmed.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(DownloadError::InvalidUrl(
+                "URL must not contain spaces or control characters".to_string()
+            ));
+        }
+        
+        // Extract host (strip path, query, fragment, credentials and port)
+        let authority = rest
+            .split(['/', '?', '#'])
+            .next()
+            .unwrap_or("");
+        let host_port = authority.rsplit('@').next().unwrap_or("");
+        let host = host_port.split(':').next().unwrap_or("");
+        
+        let host_is_valid = !host.is_empty()
+            && (host.contains('.') || host == "localhost")
+            && !host.starts_with('.')
+            && !host.ends_with('.')
+            && host.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+        
+        if !host_is_valid {
+            return Err(DownloadError::InvalidUrl(
+                "URL does not contain a valid host name".to_string()
+            ));
+        }
+        
+        Ok(trimmed.to_string())
+    }
+    
+    /// Rewrite Shorts and live stream URLs (`/shorts/ID`, `/live/ID`) to the
+    /// equivalent watch URL; other URLs are returned unchanged
+    pub fn normalize_video_path(url: &str) -> String {
+        let re = Regex::new(r"^https?://(?:www\.|m\.)?youtube\.com/(?:shorts|live)/([\w-]+)").unwrap();
+        match re.captures(url) {
+            Some(caps) => format!("https://www.youtube.com/watch?v={}", &caps[1]),
+            None => url.to_string(),
+        }
+    }
+    
     /// Remove tracking parameters from URL
-    fn remove_tracking_params(&self, url: &str) -> String {
+    pub fn remove_tracking_params(url: &str) -> String {
         // Remove common tracking parameters
         let tracking_params = ["&feature=", "&t=", "&list=", "&index="];
         let mut cleaned = url.to_string();
@@ -269,6 +366,15 @@ impl ErrorMessageGenerator {
             DownloadError::Timeout => {
                 "The operation timed out. Please try again.".to_string()
             }
+            DownloadError::InvalidOption(msg) => {
+                format!("Invalid download option: {}", msg)
+            }
+            DownloadError::LiveStream(_) => {
+                "This stream is still live. It can be downloaded once the broadcast ends.".to_string()
+            }
+            DownloadError::BotCheck(_) => {
+                "YouTube is asking to confirm you're not a bot. Sign in with cookies or add a PO token in Settings.".to_string()
+            }
             DownloadError::Io(e) => {
                 format!("File system error: {}", e)
             }
@@ -307,6 +413,8 @@ mod tests {
         assert!(validator.validate_youtube_url("https://youtu.be/dQw4w9WgXcQ").is_ok());
         assert!(validator.validate_youtube_url("https://www.youtube.com/playlist?list=PLtest").is_ok());
         assert!(validator.validate_youtube_url("https://www.youtube.com/@channel").is_ok());
+        assert!(validator.validate_youtube_url("https://music.youtube.com/watch?v=lYBUbBu4W08").is_ok());
+        assert!(validator.validate_youtube_url("https://music.youtube.com/playlist?list=OLAK5uy_test").is_ok());
     }
 
     #[test]
@@ -326,6 +434,25 @@ mod tests {
         let url = "https://www.youtube.com/watch?v=dQw4w9WgXcQ&feature=share";
         let normalized = validator.validate_and_normalize(url).unwrap();
         assert_eq!(normalized, "https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+        
+        let shorts = validator.validate_and_normalize("https://www.youtube.com/shorts/aqz-KE-bpKQ?feature=share").unwrap();
+        assert_eq!(shorts, "https://www.youtube.com/watch?v=aqz-KE-bpKQ");
+        let live = validator.validate_and_normalize("https://youtube.com/live/jfKfPfyJRdk?si=xyz").unwrap();
+        assert_eq!(live, "https://www.youtube.com/watch?v=jfKfPfyJRdk");
+    }
+
+    #[test]
+    fn test_generic_url_validation() {
+        assert!(UrlValidator::validate_generic_url("https://vimeo.com/123456").is_ok());
+        assert!(UrlValidator::validate_generic_url("  https://www.bilibili.com/video/BV1xx411c7mD  ").is_ok());
+        assert!(UrlValidator::validate_generic_url("http://localhost:8080/video.mp4").is_ok());
+        
+        assert!(UrlValidator::validate_generic_url("").is_err());
+        assert!(UrlValidator::validate_generic_url("ftp://example.com/file").is_err());
+        assert!(UrlValidator::validate_generic_url("https://").is_err());
+        assert!(UrlValidator::validate_generic_url("https://exa mple.com/").is_err());
+        assert!(UrlValidator::validate_generic_url("https://nodot/").is_err());
+        assert!(UrlValidator::validate_generic_url(&format!("https://example.com/{}", "a".repeat(3000))).is_err());
     }
 
     #[test]
@@ -368,4 +495,48 @@ mod tests {
         assert_eq!(config.max_attempts, 3);
         assert_eq!(config.initial_delay, Duration::from_secs(1));
     }
+
+    #[tokio::test]
+    async fn test_retry_with_cancellation_stops_during_backoff() {
+        let token = CancellationToken::new();
+        let attempts = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+        
+        let config = RetryConfig {
+            max_attempts: 5,
+            initial_delay: Duration::from_secs(10),
+            ..RetryConfig::default()
+        };
+        
+        let cancel = token.clone();
+        tokio::spawn(async move {
+            sleep(Duration::from_millis(50)).await;
+            cancel.cancel();
+        });
+        
+        let counter = attempts.clone();
+        let result: Result<()> = retry_with_cancellation(
+            || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    Err(DownloadError::Network("flaky".to_string()))
+                }
+            },
+            config,
+            token,
+        )
+        .await;
+        
+        assert!(matches!(result, Err(DownloadError::Cancelled)));
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_cancellation_already_cancelled() {
+        let token = CancellationToken::new();
+        token.cancel();
+        
+        let result = retry_with_cancellation(|| async { Ok(42) }, RetryConfig::default(), token).await;
+        assert!(matches!(result, Err(DownloadError::Cancelled)));
+    }
 }
@@ -0,0 +1,119 @@
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Manager};
+use crate::log_error;
+
+/// Destination for backend events (`download:progress`, `queue:update`, ...)
+///
+/// The download engine only talks to this trait, so events can go to the Tauri
+/// frontend, a log, or any other transport, and the engine can run without a window.
+pub trait EventSink: Send + Sync {
+    fn emit(&self, event: &str, payload: serde_json::Value);
+}
+
+/// Sends events to every Tauri window
+pub struct TauriEventSink {
+    app_handle: AppHandle,
+}
+
+impl TauriEventSink {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl EventSink for TauriEventSink {
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        let _ = self.app_handle.emit_all(event, payload);
+    }
+}
+
+/// Prints events to stdout, for headless runs and debugging
+pub struct LogEventSink;
+
+impl EventSink for LogEventSink {
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        println!("[Event] {} {}", event, payload);
+    }
+}
+
+/// Discards events
+pub struct NullEventSink;
+
+impl EventSink for NullEventSink {
+    fn emit(&self, _event: &str, _payload: serde_json::Value) {}
+}
+
+/// Fans each event out to every registered sink
+#[derive(Default)]
+pub struct EventRouter {
+    sinks: RwLock<Vec<Arc<dyn EventSink>>>,
+}
+
+impl EventRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Route events to `sink` as well, from now on
+    pub fn add_sink(&self, sink: Arc<dyn EventSink>) {
+        if let Ok(mut sinks) = self.sinks.write() {
+            sinks.push(sink);
+        }
+    }
+}
+
+impl EventSink for EventRouter {
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        let sinks = match self.sinks.read() {
+            Ok(sinks) => sinks.clone(),
+            Err(_) => return,
+        };
+        for sink in sinks {
+            sink.emit(event, payload.clone());
+        }
+    }
+}
+
+/// Serialize `payload` and emit it, for typed payloads
+pub fn emit_serialized<T: serde::Serialize>(sink: &dyn EventSink, event: &str, payload: &T) {
+    match serde_json::to_value(payload) {
+        Ok(value) => sink.emit(event, value),
+        Err(e) => log_error!("[EventSink] Failed to serialize {} payload: {}", event, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<(String, serde_json::Value)>>,
+    }
+    
+    impl EventSink for RecordingSink {
+        fn emit(&self, event: &str, payload: serde_json::Value) {
+            self.events.lock().unwrap().push((event.to_string(), payload));
+        }
+    }
+
+    #[test]
+    fn test_router_fans_out_to_all_sinks() {
+        let router = EventRouter::new();
+        let first = Arc::new(RecordingSink::default());
+        let second = Arc::new(RecordingSink::default());
+        router.add_sink(first.clone());
+        router.add_sink(Arc::new(NullEventSink));
+        router.add_sink(second.clone());
+        
+        router.emit("queue:update", serde_json::json!([]));
+        emit_serialized(&router, "download:complete", &serde_json::json!({ "id": "a" }));
+        
+        for sink in [first, second] {
+            let events = sink.events.lock().unwrap();
+            assert_eq!(events.len(), 2);
+            assert_eq!(events[1], ("download:complete".to_string(), serde_json::json!({ "id": "a" })));
+        }
+    }
+}
@@ -4,12 +4,15 @@ use std::os::unix::fs::PermissionsExt;
 use sha2::{Sha256, Digest};
 use tauri::api::path::resource_dir;
 use tauri::PackageInfo;
+use crate::checksum_manifest::{ChecksumManifest, RELEASE_PUBLIC_KEY};
 use crate::error::{DownloadError, Result};
 
 /// Manages bundled executable files (yt-dlp and ffmpeg)
 pub struct ExecutableManager {
     resource_dir: PathBuf,
     arch: Architecture,
+    /// Key the signed checksum manifest is checked against
+    manifest_key: Option<&'static str>,
 }
 
 /// System architecture
@@ -81,6 +84,7 @@ impl ExecutableManager {
         Ok(Self {
             resource_dir: final_resource_dir,
             arch,
+            manifest_key: RELEASE_PUBLIC_KEY,
         })
     }
     
@@ -100,6 +104,19 @@ impl ExecutableManager {
             .join("ffmpeg")
     }
     
+    /// Get the path to the optional whisper.cpp executable used for transcription
+    pub fn get_whisper_path(&self) -> PathBuf {
+        self.resource_dir
+            .join("bin")
+            .join(self.arch.dir_name())
+            .join("whisper-cli")
+    }
+    
+    /// Get the path to the optional whisper model
+    pub fn get_whisper_model_path(&self) -> PathBuf {
+        self.resource_dir.join("models").join("ggml-base.bin")
+    }
+    
     /// Verify the integrity of a file using SHA256 checksum
     pub fn verify_checksum(&self, file_path: &Path, expected_checksum: &str) -> Result<bool> {
         let contents = fs::read(file_path)
@@ -114,57 +131,43 @@ impl ExecutableManager {
     }
     
     /// Verify all bundled executables
+    ///
+    /// Checksums come from the signed manifest; yt-dlp may instead match the
+    /// user manifest the updater writes after installing a newer release.
     pub fn verify_all_executables(&self) -> Result<()> {
-        // Load checksums from the bundled CHECKSUMS.txt file
-        let checksums_path = self.resource_dir.join("bin").join("CHECKSUMS.txt");
-        let checksums_content = fs::read_to_string(&checksums_path)
-            .map_err(|e| DownloadError::DownloadFailed(format!("Failed to read checksums file: {}", e)))?;
-        
-        // Parse checksums
-        let mut checksums = std::collections::HashMap::new();
-        for line in checksums_content.lines() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
-            
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() == 2 {
-                checksums.insert(parts[1].to_string(), parts[0].to_string());
-            }
-        }
+        let bin_dir = self.resource_dir.join("bin");
+        let signed = ChecksumManifest::load_signed(&bin_dir, self.manifest_key)?;
+        let user = ChecksumManifest::load_user(&bin_dir)?;
         
-        // Verify yt-dlp
-        let ytdlp_path = self.get_ytdlp_path();
-        let ytdlp_key = format!("{}/yt-dlp", self.arch.dir_name());
-        if let Some(expected_checksum) = checksums.get(&ytdlp_key) {
-            if !self.verify_checksum(&ytdlp_path, expected_checksum)? {
-                return Err(DownloadError::DownloadFailed(
-                    format!("yt-dlp checksum verification failed for {}", self.arch.dir_name())
-                ));
-            }
-        } else {
+        self.verify_executable("yt-dlp", &self.get_ytdlp_path(), &[&signed, &user])?;
+        self.verify_executable("ffmpeg", &self.get_ffmpeg_path(), &[&signed])?;
+        
+        // whisper is optional; when installed it must still match the signed manifest
+        let whisper_path = self.get_whisper_path();
+        if whisper_path.exists() {
+            self.verify_executable("whisper-cli", &whisper_path, &[&signed])?;
+        }
+        Ok(())
+    }
+    
+    /// Check `path` against the first manifest entry for `name` it matches
+    fn verify_executable(&self, name: &str, path: &Path, manifests: &[&ChecksumManifest]) -> Result<()> {
+        let key = format!("{}/{}", self.arch.dir_name(), name);
+        let expected: Vec<&str> = manifests.iter().filter_map(|m| m.get(&key)).collect();
+        if expected.is_empty() {
             return Err(DownloadError::DownloadFailed(
-                format!("No checksum found for yt-dlp ({})", self.arch.dir_name())
+                format!("No checksum found for {} ({})", name, self.arch.dir_name())
             ));
         }
         
-        // Verify ffmpeg
-        let ffmpeg_path = self.get_ffmpeg_path();
-        let ffmpeg_key = format!("{}/ffmpeg", self.arch.dir_name());
-        if let Some(expected_checksum) = checksums.get(&ffmpeg_key) {
-            if !self.verify_checksum(&ffmpeg_path, expected_checksum)? {
-                return Err(DownloadError::DownloadFailed(
-                    format!("ffmpeg checksum verification failed for {}", self.arch.dir_name())
-                ));
+        for checksum in expected {
+            if self.verify_checksum(path, checksum)? {
+                return Ok(());
             }
-        } else {
-            return Err(DownloadError::DownloadFailed(
-                format!("No checksum found for ffmpeg ({})", self.arch.dir_name())
-            ));
         }
-        
-        Ok(())
+        Err(DownloadError::DownloadFailed(
+            format!("{} checksum verification failed for {}", name, self.arch.dir_name())
+        ))
     }
     
     /// Ensure executable permissions are set on the bundled executables
@@ -176,6 +179,11 @@ impl ExecutableManager {
         self.set_permissions(&ytdlp_path, 0o755)?;
         self.set_permissions(&ffmpeg_path, 0o755)?;
         
+        let whisper_path = self.get_whisper_path();
+        if whisper_path.exists() {
+            self.set_permissions(&whisper_path, 0o755)?;
+        }
+        
         Ok(())
     }
     
@@ -230,4 +238,35 @@ mod tests {
         assert_eq!(Architecture::X86_64.dir_name(), "x86_64");
         assert_eq!(Architecture::Aarch64.dir_name(), "aarch64");
     }
+
+    #[test]
+    fn test_user_manifest_only_vouches_for_ytdlp() {
+        let dir = tempfile::tempdir().unwrap();
+        // Unsigned, whatever key this build was compiled with
+        let manager = ExecutableManager {
+            resource_dir: dir.path().to_path_buf(),
+            arch: Architecture::Aarch64,
+            manifest_key: None,
+        };
+        let bin_dir = dir.path().join("bin");
+        fs::create_dir_all(bin_dir.join("aarch64")).unwrap();
+        fs::write(manager.get_ytdlp_path(), "updated yt-dlp").unwrap();
+        fs::write(manager.get_ffmpeg_path(), "patched ffmpeg").unwrap();
+        let checksum = |text: &str| format!("{:x}", Sha256::digest(text.as_bytes()));
+        
+        fs::write(
+            bin_dir.join("CHECKSUMS.txt"),
+            format!("{}  aarch64/yt-dlp\n{}  aarch64/ffmpeg\n", checksum("bundled yt-dlp"), checksum("bundled ffmpeg")),
+        ).unwrap();
+        let mut user = ChecksumManifest::default();
+        user.set("aarch64/yt-dlp", &checksum("updated yt-dlp"));
+        user.set("aarch64/ffmpeg", &checksum("patched ffmpeg"));
+        user.save_user(&bin_dir).unwrap();
+        
+        let error = manager.verify_all_executables().unwrap_err().to_string();
+        assert!(error.contains("ffmpeg checksum verification failed"), "{}", error);
+        
+        fs::write(manager.get_ffmpeg_path(), "bundled ffmpeg").unwrap();
+        assert!(manager.verify_all_executables().is_ok());
+    }
 }
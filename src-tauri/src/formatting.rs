@@ -0,0 +1,147 @@
+//! Human-readable sizes, speeds and durations for the frontend
+//!
+//! Numbers follow the display locale's decimal separator (`1.50 MB` in
+//! English, `1,50 MB` in German). The locale comes from the `locale` setting,
+//! falling back to the system locale.
+
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use crate::error_handler::DiskSpaceChecker;
+use crate::i18n::Language;
+use crate::platform::DownloadProgress;
+
+/// Languages that write decimals with a comma
+const COMMA_DECIMAL_LANGUAGES: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "it", "lt", "lv",
+    "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr", "uk", "vi",
+];
+
+static LOCALE: RwLock<Option<Locale>> = RwLock::new(None);
+
+/// Number formatting conventions and label language of a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    decimal_separator: char,
+    language: Language,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self { decimal_separator: '.', language: Language::default() }
+    }
+}
+
+impl Locale {
+    /// Locale from a tag like `de-DE`, `pt_BR.UTF-8` or `fr`
+    pub fn from_tag(tag: &str) -> Self {
+        let language = tag
+            .split(['-', '_', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let decimal_separator = if COMMA_DECIMAL_LANGUAGES.contains(&language.as_str()) { ',' } else { '.' };
+        Self { decimal_separator, language: Language::from_tag(tag) }
+    }
+    
+    /// Language for translated labels
+    pub fn language(&self) -> Language {
+        self.language
+    }
+    
+    /// Locale from the environment (`LC_ALL`, `LC_NUMERIC`, `LANG`)
+    pub fn system() -> Self {
+        ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|tag| !tag.is_empty() && tag != "C" && tag != "POSIX")
+            .map(|tag| Self::from_tag(&tag))
+            .unwrap_or_default()
+    }
+    
+    fn localize(&self, formatted: String) -> String {
+        if self.decimal_separator == '.' {
+            formatted
+        } else {
+            formatted.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+}
+
+/// Apply the `locale` setting; `None` uses the system locale
+pub fn set_locale(tag: Option<&str>) {
+    let locale = tag.filter(|t| !t.is_empty()).map(Locale::from_tag);
+    if let Ok(mut current) = LOCALE.write() {
+        *current = locale;
+    }
+}
+
+/// Locale used for display strings
+pub fn current_locale() -> Locale {
+    LOCALE
+        .read()
+        .ok()
+        .and_then(|locale| *locale)
+        .unwrap_or_else(Locale::system)
+}
+
+/// Display strings for a progress update
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProgressDisplay {
+    pub downloaded: String,
+    pub total: String,
+    pub speed: String,
+    pub eta: String,
+}
+
+impl ProgressDisplay {
+    pub fn new(progress: &DownloadProgress, locale: Locale) -> Self {
+        Self {
+            downloaded: format_size(progress.downloaded_bytes, locale),
+            total: format_size(progress.total_bytes, locale),
+            speed: format_speed(progress.speed, locale),
+            eta: format_duration(progress.eta),
+        }
+    }
+}
+
+/// Size such as `1.50 GB`
+pub fn format_size(bytes: u64, locale: Locale) -> String {
+    locale.localize(DiskSpaceChecker::format_bytes(bytes))
+}
+
+/// Transfer rate such as `2.30 MB/s`
+pub fn format_speed(bytes_per_second: f64, locale: Locale) -> String {
+    format!("{}/s", format_size(bytes_per_second.max(0.0) as u64, locale))
+}
+
+/// Duration such as `45s`, `3m 05s` or `1h 02m`
+pub fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, secs) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, secs)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_localized_formatting() {
+        let english = Locale::from_tag("en-US");
+        let german = Locale::from_tag("de_DE.UTF-8");
+        
+        assert_eq!(format_size(1536 * 1024 * 1024, english), "1.50 GB");
+        assert_eq!(format_size(1536 * 1024 * 1024, german), "1,50 GB");
+        assert_eq!(format_speed(2.5 * 1024.0 * 1024.0, german), "2,50 MB/s");
+        assert_eq!(format_speed(-1.0, english), "0.00 B/s");
+        
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(185), "3m 05s");
+        assert_eq!(format_duration(3720), "1h 02m");
+    }
+}
@@ -0,0 +1,111 @@
+//! Translations of UI text defined in the backend
+//!
+//! Provider setting definitions carry a label key (`PlatformSetting::label_key`)
+//! rather than display text; `translate` turns the key into the display
+//! language. Keys missing from a language fall back to English, then to the
+//! key itself, so a provider without translations never shows another
+//! language's text.
+
+/// Language for translated labels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    Chinese,
+}
+
+impl Language {
+    /// Language from a locale tag like `zh-CN`, `en_US.UTF-8` or `fr`; English when there is no translation
+    pub fn from_tag(tag: &str) -> Self {
+        let language = tag.split(['-', '_', '.', '@']).next().unwrap_or_default();
+        if language.eq_ignore_ascii_case("zh") {
+            Self::Chinese
+        } else {
+            Self::English
+        }
+    }
+    
+    fn translations(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::English => ENGLISH,
+            Self::Chinese => CHINESE,
+        }
+    }
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    ("settings.youtube.prefer_av1", "Prefer AV1 codec"),
+    ("settings.youtube.video_codec", "Preferred video codec"),
+    ("settings.youtube.hdr", "HDR formats (not supported by some players)"),
+    ("settings.youtube.high_fps", "High frame rate (60fps) formats"),
+    ("settings.youtube.skip_ads", "Skip sponsor segments (SponsorBlock)"),
+    ("settings.youtube.sponsorblock_categories", "Segment types to skip (sponsor, intro, outro, selfpromo)"),
+    ("settings.youtube.subtitle_language", "Subtitle language"),
+    ("settings.youtube.auto_subtitles", "Use auto-generated captions when there are no subtitles"),
+    ("settings.youtube.live_chat", "Save live chat replay (JSON)"),
+    ("settings.youtube.embed_thumbnail", "Embed thumbnail in the video file"),
+    ("settings.youtube.write_thumbnail", "Save thumbnail as an image file"),
+    ("settings.youtube.embed_metadata", "Embed metadata (title, description, ...)"),
+    ("settings.youtube.max_resolution", "Maximum resolution"),
+    ("settings.youtube.parse_metadata_rules", "Metadata parse rules (FROM:TO, e.g. title:%(artist)s - %(track)s)"),
+    ("settings.youtube.audio_language", "Preferred audio language for dubbed videos (e.g. en, ja; empty for the original)"),
+    ("settings.youtube.all_audio_tracks", "Download every audio track into an MKV file"),
+];
+
+const CHINESE: &[(&str, &str)] = &[
+    ("settings.youtube.prefer_av1", "优先使用 AV1 编码"),
+    ("settings.youtube.video_codec", "视频编码偏好"),
+    ("settings.youtube.hdr", "HDR 格式 (部分播放器不支持)"),
+    ("settings.youtube.high_fps", "高帧率 (60fps) 格式"),
+    ("settings.youtube.skip_ads", "跳过赞助片段 (SponsorBlock)"),
+    ("settings.youtube.sponsorblock_categories", "跳过的片段类型 (sponsor, intro, outro, selfpromo)"),
+    ("settings.youtube.subtitle_language", "字幕语言"),
+    ("settings.youtube.auto_subtitles", "无字幕时使用自动生成字幕"),
+    ("settings.youtube.live_chat", "保存直播聊天回放 (JSON)"),
+    ("settings.youtube.embed_thumbnail", "嵌入缩略图到视频文件"),
+    ("settings.youtube.write_thumbnail", "另存缩略图为图片文件"),
+    ("settings.youtube.embed_metadata", "嵌入元数据 (标题、描述等)"),
+    ("settings.youtube.max_resolution", "最大分辨率"),
+    ("settings.youtube.parse_metadata_rules", "元数据解析规则 (FROM:TO, 例如 title:%(artist)s - %(track)s)"),
+    ("settings.youtube.audio_language", "多音轨视频的首选音频语言 (例如 en, ja; 留空使用原声)"),
+    ("settings.youtube.all_audio_tracks", "下载所有音轨并保存为 MKV 文件"),
+];
+
+fn lookup(key: &str, language: Language) -> Option<&'static str> {
+    language
+        .translations()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, text)| *text)
+}
+
+/// Text for `key` in `language`, falling back to English and then to the key
+pub fn translate(key: &str, language: Language) -> String {
+    lookup(key, language)
+        .or_else(|| lookup(key, Language::English))
+        .unwrap_or(key)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_translate_falls_back_to_english_then_key() {
+        assert_eq!(Language::from_tag("zh-CN"), Language::Chinese);
+        assert_eq!(Language::from_tag("zh_TW.UTF-8"), Language::Chinese);
+        assert_eq!(Language::from_tag("de-DE"), Language::English);
+        
+        assert_eq!(translate("settings.youtube.max_resolution", Language::Chinese), "最大分辨率");
+        assert_eq!(translate("settings.youtube.max_resolution", Language::English), "Maximum resolution");
+        assert_eq!(translate("settings.example.unknown", Language::Chinese), "settings.example.unknown");
+    }
+
+    #[test]
+    fn test_every_translation_has_an_english_label() {
+        for (key, _) in CHINESE {
+            assert!(lookup(key, Language::English).is_some(), "{} has no English label", key);
+        }
+    }
+}
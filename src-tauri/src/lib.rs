@@ -5,5 +5,19 @@ pub mod download;
 pub mod storage;
 pub mod error;
 pub mod error_handler;
+pub mod formatting;
+pub mod i18n;
+pub mod events;
+pub mod paths;
 pub mod executable_manager;
+pub mod checksum_manifest;
 pub mod update_service;
+pub mod media_dedupe;
+pub mod transcription;
+pub mod chapters;
+pub mod preview;
+pub mod sounds;
+pub mod diagnostics;
+pub mod logging;
+#[cfg(feature = "test-harness")]
+pub mod testing;
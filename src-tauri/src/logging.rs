@@ -0,0 +1,469 @@
+//! Backend log records for the in-app log console
+//!
+//! The `log_*!` macros print like `println!`/`eprintln!` and also send a
+//! `log:record` event (level, module, message) once an event sink is set. The
+//! stream is rate limited so chatty output such as yt-dlp progress lines can't
+//! flood the frontend; records over the limit are counted and reported in a
+//! summary record instead.
+//!
+//! `LogSettings` picks the minimum level and which categories are streamed to
+//! the frontend and appended to the log file.
+//!
+//! Records logged inside `in_request`/`in_span` carry the request ID and span
+//! path of the work they belong to. A download keeps the ID it was queued
+//! under from the command that added it through scheduling, the provider, its
+//! helper processes and completion, so one download's lifecycle can be pulled
+//! out of the log file with a single search.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Instant;
+use crate::events::EventSink;
+
+/// Records sent per second once the burst allowance is used up
+const RECORDS_PER_SECOND: f64 = 20.0;
+/// Records that can be sent back to back
+const RECORD_BURST: f64 = 100.0;
+/// The log file is rotated to `<name>.1` once it grows past this size
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// Area of the backend a record comes from, by Rust module
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum LogCategory {
+    /// Download queue and scheduling
+    Scheduler,
+    /// Platform providers and yt-dlp output
+    Ytdlp,
+    /// Settings, queue and history persistence
+    Storage,
+    /// Everything else (commands, updates, ...)
+    General,
+}
+
+impl LogCategory {
+    pub const ALL: [LogCategory; 4] = [Self::Scheduler, Self::Ytdlp, Self::Storage, Self::General];
+    
+    pub fn from_module_path(module_path: &str) -> Self {
+        match module_path.split("::").nth(1) {
+            Some("download") => Self::Scheduler,
+            Some("platform") => Self::Ytdlp,
+            Some("storage") => Self::Storage,
+            _ => Self::General,
+        }
+    }
+}
+
+/// Log verbosity; records below `level` are only printed to the terminal
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LogSettings {
+    #[serde(default = "default_log_level")]
+    pub level: LogLevel,
+    /// Categories sent to the frontend as `log:record` events
+    #[serde(default = "all_categories")]
+    pub frontend: Vec<LogCategory>,
+    /// Categories appended to the log file
+    #[serde(default = "all_categories")]
+    pub file: Vec<LogCategory>,
+}
+
+fn default_log_level() -> LogLevel {
+    LogLevel::Info
+}
+
+fn all_categories() -> Vec<LogCategory> {
+    LogCategory::ALL.to_vec()
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            level: default_log_level(),
+            frontend: all_categories(),
+            file: all_categories(),
+        }
+    }
+}
+
+impl LogSettings {
+    fn streams(&self, level: LogLevel, category: LogCategory) -> bool {
+        level >= self.level && self.frontend.contains(&category)
+    }
+    
+    fn writes(&self, level: LogLevel, category: LogCategory) -> bool {
+        level >= self.level && self.file.contains(&category)
+    }
+}
+
+/// Request and span a record was logged in
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceContext {
+    pub request_id: String,
+    /// Nested span names, outermost first, e.g. `download/provider`
+    pub span: String,
+}
+
+tokio::task_local! {
+    static TRACE: TraceContext;
+}
+
+/// Short random ID for a new request
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..8].to_string()
+}
+
+/// Trace of the task this is called from, if it runs inside a span
+pub fn current_trace() -> Option<TraceContext> {
+    TRACE.try_with(TraceContext::clone).ok()
+}
+
+/// Run `future` as the root span `name` of request `request_id`
+pub fn in_request<F: Future>(request_id: &str, name: &str, future: F) -> impl Future<Output = F::Output> {
+    let trace = TraceContext { request_id: request_id.to_string(), span: name.to_string() };
+    traced(trace, future)
+}
+
+/// Run `future` as span `name` inside the current request, or as a new request outside one
+pub async fn in_span<F: Future>(name: &str, future: F) -> F::Output {
+    let trace = match current_trace() {
+        Some(parent) => TraceContext { span: format!("{}/{}", parent.span, name), ..parent },
+        None => TraceContext { request_id: new_request_id(), span: name.to_string() },
+    };
+    traced(trace, future).await
+}
+
+async fn traced<F: Future>(trace: TraceContext, future: F) -> F::Output {
+    TRACE
+        .scope(trace, async {
+            let started = Instant::now();
+            log(LogLevel::Info, module_path!(), format_args!("[Trace] Span started"));
+            let output = future.await;
+            log(LogLevel::Info, module_path!(), format_args!("[Trace] Span finished after {:.1}s", started.elapsed().as_secs_f64()));
+            output
+        })
+        .await
+}
+
+/// Payload of a `log:record` event
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    /// The message's `[Tag]` prefix, or the Rust module path when it has none
+    pub module: String,
+    pub message: String,
+    pub timestamp: String,
+    /// Request and span the record was logged in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<TraceContext>,
+}
+
+impl LogRecord {
+    pub fn new(level: LogLevel, module_path: &str, text: &str) -> Self {
+        let tagged = text
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .filter(|(tag, _)| !tag.is_empty() && !tag.contains('\n'));
+        let (module, message) = match tagged {
+            Some((tag, message)) => (tag.to_string(), message.trim_start().to_string()),
+            None => (module_path.to_string(), text.to_string()),
+        };
+        Self {
+            level,
+            module,
+            message,
+            timestamp: chrono::Local::now().to_rfc3339(),
+            trace: current_trace(),
+        }
+    }
+    
+    /// One line of the log file
+    fn to_line(&self) -> String {
+        let trace = match &self.trace {
+            Some(trace) => format!(" req={} span={}", trace.request_id, trace.span),
+            None => String::new(),
+        };
+        format!(
+            "{} {:<5}{} [{}] {}\n",
+            self.timestamp,
+            format!("{:?}", self.level).to_uppercase(),
+            trace,
+            self.module,
+            self.message
+        )
+    }
+}
+
+/// Token bucket deciding which records are streamed
+#[derive(Debug)]
+struct RateLimit {
+    tokens: f64,
+    updated: Instant,
+    dropped: u64,
+}
+
+impl RateLimit {
+    fn new(now: Instant) -> Self {
+        Self { tokens: RECORD_BURST, updated: now, dropped: 0 }
+    }
+    
+    /// Take a token for one record; `Some(dropped)` when it may be sent, with the
+    /// number of records dropped since the last one that was sent
+    fn acquire(&mut self, now: Instant) -> Option<u64> {
+        let refill = now.saturating_duration_since(self.updated).as_secs_f64() * RECORDS_PER_SECOND;
+        self.tokens = (self.tokens + refill).min(RECORD_BURST);
+        self.updated = now;
+        
+        if self.tokens < 1.0 {
+            self.dropped += 1;
+            return None;
+        }
+        self.tokens -= 1.0;
+        Some(std::mem::take(&mut self.dropped))
+    }
+}
+
+/// Append-only log file, rotated once it reaches `MAX_LOG_FILE_BYTES`
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { path, file, len })
+    }
+    
+    fn write(&mut self, line: &str) -> std::io::Result<()> {
+        if self.len >= MAX_LOG_FILE_BYTES {
+            let mut rotated = self.path.clone().into_os_string();
+            rotated.push(".1");
+            std::fs::rename(&self.path, rotated)?;
+            *self = Self::open(self.path.clone())?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.len += line.len() as u64;
+        Ok(())
+    }
+}
+
+struct LogStream {
+    sink: RwLock<Option<Arc<dyn EventSink>>>,
+    limit: Mutex<RateLimit>,
+    settings: RwLock<LogSettings>,
+    file: Mutex<Option<LogFile>>,
+}
+
+fn stream() -> &'static LogStream {
+    static STREAM: OnceLock<LogStream> = OnceLock::new();
+    STREAM.get_or_init(|| LogStream {
+        sink: RwLock::new(None),
+        limit: Mutex::new(RateLimit::new(Instant::now())),
+        settings: RwLock::new(LogSettings::default()),
+        file: Mutex::new(None),
+    })
+}
+
+/// Stream log records to `sink` as `log:record` events from now on
+pub fn set_event_sink(sink: Arc<dyn EventSink>) {
+    if let Ok(mut current) = stream().sink.write() {
+        *current = Some(sink);
+    }
+}
+
+/// Append log records to the file at `path` from now on
+pub fn set_log_file(path: PathBuf) {
+    match LogFile::open(path) {
+        Ok(file) => {
+            if let Ok(mut current) = stream().file.lock() {
+                *current = Some(file);
+            }
+        }
+        Err(e) => eprintln!("[Logging] Failed to open log file: {}", e),
+    }
+}
+
+/// Path of the current log file, if one is open
+pub fn log_file_path() -> Option<PathBuf> {
+    stream().file.lock().ok()?.as_ref().map(|f| f.path.clone())
+}
+
+/// Apply the `logging` setting
+pub fn set_settings(settings: LogSettings) {
+    if let Ok(mut current) = stream().settings.write() {
+        *current = settings;
+    }
+}
+
+/// Print a message and stream it; use the `log_*!` macros rather than calling this directly
+pub fn log(level: LogLevel, module_path: &str, args: fmt::Arguments) {
+    let text = args.to_string();
+    let printed = match current_trace() {
+        Some(trace) => format!("{} (req {})", text, trace.request_id),
+        None => text.clone(),
+    };
+    match level {
+        LogLevel::Warn | LogLevel::Error => eprintln!("{}", printed),
+        LogLevel::Debug | LogLevel::Info => println!("{}", printed),
+    }
+    
+    let category = LogCategory::from_module_path(module_path);
+    let Some((writes, streams)) = stream().settings.read().ok().map(|s| (s.writes(level, category), s.streams(level, category))) else {
+        return;
+    };
+    if writes {
+        write_to_file(&LogRecord::new(level, module_path, &text));
+    }
+    if !streams {
+        return;
+    }
+    
+    let Some(sink) = stream().sink.read().ok().and_then(|s| s.clone()) else {
+        return;
+    };
+    let Some(dropped) = stream().limit.lock().ok().and_then(|mut l| l.acquire(Instant::now())) else {
+        return;
+    };
+    if dropped > 0 {
+        let summary = LogRecord::new(LogLevel::Warn, "Logging", &format!("{} log records dropped (rate limit)", dropped));
+        emit(sink.as_ref(), &summary);
+    }
+    emit(sink.as_ref(), &LogRecord::new(level, module_path, &text));
+}
+
+fn write_to_file(record: &LogRecord) {
+    if let Ok(mut file) = stream().file.lock() {
+        if let Some(file) = file.as_mut() {
+            // A failed write can't be logged without recursing; the terminal still has the record
+            let _ = file.write(&record.to_line());
+        }
+    }
+}
+
+fn emit(sink: &dyn EventSink, record: &LogRecord) {
+    if let Ok(payload) = serde_json::to_value(record) {
+        sink.emit("log:record", payload);
+    }
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        $crate::logging::log($crate::logging::LogLevel::Debug, module_path!(), format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => {
+        $crate::logging::log($crate::logging::LogLevel::Info, module_path!(), format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        $crate::logging::log($crate::logging::LogLevel::Warn, module_path!(), format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => {
+        $crate::logging::log($crate::logging::LogLevel::Error, module_path!(), format_args!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    
+    #[test]
+    fn test_record_takes_module_from_tag() {
+        let record = LogRecord::new(LogLevel::Info, "crate::download", "[DownloadManager] Added 2 items");
+        assert_eq!(record.module, "DownloadManager");
+        assert_eq!(record.message, "Added 2 items");
+        
+        let record = LogRecord::new(LogLevel::Warn, "crate::download", "Untagged message");
+        assert_eq!(record.module, "crate::download");
+        assert_eq!(record.message, "Untagged message");
+    }
+
+    #[tokio::test]
+    async fn test_spans_nest_under_request() {
+        assert_eq!(current_trace(), None);
+        
+        let trace = in_request("abc12345", "download", async {
+            in_span("provider", async { current_trace() }).await
+        })
+        .await
+        .unwrap();
+        assert_eq!(trace.request_id, "abc12345");
+        assert_eq!(trace.span, "download/provider");
+        
+        let record = in_request("abc12345", "download", async {
+            LogRecord::new(LogLevel::Info, "crate::download", "[DownloadManager] Started")
+        })
+        .await;
+        assert!(record.to_line().contains(" req=abc12345 span=download [DownloadManager] Started"));
+        
+        // Outside a request, a span starts a new one
+        let trace = in_span("update", async { current_trace() }).await.unwrap();
+        assert_eq!(trace.request_id.len(), 8);
+        assert_eq!(trace.span, "update");
+    }
+
+    #[test]
+    fn test_rate_limit_drops_and_counts() {
+        let start = Instant::now();
+        let mut limit = RateLimit::new(start);
+        for _ in 0..RECORD_BURST as usize {
+            assert_eq!(limit.acquire(start), Some(0));
+        }
+        assert_eq!(limit.acquire(start), None);
+        assert_eq!(limit.acquire(start), None);
+        
+        // One token back after 1/20 s, reporting the two dropped records
+        assert_eq!(limit.acquire(start + Duration::from_millis(50)), Some(2));
+        assert!(limit.acquire(start + Duration::from_secs(5)).is_some());
+    }
+
+    #[test]
+    fn test_settings_filter_by_level_and_category() {
+        assert_eq!(LogCategory::from_module_path("youtube_downloader_gui::download::manager"), LogCategory::Scheduler);
+        assert_eq!(LogCategory::from_module_path("youtube_downloader_gui::platform::youtube"), LogCategory::Ytdlp);
+        assert_eq!(LogCategory::from_module_path("vortex_downloader::commands"), LogCategory::General);
+        
+        let settings = LogSettings {
+            level: LogLevel::Info,
+            frontend: vec![LogCategory::Scheduler],
+            file: vec![LogCategory::Scheduler, LogCategory::Ytdlp],
+        };
+        assert!(settings.streams(LogLevel::Info, LogCategory::Scheduler));
+        assert!(!settings.streams(LogLevel::Debug, LogCategory::Scheduler));
+        assert!(!settings.streams(LogLevel::Error, LogCategory::Ytdlp));
+        assert!(settings.writes(LogLevel::Warn, LogCategory::Ytdlp));
+        assert!(!settings.writes(LogLevel::Warn, LogCategory::Storage));
+    }
+}
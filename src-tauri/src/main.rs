@@ -3,13 +3,17 @@
 
 mod commands;
 
-use youtube_downloader_gui::{platform, download, storage, executable_manager};
+use youtube_downloader_gui::{platform, download, storage, executable_manager, events, formatting, logging, paths, sounds, transcription};
 
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
-use platform::{PlatformRegistry, YouTubeProvider};
+use platform::{PlatformRegistry, YouTubeProvider, RedditProvider, BandcampProvider, KickProvider, PeerTubeProvider, GenericProvider, DirectUrlProvider};
 use download::DownloadManager;
-use storage::StorageService;
+use events::{EventRouter, TauriEventSink};
+use sounds::SoundEventSink;
+use transcription::TranscriptionService;
+use paths::TauriPathProvider;
+use storage::{HistoryStore, StorageService};
 use executable_manager::ExecutableManager;
 
 #[derive(Clone)]
@@ -18,6 +22,10 @@ pub struct AppState {
     download_manager: Arc<DownloadManager>,
     storage_service: Arc<StorageService>,
     metadata_cache: Arc<platform::MetadataCache>,
+    metadata_rate_limiter: Arc<platform::RateLimiter>,
+    info_requests: Arc<platform::InfoRequestRegistry>,
+    events: Arc<EventRouter>,
+    sounds: Arc<SoundEventSink>,
 }
 
 /// Initialize the application with all required services and state
@@ -49,12 +57,22 @@ fn initialize_app(app_handle: AppHandle) -> Result<(), Box<dyn std::error::Error
     println!("  ✓ yt-dlp path: {:?}", ytdlp_path);
     println!("  ✓ ffmpeg path: {:?}", ffmpeg_path);
     
+    // Optional whisper.cpp for transcribing videos without subtitles
+    let transcription_service = TranscriptionService::new(
+        executable_manager.get_whisper_path(),
+        executable_manager.get_whisper_model_path(),
+        ffmpeg_path.clone(),
+    );
+    if transcription_service.is_available() {
+        println!("  ✓ whisper path: {:?}", executable_manager.get_whisper_path());
+    }
+    
     // Step 1: Initialize platform registry and register all providers
     println!("Registering platform providers...");
     let mut platform_registry = PlatformRegistry::new();
     
     // Register YouTube provider with bundled executables
-    let youtube_provider = Arc::new(YouTubeProvider::with_executables(ytdlp_path, ffmpeg_path));
+    let youtube_provider = Arc::new(YouTubeProvider::with_executables(ytdlp_path.clone(), ffmpeg_path.clone()));
     
     // Log versions at startup
     let provider_clone = Arc::clone(&youtube_provider);
@@ -65,9 +83,41 @@ fn initialize_app(app_handle: AppHandle) -> Result<(), Box<dyn std::error::Error
     platform_registry.register(youtube_provider);
     println!("  ✓ YouTube provider registered");
     
+    // Register Reddit provider (needs ffmpeg to merge separate video/audio streams)
+    platform_registry.register(Arc::new(RedditProvider::with_executables(ytdlp_path.clone(), ffmpeg_path.clone())));
+    println!("  ✓ Reddit provider registered");
+    
+    // Register Bandcamp provider (audio extraction and tagging need ffmpeg)
+    platform_registry.register(Arc::new(BandcampProvider::with_executables(ytdlp_path.clone(), ffmpeg_path.clone())));
+    println!("  ✓ Bandcamp provider registered");
+    
+    // Register Kick provider (VODs and clips; live streams are rejected)
+    platform_registry.register(Arc::new(KickProvider::with_executables(ytdlp_path.clone(), ffmpeg_path.clone())));
+    println!("  ✓ Kick provider registered");
+    
+    // Register PeerTube provider (any instance, confirmed by probing its API)
+    platform_registry.register(Arc::new(PeerTubeProvider::with_executables(ytdlp_path.clone(), ffmpeg_path.clone())));
+    println!("  ✓ PeerTube provider registered");
+    
+    // Register direct file provider (plain media URLs, downloaded without yt-dlp)
+    platform_registry.register(Arc::new(DirectUrlProvider::new()));
+    println!("  ✓ Direct URL provider registered");
+    
+    // Simulation provider for UI development (no network or yt-dlp required)
+    #[cfg(feature = "mock-provider")]
+    {
+        let config = platform::MockProviderConfig::from_env();
+        platform_registry.register(Arc::new(platform::MockProvider::new(config)));
+        println!("  ✓ Mock provider registered");
+    }
+
     // Future providers can be registered here:
     // platform_registry.register(Arc::new(BilibiliProvider::new()));
     
+    // Generic fallback must be registered last so specific providers win detection
+    platform_registry.register(Arc::new(GenericProvider::with_executables(ytdlp_path, ffmpeg_path)));
+    println!("  ✓ Generic provider registered (enabled by the allow_generic_sites setting)");
+    
     let platform_registry = Arc::new(platform_registry);
     
     // Step 2: Initialize storage service
@@ -86,26 +136,94 @@ fn initialize_app(app_handle: AppHandle) -> Result<(), Box<dyn std::error::Error
             storage::AppSettings::default()
         });
     println!("  ✓ Settings loaded");
+    GenericProvider::apply_setting(&platform_registry, settings.allow_generic_sites);
+    DirectUrlProvider::apply_dns_setting(&platform_registry, &settings.dns_over_https);
+    platform_registry.apply_metadata_concurrency(&settings.metadata_concurrency);
+    platform_registry.apply_network_settings(&settings.proxy, &settings.http_headers);
+    YouTubeProvider::apply_cookies_setting(
+        &platform_registry,
+        settings.cookies_file_path.as_deref(),
+        settings.cookies_from_browser.as_deref(),
+    );
+    YouTubeProvider::apply_po_token_setting(&platform_registry, &settings.youtube_po_token);
     
     // Step 4: Initialize download manager
     println!("Initializing download manager...");
+    let events = Arc::new(EventRouter::new());
+    events.add_sink(Arc::new(TauriEventSink::new(app_handle.clone())));
+    let sounds = Arc::new(SoundEventSink::new(settings.sounds.clone()));
+    events.add_sink(sounds.clone());
+    logging::set_event_sink(events.clone());
+    logging::set_settings(settings.logging.clone());
+    if let Some(dir) = app_handle.path_resolver().app_log_dir() {
+        logging::set_log_file(dir.join("vortex.log"));
+    }
     let download_manager = Arc::new(DownloadManager::new(
-        app_handle.clone(),
+        Arc::new(TauriPathProvider::new(app_handle.clone())),
+        events.clone(),
         Arc::clone(&platform_registry),
     ));
     
     // Set max concurrent downloads from settings
     let max_concurrent = settings.max_concurrent_downloads;
-    let dm_clone = Arc::clone(&download_manager);
-    tauri::async_runtime::spawn(async move {
-        dm_clone.set_max_concurrent(max_concurrent).await;
-    });
+    let mp4_faststart = settings.mp4_faststart;
+    let embed_chapters = settings.embed_chapters;
+    let concurrent_fragments = settings.concurrent_fragments;
+    let ytdlp_retries = settings.ytdlp_retries;
+    let use_download_archive = settings.use_download_archive;
+    let min_free_space = settings.min_free_space_mb * 1024 * 1024;
+    let mixing_policy = settings.queue_mixing_policy;
+    let quick_wins = settings.queue_quick_wins;
+    let global_speed_limit = settings.global_speed_limit_bytes();
+    let helper_priority = settings.helper_process_priority;
+    let proxy = settings.proxy.clone();
+    let http_headers = settings.http_headers.clone();
+    let audio = settings.audio.clone();
+    let transcription = settings.transcription.clone();
+    let inbox = settings.inbox.clone();
+    let history: Arc<dyn HistoryStore> = storage_service.clone();
+    let platform_settings: Vec<_> = platform_registry
+        .get_all_providers()
+        .iter()
+        .map(|p| {
+            let saved = storage_service.load_platform_settings(p.name()).unwrap_or_else(|e| {
+                eprintln!("Warning: Failed to load {} settings, using defaults: {}", p.name(), e);
+                Default::default()
+            });
+            (p.name().to_string(), saved)
+        })
+        .collect();
+    download_manager.set_postprocess_limits(settings.postprocess_limits);
+    formatting::set_locale(settings.locale.as_deref());
     println!("  ✓ Download manager initialized (max concurrent: {})", max_concurrent);
     
-    // Step 5: Restore previous queue state
+    // Step 5: Restore previous queue state, only once the settings are applied so
+    // resumed items don't start under the defaults
     println!("Restoring download queue...");
     let dm_clone = Arc::clone(&download_manager);
     tauri::async_runtime::spawn(async move {
+        dm_clone.set_max_concurrent(max_concurrent).await;
+        dm_clone.set_mp4_faststart(mp4_faststart).await;
+        dm_clone.set_concurrent_fragments(concurrent_fragments).await;
+        dm_clone.set_ytdlp_retries(ytdlp_retries).await;
+        dm_clone.set_embed_chapters(embed_chapters).await;
+        dm_clone.set_download_archive(use_download_archive).await;
+        dm_clone.set_min_free_space(min_free_space).await;
+        dm_clone.set_mixing_policy(mixing_policy).await;
+        dm_clone.set_quick_wins(quick_wins).await;
+        dm_clone.set_global_speed_limit(global_speed_limit).await;
+        dm_clone.set_helper_priority(helper_priority).await;
+        dm_clone.set_proxy_settings(proxy.with_system_proxy().await).await;
+        dm_clone.set_http_headers(http_headers).await;
+        dm_clone.set_audio_settings(audio).await;
+        dm_clone.set_transcription_service(transcription_service).await;
+        dm_clone.set_transcription_settings(transcription).await;
+        dm_clone.set_inbox_settings(inbox).await;
+        dm_clone.set_history_store(history).await;
+        for (platform, saved) in platform_settings {
+            dm_clone.set_platform_settings(&platform, saved).await;
+        }
+        
         match dm_clone.restore_queue_state().await {
             Ok(_) => {
                 let queue = dm_clone.get_queue_status().await;
@@ -121,6 +239,8 @@ fn initialize_app(app_handle: AppHandle) -> Result<(), Box<dyn std::error::Error
     println!("Initializing metadata cache...");
     let metadata_cache = Arc::new(platform::MetadataCache::with_default_ttl());
     println!("  ✓ Metadata cache initialized (TTL: 5 minutes)");
+    let metadata_rate_limiter = Arc::new(platform::RateLimiter::with_default_interval());
+    let info_requests = Arc::new(platform::InfoRequestRegistry::new());
     
     // Step 7: Store state in Tauri's managed state
     app_handle.manage(AppState {
@@ -128,6 +248,10 @@ fn initialize_app(app_handle: AppHandle) -> Result<(), Box<dyn std::error::Error
         download_manager,
         storage_service,
         metadata_cache,
+        metadata_rate_limiter,
+        info_requests,
+        events,
+        sounds,
     });
     
     println!("✓ Application initialization complete");
@@ -148,24 +272,68 @@ fn main() {
             commands::detect_platform,
             commands::get_supported_platforms,
             commands::get_video_info,
+            commands::get_videos_info,
             commands::get_playlist_info,
             commands::get_channel_info,
+            commands::diff_playlist,
+            commands::diff_channel,
+            commands::export_m3u,
+            commands::cancel_info_request,
             commands::add_to_download_queue,
+            commands::add_quality_variants,
+            commands::get_variant_group_progress,
             commands::pause_download,
             commands::resume_download,
             commands::cancel_download,
+            commands::retry_download,
+            commands::pause_all,
+            commands::resume_all,
+            commands::cancel_all,
+            commands::remove_from_queue,
+            commands::clear_completed,
+            commands::clear_failed,
+            commands::clear_queue,
             commands::reorder_queue,
+            commands::subscribe_progress,
+            commands::unsubscribe_progress,
+            commands::get_dashboard_snapshot,
+            commands::get_error_stats,
+            commands::get_platform_stats,
+            commands::export_diagnostics,
             commands::get_settings,
             commands::save_settings,
+            commands::get_platform_settings,
+            commands::get_platform_setting_definitions,
+            commands::save_platform_settings,
             commands::select_directory,
+            commands::select_cookies_file,
+            commands::benchmark_destination,
+            commands::reset_download_archive,
+            commands::export_download_archive,
+            commands::validate_cookies_file_path,
+            commands::list_available_browsers,
             commands::check_dependencies,
             commands::verify_bundled_executables,
             commands::check_homebrew_installed,
             commands::install_ytdlp_via_homebrew,
             commands::check_ytdlp_update,
             commands::update_ytdlp,
+            commands::rollback_ytdlp,
             commands::test_ytdlp,
             commands::get_dependency_versions,
+            commands::find_duplicate_media,
+            commands::get_inbox,
+            commands::approve_inbox_items,
+            commands::reject_inbox_items,
+            commands::set_download_starred,
+            commands::get_collections,
+            commands::save_collection,
+            commands::delete_collection,
+            commands::get_collection_downloads,
+            commands::get_chapters,
+            commands::save_chapters,
+            commands::suggest_sponsorblock_chapters,
+            commands::get_preview_frames,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::error::{DownloadError, Result};
+use crate::platform::{CommandRequest, CommandRunner, SystemCommandRunner};
+use crate::log_warn;
+
+/// Seconds of audio analysed per file
+const ANALYSIS_SECONDS: u32 = 180;
+
+/// Samples per analysis window at 8 kHz (100 ms)
+const WINDOW_SAMPLES: u32 = 800;
+
+/// Fingerprints shorter than this many windows are too short to compare reliably
+const MIN_WINDOWS: usize = 100;
+
+/// Windows of misalignment tolerated between two files (2 s)
+const MAX_OFFSET: usize = 20;
+
+/// Default similarity above which two files are treated as the same content
+pub const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.9;
+
+/// Coarse audio fingerprint: one bit per window, set when loudness rose from the previous window
+///
+/// Loudness contours survive re-encoding, bitrate and container changes, so the
+/// same recording downloaded in different qualities yields near-identical bits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioFingerprint {
+    pub bits: Vec<bool>,
+}
+
+impl AudioFingerprint {
+    /// Build a fingerprint from per-window RMS levels in dB
+    pub fn from_levels(levels: &[f64]) -> Self {
+        Self {
+            bits: levels.windows(2).map(|w| w[1] > w[0]).collect(),
+        }
+    }
+    
+    /// Best fraction of matching bits over small alignment offsets, 0.0 if either is too short
+    pub fn similarity(&self, other: &AudioFingerprint) -> f64 {
+        if self.bits.len() < MIN_WINDOWS || other.bits.len() < MIN_WINDOWS {
+            return 0.0;
+        }
+        
+        let score = |a: &[bool], b: &[bool]| {
+            let len = a.len().min(b.len());
+            if len < MIN_WINDOWS {
+                return 0.0;
+            }
+            let matching = a.iter().zip(b).filter(|(x, y)| x == y).count();
+            matching as f64 / len as f64
+        };
+        
+        (0..=MAX_OFFSET)
+            .flat_map(|offset| {
+                [
+                    score(self.bits.get(offset..).unwrap_or_default(), &other.bits),
+                    score(&self.bits, other.bits.get(offset..).unwrap_or_default()),
+                ]
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Files that appear to hold the same content
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DuplicateGroup {
+    pub files: Vec<String>,
+    /// Largest file in the group, usually the highest quality copy
+    pub suggested_keep: String,
+    /// Lowest pairwise similarity that linked the group
+    pub similarity: f64,
+}
+
+/// Parse `ametadata` output into per-window RMS levels, mapping silence (-inf) to -120 dB
+pub fn parse_rms_levels(output: &str) -> Vec<f64> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("lavfi.astats.Overall.RMS_level="))
+        .map(|value| match value.trim() {
+            "-inf" | "inf" | "nan" => -120.0,
+            v => v.parse().unwrap_or(-120.0),
+        })
+        .collect()
+}
+
+/// Content-level duplicate detection over downloaded files using ffmpeg
+pub struct MediaDedupeService {
+    ffmpeg_path: PathBuf,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl MediaDedupeService {
+    pub fn new(ffmpeg_path: PathBuf) -> Self {
+        Self {
+            ffmpeg_path,
+            runner: Arc::new(SystemCommandRunner),
+        }
+    }
+    
+    /// Replace the process runner (used by tests)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.runner = runner;
+        self
+    }
+    
+    /// Fingerprint the audio track of a media file
+    pub async fn fingerprint(&self, path: &Path) -> Result<AudioFingerprint> {
+        let filter = format!(
+            "aresample=8000,asetnsamples=n={}:p=0,astats=metadata=1:reset=1,ametadata=mode=print:key=lavfi.astats.Overall.RMS_level:file=-",
+            WINDOW_SAMPLES
+        );
+        let args = vec![
+            "-hide_banner".to_string(),
+            "-nostats".to_string(),
+            "-i".to_string(),
+            path.to_string_lossy().to_string(),
+            "-t".to_string(),
+            ANALYSIS_SECONDS.to_string(),
+            "-vn".to_string(),
+            "-ac".to_string(),
+            "1".to_string(),
+            "-af".to_string(),
+            filter,
+            "-f".to_string(),
+            "null".to_string(),
+            "-".to_string(),
+        ];
+        
+        let output = self
+            .runner
+            .output(&CommandRequest::new(&self.ffmpeg_path, args))
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => DownloadError::DependencyMissing("ffmpeg".to_string()),
+                _ => DownloadError::Io(e),
+            })?;
+        
+        if !output.success() {
+            return Err(DownloadError::DownloadFailed(format!(
+                "ffmpeg could not analyse {}: {}",
+                path.display(),
+                output.stderr.lines().last().unwrap_or("unknown error")
+            )));
+        }
+        
+        Ok(AudioFingerprint::from_levels(&parse_rms_levels(&output.stdout)))
+    }
+    
+    /// Group files whose audio fingerprints match above `threshold`
+    ///
+    /// Files that cannot be analysed (missing, no audio track) are skipped.
+    pub async fn find_duplicates(&self, paths: &[PathBuf], threshold: f64) -> Vec<DuplicateGroup> {
+        let mut fingerprints = Vec::new();
+        for path in paths {
+            match self.fingerprint(path).await {
+                Ok(fp) => fingerprints.push((path.clone(), fp)),
+                Err(e) => log_warn!("[MediaDedupe] Skipping {}: {}", path.display(), e),
+            }
+        }
+        
+        group_duplicates(&fingerprints, threshold)
+    }
+}
+
+/// Link fingerprints pairwise and collect connected groups of two or more files
+fn group_duplicates(fingerprints: &[(PathBuf, AudioFingerprint)], threshold: f64) -> Vec<DuplicateGroup> {
+    let count = fingerprints.len();
+    let mut parent: Vec<usize> = (0..count).collect();
+    let mut min_similarity = vec![1.0f64; count];
+    
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    
+    for i in 0..count {
+        for j in (i + 1)..count {
+            let similarity = fingerprints[i].1.similarity(&fingerprints[j].1);
+            if similarity >= threshold {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                if a != b {
+                    parent[b] = a;
+                }
+                min_similarity[a] = min_similarity[a].min(min_similarity[b]).min(similarity);
+            }
+        }
+    }
+    
+    let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+    for i in 0..count {
+        let r = root(&mut parent, i);
+        match groups.iter_mut().find(|(g, _)| *g == r) {
+            Some((_, members)) => members.push(i),
+            None => groups.push((r, vec![i])),
+        }
+    }
+    
+    groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(r, members)| {
+            let files: Vec<String> = members
+                .iter()
+                .map(|&i| fingerprints[i].0.to_string_lossy().to_string())
+                .collect();
+            let suggested_keep = members
+                .iter()
+                .max_by_key(|&&i| std::fs::metadata(&fingerprints[i].0).map(|m| m.len()).unwrap_or(0))
+                .map(|&i| fingerprints[i].0.to_string_lossy().to_string())
+                .unwrap_or_default();
+            DuplicateGroup {
+                files,
+                suggested_keep,
+                similarity: min_similarity[r],
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{MockCommandRunner, MockResponse};
+    
+    /// Deterministic pseudo-random loudness curve
+    fn levels(seed: u64, len: usize) -> Vec<f64> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                -60.0 + (state >> 33) as f64 % 50.0
+            })
+            .collect()
+    }
+    
+    fn ametadata_output(levels: &[f64]) -> String {
+        levels
+            .iter()
+            .enumerate()
+            .flat_map(|(i, l)| {
+                vec![
+                    format!("frame:{} pts:{} pts_time:{}", i, i * 800, i as f64 / 10.0),
+                    format!("lavfi.astats.Overall.RMS_level={}", l),
+                ]
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_parse_rms_levels_handles_silence() {
+        let output = "frame:0 pts:0 pts_time:0\nlavfi.astats.Overall.RMS_level=-23.5\nframe:1 pts:800 pts_time:0.1\nlavfi.astats.Overall.RMS_level=-inf\n";
+        assert_eq!(parse_rms_levels(output), vec![-23.5, -120.0]);
+    }
+
+    #[test]
+    fn test_similarity_tolerates_offset_and_rejects_different_content() {
+        let original = levels(7, 600);
+        let a = AudioFingerprint::from_levels(&original);
+        // Same content starting half a second later, e.g. a trimmed intro
+        let b = AudioFingerprint::from_levels(&original[5..]);
+        let c = AudioFingerprint::from_levels(&levels(99, 600));
+        
+        assert!(a.similarity(&b) > 0.99);
+        assert!(a.similarity(&c) < 0.7);
+        assert_eq!(a.similarity(&AudioFingerprint::from_levels(&original[..50])), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_find_duplicates_groups_matching_files() {
+        let shared = levels(1, 400);
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![
+            MockResponse::success(&ametadata_output(&shared)),
+            MockResponse::success(&ametadata_output(&levels(2, 400))),
+            MockResponse::success(&ametadata_output(&shared[3..])),
+            MockResponse::failure(1, "Output file #0 does not contain any stream"),
+        ]));
+        let service = MediaDedupeService::new(PathBuf::from("ffmpeg")).with_command_runner(runner.clone());
+        
+        let paths: Vec<PathBuf> = ["/a/720p.mp4", "/a/other.mp4", "/a/1080p.mkv", "/a/silent.mp4"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let groups = service.find_duplicates(&paths, DEFAULT_SIMILARITY_THRESHOLD).await;
+        
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].files, vec!["/a/720p.mp4", "/a/1080p.mkv"]);
+        assert_eq!(runner.calls().len(), 4);
+    }
+}
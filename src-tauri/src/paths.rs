@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// Where the backend keeps its own files (saved queue, error log, ...)
+///
+/// Lets library components run without a Tauri app, e.g. in tests or a CLI.
+pub trait PathProvider: Send + Sync {
+    /// App data directory, if one is available
+    fn app_data_dir(&self) -> Option<PathBuf>;
+}
+
+/// Paths resolved by Tauri for the running app
+pub struct TauriPathProvider {
+    app_handle: AppHandle,
+}
+
+impl TauriPathProvider {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl PathProvider for TauriPathProvider {
+    fn app_data_dir(&self) -> Option<PathBuf> {
+        self.app_handle.path_resolver().app_data_dir()
+    }
+}
+
+/// A fixed app data directory
+pub struct FixedPathProvider {
+    app_data_dir: PathBuf,
+}
+
+impl FixedPathProvider {
+    pub fn new(app_data_dir: impl Into<PathBuf>) -> Self {
+        Self { app_data_dir: app_data_dir.into() }
+    }
+}
+
+impl PathProvider for FixedPathProvider {
+    fn app_data_dir(&self) -> Option<PathBuf> {
+        Some(self.app_data_dir.clone())
+    }
+}
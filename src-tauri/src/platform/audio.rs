@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use crate::error::{DownloadError, Result};
+
+/// Output format of audio-only downloads
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    #[default]
+    Mp3,
+    Opus,
+    M4a,
+    Flac,
+    Wav,
+}
+
+impl AudioFormat {
+    /// Name passed to yt-dlp's `--audio-format`, also the file extension
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::Opus => "opus",
+            AudioFormat::M4a => "m4a",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Wav => "wav",
+        }
+    }
+    
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_lowercase().as_str() {
+            "mp3" => Some(AudioFormat::Mp3),
+            "opus" => Some(AudioFormat::Opus),
+            "m4a" => Some(AudioFormat::M4a),
+            "flac" => Some(AudioFormat::Flac),
+            "wav" => Some(AudioFormat::Wav),
+            _ => None,
+        }
+    }
+    
+    /// Audio format for an audio-only download that asked for `container`
+    ///
+    /// An audio format is kept; a video container maps to the codec it usually
+    /// carries (`mp4` → `m4a`, `webm` → `opus`); anything else gets the default.
+    pub fn for_container(container: &str) -> Self {
+        Self::parse(container).unwrap_or_else(|| match container.to_lowercase().as_str() {
+            "mp4" | "m4v" | "mov" => AudioFormat::M4a,
+            "webm" | "mkv" => AudioFormat::Opus,
+            _ => AudioFormat::default(),
+        })
+    }
+    
+    /// Lossless formats ignore the bitrate setting
+    pub fn is_lossless(&self) -> bool {
+        matches!(self, AudioFormat::Flac | AudioFormat::Wav)
+    }
+    
+    /// yt-dlp format selector preferring a source stream that converts without re-encoding
+    pub fn source_selector(&self) -> &'static str {
+        match self {
+            AudioFormat::Opus => "bestaudio[acodec=opus]/bestaudio",
+            AudioFormat::M4a => "bestaudio[ext=m4a]/bestaudio",
+            _ => "bestaudio",
+        }
+    }
+}
+
+/// Default format and bitrate for audio-only downloads
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct AudioSettings {
+    #[serde(default)]
+    pub format: AudioFormat,
+    /// yt-dlp `--audio-quality`: a VBR level from `0` (best) to `10`, or a bitrate like `192K`.
+    /// yt-dlp's default (`5`) when unset
+    #[serde(default)]
+    pub quality: Option<String>,
+}
+
+impl AudioSettings {
+    pub fn validate(&self) -> Result<()> {
+        match &self.quality {
+            Some(quality) => validate_audio_quality(quality),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Check an `--audio-quality` value: `0`-`10`, or a bitrate between 8K and 512K
+pub fn validate_audio_quality(quality: &str) -> Result<()> {
+    let invalid = || DownloadError::InvalidOption(format!(
+        "Invalid audio quality '{}': use 0 (best) to 10, or a bitrate like 192K",
+        quality
+    ));
+    
+    if let Ok(level) = quality.parse::<u32>() {
+        return if level <= 10 { Ok(()) } else { Err(invalid()) };
+    }
+    let kbps = quality
+        .strip_suffix(['K', 'k'])
+        .and_then(|n| n.parse::<u32>().ok())
+        .ok_or_else(invalid)?;
+    if (8..=512).contains(&kbps) {
+        Ok(())
+    } else {
+        Err(invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_validate_audio_quality() {
+        for quality in ["0", "10", "192K", "320k"] {
+            assert!(validate_audio_quality(quality).is_ok(), "{}", quality);
+        }
+        for quality in ["11", "-1", "192", "1000K", "best", ""] {
+            assert!(validate_audio_quality(quality).is_err(), "{}", quality);
+        }
+    }
+
+    #[test]
+    fn test_audio_format_for_container() {
+        assert_eq!(AudioFormat::for_container("flac"), AudioFormat::Flac);
+        assert_eq!(AudioFormat::for_container("MP4"), AudioFormat::M4a);
+        assert_eq!(AudioFormat::for_container("webm"), AudioFormat::Opus);
+        assert_eq!(AudioFormat::for_container("avi"), AudioFormat::Mp3);
+    }
+}
@@ -0,0 +1,411 @@
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use super::command_runner::CommandRunner;
+use super::metadata_limit::MetadataLimit;
+use super::provider::*;
+use super::ytdlp_backend::{exit_error, network_options, MetadataNetwork, YtDlpBackend};
+use super::ytdlp_command::{validate_postprocessor_args, YtDlpCommandBuilder};
+use crate::error::{DownloadError, Result};
+use crate::log_info;
+
+/// Audio formats yt-dlp can convert to; anything else falls back to mp3
+const AUDIO_FORMATS: &[&str] = &["mp3", "m4a", "aac", "flac", "opus", "vorbis", "wav", "alac"];
+
+/// Bandcamp track and album provider
+///
+/// Downloads the best available audio, converts it to the requested audio
+/// format and embeds artist/album/track-number tags. Albums map to
+/// `PlaylistInfo` with one entry per track, in album order.
+pub struct BandcampProvider {
+    backend: YtDlpBackend,
+    ffmpeg_path: PathBuf,
+    track_pattern: Regex,
+    album_pattern: Regex,
+}
+
+impl BandcampProvider {
+    pub fn new() -> Self {
+        Self::with_executables(PathBuf::from("yt-dlp"), PathBuf::from("ffmpeg"))
+    }
+    
+    /// Create a new BandcampProvider with custom executable paths
+    pub fn with_executables(ytdlp_path: PathBuf, ffmpeg_path: PathBuf) -> Self {
+        Self {
+            backend: YtDlpBackend::new("Bandcamp", ytdlp_path)
+                .unavailable_when(&["404", "Unable to extract"]),
+            ffmpeg_path,
+            track_pattern: Regex::new(r"^https?://[\w-]+\.bandcamp\.com/track/[\w-]+").unwrap(),
+            album_pattern: Regex::new(r"^https?://[\w-]+\.bandcamp\.com/album/[\w-]+").unwrap(),
+        }
+    }
+    
+    /// Replace the process runner (used by tests to replay canned output)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.backend = self.backend.with_command_runner(runner);
+        self
+    }
+    
+    /// Parse one track's yt-dlp JSON
+    fn parse_track(json: &Value, fallback_url: &str) -> VideoInfo {
+        let formats = json["formats"]
+            .as_array()
+            .map(|formats| {
+                formats
+                    .iter()
+                    .filter_map(|format| {
+                        Some(FormatInfo {
+                            format_id: format["format_id"].as_str()?.to_string(),
+                            ext: format["ext"].as_str().unwrap_or("mp3").to_string(),
+                            resolution: None,
+                            filesize: format["filesize"]
+                                .as_u64()
+                                .or_else(|| format["filesize_approx"].as_u64()),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        
+        let title = json["track"]
+            .as_str()
+            .or_else(|| json["title"].as_str())
+            .unwrap_or("Bandcamp Track");
+        let title = match json["track_number"].as_u64() {
+            Some(number) => format!("{:02}. {}", number, title),
+            None => title.to_string(),
+        };
+        
+        VideoInfo {
+            id: json["id"].as_str().map(|s| s.to_string())
+                .or_else(|| json["id"].as_u64().map(|n| n.to_string()))
+                .unwrap_or_default(),
+            title,
+            description: json["album"].as_str().unwrap_or("").to_string(),
+            thumbnail: json["thumbnail"].as_str().unwrap_or("").to_string(),
+            duration: json["duration"].as_f64().map(|d| d.round() as u64).unwrap_or(0),
+            uploader: json["artist"]
+                .as_str()
+                .or_else(|| json["uploader"].as_str())
+                .unwrap_or("Unknown")
+                .to_string(),
+            upload_date: json["upload_date"].as_str().unwrap_or("").to_string(),
+            view_count: 0,
+            available_formats: formats,
+            platform: "Bandcamp".to_string(),
+            url: json["webpage_url"].as_str().unwrap_or(fallback_url).to_string(),
+            is_live: false,
+            premiere_at: None,
+        }
+    }
+    
+    /// Build an album playlist from one JSON object per track
+    fn parse_album(output: &str, url: &str) -> Result<PlaylistInfo> {
+        let tracks: Vec<Value> = output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        
+        let first = tracks.first()
+            .ok_or_else(|| DownloadError::VideoUnavailable(format!("No tracks found in album: {}", url)))?;
+        
+        let videos: Vec<VideoInfo> = tracks.iter().map(|t| Self::parse_track(t, url)).collect();
+        
+        Ok(PlaylistInfo {
+            id: first["playlist_id"].as_str().unwrap_or("").to_string(),
+            title: first["album"]
+                .as_str()
+                .or_else(|| first["playlist_title"].as_str())
+                .unwrap_or("Bandcamp Album")
+                .to_string(),
+            description: String::new(),
+            uploader: first["album_artist"]
+                .as_str()
+                .or_else(|| first["artist"].as_str())
+                .unwrap_or("Unknown")
+                .to_string(),
+            video_count: videos.len(),
+            videos,
+            platform: "Bandcamp".to_string(),
+            url: url.to_string(),
+            has_more: false,
+            page: 0,
+            page_size: 0,
+        })
+    }
+    
+    /// Pick the audio format to convert to
+    fn audio_format(options: &DownloadOptions) -> &str {
+        if AUDIO_FORMATS.contains(&options.format.as_str()) {
+            &options.format
+        } else {
+            "mp3"
+        }
+    }
+    
+    /// Resolve the directory downloads should be written to
+    fn output_dir(save_path: &Path) -> PathBuf {
+        if save_path.is_dir() {
+            save_path.to_path_buf()
+        } else {
+            save_path
+                .parent()
+                .map(|p| p.to_path_buf())
+                .unwrap_or_else(|| PathBuf::from("."))
+        }
+    }
+    
+    /// Internal download implementation with cancellation support
+    async fn download_video_impl(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<()> {
+        // Audio conversion and tagging run through ffmpeg
+        if !self.ffmpeg_path.exists() {
+            return Err(DownloadError::DependencyMissing(
+                format!("ffmpeg not found at: {:?}", self.ffmpeg_path)
+            ));
+        }
+        
+        let output_dir = Self::output_dir(save_path);
+        tokio::fs::create_dir_all(&output_dir).await?;
+        let template = output_dir.join("%(artist,uploader)s - %(track,title)s.%(ext)s");
+        let template = template.to_str()
+            .ok_or_else(|| DownloadError::DownloadFailed(format!("Invalid save path: {:?}", save_path)))?;
+        
+        let mut command = YtDlpCommandBuilder::new()
+            .machine_progress()
+            .no_warnings()
+            .no_playlist()
+            .output(template)
+            .ffmpeg_location(&self.ffmpeg_path)
+            .format("bestaudio/best")
+            .extract_audio(Self::audio_format(&options))
+            .tag_audio(Self::audio_format(&options), &options.tags);
+        
+        if let Some(ppa) = options.postprocessor_args.as_deref() {
+            command = command.postprocessor_args(&validate_postprocessor_args(ppa)?);
+        }
+        
+        if let Some(threads) = options.ffmpeg_threads() {
+            command = command.ffmpeg_threads(threads);
+        }
+        
+        command = network_options(command, &options);
+        
+        if let Some(archive) = &options.download_archive {
+            command = command.download_archive(archive);
+        }
+        
+        let command = command.url(url);
+        
+        let output = self.backend
+            .download(&command, save_path, &options, progress_callback, cancel_token.as_ref())
+            .await?;
+        
+        if !output.success() {
+            if output.stderr.contains("purchase") || output.stderr.contains("No formats") {
+                return Err(DownloadError::VideoUnavailable(
+                    "This track is not available for streaming on Bandcamp".to_string()
+                ));
+            }
+            return Err(exit_error(&output));
+        }
+        
+        log_info!("[Bandcamp] ✓ Download completed: {}", url);
+        Ok(())
+    }
+    
+    /// Download video with cancellation support (public method for download manager)
+    pub async fn download_with_cancellation(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: CancellationToken,
+    ) -> Result<()> {
+        self.download_video_impl(url, options, save_path, progress_callback, Some(cancel_token)).await
+    }
+}
+
+#[async_trait]
+impl PlatformProvider for BandcampProvider {
+    fn name(&self) -> &str {
+        "Bandcamp"
+    }
+    
+    fn matches_url(&self, url: &str) -> bool {
+        let url = url.trim();
+        self.track_pattern.is_match(url) || self.album_pattern.is_match(url)
+    }
+    
+    fn supported_patterns(&self) -> Vec<String> {
+        vec![
+            "https://ARTIST.bandcamp.com/track/TRACK".to_string(),
+            "https://ARTIST.bandcamp.com/album/ALBUM".to_string(),
+        ]
+    }
+    
+    async fn get_video_info(&self, url: &str) -> Result<VideoInfo> {
+        if !self.track_pattern.is_match(url.trim()) {
+            return Err(DownloadError::InvalidUrl(format!("Not a Bandcamp track URL: {}", url)));
+        }
+        
+        let json = self.backend
+            .run_json(&YtDlpCommandBuilder::new().dump_json().no_playlist().url(url))
+            .await?;
+        
+        Ok(Self::parse_track(&json, url))
+    }
+    
+    async fn get_playlist_info(&self, url: &str) -> Result<PlaylistInfo> {
+        if !self.album_pattern.is_match(url.trim()) {
+            return Err(DownloadError::InvalidUrl(format!("Not a Bandcamp album URL: {}", url)));
+        }
+        
+        // Full (non-flat) extraction so every track carries its number and duration
+        let output = self.backend.run(&YtDlpCommandBuilder::new().dump_json().url(url)).await?;
+        Self::parse_album(&output, url)
+    }
+    
+    async fn get_channel_info(&self, url: &str) -> Result<ChannelInfo> {
+        Err(DownloadError::InvalidUrl(format!("Bandcamp artist pages are not supported, use an album URL: {}", url)))
+    }
+    
+    async fn download_video(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+    ) -> Result<()> {
+        self.download_video_impl(url, options, save_path, progress_callback, None).await
+    }
+    
+    async fn check_dependencies(&self) -> Result<Vec<Dependency>> {
+        Ok(vec![
+            Dependency {
+                name: "yt-dlp (bundled)".to_string(),
+                installed: self.backend.ytdlp_path().exists(),
+                version: None,
+                install_instructions: "yt-dlp is bundled with the application. If missing, please reinstall the application.".to_string(),
+            },
+            Dependency {
+                name: "ffmpeg (bundled)".to_string(),
+                installed: self.ffmpeg_path.exists(),
+                version: None,
+                install_instructions: "ffmpeg is required to convert and tag Bandcamp audio. If missing, please reinstall the application.".to_string(),
+            },
+        ])
+    }
+    
+    fn get_platform_settings(&self) -> Vec<PlatformSetting> {
+        Vec::new()
+    }
+    
+    fn metadata_limit(&self) -> Option<&MetadataLimit> {
+        Some(self.backend.metadata_limit())
+    }
+    
+    fn metadata_network(&self) -> Option<&MetadataNetwork> {
+        Some(self.backend.metadata_network())
+    }
+    
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl Default for BandcampProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::command_runner::{MockCommandRunner, MockResponse};
+    
+    fn track_json(number: u64, title: &str) -> String {
+        serde_json::json!({
+            "id": format!("{}", 1000 + number),
+            "title": format!("Artist - {}", title),
+            "track": title,
+            "track_number": number,
+            "artist": "Artist",
+            "album": "Album",
+            "duration": 180.4,
+            "playlist_id": "album-slug",
+            "webpage_url": format!("https://artist.bandcamp.com/track/{}", title.to_lowercase()),
+            "formats": [{ "format_id": "mp3-128", "ext": "mp3" }]
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_matches_bandcamp_urls() {
+        let provider = BandcampProvider::new();
+        assert!(provider.matches_url("https://artist.bandcamp.com/track/song-name"));
+        assert!(provider.matches_url("https://some-artist.bandcamp.com/album/album-name"));
+        assert!(!provider.matches_url("https://artist.bandcamp.com/"));
+        assert!(!provider.matches_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+    }
+
+    #[tokio::test]
+    async fn test_album_maps_to_playlist_in_track_order() {
+        let output = format!("{}\n{}\n", track_json(1, "Intro"), track_json(2, "Second"));
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![MockResponse::success(&output)]));
+        let provider = BandcampProvider::new().with_command_runner(runner.clone());
+        
+        let album = provider
+            .get_playlist_info("https://artist.bandcamp.com/album/album-slug")
+            .await
+            .unwrap();
+        
+        assert_eq!(album.title, "Album");
+        assert_eq!(album.uploader, "Artist");
+        assert_eq!(album.video_count, 2);
+        assert_eq!(album.videos[0].title, "01. Intro");
+        assert_eq!(album.videos[1].url, "https://artist.bandcamp.com/track/second");
+        assert_eq!(album.videos[1].duration, 180);
+        assert!(!runner.calls()[0].args.contains(&"--no-playlist".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_download_requests_tagged_audio() {
+        let ffmpeg = tempfile::NamedTempFile::new().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![MockResponse::success(
+            "[vortex-progress] 500 1000 100 5\n[vortex-progress] 1000 1000 100 0",
+        )]));
+        let provider = BandcampProvider::with_executables(PathBuf::from("yt-dlp"), ffmpeg.path().to_path_buf())
+            .with_command_runner(runner.clone());
+        let updates = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = updates.clone();
+        
+        provider
+            .download_video(
+                "https://artist.bandcamp.com/track/song",
+                DownloadOptions { quality: "best".to_string(), format: "flac".to_string(), audio_only: true, ..Default::default() },
+                dir.path(),
+                Box::new(move |p| sink.lock().unwrap().push(p.percentage)),
+            )
+            .await
+            .unwrap();
+        
+        let args = &runner.calls()[0].args;
+        assert!(args.windows(2).any(|w| w[0] == "--audio-format" && w[1] == "flac"));
+        assert!(args.contains(&"--embed-metadata".to_string()));
+        assert_eq!(*updates.lock().unwrap(), vec![50.0, 100.0]);
+    }
+}
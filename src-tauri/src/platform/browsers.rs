@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Browsers yt-dlp can read cookies from: (yt-dlp name, display name, macOS app bundle, profile dir under ~/Library)
+const KNOWN_BROWSERS: &[(&str, &str, &str, &str)] = &[
+    ("safari", "Safari", "Safari.app", "Containers/com.apple.Safari"),
+    ("chrome", "Google Chrome", "Google Chrome.app", "Application Support/Google/Chrome"),
+    ("firefox", "Firefox", "Firefox.app", "Application Support/Firefox"),
+    ("brave", "Brave", "Brave Browser.app", "Application Support/BraveSoftware/Brave-Browser"),
+    ("edge", "Microsoft Edge", "Microsoft Edge.app", "Application Support/Microsoft Edge"),
+    ("chromium", "Chromium", "Chromium.app", "Application Support/Chromium"),
+    ("opera", "Opera", "Opera.app", "Application Support/com.operasoftware.Opera"),
+    ("vivaldi", "Vivaldi", "Vivaldi.app", "Application Support/Vivaldi"),
+];
+
+/// An installed browser that `--cookies-from-browser` can use
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BrowserInfo {
+    /// Name passed to yt-dlp, e.g. `chrome`
+    pub id: String,
+    pub name: String,
+    /// A profile exists, so there are cookies to read
+    pub has_profile: bool,
+    /// macOS only lets apps read Safari cookies with Full Disk Access
+    pub needs_full_disk_access: bool,
+}
+
+/// Whether `id` is a browser name yt-dlp accepts for `--cookies-from-browser`
+pub fn is_supported_browser(id: &str) -> bool {
+    KNOWN_BROWSERS.iter().any(|(known, ..)| *known == id)
+}
+
+/// Browsers installed on this Mac, in a fixed order
+pub fn list_available_browsers() -> Vec<BrowserInfo> {
+    let home = dirs::home_dir().unwrap_or_default();
+    detect_browsers(
+        &[PathBuf::from("/Applications"), home.join("Applications")],
+        &home.join("Library"),
+    )
+}
+
+/// Detect browsers from app bundles in `app_dirs` and profiles under `library_dir`
+pub fn detect_browsers(app_dirs: &[PathBuf], library_dir: &Path) -> Vec<BrowserInfo> {
+    KNOWN_BROWSERS
+        .iter()
+        .filter(|(_, _, bundle, _)| {
+            // Safari lives in /Applications on older systems and as a system app on newer ones
+            app_dirs.iter().any(|dir| dir.join(bundle).exists())
+                || (*bundle == "Safari.app" && Path::new("/System/Cryptexes/App/System/Applications/Safari.app").exists())
+        })
+        .map(|(id, name, _, profile)| BrowserInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            has_profile: library_dir.join(profile).exists(),
+            needs_full_disk_access: *id == "safari",
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_detect_browsers_from_bundles_and_profiles() {
+        let apps = tempfile::tempdir().unwrap();
+        let library = tempfile::tempdir().unwrap();
+        std::fs::create_dir(apps.path().join("Firefox.app")).unwrap();
+        std::fs::create_dir(apps.path().join("Google Chrome.app")).unwrap();
+        std::fs::create_dir_all(library.path().join("Application Support/Google/Chrome")).unwrap();
+        
+        let browsers = detect_browsers(&[apps.path().to_path_buf()], library.path());
+        let ids: Vec<&str> = browsers.iter().map(|b| b.id.as_str()).collect();
+        assert!(ids.contains(&"chrome") && ids.contains(&"firefox"));
+        assert!(!ids.contains(&"brave"));
+        
+        let chrome = browsers.iter().find(|b| b.id == "chrome").unwrap();
+        assert!(chrome.has_profile);
+        assert!(!browsers.iter().find(|b| b.id == "firefox").unwrap().has_profile);
+    }
+
+    #[test]
+    fn test_supported_browser_names() {
+        assert!(is_supported_browser("safari"));
+        assert!(!is_supported_browser("netscape"));
+    }
+}
@@ -0,0 +1,650 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+use crate::{log_debug, log_info};
+
+/// Scheduling priority for helper processes (yt-dlp and the ffmpeg it spawns)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessPriority {
+    #[default]
+    Normal,
+    /// Lowered CPU priority (nice 10) and, on macOS, the background QoS class,
+    /// so long merges don't make the machine sluggish. Inherited by child processes.
+    Background,
+}
+
+/// Niceness used for `ProcessPriority::Background`
+#[cfg(unix)]
+const BACKGROUND_NICENESS: i32 = 10;
+
+/// Environment variables helpers inherit from the app; everything else is dropped.
+/// Locale and certificate paths keep yt-dlp working, proxy variables keep
+/// user network setups working. Profile and config directories let
+/// `--cookies-from-browser` find browser profiles and the keyring.
+const INHERITED_ENV: &[&str] = &[
+    "PATH", "HOME", "USER", "TMPDIR", "TMP", "TEMP", "SYSTEMROOT",
+    "APPDATA", "LOCALAPPDATA", "USERPROFILE", "XDG_CONFIG_HOME", "XDG_DATA_HOME", "DBUS_SESSION_BUS_ADDRESS",
+    "LANG", "LC_ALL", "LC_CTYPE", "TZ",
+    "SSL_CERT_FILE", "SSL_CERT_DIR",
+    "http_proxy", "https_proxy", "no_proxy", "HTTP_PROXY", "HTTPS_PROXY", "NO_PROXY",
+];
+
+/// Highest descriptor checked when marking inherited descriptors close-on-exec
+#[cfg(unix)]
+const MAX_INHERITED_FD: nix::libc::rlim_t = 1 << 16;
+
+/// Shared slot a runner fills with the PID of the process it is streaming
+#[derive(Debug, Clone, Default)]
+pub struct ProcessSlot(Arc<AtomicU32>);
+
+impl ProcessSlot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// PID of the running process, if any
+    pub fn pid(&self) -> Option<u32> {
+        Some(self.0.load(Ordering::Relaxed)).filter(|pid| *pid != 0)
+    }
+    
+    /// Record `pid` until the returned guard is dropped
+    fn hold(&self, pid: Option<u32>) -> ProcessSlotGuard<'_> {
+        self.0.store(pid.unwrap_or(0), Ordering::Relaxed);
+        ProcessSlotGuard(self)
+    }
+}
+
+impl PartialEq for ProcessSlot {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+struct ProcessSlotGuard<'a>(&'a ProcessSlot);
+
+impl Drop for ProcessSlotGuard<'_> {
+    fn drop(&mut self) {
+        self.0.0.store(0, Ordering::Relaxed);
+    }
+}
+
+/// A process invocation: program, arguments and extra environment
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandRequest {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    /// Directory the process runs in; the system temp directory when unset
+    pub working_dir: Option<PathBuf>,
+    pub priority: ProcessPriority,
+    /// Filled with the PID while the process is streamed
+    pub process_slot: Option<ProcessSlot>,
+}
+
+impl CommandRequest {
+    pub fn new(program: &Path, args: Vec<String>) -> Self {
+        Self {
+            program: program.to_path_buf(),
+            args,
+            env: Vec::new(),
+            working_dir: None,
+            priority: ProcessPriority::Normal,
+            process_slot: None,
+        }
+    }
+    
+    /// Publish the process's PID in `slot` while it runs
+    pub fn track_process(mut self, slot: Option<ProcessSlot>) -> Self {
+        self.process_slot = slot;
+        self
+    }
+    
+    /// Add an environment variable for the process
+    pub fn env(mut self, key: &str, value: &str) -> Self {
+        self.env.push((key.to_string(), value.to_string()));
+        self
+    }
+    
+    /// Run the process in the directory it writes `output` to
+    pub fn confine_to(mut self, output: &Path) -> Self {
+        let dir = if output.is_dir() { Some(output) } else { output.parent() };
+        self.working_dir = dir.map(Path::to_path_buf);
+        self
+    }
+    
+    /// Run the process at the given scheduling priority
+    pub fn priority(mut self, priority: ProcessPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// A single line of process output
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+/// Captured result of a finished process
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandOutput {
+    /// Exit code, or None if the process was terminated by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Process execution layer used by providers
+///
+/// Providers never spawn processes directly; they go through a runner so that
+/// tests can substitute `MockCommandRunner` and replay canned output.
+/// Cancellation is reported as an `io::ErrorKind::Interrupted` error.
+#[async_trait]
+pub trait CommandRunner: Send + Sync {
+    /// Run a process to completion and capture its output
+    async fn output(&self, request: &CommandRequest) -> io::Result<CommandOutput>;
+    
+    /// Run a process, forwarding each output line as it arrives
+    async fn stream(
+        &self,
+        request: &CommandRequest,
+        on_line: &mut (dyn FnMut(OutputLine) + Send),
+        cancel_token: Option<&CancellationToken>,
+    ) -> io::Result<CommandOutput>;
+}
+
+/// Error returned when a process is cancelled
+fn cancelled_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, "process cancelled")
+}
+
+/// Resolve when the token is cancelled, or never if there is no token
+async fn wait_for_cancel(cancel_token: Option<&CancellationToken>) {
+    match cancel_token {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Build the command for `request` with the helper sandbox profile
+///
+/// Helpers get a minimal environment (`INHERITED_ENV` plus the request's own
+/// variables), run in the request's working directory, read from /dev/null and
+/// inherit no descriptors besides their stdio, so a compromised yt-dlp or
+/// ffmpeg sees as little of the app as possible.
+pub fn sandboxed_command(request: &CommandRequest) -> Command {
+    sandboxed_command_in(request, |key| std::env::var_os(key))
+}
+
+/// `sandboxed_command` with the app's environment read through `parent_env`
+fn sandboxed_command_in(request: &CommandRequest, parent_env: impl Fn(&str) -> Option<OsString>) -> Command {
+    let mut command = Command::new(&request.program);
+    command
+        .args(&request.args)
+        .env_clear()
+        .envs(INHERITED_ENV.iter().filter_map(|key| Some((*key, parent_env(key)?))))
+        .envs(request.env.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+        .current_dir(
+            request.working_dir
+                .clone()
+                .filter(|dir| dir.is_dir())
+                .unwrap_or_else(std::env::temp_dir),
+        )
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Abandoned (cancelled) callers must not leave the process running
+        .kill_on_drop(true);
+    
+    #[cfg(unix)]
+    {
+        let background = request.priority == ProcessPriority::Background;
+        // SAFETY: the closure only makes async-signal-safe getrlimit, fcntl and setpriority calls
+        unsafe {
+            command.pre_exec(move || {
+                mark_inherited_fds_cloexec();
+                if background {
+                    // Best effort: failing to lower priority must not prevent the download
+                    nix::libc::setpriority(nix::libc::PRIO_PROCESS, 0, BACKGROUND_NICENESS);
+                    #[cfg(target_os = "macos")]
+                    nix::libc::setpriority(nix::libc::PRIO_DARWIN_PROCESS, 0, nix::libc::PRIO_DARWIN_BG);
+                }
+                Ok(())
+            });
+        }
+    }
+    
+    command
+}
+
+/// Close every descriptor above stderr on exec, including ones opened without `O_CLOEXEC`
+///
+/// Runs in the forked child, so it only marks descriptors instead of closing
+/// them: the pipe std uses to report exec failures must stay open until exec.
+#[cfg(unix)]
+fn mark_inherited_fds_cloexec() {
+    use nix::libc;
+    
+    let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    // SAFETY: getrlimit only writes to `limit`
+    let max_fd = if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } == 0 {
+        limit.rlim_cur.min(MAX_INHERITED_FD)
+    } else {
+        MAX_INHERITED_FD
+    };
+    for fd in 3..max_fd as libc::c_int {
+        // SAFETY: fcntl on a descriptor that may not exist just fails with EBADF
+        unsafe {
+            let flags = libc::fcntl(fd, libc::F_GETFD);
+            if flags >= 0 && flags & libc::FD_CLOEXEC == 0 {
+                libc::fcntl(fd, libc::F_SETFD, flags | libc::FD_CLOEXEC);
+            }
+        }
+    }
+}
+
+/// Runner that spawns real processes with tokio
+#[derive(Debug, Default)]
+pub struct SystemCommandRunner;
+
+impl SystemCommandRunner {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl CommandRunner for SystemCommandRunner {
+    async fn output(&self, request: &CommandRequest) -> io::Result<CommandOutput> {
+        log_debug!("[Process] Running {:?}", request.program);
+        let output = sandboxed_command(request).output().await?;
+        
+        Ok(CommandOutput {
+            exit_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+    
+    async fn stream(
+        &self,
+        request: &CommandRequest,
+        on_line: &mut (dyn FnMut(OutputLine) + Send),
+        cancel_token: Option<&CancellationToken>,
+    ) -> io::Result<CommandOutput> {
+        let mut child = sandboxed_command(request).spawn()?;
+        log_info!("[Process] Spawned {:?} (pid {:?})", request.program, child.id());
+        let _pid = request.process_slot.as_ref().map(|slot| slot.hold(child.id()));
+        
+        let stdout = child.stdout.take()
+            .ok_or_else(|| io::Error::other("failed to capture stdout"))?;
+        let stderr = child.stderr.take()
+            .ok_or_else(|| io::Error::other("failed to capture stderr"))?;
+        
+        let mut stdout_lines = BufReader::new(stdout).lines();
+        let mut stderr_lines = BufReader::new(stderr).lines();
+        let mut stdout_open = true;
+        let mut stderr_open = true;
+        let mut output = CommandOutput::default();
+        
+        // Read both pipes concurrently so neither can fill up and block the process
+        while stdout_open || stderr_open {
+            tokio::select! {
+                line = stdout_lines.next_line(), if stdout_open => match line {
+                    Ok(Some(line)) => {
+                        output.stdout.push_str(&line);
+                        output.stdout.push('\n');
+                        on_line(OutputLine::Stdout(line));
+                    }
+                    _ => stdout_open = false,
+                },
+                line = stderr_lines.next_line(), if stderr_open => match line {
+                    Ok(Some(line)) => {
+                        output.stderr.push_str(&line);
+                        output.stderr.push('\n');
+                        on_line(OutputLine::Stderr(line));
+                    }
+                    _ => stderr_open = false,
+                },
+                _ = wait_for_cancel(cancel_token) => {
+                    let _ = child.kill().await;
+                    log_info!("[Process] Killed {:?} (cancelled)", request.program);
+                    return Err(cancelled_error());
+                }
+            }
+        }
+        
+        let status = tokio::select! {
+            status = child.wait() => status?,
+            _ = wait_for_cancel(cancel_token) => {
+                let _ = child.kill().await;
+                log_info!("[Process] Killed {:?} (cancelled)", request.program);
+                return Err(cancelled_error());
+            }
+        };
+        
+        log_info!("[Process] {:?} exited with {}", request.program, status);
+        output.exit_code = status.code();
+        Ok(output)
+    }
+}
+
+/// A canned process result replayed by `MockCommandRunner`
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub stdout_lines: Vec<String>,
+    pub stderr_lines: Vec<String>,
+    pub exit_code: i32,
+    /// Fail to spawn with this error kind instead of producing output
+    pub spawn_error: Option<io::ErrorKind>,
+}
+
+impl MockResponse {
+    /// Successful run printing `stdout`
+    pub fn success(stdout: &str) -> Self {
+        Self {
+            stdout_lines: stdout.lines().map(|l| l.to_string()).collect(),
+            stderr_lines: Vec::new(),
+            exit_code: 0,
+            spawn_error: None,
+        }
+    }
+    
+    /// Failed run printing `stderr`
+    pub fn failure(exit_code: i32, stderr: &str) -> Self {
+        Self {
+            stdout_lines: Vec::new(),
+            stderr_lines: stderr.lines().map(|l| l.to_string()).collect(),
+            exit_code,
+            spawn_error: None,
+        }
+    }
+    
+    /// Executable could not be found
+    pub fn not_found() -> Self {
+        Self {
+            stdout_lines: Vec::new(),
+            stderr_lines: Vec::new(),
+            exit_code: -1,
+            spawn_error: Some(io::ErrorKind::NotFound),
+        }
+    }
+    
+    fn into_output(self) -> io::Result<CommandOutput> {
+        if let Some(kind) = self.spawn_error {
+            return Err(io::Error::new(kind, "mock spawn error"));
+        }
+        
+        let join = |lines: &[String]| lines.iter().map(|l| format!("{}\n", l)).collect::<String>();
+        Ok(CommandOutput {
+            exit_code: Some(self.exit_code),
+            stdout: join(&self.stdout_lines),
+            stderr: join(&self.stderr_lines),
+        })
+    }
+}
+
+/// Runner that replays queued responses in order and records every request
+#[derive(Debug, Default)]
+pub struct MockCommandRunner {
+    responses: Mutex<VecDeque<MockResponse>>,
+    calls: Mutex<Vec<CommandRequest>>,
+}
+
+impl MockCommandRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Create a runner with responses queued in order
+    pub fn with_responses(responses: Vec<MockResponse>) -> Self {
+        let runner = Self::new();
+        for response in responses {
+            runner.push_response(response);
+        }
+        runner
+    }
+    
+    /// Queue a response for the next invocation
+    pub fn push_response(&self, response: MockResponse) {
+        self.responses.lock().unwrap().push_back(response);
+    }
+    
+    /// All requests received so far
+    pub fn calls(&self) -> Vec<CommandRequest> {
+        self.calls.lock().unwrap().clone()
+    }
+    
+    fn next_response(&self, request: &CommandRequest) -> io::Result<MockResponse> {
+        self.calls.lock().unwrap().push(request.clone());
+        self.responses.lock().unwrap().pop_front().ok_or_else(|| {
+            io::Error::other(format!("no mock response queued for {:?}", request.program))
+        })
+    }
+}
+
+#[async_trait]
+impl CommandRunner for MockCommandRunner {
+    async fn output(&self, request: &CommandRequest) -> io::Result<CommandOutput> {
+        self.next_response(request)?.into_output()
+    }
+    
+    async fn stream(
+        &self,
+        request: &CommandRequest,
+        on_line: &mut (dyn FnMut(OutputLine) + Send),
+        cancel_token: Option<&CancellationToken>,
+    ) -> io::Result<CommandOutput> {
+        let response = self.next_response(request)?;
+        
+        if response.spawn_error.is_none() {
+            for line in &response.stdout_lines {
+                if cancel_token.is_some_and(|t| t.is_cancelled()) {
+                    return Err(cancelled_error());
+                }
+                on_line(OutputLine::Stdout(line.clone()));
+            }
+            for line in &response.stderr_lines {
+                on_line(OutputLine::Stderr(line.clone()));
+            }
+        }
+        
+        response.into_output()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    
+    #[tokio::test]
+    async fn test_mock_replays_responses_in_order() {
+        let runner = MockCommandRunner::with_responses(vec![
+            MockResponse::success("first"),
+            MockResponse::failure(1, "boom"),
+        ]);
+        let request = CommandRequest::new(Path::new("yt-dlp"), vec!["--version".to_string()]);
+        
+        let first = runner.output(&request).await.unwrap();
+        assert!(first.success());
+        assert_eq!(first.stdout, "first\n");
+        
+        let second = runner.output(&request).await.unwrap();
+        assert!(!second.success());
+        assert_eq!(second.stderr, "boom\n");
+        
+        // Queue exhausted
+        assert!(runner.output(&request).await.is_err());
+        assert_eq!(runner.calls().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_mock_stream_forwards_lines() {
+        let runner = MockCommandRunner::with_responses(vec![MockResponse {
+            stdout_lines: vec!["a".to_string(), "b".to_string()],
+            stderr_lines: vec!["warn".to_string()],
+            exit_code: 0,
+            spawn_error: None,
+        }]);
+        let request = CommandRequest::new(Path::new("yt-dlp"), Vec::new());
+        
+        let mut lines = Vec::new();
+        let output = runner.stream(&request, &mut |line| lines.push(line), None).await.unwrap();
+        
+        assert!(output.success());
+        assert_eq!(lines, vec![
+            OutputLine::Stdout("a".to_string()),
+            OutputLine::Stdout("b".to_string()),
+            OutputLine::Stderr("warn".to_string()),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_not_found() {
+        let runner = MockCommandRunner::with_responses(vec![MockResponse::not_found()]);
+        let request = CommandRequest::new(Path::new("missing"), Vec::new());
+        
+        let error = runner.output(&request).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_system_runner_streams_output() {
+        let runner = SystemCommandRunner::new();
+        let request = CommandRequest::new(
+            Path::new("sh"),
+            vec!["-c".to_string(), "echo out; echo err >&2; exit 3".to_string()],
+        );
+        
+        let mut lines = Vec::new();
+        let output = runner.stream(&request, &mut |line| lines.push(line), None).await.unwrap();
+        
+        assert_eq!(output.exit_code, Some(3));
+        assert!(lines.contains(&OutputLine::Stdout("out".to_string())));
+        assert!(lines.contains(&OutputLine::Stderr("err".to_string())));
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_system_runner_publishes_pid_while_running() {
+        let runner = SystemCommandRunner::new();
+        let slot = ProcessSlot::new();
+        let request = CommandRequest::new(Path::new("sh"), vec!["-c".to_string(), "echo $$".to_string()])
+            .track_process(Some(slot.clone()));
+        
+        let mut seen = None;
+        runner.stream(&request, &mut |_| seen = slot.pid(), None).await.unwrap();
+        
+        assert!(seen.is_some());
+        assert_eq!(slot.pid(), None);
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_system_runner_background_priority() {
+        let runner = SystemCommandRunner::new();
+        let request = CommandRequest::new(
+            Path::new("sh"),
+            vec!["-c".to_string(), "ps -o ni= -p $$".to_string()],
+        )
+        .priority(ProcessPriority::Background);
+        
+        let output = runner.output(&request).await.unwrap();
+        let niceness: i32 = output.stdout.trim().parse().unwrap();
+        assert!(niceness >= BACKGROUND_NICENESS);
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_system_runner_sandbox_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        // Opened without O_CLOEXEC, as a careless library might
+        let leaked_fd = unsafe { nix::libc::dup(2) };
+        assert!(leaked_fd > 2);
+        
+        let runner = SystemCommandRunner::new();
+        let script = format!("pwd; test -e /dev/fd/{} && echo leaked; env", leaked_fd);
+        let request = CommandRequest::new(Path::new("sh"), vec!["-c".to_string(), script])
+            .env("PYTHONIOENCODING", "utf-8")
+            .confine_to(&dir.path().join("%(title)s.%(ext)s"));
+        let output = runner.output(&request).await.unwrap();
+        unsafe { nix::libc::close(leaked_fd) };
+        
+        let mut lines = output.stdout.lines();
+        let cwd = PathBuf::from(lines.next().unwrap());
+        assert_eq!(cwd.canonicalize().unwrap(), dir.path().canonicalize().unwrap());
+        
+        let lines: Vec<&str> = lines.collect();
+        assert!(!lines.contains(&"leaked"));
+        assert!(lines.contains(&"PYTHONIOENCODING=utf-8"));
+        // Only allowlisted variables, plus the ones the shell sets itself
+        for line in lines {
+            let key = line.split('=').next().unwrap();
+            assert!(
+                INHERITED_ENV.contains(&key) || ["PYTHONIOENCODING", "PWD", "OLDPWD", "SHLVL", "_"].contains(&key),
+                "unexpected variable {}",
+                key
+            );
+        }
+    }
+    
+    #[test]
+    fn test_sandboxed_command_keeps_profile_directories() {
+        let profile_vars = [
+            "APPDATA", "LOCALAPPDATA", "USERPROFILE", "XDG_CONFIG_HOME", "XDG_DATA_HOME", "DBUS_SESSION_BUS_ADDRESS",
+        ];
+        let parent_env: HashMap<String, OsString> = profile_vars
+            .iter()
+            .chain(&["VORTEX_TEST_SECRET"])
+            .map(|key| (key.to_string(), OsString::from(format!("/test/{}", key))))
+            .collect();
+        
+        let request = CommandRequest::new(Path::new("yt-dlp"), Vec::new());
+        let command = sandboxed_command_in(&request, |key| parent_env.get(key).cloned());
+        let envs: HashMap<String, Option<OsString>> = command
+            .as_std()
+            .get_envs()
+            .map(|(key, value)| (key.to_string_lossy().to_string(), value.map(OsString::from)))
+            .collect();
+        for key in profile_vars {
+            assert_eq!(envs.get(key), Some(&parent_env.get(key).cloned()), "{} was not passed through", key);
+        }
+        assert!(!envs.contains_key("VORTEX_TEST_SECRET"));
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_system_runner_cancellation() {
+        let runner = SystemCommandRunner::new();
+        let request = CommandRequest::new(Path::new("sleep"), vec!["10".to_string()]);
+        let token = CancellationToken::new();
+        
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+        
+        let error = runner.stream(&request, &mut |_| {}, Some(&token)).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+    }
+}
@@ -0,0 +1,174 @@
+//! DNS-over-HTTPS for the app's own HTTP clients
+//!
+//! Some networks break resolution of GitHub and CDN hosts. With DoH enabled,
+//! the updater and the direct file provider look hosts up through a DoH
+//! server's JSON API instead of the system resolver. yt-dlp is unaffected.
+
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::{header, Url};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::error::{DownloadError, Result};
+
+/// Cloudflare's resolver, addressed by IP so reaching it needs no DNS
+pub const DEFAULT_DOH_SERVER: &str = "https://1.1.1.1/dns-query";
+
+/// How long a single DoH query may take
+const DOH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// DNS record types
+const RECORD_A: u16 = 1;
+const RECORD_AAAA: u16 = 28;
+
+/// DNS-over-HTTPS settings
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DohSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// DoH endpoint speaking the JSON API, e.g. `https://dns.google/resolve`
+    #[serde(default = "default_server")]
+    pub server: String,
+}
+
+fn default_server() -> String {
+    DEFAULT_DOH_SERVER.to_string()
+}
+
+impl Default for DohSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server: default_server(),
+        }
+    }
+}
+
+impl DohSettings {
+    /// Check that the server is an HTTPS URL when DoH is enabled
+    pub fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let url = Url::parse(&self.server)
+            .map_err(|e| DownloadError::InvalidOption(format!("Invalid DNS-over-HTTPS server {}: {}", self.server, e)))?;
+        if url.scheme() != "https" {
+            return Err(DownloadError::InvalidOption(format!(
+                "DNS-over-HTTPS server must use https://: {}",
+                self.server
+            )));
+        }
+        Ok(())
+    }
+    
+    /// Resolver for `ClientBuilder::dns_resolver`, when enabled
+    pub fn resolver(&self) -> Option<Arc<DohResolver>> {
+        self.enabled.then(|| Arc::new(DohResolver::new(&self.server)))
+    }
+}
+
+/// Resolves hosts with A and AAAA queries to a DoH server
+#[derive(Clone)]
+pub struct DohResolver {
+    server: String,
+    /// Plain client for the queries themselves; the server host uses system DNS
+    client: reqwest::Client,
+}
+
+impl DohResolver {
+    pub fn new(server: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DOH_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self { server: server.to_string(), client }
+    }
+    
+    async fn query(&self, host: &str, record_type: u16) -> Result<Vec<IpAddr>> {
+        let network = |e: reqwest::Error| DownloadError::Network(format!("DNS-over-HTTPS lookup of {} failed: {}", host, e));
+        let body = self.client
+            .get(&self.server)
+            .query(&[("name", host.to_string()), ("type", record_type.to_string())])
+            .header(header::ACCEPT, "application/dns-json")
+            .send()
+            .await
+            .map_err(network)?
+            .error_for_status()
+            .map_err(network)?
+            .text()
+            .await
+            .map_err(network)?;
+        parse_doh_response(&body)
+    }
+    
+    /// IPv4 and IPv6 addresses of `host`
+    pub async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>> {
+        let (v4, v6) = tokio::join!(self.query(host, RECORD_A), self.query(host, RECORD_AAAA));
+        let addrs: Vec<IpAddr> = match (v4, v6) {
+            (Err(e), Err(_)) => return Err(e),
+            (v4, v6) => v4.unwrap_or_default().into_iter().chain(v6.unwrap_or_default()).collect(),
+        };
+        if addrs.is_empty() {
+            return Err(DownloadError::Network(format!("DNS-over-HTTPS found no addresses for {}", host)));
+        }
+        Ok(addrs)
+    }
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let addrs = resolver.lookup(name.as_str()).await?;
+            // reqwest fills in the port
+            let addrs: Addrs = Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+/// Addresses in a DoH JSON response, skipping CNAME and other records
+pub fn parse_doh_response(body: &str) -> Result<Vec<IpAddr>> {
+    let json: serde_json::Value = serde_json::from_str(body)?;
+    let status = json["Status"].as_u64().unwrap_or(0);
+    if status != 0 {
+        return Err(DownloadError::Network(format!("DNS-over-HTTPS server returned status {}", status)));
+    }
+    Ok(json["Answer"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|answer| matches!(answer["type"].as_u64(), Some(t) if t == RECORD_A as u64 || t == RECORD_AAAA as u64))
+        .filter_map(|answer| answer["data"].as_str()?.parse().ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_doh_response() {
+        let body = r#"{"Status":0,"Answer":[
+            {"name":"api.github.com","type":5,"TTL":60,"data":"github.map.fastly.net."},
+            {"name":"github.map.fastly.net","type":1,"TTL":60,"data":"140.82.121.6"},
+            {"name":"github.map.fastly.net","type":28,"TTL":60,"data":"2606:50c0:8000::154"}
+        ]}"#;
+        let addrs = parse_doh_response(body).unwrap();
+        assert_eq!(addrs, vec!["140.82.121.6".parse::<IpAddr>().unwrap(), "2606:50c0:8000::154".parse().unwrap()]);
+        
+        assert!(parse_doh_response(r#"{"Status":0}"#).unwrap().is_empty());
+        // NXDOMAIN
+        assert!(parse_doh_response(r#"{"Status":3}"#).is_err());
+    }
+
+    #[test]
+    fn test_validate_doh_settings() {
+        assert!(DohSettings::default().validate().is_ok());
+        assert!(DohSettings { enabled: true, ..Default::default() }.validate().is_ok());
+        assert!(DohSettings { enabled: true, server: "http://1.1.1.1/dns-query".to_string() }.validate().is_err());
+        assert!(DohSettings::default().resolver().is_none());
+    }
+}
@@ -0,0 +1,424 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use super::command_runner::CommandRunner;
+use super::metadata_limit::MetadataLimit;
+use super::provider::*;
+use super::registry::PlatformRegistry;
+use super::ytdlp_backend::{exit_error, height_capped_format, network_options, MetadataNetwork, YtDlpBackend};
+use super::ytdlp_command::{validate_postprocessor_args, YtDlpCommandBuilder};
+use crate::error::{DownloadError, Result};
+
+/// Name the generic provider is registered under
+pub const GENERIC_PROVIDER_NAME: &str = "Generic";
+
+/// Fallback provider for any http(s) URL, backed by yt-dlp's extractors
+///
+/// Must be registered last so that specific providers win detection. It only
+/// matches URLs while the `allow_generic_sites` setting is enabled.
+pub struct GenericProvider {
+    backend: YtDlpBackend,
+    ffmpeg_path: PathBuf,
+    enabled: AtomicBool,
+}
+
+impl GenericProvider {
+    pub fn new() -> Self {
+        Self::with_executables(PathBuf::from("yt-dlp"), PathBuf::from("ffmpeg"))
+    }
+    
+    /// Create a new GenericProvider with custom executable paths (disabled by default)
+    pub fn with_executables(ytdlp_path: PathBuf, ffmpeg_path: PathBuf) -> Self {
+        Self {
+            backend: YtDlpBackend::new(GENERIC_PROVIDER_NAME, ytdlp_path),
+            ffmpeg_path,
+            enabled: AtomicBool::new(false),
+        }
+    }
+    
+    /// Replace the process runner (used by tests to replay canned output)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.backend = self.backend.with_command_runner(runner);
+        self
+    }
+    
+    /// Enable or disable matching of arbitrary sites
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+    
+    /// Apply the `allow_generic_sites` setting to the provider in `registry`, if registered
+    pub fn apply_setting(registry: &PlatformRegistry, allow_generic_sites: bool) {
+        if let Some(provider) = registry.get_provider(GENERIC_PROVIDER_NAME) {
+            if let Some(generic) = provider.as_any().downcast_ref::<GenericProvider>() {
+                generic.set_enabled(allow_generic_sites);
+            }
+        }
+    }
+    
+    /// Parse video info from yt-dlp JSON (full or flat-playlist entry)
+    fn parse_video_info(json: &Value, fallback_url: &str) -> VideoInfo {
+        let formats = json["formats"]
+            .as_array()
+            .map(|formats| {
+                formats
+                    .iter()
+                    .filter_map(|format| {
+                        Some(FormatInfo {
+                            format_id: format["format_id"].as_str()?.to_string(),
+                            ext: format["ext"].as_str().unwrap_or("mp4").to_string(),
+                            resolution: format["resolution"].as_str().map(|s| s.to_string()),
+                            filesize: format["filesize"]
+                                .as_u64()
+                                .or_else(|| format["filesize_approx"].as_u64()),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        
+        // Report the extractor (e.g. "Vimeo") so the UI can show where media came from
+        let extractor = json["extractor_key"]
+            .as_str()
+            .or_else(|| json["ie_key"].as_str())
+            .unwrap_or(GENERIC_PROVIDER_NAME);
+        
+        VideoInfo {
+            id: json["id"].as_str().unwrap_or("").to_string(),
+            title: json["title"].as_str().unwrap_or("Unknown Title").to_string(),
+            description: json["description"].as_str().unwrap_or("").to_string(),
+            thumbnail: json["thumbnail"].as_str().unwrap_or("").to_string(),
+            duration: json["duration"].as_f64().map(|d| d.round() as u64).unwrap_or(0),
+            uploader: json["uploader"]
+                .as_str()
+                .or_else(|| json["channel"].as_str())
+                .unwrap_or("Unknown")
+                .to_string(),
+            upload_date: json["upload_date"].as_str().unwrap_or("").to_string(),
+            view_count: json["view_count"].as_u64().unwrap_or(0),
+            available_formats: formats,
+            platform: extractor.to_string(),
+            url: json["webpage_url"]
+                .as_str()
+                .or_else(|| json["url"].as_str())
+                .unwrap_or(fallback_url)
+                .to_string(),
+                is_live: false,
+                premiere_at: None,
+        }
+    }
+    
+    /// Parse `--flat-playlist` output into playlist info
+    fn parse_playlist(output: &str, url: &str) -> Result<PlaylistInfo> {
+        let mut playlist = PlaylistInfo {
+            id: String::new(),
+            title: String::new(),
+            description: String::new(),
+            uploader: String::new(),
+            video_count: 0,
+            videos: Vec::new(),
+            platform: GENERIC_PROVIDER_NAME.to_string(),
+            url: url.to_string(),
+            has_more: false,
+            page: 0,
+            page_size: 0,
+        };
+        
+        for line in output.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            
+            let json: Value = serde_json::from_str(line)
+                .map_err(|e| DownloadError::DownloadFailed(format!("Failed to parse playlist entry: {}", e)))?;
+            
+            // Extract playlist metadata from first entry
+            if playlist.title.is_empty() {
+                playlist.id = json["playlist_id"].as_str().unwrap_or("").to_string();
+                playlist.title = json["playlist_title"]
+                    .as_str()
+                    .or_else(|| json["playlist"].as_str())
+                    .unwrap_or("Unknown Playlist")
+                    .to_string();
+                playlist.uploader = json["playlist_uploader"]
+                    .as_str()
+                    .or_else(|| json["uploader"].as_str())
+                    .unwrap_or("Unknown")
+                    .to_string();
+            }
+            
+            playlist.videos.push(Self::parse_video_info(&json, url));
+        }
+        
+        playlist.video_count = playlist.videos.len();
+        Ok(playlist)
+    }
+    
+    /// Internal download implementation with cancellation support
+    async fn download_video_impl(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<()> {
+        let save_path_str = save_path.to_str()
+            .ok_or_else(|| DownloadError::DownloadFailed(format!("Invalid save path: {:?}", save_path)))?;
+        
+        let mut command = YtDlpCommandBuilder::new()
+            .machine_progress()
+            .no_warnings()
+            .no_playlist()
+            .output(save_path_str)
+            .ffmpeg_location(&self.ffmpeg_path)
+            .format(&height_capped_format(&options));
+        
+        if options.audio_only {
+            command = command
+                .extract_audio(&options.format)
+                .tag_audio(&options.format, &options.tags);
+            if let Some(quality) = options.audio_quality_arg() {
+                command = command.audio_quality(quality);
+            }
+        }
+        
+        if options.embed_chapters {
+            command = command.embed_chapters();
+        }
+        
+        if options.wants_faststart() {
+            command = command.faststart();
+        }
+        
+        if let Some(ppa) = options.postprocessor_args.as_deref() {
+            command = command.postprocessor_args(&validate_postprocessor_args(ppa)?);
+        }
+        
+        if let Some(threads) = options.ffmpeg_threads() {
+            command = command.ffmpeg_threads(threads);
+        }
+        
+        command = network_options(command, &options);
+        
+        if let Some(archive) = &options.download_archive {
+            command = command.download_archive(archive);
+        }
+        
+        if let Some((start, end)) = options.section() {
+            command = command.download_sections(start, end);
+        }
+        
+        let command = command.url(url);
+        let output = self.backend
+            .download(&command, save_path, &options, progress_callback, cancel_token.as_ref())
+            .await?;
+        
+        if !output.success() {
+            if output.stderr.contains("Unsupported URL") {
+                return Err(DownloadError::PlatformNotSupported(url.to_string()));
+            }
+            return Err(exit_error(&output));
+        }
+        
+        Ok(())
+    }
+    
+    /// Download video with cancellation support (public method for download manager)
+    pub async fn download_with_cancellation(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: CancellationToken,
+    ) -> Result<()> {
+        self.download_video_impl(url, options, save_path, progress_callback, Some(cancel_token)).await
+    }
+}
+
+#[async_trait]
+impl PlatformProvider for GenericProvider {
+    fn name(&self) -> &str {
+        GENERIC_PROVIDER_NAME
+    }
+    
+    fn matches_url(&self, url: &str) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        
+        let url = url.trim().to_lowercase();
+        url.starts_with("http://") || url.starts_with("https://")
+    }
+    
+    fn supported_patterns(&self) -> Vec<String> {
+        vec!["Any http(s) URL supported by yt-dlp (opt-in)".to_string()]
+    }
+    
+    async fn get_video_info(&self, url: &str) -> Result<VideoInfo> {
+        let json_output = self.backend.run(
+            &YtDlpCommandBuilder::new().dump_json().no_playlist().url(url)
+        ).await?;
+        
+        let json: Value = serde_json::from_str(json_output.lines().next().unwrap_or(""))
+            .map_err(|e| DownloadError::DownloadFailed(format!("Failed to parse video info: {}", e)))?;
+        
+        Ok(Self::parse_video_info(&json, url))
+    }
+    
+    async fn get_playlist_info(&self, url: &str) -> Result<PlaylistInfo> {
+        let json_output = self.backend.run(
+            &YtDlpCommandBuilder::new().dump_flat_playlist().url(url)
+        ).await?;
+        
+        Self::parse_playlist(&json_output, url)
+    }
+    
+    async fn get_channel_info(&self, url: &str) -> Result<ChannelInfo> {
+        // Generic sites have no channel concept beyond "a list of videos"
+        let playlist = self.get_playlist_info(url).await?;
+        
+        Ok(ChannelInfo {
+            id: playlist.id.clone(),
+            name: playlist.uploader.clone(),
+            description: playlist.description.clone(),
+            all_videos: playlist.videos.clone(),
+            playlists: vec![playlist],
+            platform: GENERIC_PROVIDER_NAME.to_string(),
+            url: url.to_string(),
+        })
+    }
+    
+    async fn download_video(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+    ) -> Result<()> {
+        self.download_video_impl(url, options, save_path, progress_callback, None).await
+    }
+    
+    async fn check_dependencies(&self) -> Result<Vec<Dependency>> {
+        Ok(vec![
+            Dependency {
+                name: "yt-dlp (bundled)".to_string(),
+                installed: self.backend.ytdlp_path().exists(),
+                version: None,
+                install_instructions: "yt-dlp is bundled with the application. If missing, please reinstall the application.".to_string(),
+            },
+        ])
+    }
+    
+    fn get_platform_settings(&self) -> Vec<PlatformSetting> {
+        Vec::new()
+    }
+    
+    fn metadata_limit(&self) -> Option<&MetadataLimit> {
+        Some(self.backend.metadata_limit())
+    }
+    
+    fn metadata_network(&self) -> Option<&MetadataNetwork> {
+        Some(self.backend.metadata_network())
+    }
+    
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl Default for GenericProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::command_runner::{MockCommandRunner, MockResponse};
+    
+    #[test]
+    fn test_disabled_by_default() {
+        let provider = GenericProvider::new();
+        assert!(!provider.matches_url("https://vimeo.com/123456"));
+        
+        provider.set_enabled(true);
+        assert!(provider.matches_url("https://vimeo.com/123456"));
+        assert!(provider.matches_url("HTTP://example.com/video"));
+        assert!(!provider.matches_url("ftp://example.com/video"));
+        assert!(!provider.matches_url("not a url"));
+    }
+
+    #[test]
+    fn test_apply_setting_through_registry() {
+        let mut registry = PlatformRegistry::new();
+        registry.register(Arc::new(GenericProvider::new()));
+        
+        GenericProvider::apply_setting(&registry, true);
+        assert!(registry.detect_provider("https://vimeo.com/123456").is_some());
+        
+        GenericProvider::apply_setting(&registry, false);
+        assert!(registry.detect_provider("https://vimeo.com/123456").is_none());
+    }
+
+    #[test]
+    fn test_build_format_string() {
+        let mut options = DownloadOptions {
+            quality: "720p".to_string(),
+            format: "mp4".to_string(),
+            audio_only: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            height_capped_format(&options),
+            "bestvideo[height<=720]+bestaudio/best[height<=720]/best"
+        );
+        
+        options.audio_only = true;
+        assert_eq!(height_capped_format(&options), "bestaudio/best");
+    }
+
+    #[tokio::test]
+    async fn test_video_info_reports_extractor() {
+        let json = r#"{"id":"123456","title":"A Vimeo clip","duration":61.4,"extractor_key":"Vimeo","webpage_url":"https://vimeo.com/123456"}"#;
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![MockResponse::success(json)]));
+        let provider = GenericProvider::new().with_command_runner(runner);
+        
+        let info = provider.get_video_info("https://vimeo.com/123456").await.unwrap();
+        assert_eq!(info.platform, "Vimeo");
+        assert_eq!(info.duration, 61);
+        assert_eq!(info.url, "https://vimeo.com/123456");
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_url_error() {
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![
+            MockResponse::failure(1, "ERROR: Unsupported URL: https://example.com/"),
+        ]));
+        let provider = GenericProvider::new().with_command_runner(runner);
+        
+        let result = provider.get_video_info("https://example.com/").await;
+        assert!(matches!(result, Err(DownloadError::PlatformNotSupported(_))));
+    }
+
+    #[test]
+    fn test_parse_flat_playlist() {
+        let output = concat!(
+            r#"{"id":"a","title":"First","playlist_id":"p1","playlist_title":"Clips","url":"https://example.com/a"}"#, "\n",
+            r#"{"id":"b","title":"Second","playlist_id":"p1","playlist_title":"Clips","url":"https://example.com/b"}"#, "\n",
+        );
+        let playlist = GenericProvider::parse_playlist(output, "https://example.com/list").unwrap();
+        assert_eq!(playlist.title, "Clips");
+        assert_eq!(playlist.video_count, 2);
+        assert_eq!(playlist.videos[1].url, "https://example.com/b");
+    }
+}
@@ -0,0 +1,135 @@
+//! User-agent and extra HTTP headers sent by yt-dlp
+//!
+//! Some sites reject yt-dlp's default client. Users can set a user-agent and
+//! headers for every download, and override them for individual platforms.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use crate::error::{DownloadError, Result};
+
+/// User-agent and headers for one set of requests
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RequestHeaders {
+    /// Passed as `--user-agent`; yt-dlp's own when unset
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Passed as `--add-header Name:Value`
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl RequestHeaders {
+    /// Check that header names are HTTP tokens and nothing contains a line break
+    pub fn validate(&self) -> Result<()> {
+        if let Some(user_agent) = &self.user_agent {
+            validate_header_value("User-Agent", user_agent)?;
+        }
+        for (name, value) in &self.headers {
+            validate_header_name(name)?;
+            validate_header_value(name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// HTTP headers for yt-dlp, with per-platform overrides
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct HttpHeaderSettings {
+    /// Sent with every download
+    #[serde(default)]
+    pub default: RequestHeaders,
+    /// Headers by platform name (`YouTube`, `Kick`, ...), added on top of the defaults
+    #[serde(default)]
+    pub platform_overrides: HashMap<String, RequestHeaders>,
+}
+
+impl HttpHeaderSettings {
+    /// Headers for `platform`: the defaults, with its override's user-agent and headers taking precedence
+    pub fn for_platform(&self, platform: &str) -> RequestHeaders {
+        let mut headers = self.default.clone();
+        if let Some(platform_headers) = self.platform_overrides.get(platform) {
+            if platform_headers.user_agent.is_some() {
+                headers.user_agent = platform_headers.user_agent.clone();
+            }
+            headers.headers.extend(platform_headers.headers.clone());
+        }
+        headers.user_agent = headers.user_agent.filter(|ua| !ua.trim().is_empty());
+        headers
+    }
+    
+    /// Check the defaults and every override
+    pub fn validate(&self) -> Result<()> {
+        std::iter::once(&self.default)
+            .chain(self.platform_overrides.values())
+            .try_for_each(RequestHeaders::validate)
+    }
+}
+
+fn validate_header_name(name: &str) -> Result<()> {
+    let is_token_char = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+    if name.is_empty() || !name.chars().all(is_token_char) {
+        return Err(DownloadError::InvalidOption(format!("Invalid HTTP header name: '{}'", name)));
+    }
+    Ok(())
+}
+
+fn validate_header_value(name: &str, value: &str) -> Result<()> {
+    if value.chars().any(|c| c.is_control() && c != '\t') {
+        return Err(DownloadError::InvalidOption(format!(
+            "HTTP header {} contains control characters",
+            name
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_platform_override_merges_with_defaults() {
+        let settings = HttpHeaderSettings {
+            default: RequestHeaders {
+                user_agent: Some("Mozilla/5.0".to_string()),
+                headers: BTreeMap::from([
+                    ("Accept-Language".to_string(), "en-US".to_string()),
+                    ("Referer".to_string(), "https://example.com/".to_string()),
+                ]),
+            },
+            platform_overrides: HashMap::from([(
+                "Kick".to_string(),
+                RequestHeaders {
+                    user_agent: None,
+                    headers: BTreeMap::from([("Referer".to_string(), "https://kick.com/".to_string())]),
+                },
+            )]),
+        };
+        assert_eq!(settings.for_platform("YouTube"), settings.default);
+        
+        let kick = settings.for_platform("Kick");
+        assert_eq!(kick.user_agent.as_deref(), Some("Mozilla/5.0"));
+        assert_eq!(kick.headers["Referer"], "https://kick.com/");
+        assert_eq!(kick.headers["Accept-Language"], "en-US");
+    }
+
+    #[test]
+    fn test_validate_headers() {
+        assert!(HttpHeaderSettings::default().validate().is_ok());
+        
+        let headers = |name: &str, value: &str| RequestHeaders {
+            user_agent: None,
+            headers: BTreeMap::from([(name.to_string(), value.to_string())]),
+        };
+        assert!(headers("X-Requested-With", "XMLHttpRequest").validate().is_ok());
+        assert!(headers("Bad Name", "value").validate().is_err());
+        assert!(headers("Referer:", "value").validate().is_err());
+        assert!(headers("Cookie", "a=1\r\nX-Injected: 1").validate().is_err());
+        
+        let settings = HttpHeaderSettings {
+            platform_overrides: HashMap::from([("YouTube".to_string(), headers("", "value"))]),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+}
@@ -0,0 +1,346 @@
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use super::command_runner::CommandRunner;
+use super::metadata_limit::MetadataLimit;
+use super::provider::*;
+use super::ytdlp_backend::{exit_error, network_options, MetadataNetwork, YtDlpBackend};
+use super::ytdlp_command::{validate_postprocessor_args, YtDlpCommandBuilder};
+use crate::error::{DownloadError, Result};
+use crate::log_info;
+
+/// Kick.com VOD and clip provider
+///
+/// Channel URLs resolve to the channel's current livestream, which is rejected
+/// with `DownloadError::LiveStream` until live recording is supported.
+pub struct KickProvider {
+    backend: YtDlpBackend,
+    ffmpeg_path: PathBuf,
+    url_patterns: Vec<Regex>,
+}
+
+impl KickProvider {
+    pub fn new() -> Self {
+        Self::with_executables(PathBuf::from("yt-dlp"), PathBuf::from("ffmpeg"))
+    }
+    
+    /// Create a new KickProvider with custom executable paths
+    pub fn with_executables(ytdlp_path: PathBuf, ffmpeg_path: PathBuf) -> Self {
+        let url_patterns = vec![
+            // VODs
+            Regex::new(r"^https?://(www\.)?kick\.com/video/[\w-]+").unwrap(),
+            Regex::new(r"^https?://(www\.)?kick\.com/[\w-]+/videos/[\w-]+").unwrap(),
+            // Clips
+            Regex::new(r"^https?://(www\.)?kick\.com/[\w-]+/clips/clip_\w+").unwrap(),
+            Regex::new(r"^https?://(www\.)?kick\.com/[\w-]+/?\?clip=clip_\w+").unwrap(),
+            // Channel pages (the livestream, if any)
+            Regex::new(r"^https?://(www\.)?kick\.com/[\w-]+/?$").unwrap(),
+        ];
+        
+        Self {
+            backend: YtDlpBackend::new("Kick", ytdlp_path)
+                .unavailable_when(&["not live", "offline", "404", "not found"]),
+            ffmpeg_path,
+            url_patterns,
+        }
+    }
+    
+    /// Replace the process runner (used by tests to replay canned output)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.backend = self.backend.with_command_runner(runner);
+        self
+    }
+    
+    /// Whether yt-dlp reports the media as an in-progress livestream
+    fn is_live(json: &Value) -> bool {
+        json["is_live"].as_bool().unwrap_or(false)
+            || matches!(json["live_status"].as_str(), Some("is_live") | Some("is_upcoming"))
+    }
+    
+    /// Fetch metadata, rejecting in-progress livestreams
+    async fn fetch_info_json(&self, url: &str) -> Result<Value> {
+        let json = self.backend
+            .run_json(&YtDlpCommandBuilder::new().dump_json().no_playlist().url(url))
+            .await
+            .map_err(|e| match e {
+                DownloadError::VideoUnavailable(msg) if msg.contains("not live") || msg.contains("offline") => {
+                    DownloadError::VideoUnavailable("This channel is not live right now".to_string())
+                }
+                e => e,
+            })?;
+        
+        if Self::is_live(&json) {
+            let channel = json["channel"]
+                .as_str()
+                .or_else(|| json["uploader"].as_str())
+                .unwrap_or("This channel");
+            return Err(DownloadError::LiveStream(format!("{} is currently streaming", channel)));
+        }
+        
+        Ok(json)
+    }
+    
+    /// Parse video info from yt-dlp JSON output
+    fn parse_video_info(json: &Value, url: &str) -> VideoInfo {
+        let formats = json["formats"]
+            .as_array()
+            .map(|formats| {
+                formats
+                    .iter()
+                    .filter_map(|format| {
+                        Some(FormatInfo {
+                            format_id: format["format_id"].as_str()?.to_string(),
+                            ext: format["ext"].as_str().unwrap_or("mp4").to_string(),
+                            resolution: format["resolution"].as_str().map(|s| s.to_string()),
+                            filesize: format["filesize"]
+                                .as_u64()
+                                .or_else(|| format["filesize_approx"].as_u64()),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        
+        VideoInfo {
+            id: json["id"].as_str().unwrap_or("").to_string(),
+            title: json["title"].as_str().unwrap_or("Kick Video").to_string(),
+            description: json["description"].as_str().unwrap_or("").to_string(),
+            thumbnail: json["thumbnail"].as_str().unwrap_or("").to_string(),
+            duration: json["duration"].as_f64().map(|d| d.round() as u64).unwrap_or(0),
+            uploader: json["channel"]
+                .as_str()
+                .or_else(|| json["uploader"].as_str())
+                .unwrap_or("Unknown")
+                .to_string(),
+            upload_date: json["upload_date"].as_str().unwrap_or("").to_string(),
+            view_count: json["view_count"].as_u64().unwrap_or(0),
+            available_formats: formats,
+            platform: "Kick".to_string(),
+            url: url.to_string(),
+            is_live: false,
+            premiere_at: None,
+        }
+    }
+    
+    /// Build format string based on quality preference (Kick serves combined HLS renditions)
+    fn build_format_string(options: &DownloadOptions) -> String {
+        if let Some(format_id) = &options.format_id {
+            return format_id.clone();
+        }
+        if options.audio_only {
+            return "bestaudio/best".to_string();
+        }
+        
+        match options.quality.trim_end_matches('p').parse::<u32>() {
+            Ok(height) => format!("best[height<={h}]/best", h = height),
+            Err(_) => "best".to_string(),
+        }
+    }
+    
+    /// Internal download implementation with cancellation support
+    async fn download_video_impl(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<()> {
+        // Check before downloading: yt-dlp would otherwise record the stream until it ends
+        self.fetch_info_json(url).await?;
+        
+        let save_path_str = save_path.to_str()
+            .ok_or_else(|| DownloadError::DownloadFailed(format!("Invalid save path: {:?}", save_path)))?;
+        
+        let mut command = YtDlpCommandBuilder::new()
+            .machine_progress()
+            .no_warnings()
+            .no_playlist()
+            .output(save_path_str)
+            .ffmpeg_location(&self.ffmpeg_path)
+            .format(&Self::build_format_string(&options));
+        
+        if options.audio_only {
+            command = command
+                .extract_audio(&options.format)
+                .tag_audio(&options.format, &options.tags);
+            if let Some(quality) = options.audio_quality_arg() {
+                command = command.audio_quality(quality);
+            }
+        }
+        
+        if options.embed_chapters {
+            command = command.embed_chapters();
+        }
+        
+        if options.wants_faststart() {
+            command = command.faststart();
+        }
+        
+        if let Some(ppa) = options.postprocessor_args.as_deref() {
+            command = command.postprocessor_args(&validate_postprocessor_args(ppa)?);
+        }
+        
+        if let Some(threads) = options.ffmpeg_threads() {
+            command = command.ffmpeg_threads(threads);
+        }
+        
+        command = network_options(command, &options);
+        
+        if let Some(archive) = &options.download_archive {
+            command = command.download_archive(archive);
+        }
+        
+        if let Some((start, end)) = options.section() {
+            command = command.download_sections(start, end);
+        }
+        
+        let command = command.url(url);
+        let output = self.backend
+            .download(&command, save_path, &options, progress_callback, cancel_token.as_ref())
+            .await?;
+        
+        if !output.success() {
+            return Err(exit_error(&output));
+        }
+        
+        log_info!("[Kick] ✓ Download completed: {}", url);
+        Ok(())
+    }
+    
+    /// Download video with cancellation support (public method for download manager)
+    pub async fn download_with_cancellation(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: CancellationToken,
+    ) -> Result<()> {
+        self.download_video_impl(url, options, save_path, progress_callback, Some(cancel_token)).await
+    }
+}
+
+#[async_trait]
+impl PlatformProvider for KickProvider {
+    fn name(&self) -> &str {
+        "Kick"
+    }
+    
+    fn matches_url(&self, url: &str) -> bool {
+        let url = url.trim();
+        self.url_patterns.iter().any(|pattern| pattern.is_match(url))
+    }
+    
+    fn supported_patterns(&self) -> Vec<String> {
+        vec![
+            "https://kick.com/video/VIDEO_ID".to_string(),
+            "https://kick.com/CHANNEL/videos/VIDEO_ID".to_string(),
+            "https://kick.com/CHANNEL/clips/CLIP_ID".to_string(),
+        ]
+    }
+    
+    async fn get_video_info(&self, url: &str) -> Result<VideoInfo> {
+        let json = self.fetch_info_json(url).await?;
+        Ok(Self::parse_video_info(&json, url))
+    }
+    
+    async fn get_playlist_info(&self, url: &str) -> Result<PlaylistInfo> {
+        Err(DownloadError::InvalidUrl(format!("Kick videos are not playlists: {}", url)))
+    }
+    
+    async fn get_channel_info(&self, url: &str) -> Result<ChannelInfo> {
+        Err(DownloadError::InvalidUrl(format!("Kick channel listings are not supported, use a VOD or clip URL: {}", url)))
+    }
+    
+    async fn download_video(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+    ) -> Result<()> {
+        self.download_video_impl(url, options, save_path, progress_callback, None).await
+    }
+    
+    async fn check_dependencies(&self) -> Result<Vec<Dependency>> {
+        Ok(vec![
+            Dependency {
+                name: "yt-dlp (bundled)".to_string(),
+                installed: self.backend.ytdlp_path().exists(),
+                version: None,
+                install_instructions: "yt-dlp is bundled with the application. If missing, please reinstall the application.".to_string(),
+            },
+        ])
+    }
+    
+    fn get_platform_settings(&self) -> Vec<PlatformSetting> {
+        Vec::new()
+    }
+    
+    fn metadata_limit(&self) -> Option<&MetadataLimit> {
+        Some(self.backend.metadata_limit())
+    }
+    
+    fn metadata_network(&self) -> Option<&MetadataNetwork> {
+        Some(self.backend.metadata_network())
+    }
+    
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl Default for KickProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::command_runner::{MockCommandRunner, MockResponse};
+    
+    #[test]
+    fn test_matches_kick_urls() {
+        let provider = KickProvider::new();
+        assert!(provider.matches_url("https://kick.com/video/5b8f2a1c-1234-4cde-9f00-abcdef123456"));
+        assert!(provider.matches_url("https://kick.com/streamer/videos/5b8f2a1c-1234"));
+        assert!(provider.matches_url("https://kick.com/streamer/clips/clip_01HABCDEF"));
+        assert!(provider.matches_url("https://kick.com/streamer?clip=clip_01HABCDEF"));
+        assert!(provider.matches_url("https://kick.com/streamer"));
+        assert!(!provider.matches_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+    }
+
+    #[tokio::test]
+    async fn test_vod_info_is_parsed() {
+        let json = r#"{"id":"abc","title":"Big Stream","channel":"streamer","duration":3600.2,"live_status":"was_live","formats":[{"format_id":"1080p60","ext":"mp4","resolution":"1920x1080"}]}"#;
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![MockResponse::success(json)]));
+        let provider = KickProvider::new().with_command_runner(runner);
+        
+        let info = provider.get_video_info("https://kick.com/video/abc").await.unwrap();
+        assert_eq!(info.title, "Big Stream");
+        assert_eq!(info.uploader, "streamer");
+        assert_eq!(info.duration, 3600);
+        assert_eq!(info.platform, "Kick");
+    }
+
+    #[tokio::test]
+    async fn test_live_stream_is_rejected_before_download() {
+        let json = r#"{"id":"live","title":"Live now","channel":"streamer","is_live":true}"#;
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![MockResponse::success(json)]));
+        let provider = KickProvider::new().with_command_runner(runner.clone());
+        
+        let result = provider
+            .download_video("https://kick.com/streamer", DownloadOptions::default(), Path::new("/tmp"), Box::new(|_| {}))
+            .await;
+        
+        assert!(matches!(result, Err(DownloadError::LiveStream(_))));
+        // Only the metadata probe ran; no download was started
+        assert_eq!(runner.calls().len(), 1);
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Metadata operations a provider runs at once unless the user changes it
+pub const DEFAULT_METADATA_CONCURRENCY: usize = 2;
+
+/// Upper bound for the `metadata_concurrency` setting
+pub const MAX_METADATA_CONCURRENCY: usize = 8;
+
+/// Per-provider cap on concurrent metadata operations (video, playlist and channel lookups)
+///
+/// Separate from download concurrency: a channel lookup runs several yt-dlp
+/// calls, and they run in parallel up to this limit.
+#[derive(Debug)]
+pub struct MetadataLimit {
+    semaphore: Arc<Semaphore>,
+    limit: Mutex<usize>,
+}
+
+impl MetadataLimit {
+    pub fn new(limit: usize) -> Self {
+        let limit = limit.clamp(1, MAX_METADATA_CONCURRENCY);
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            limit: Mutex::new(limit),
+        }
+    }
+    
+    pub fn limit(&self) -> usize {
+        self.limit.lock().map(|limit| *limit).unwrap_or(DEFAULT_METADATA_CONCURRENCY)
+    }
+    
+    /// Change the limit; operations already running finish first when it shrinks
+    pub fn set_limit(&self, limit: usize) {
+        let limit = limit.clamp(1, MAX_METADATA_CONCURRENCY);
+        let Ok(mut current) = self.limit.lock() else {
+            return;
+        };
+        if limit > *current {
+            self.semaphore.add_permits(limit - *current);
+        } else if limit < *current {
+            let excess = *current - limit;
+            let forgotten = self.semaphore.forget_permits(excess);
+            // Permits held by running operations are retired as they come back
+            if forgotten < excess {
+                if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                    let semaphore = Arc::clone(&self.semaphore);
+                    runtime.spawn(async move {
+                        if let Ok(permits) = semaphore.acquire_many_owned((excess - forgotten) as u32).await {
+                            permits.forget();
+                        }
+                    });
+                }
+            }
+        }
+        *current = limit;
+    }
+    
+    /// Wait for a free slot; hold the permit for the duration of the operation
+    pub async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        // Semaphore is never closed, so acquiring can't fail
+        self.semaphore.acquire().await.ok()
+    }
+}
+
+impl Default for MetadataLimit {
+    fn default() -> Self {
+        Self::new(DEFAULT_METADATA_CONCURRENCY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_limit_caps_concurrent_operations() {
+        let limit = MetadataLimit::new(2);
+        let first = limit.acquire().await;
+        let _second = limit.acquire().await;
+        assert_eq!(limit.semaphore.available_permits(), 0);
+        
+        limit.set_limit(3);
+        assert_eq!(limit.semaphore.available_permits(), 1);
+        
+        // Shrinking below the permits in use retires them once they are released
+        limit.set_limit(1);
+        assert_eq!(limit.limit(), 1);
+        drop(first);
+        tokio::task::yield_now().await;
+        assert_eq!(limit.semaphore.available_permits(), 0);
+        
+        assert_eq!(MetadataLimit::new(0).limit(), 1);
+        assert_eq!(MetadataLimit::new(100).limit(), MAX_METADATA_CONCURRENCY);
+    }
+}
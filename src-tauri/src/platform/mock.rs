@@ -0,0 +1,400 @@
+use async_trait::async_trait;
+use regex::Regex;
+use std::path::Path;
+use std::time::Duration;
+use super::provider::*;
+use crate::error::{DownloadError, Result};
+
+/// Host used by simulated URLs
+const MOCK_HOST: &str = "mock.vortex.test";
+
+/// Tuning for simulated downloads
+#[derive(Debug, Clone)]
+pub struct MockProviderConfig {
+    /// Simulated transfer speed in bytes per second
+    pub bytes_per_second: u64,
+    /// Size of every simulated download in bytes
+    pub file_size: u64,
+    /// Interval between progress events
+    pub tick_interval: Duration,
+}
+
+impl Default for MockProviderConfig {
+    fn default() -> Self {
+        Self {
+            bytes_per_second: 2 * 1024 * 1024,
+            file_size: 20 * 1024 * 1024,
+            tick_interval: Duration::from_millis(250),
+        }
+    }
+}
+
+impl MockProviderConfig {
+    /// Build a config from VORTEX_MOCK_SPEED / VORTEX_MOCK_SIZE (bytes), falling back to defaults
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(speed) = std::env::var("VORTEX_MOCK_SPEED").ok().and_then(|v| v.parse().ok()) {
+            config.bytes_per_second = speed;
+        }
+        if let Some(size) = std::env::var("VORTEX_MOCK_SIZE").ok().and_then(|v| v.parse().ok()) {
+            config.file_size = size;
+        }
+        config
+    }
+}
+
+/// Simulation provider for UI development
+///
+/// Fabricates metadata for `https://mock.vortex.test/...` URLs and emits
+/// synthetic progress without touching the network or yt-dlp. Only compiled
+/// with the `mock-provider` feature.
+///
+/// Supported query parameters on video URLs:
+/// - `speed=<bytes/s>` overrides the configured transfer speed; a rate limit still caps it
+/// - `fail_at=<percent>` fails the download with a network error at that point
+/// - `panic_at=<percent>` panics at that point, as a crashed download task would
+/// - `hang_at=<percent>` stops reporting progress at that point and never returns, as a hung helper would
+pub struct MockProvider {
+    config: MockProviderConfig,
+    video_pattern: Regex,
+    playlist_pattern: Regex,
+    channel_pattern: Regex,
+}
+
+impl MockProvider {
+    pub fn new(config: MockProviderConfig) -> Self {
+        Self {
+            config,
+            video_pattern: Regex::new(r"^https?://mock\.vortex\.test/watch/([\w-]+)").unwrap(),
+            playlist_pattern: Regex::new(r"^https?://mock\.vortex\.test/playlist/([\w-]+)").unwrap(),
+            channel_pattern: Regex::new(r"^https?://mock\.vortex\.test/channel/([\w-]+)").unwrap(),
+        }
+    }
+    
+    /// Extract the ID captured by `pattern`
+    fn capture_id(pattern: &Regex, url: &str) -> Option<String> {
+        pattern
+            .captures(url.trim())
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+    
+    /// Read a numeric query parameter from a URL
+    fn query_param(url: &str, key: &str) -> Option<f64> {
+        let query = url.split_once('?')?.1;
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, v)| v.parse().ok())
+    }
+    
+    /// Stable pseudo-random number derived from an ID
+    fn seed(id: &str) -> u64 {
+        id.bytes().fold(0u64, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64))
+    }
+    
+    /// Fabricate metadata for a video ID
+    fn fake_video(&self, id: &str) -> VideoInfo {
+        let seed = Self::seed(id);
+        VideoInfo {
+            id: id.to_string(),
+            title: format!("Mock Video {}", id),
+            description: "Simulated video for UI development".to_string(),
+            thumbnail: String::new(),
+            duration: 30 + seed % 600,
+            uploader: "Mock Channel".to_string(),
+            upload_date: "20240101".to_string(),
+            view_count: seed % 1_000_000,
+            available_formats: vec![
+                FormatInfo {
+                    format_id: "mock-720".to_string(),
+                    ext: "mp4".to_string(),
+                    resolution: Some("1280x720".to_string()),
+                    filesize: Some(self.config.file_size),
+                },
+                FormatInfo {
+                    format_id: "mock-audio".to_string(),
+                    ext: "m4a".to_string(),
+                    resolution: None,
+                    filesize: Some(self.config.file_size / 10),
+                },
+            ],
+            platform: "Mock".to_string(),
+            url: format!("https://{}/watch/{}", MOCK_HOST, id),
+            is_live: false,
+            premiere_at: None,
+        }
+    }
+    
+    /// Fabricate a playlist with a fixed number of videos
+    fn fake_playlist(&self, id: &str, video_count: usize) -> PlaylistInfo {
+        let videos: Vec<VideoInfo> = (1..=video_count)
+            .map(|i| self.fake_video(&format!("{}-{}", id, i)))
+            .collect();
+        
+        PlaylistInfo {
+            id: id.to_string(),
+            title: format!("Mock Playlist {}", id),
+            description: "Simulated playlist for UI development".to_string(),
+            uploader: "Mock Channel".to_string(),
+            video_count: videos.len(),
+            videos,
+            platform: "Mock".to_string(),
+            url: format!("https://{}/playlist/{}", MOCK_HOST, id),
+            has_more: false,
+            page: 0,
+            page_size: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl PlatformProvider for MockProvider {
+    fn name(&self) -> &str {
+        "Mock"
+    }
+    
+    fn matches_url(&self, url: &str) -> bool {
+        let url = url.trim();
+        self.video_pattern.is_match(url)
+            || self.playlist_pattern.is_match(url)
+            || self.channel_pattern.is_match(url)
+    }
+    
+    fn supported_patterns(&self) -> Vec<String> {
+        vec![
+            format!("https://{}/watch/VIDEO_ID", MOCK_HOST),
+            format!("https://{}/playlist/PLAYLIST_ID", MOCK_HOST),
+            format!("https://{}/channel/CHANNEL_ID", MOCK_HOST),
+        ]
+    }
+    
+    async fn get_video_info(&self, url: &str) -> Result<VideoInfo> {
+        let id = Self::capture_id(&self.video_pattern, url)
+            .ok_or_else(|| DownloadError::InvalidUrl(url.to_string()))?;
+        Ok(self.fake_video(&id))
+    }
+    
+    async fn get_playlist_info(&self, url: &str) -> Result<PlaylistInfo> {
+        let id = Self::capture_id(&self.playlist_pattern, url)
+            .ok_or_else(|| DownloadError::InvalidUrl(url.to_string()))?;
+        Ok(self.fake_playlist(&id, 5))
+    }
+    
+    async fn get_channel_info(&self, url: &str) -> Result<ChannelInfo> {
+        let id = Self::capture_id(&self.channel_pattern, url)
+            .ok_or_else(|| DownloadError::InvalidUrl(url.to_string()))?;
+        
+        let playlists = vec![
+            self.fake_playlist(&format!("{}-uploads", id), 10),
+            self.fake_playlist(&format!("{}-favorites", id), 3),
+        ];
+        let all_videos = playlists[0].videos.clone();
+        
+        Ok(ChannelInfo {
+            id: id.clone(),
+            name: format!("Mock Channel {}", id),
+            description: "Simulated channel for UI development".to_string(),
+            playlists,
+            all_videos,
+            platform: "Mock".to_string(),
+            url: format!("https://{}/channel/{}", MOCK_HOST, id),
+        })
+    }
+    
+    async fn download_video(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        _save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+    ) -> Result<()> {
+        // Nothing is written; the URL only needs to name a mock video
+        Self::capture_id(&self.video_pattern, url)
+            .ok_or_else(|| DownloadError::InvalidUrl(url.to_string()))?;
+        
+        let full_speed = Self::query_param(url, "speed")
+            .map(|s| s as u64)
+            .unwrap_or(self.config.bytes_per_second);
+        let capped = |rate_limit: Option<u64>| full_speed.min(rate_limit.unwrap_or(u64::MAX)).max(1);
+        let mut speed = capped(options.rate_limit);
+        let mut rate_updates = options.rate_limit_updates;
+        let fail_at = Self::query_param(url, "fail_at");
+        let panic_at = Self::query_param(url, "panic_at");
+        let hang_at = Self::query_param(url, "hang_at");
+        let total = self.config.file_size;
+        let tick_secs = self.config.tick_interval.as_secs_f64();
+        
+        let mut downloaded = 0u64;
+        while downloaded < total {
+            tokio::time::sleep(self.config.tick_interval).await;
+            // Speed limit changes apply to the running download, as in a real transfer
+            if let Some(updates) = rate_updates.as_mut().filter(|u| u.has_changed().unwrap_or(false)) {
+                speed = capped(*updates.borrow_and_update());
+            }
+            let bytes_per_tick = ((speed as f64 * tick_secs) as u64).max(1);
+            downloaded = (downloaded + bytes_per_tick).min(total);
+            
+            let percentage = downloaded as f64 / total as f64 * 100.0;
+            if panic_at.is_some_and(|at| percentage >= at) {
+                panic!("Simulated crash at {:.0}%", percentage);
+            }
+            if hang_at.is_some_and(|at| percentage >= at) {
+                std::future::pending::<()>().await;
+            }
+            if let Some(fail_at) = fail_at {
+                if percentage >= fail_at {
+                    return Err(DownloadError::Network(format!(
+                        "Simulated network failure at {:.0}%",
+                        percentage
+                    )));
+                }
+            }
+            
+            progress_callback(DownloadProgress {
+                percentage,
+                downloaded_bytes: downloaded,
+                total_bytes: total,
+                speed: speed as f64,
+                eta: (total - downloaded) / speed,
+                elapsed: None,
+                display: None,
+            });
+        }
+        
+        Ok(())
+    }
+    
+    async fn check_dependencies(&self) -> Result<Vec<Dependency>> {
+        Ok(Vec::new())
+    }
+    
+    fn get_platform_settings(&self) -> Vec<PlatformSetting> {
+        Vec::new()
+    }
+    
+    /// Simulated downloads start from zero every time
+    fn supports_resume(&self) -> bool {
+        false
+    }
+    
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self::new(MockProviderConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    
+    fn fast_provider() -> MockProvider {
+        MockProvider::new(MockProviderConfig {
+            bytes_per_second: 1000,
+            file_size: 1000,
+            tick_interval: Duration::from_millis(10),
+        })
+    }
+    
+    fn options() -> DownloadOptions {
+        DownloadOptions {
+            quality: "best".to_string(),
+            format: "mp4".to_string(),
+            audio_only: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_matches_mock_urls() {
+        let provider = MockProvider::default();
+        assert!(provider.matches_url("https://mock.vortex.test/watch/abc"));
+        assert!(provider.matches_url("https://mock.vortex.test/playlist/list1"));
+        assert!(provider.matches_url("https://mock.vortex.test/channel/chan"));
+        assert!(!provider.matches_url("https://www.youtube.com/watch?v=abc"));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_is_deterministic() {
+        let provider = MockProvider::default();
+        let a = provider.get_video_info("https://mock.vortex.test/watch/abc").await.unwrap();
+        let b = provider.get_video_info("https://mock.vortex.test/watch/abc").await.unwrap();
+        assert_eq!(a.duration, b.duration);
+        assert_eq!(a.platform, "Mock");
+        
+        let playlist = provider.get_playlist_info("https://mock.vortex.test/playlist/p").await.unwrap();
+        assert_eq!(playlist.video_count, 5);
+        
+        let channel = provider.get_channel_info("https://mock.vortex.test/channel/c").await.unwrap();
+        assert_eq!(channel.all_videos.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_download_emits_progress_to_completion() {
+        let provider = fast_provider();
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let sink = updates.clone();
+        
+        // 25000 B/s with 10ms ticks moves 250 bytes per tick: 4 updates for 1000 bytes
+        provider
+            .download_video(
+                "https://mock.vortex.test/watch/abc?speed=25000",
+                options(),
+                Path::new("/tmp"),
+                Box::new(move |p| sink.lock().unwrap().push(p.percentage)),
+            )
+            .await
+            .unwrap();
+        
+        let updates = updates.lock().unwrap();
+        assert_eq!(updates.len(), 4);
+        assert_eq!(*updates.last().unwrap(), 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_download_follows_speed_limit_changes() {
+        let provider = fast_provider();
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let sink = updates.clone();
+        let (rate_tx, rate_rx) = tokio::sync::watch::channel(None);
+        
+        // 250 bytes in the first tick, then 100 per tick once capped at 10000 B/s
+        provider
+            .download_video(
+                "https://mock.vortex.test/watch/abc?speed=25000",
+                DownloadOptions { rate_limit_updates: Some(rate_rx), ..options() },
+                Path::new("/tmp"),
+                Box::new(move |p| {
+                    let _ = rate_tx.send(Some(10_000));
+                    sink.lock().unwrap().push(p.downloaded_bytes);
+                }),
+            )
+            .await
+            .unwrap();
+        
+        let updates = updates.lock().unwrap();
+        assert_eq!(updates[..3], [250, 350, 450]);
+        assert_eq!(updates.len(), 9);
+    }
+
+    #[tokio::test]
+    async fn test_download_simulated_failure() {
+        let provider = fast_provider();
+        let result = provider
+            .download_video(
+                "https://mock.vortex.test/watch/abc?speed=50000&fail_at=50",
+                options(),
+                Path::new("/tmp"),
+                Box::new(|_| {}),
+            )
+            .await;
+        assert!(matches!(result, Err(DownloadError::Network(_))));
+    }
+}
@@ -1,9 +1,53 @@
 pub mod provider;
 pub mod registry;
 pub mod youtube;
+pub mod reddit;
+pub mod generic;
+pub mod direct;
+pub mod bandcamp;
+pub mod kick;
+pub mod peertube;
 pub mod cache;
+pub mod rate_limit;
+pub mod metadata_limit;
+pub mod request_registry;
+pub mod ytdlp_command;
+pub mod ytdlp_backend;
+pub mod command_runner;
+pub mod postprocess;
+pub mod browsers;
+pub mod proxy;
+pub mod http_headers;
+pub mod doh;
+pub mod playlist_items;
+pub mod audio;
+pub mod po_token;
+#[cfg(feature = "mock-provider")]
+pub mod mock;
 
 pub use provider::{PlatformProvider, VideoInfo, PlaylistInfo, ChannelInfo, DownloadOptions, DownloadProgress, Dependency, PlatformSetting, SettingType, FormatInfo};
 pub use registry::PlatformRegistry;
-pub use youtube::YouTubeProvider;
+pub use youtube::{ChannelTab, YouTubeProvider};
+pub use reddit::RedditProvider;
+pub use generic::GenericProvider;
+pub use direct::DirectUrlProvider;
+pub use bandcamp::BandcampProvider;
+pub use kick::KickProvider;
+pub use peertube::PeerTubeProvider;
 pub use cache::MetadataCache;
+pub use rate_limit::RateLimiter;
+pub use metadata_limit::{MetadataLimit, DEFAULT_METADATA_CONCURRENCY, MAX_METADATA_CONCURRENCY};
+pub use request_registry::InfoRequestRegistry;
+pub use ytdlp_command::{YtDlpCommandBuilder, YtDlpRetries, SubtitleOptions, SponsorBlockOptions, AuthMethod, supports_embedded_thumbnail, validate_cookies_file, validate_format_id, validate_metadata_rule, validate_postprocessor_args};
+pub use ytdlp_backend::YtDlpBackend;
+pub use browsers::{list_available_browsers, BrowserInfo};
+pub use doh::{DohResolver, DohSettings};
+pub use http_headers::{HttpHeaderSettings, RequestHeaders};
+pub use proxy::{detect_system_proxy, validate_proxy_url, ProxySettings};
+pub use playlist_items::PlaylistItems;
+pub use audio::{validate_audio_quality, AudioFormat, AudioSettings};
+pub use po_token::PoTokenSettings;
+pub use postprocess::{AudioTags, PostprocessLimits, PostprocessPool, TranscodeSlot};
+pub use command_runner::{CommandRunner, CommandRequest, CommandOutput, OutputLine, ProcessPriority, ProcessSlot, SystemCommandRunner, sandboxed_command, MockCommandRunner, MockResponse};
+#[cfg(feature = "mock-provider")]
+pub use mock::{MockProvider, MockProviderConfig};
@@ -0,0 +1,596 @@
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::Url;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use super::command_runner::CommandRunner;
+use super::provider::*;
+use super::ytdlp_backend::{exit_error, height_capped_format, network_options, YtDlpBackend};
+use super::ytdlp_command::{validate_postprocessor_args, YtDlpCommandBuilder};
+use crate::error::{DownloadError, Result};
+use crate::log_info;
+
+/// Page size for PeerTube list endpoints (the API maximum)
+const API_PAGE_SIZE: usize = 100;
+
+/// Upper bound on videos listed for one playlist or channel
+const MAX_LISTING_VIDEOS: usize = 5000;
+
+/// PeerTube live video state: the broadcast is running
+const LIVE_STATE_PUBLISHED: u64 = 1;
+
+/// What a PeerTube URL points at
+#[derive(Debug, Clone, PartialEq)]
+enum PeerTubeResource {
+    Video(String),
+    Playlist(String),
+    Channel(String),
+    Account(String),
+}
+
+/// A PeerTube URL split into its instance origin and resource
+#[derive(Debug, Clone, PartialEq)]
+struct PeerTubeUrl {
+    /// e.g. `https://videos.example.org`
+    origin: String,
+    host: String,
+    resource: PeerTubeResource,
+}
+
+/// Provider for videos, playlists and channels on any PeerTube instance
+///
+/// PeerTube is federated, so there is no fixed host list. URLs with PeerTube's
+/// path layout are matched on any host, and the host is confirmed through its
+/// `/api/v1/config` endpoint before use. Hosts that fail the probe are
+/// remembered and no longer matched, letting later providers handle them.
+/// Metadata comes from the instance API; downloads go through yt-dlp.
+pub struct PeerTubeProvider {
+    backend: YtDlpBackend,
+    ffmpeg_path: PathBuf,
+    client: reqwest::Client,
+    video_pattern: Regex,
+    /// Probe results by host: true for confirmed PeerTube instances
+    instances: RwLock<HashMap<String, bool>>,
+}
+
+impl PeerTubeProvider {
+    pub fn new() -> Self {
+        Self::with_executables(PathBuf::from("yt-dlp"), PathBuf::from("ffmpeg"))
+    }
+    
+    /// Create a new PeerTubeProvider with custom executable paths
+    pub fn with_executables(ytdlp_path: PathBuf, ffmpeg_path: PathBuf) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("Vortex-Downloader")
+            .timeout(Duration::from_secs(15))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        
+        Self {
+            backend: YtDlpBackend::new("PeerTube", ytdlp_path),
+            ffmpeg_path,
+            client,
+            // UUID or 22-character short UUID (base58)
+            video_pattern: Regex::new(r"^([0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}|[1-9A-HJ-NP-Za-km-z]{22})$").unwrap(),
+            instances: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Replace the process runner (used by tests to replay canned output)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.backend = self.backend.with_command_runner(runner);
+        self
+    }
+    
+    /// Split a URL into instance and resource if its path has PeerTube's layout
+    fn parse_url(&self, url: &str) -> Option<PeerTubeUrl> {
+        let parsed = Url::parse(url.trim()).ok()?;
+        if parsed.scheme() != "http" && parsed.scheme() != "https" {
+            return None;
+        }
+        let host = parsed.host_str()?.to_lowercase();
+        let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+        
+        let resource = match segments.as_slice() {
+            ["w", "p", id] | ["videos", "watch", "playlist", id] | ["video-playlists", id] => {
+                PeerTubeResource::Playlist(id.to_string())
+            }
+            ["w", id] | ["videos", "watch", id] | ["videos", "embed", id] => {
+                PeerTubeResource::Video(id.to_string())
+            }
+            ["c", name, ..] | ["video-channels", name, ..] => PeerTubeResource::Channel(name.to_string()),
+            ["a", name, ..] | ["accounts", name, ..] => PeerTubeResource::Account(name.to_string()),
+            _ => return None,
+        };
+        
+        if let PeerTubeResource::Video(id) | PeerTubeResource::Playlist(id) = &resource {
+            if !self.video_pattern.is_match(id) {
+                return None;
+            }
+        }
+        
+        Some(PeerTubeUrl {
+            origin: parsed.origin().ascii_serialization(),
+            host,
+            resource,
+        })
+    }
+    
+    /// Cached probe result for a host, if it has been probed
+    fn known_instance(&self, host: &str) -> Option<bool> {
+        self.instances.read().ok()?.get(host).copied()
+    }
+    
+    /// Confirm that the URL's host runs PeerTube, probing its API on first use
+    async fn ensure_instance(&self, target: &PeerTubeUrl) -> Result<()> {
+        let is_instance = match self.known_instance(&target.host) {
+            Some(known) => known,
+            None => {
+                let probed = self.probe_instance(&target.origin).await;
+                log_info!("[PeerTube] Probed {}: {}", target.host, if probed { "PeerTube instance" } else { "not PeerTube" });
+                if let Ok(mut instances) = self.instances.write() {
+                    instances.insert(target.host.clone(), probed);
+                }
+                probed
+            }
+        };
+        
+        if is_instance {
+            Ok(())
+        } else {
+            Err(DownloadError::PlatformNotSupported(format!("{} is not a PeerTube instance", target.host)))
+        }
+    }
+    
+    /// Whether `origin` answers the PeerTube config endpoint
+    async fn probe_instance(&self, origin: &str) -> bool {
+        match self.get_json(&format!("{}/api/v1/config", origin)).await {
+            Ok(config) => config["serverVersion"].is_string() && config["instance"].is_object(),
+            Err(_) => false,
+        }
+    }
+    
+    /// GET a JSON document from an instance API
+    async fn get_json(&self, url: &str) -> Result<Value> {
+        let response = self.client.get(url).send().await.map_err(|e| {
+            if e.is_timeout() {
+                DownloadError::Timeout
+            } else {
+                DownloadError::Network(e.to_string())
+            }
+        })?;
+        
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(DownloadError::VideoUnavailable(format!("PeerTube returned {} for {}", status, url)));
+        }
+        if !status.is_success() {
+            return Err(DownloadError::Network(format!("PeerTube returned {} for {}", status, url)));
+        }
+        
+        response
+            .json()
+            .await
+            .map_err(|e| DownloadError::DownloadFailed(format!("Invalid PeerTube API response: {}", e)))
+    }
+    
+    /// Collect every video from a paginated list endpoint
+    async fn list_videos(&self, endpoint: &str, origin: &str) -> Result<(Vec<VideoInfo>, bool)> {
+        let mut videos = Vec::new();
+        let mut start = 0;
+        
+        loop {
+            let separator = if endpoint.contains('?') { '&' } else { '?' };
+            let page = self
+                .get_json(&format!("{}{}start={}&count={}", endpoint, separator, start, API_PAGE_SIZE))
+                .await?;
+            let data = page["data"].as_array().cloned().unwrap_or_default();
+            let total = page["total"].as_u64().unwrap_or(0) as usize;
+            
+            // Playlist elements wrap the video; deleted or private ones have `video: null`
+            videos.extend(
+                data.iter()
+                    .map(|item| if item["video"].is_object() { &item["video"] } else { item })
+                    .filter(|video| video.is_object())
+                    .map(|video| Self::parse_video(video, origin)),
+            );
+            
+            start += API_PAGE_SIZE;
+            if data.is_empty() || start >= total {
+                return Ok((videos, false));
+            }
+            if videos.len() >= MAX_LISTING_VIDEOS {
+                return Ok((videos, true));
+            }
+        }
+    }
+    
+    /// Convert a PeerTube video object to `VideoInfo`
+    fn parse_video(json: &Value, origin: &str) -> VideoInfo {
+        let id = json["uuid"].as_str().unwrap_or("").to_string();
+        let watch_id = json["shortUUID"].as_str().unwrap_or(&id).to_string();
+        
+        // Web videos are listed in `files`, HLS renditions under `streamingPlaylists`
+        let mut files: Vec<&Value> = json["files"].as_array().map(|f| f.iter().collect()).unwrap_or_default();
+        if files.is_empty() {
+            if let Some(playlists) = json["streamingPlaylists"].as_array() {
+                files.extend(playlists.iter().filter_map(|p| p["files"].as_array()).flatten());
+            }
+        }
+        let available_formats = files
+            .into_iter()
+            .filter_map(|file| {
+                let label = file["resolution"]["label"].as_str()?;
+                Some(FormatInfo {
+                    format_id: label.to_string(),
+                    ext: "mp4".to_string(),
+                    resolution: Some(label.to_string()),
+                    filesize: file["size"].as_u64(),
+                })
+            })
+            .collect();
+        
+        let uploader = json["channel"]["displayName"]
+            .as_str()
+            .or_else(|| json["account"]["displayName"].as_str())
+            .unwrap_or("Unknown")
+            .to_string();
+        
+        VideoInfo {
+            id,
+            title: json["name"].as_str().unwrap_or("PeerTube Video").to_string(),
+            description: json["description"].as_str().unwrap_or("").to_string(),
+            thumbnail: json["thumbnailPath"]
+                .as_str()
+                .map(|path| format!("{}{}", origin, path))
+                .unwrap_or_default(),
+            duration: json["duration"].as_u64().unwrap_or(0),
+            uploader,
+            // yt-dlp style YYYYMMDD
+            upload_date: json["publishedAt"]
+                .as_str()
+                .and_then(|date| date.get(..10))
+                .map(|date| date.replace('-', ""))
+                .unwrap_or_default(),
+            view_count: json["views"].as_u64().unwrap_or(0),
+            available_formats,
+            platform: "PeerTube".to_string(),
+            url: format!("{}/w/{}", origin, watch_id),
+            is_live: false,
+            premiere_at: None,
+        }
+    }
+    
+    /// Reject broadcasts that are currently running
+    fn check_not_live(json: &Value) -> Result<()> {
+        let running = json["isLive"].as_bool().unwrap_or(false)
+            && json["state"]["id"].as_u64() == Some(LIVE_STATE_PUBLISHED);
+        if running {
+            let name = json["name"].as_str().unwrap_or("This video");
+            return Err(DownloadError::LiveStream(format!("{} is currently streaming", name)));
+        }
+        Ok(())
+    }
+    
+    /// Fetch a single video object, resolving the instance first
+    async fn fetch_video(&self, url: &str) -> Result<(PeerTubeUrl, Value)> {
+        let target = self
+            .parse_url(url)
+            .ok_or_else(|| DownloadError::InvalidUrl(format!("Not a PeerTube URL: {}", url)))?;
+        let id = match &target.resource {
+            PeerTubeResource::Video(id) => id.clone(),
+            _ => return Err(DownloadError::InvalidUrl(format!("Not a PeerTube video URL: {}", url))),
+        };
+        
+        self.ensure_instance(&target).await?;
+        let json = self.get_json(&format!("{}/api/v1/videos/{}", target.origin, id)).await?;
+        Self::check_not_live(&json)?;
+        Ok((target, json))
+    }
+    
+    /// yt-dlp's host-qualified PeerTube URL, which works for instances it does not know
+    fn ytdlp_url(host: &str, uuid: &str) -> String {
+        format!("peertube:{}:{}", host, uuid)
+    }
+    
+    /// Internal download implementation with cancellation support
+    async fn download_video_impl(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<()> {
+        let (target, json) = self.fetch_video(url).await?;
+        let uuid = json["uuid"]
+            .as_str()
+            .ok_or_else(|| DownloadError::DownloadFailed("PeerTube video has no UUID".to_string()))?;
+        
+        let save_path_str = save_path.to_str()
+            .ok_or_else(|| DownloadError::DownloadFailed(format!("Invalid save path: {:?}", save_path)))?;
+        
+        let mut command = YtDlpCommandBuilder::new()
+            .machine_progress()
+            .no_warnings()
+            .no_playlist()
+            .output(save_path_str)
+            .ffmpeg_location(&self.ffmpeg_path)
+            .format(&height_capped_format(&options));
+        
+        if options.audio_only {
+            command = command
+                .extract_audio(&options.format)
+                .tag_audio(&options.format, &options.tags);
+            if let Some(quality) = options.audio_quality_arg() {
+                command = command.audio_quality(quality);
+            }
+        }
+        
+        if options.embed_chapters {
+            command = command.embed_chapters();
+        }
+        
+        if options.wants_faststart() {
+            command = command.faststart();
+        }
+        
+        if let Some(ppa) = options.postprocessor_args.as_deref() {
+            command = command.postprocessor_args(&validate_postprocessor_args(ppa)?);
+        }
+        
+        if let Some(threads) = options.ffmpeg_threads() {
+            command = command.ffmpeg_threads(threads);
+        }
+        
+        command = network_options(command, &options);
+        
+        if let Some(archive) = &options.download_archive {
+            command = command.download_archive(archive);
+        }
+        
+        if let Some((start, end)) = options.section() {
+            command = command.download_sections(start, end);
+        }
+        
+        let command = command.url(&Self::ytdlp_url(&target.host, uuid));
+        let output = self.backend
+            .download(&command, save_path, &options, progress_callback, cancel_token.as_ref())
+            .await?;
+        
+        if !output.success() {
+            return Err(exit_error(&output));
+        }
+        
+        log_info!("[PeerTube] ✓ Download completed: {}", url);
+        Ok(())
+    }
+    
+    /// Download video with cancellation support (public method for download manager)
+    pub async fn download_with_cancellation(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: CancellationToken,
+    ) -> Result<()> {
+        self.download_video_impl(url, options, save_path, progress_callback, Some(cancel_token)).await
+    }
+}
+
+#[async_trait]
+impl PlatformProvider for PeerTubeProvider {
+    fn name(&self) -> &str {
+        "PeerTube"
+    }
+    
+    fn matches_url(&self, url: &str) -> bool {
+        match self.parse_url(url) {
+            Some(target) => self.known_instance(&target.host) != Some(false),
+            None => false,
+        }
+    }
+    
+    /// Probe hosts not yet known to run PeerTube, so other providers get the rest
+    async fn confirm_url(&self, url: &str) -> bool {
+        match self.parse_url(url) {
+            Some(target) => self.ensure_instance(&target).await.is_ok(),
+            None => false,
+        }
+    }
+    
+    fn supported_patterns(&self) -> Vec<String> {
+        vec![
+            "https://INSTANCE/w/VIDEO_ID".to_string(),
+            "https://INSTANCE/videos/watch/VIDEO_UUID".to_string(),
+            "https://INSTANCE/w/p/PLAYLIST_ID".to_string(),
+            "https://INSTANCE/c/CHANNEL".to_string(),
+            "https://INSTANCE/a/ACCOUNT".to_string(),
+        ]
+    }
+    
+    async fn get_video_info(&self, url: &str) -> Result<VideoInfo> {
+        let (target, json) = self.fetch_video(url).await?;
+        Ok(Self::parse_video(&json, &target.origin))
+    }
+    
+    async fn get_playlist_info(&self, url: &str) -> Result<PlaylistInfo> {
+        let target = self
+            .parse_url(url)
+            .ok_or_else(|| DownloadError::InvalidUrl(format!("Not a PeerTube URL: {}", url)))?;
+        let id = match &target.resource {
+            PeerTubeResource::Playlist(id) => id.clone(),
+            _ => return Err(DownloadError::InvalidUrl(format!("Not a PeerTube playlist URL: {}", url))),
+        };
+        
+        self.ensure_instance(&target).await?;
+        let endpoint = format!("{}/api/v1/video-playlists/{}", target.origin, id);
+        let meta = self.get_json(&endpoint).await?;
+        let (videos, has_more) = self.list_videos(&format!("{}/videos", endpoint), &target.origin).await?;
+        
+        Ok(PlaylistInfo {
+            id: meta["uuid"].as_str().unwrap_or(&id).to_string(),
+            title: meta["displayName"].as_str().unwrap_or("PeerTube Playlist").to_string(),
+            description: meta["description"].as_str().unwrap_or("").to_string(),
+            uploader: meta["ownerAccount"]["displayName"].as_str().unwrap_or("Unknown").to_string(),
+            video_count: meta["videosLength"].as_u64().map(|n| n as usize).unwrap_or(videos.len()),
+            videos,
+            platform: "PeerTube".to_string(),
+            url: url.trim().to_string(),
+            has_more,
+            page: 0,
+            page_size: 0,
+        })
+    }
+    
+    async fn get_channel_info(&self, url: &str) -> Result<ChannelInfo> {
+        let target = self
+            .parse_url(url)
+            .ok_or_else(|| DownloadError::InvalidUrl(format!("Not a PeerTube URL: {}", url)))?;
+        let endpoint = match &target.resource {
+            PeerTubeResource::Channel(name) => format!("{}/api/v1/video-channels/{}", target.origin, name),
+            PeerTubeResource::Account(name) => format!("{}/api/v1/accounts/{}", target.origin, name),
+            _ => return Err(DownloadError::InvalidUrl(format!("Not a PeerTube channel URL: {}", url))),
+        };
+        
+        self.ensure_instance(&target).await?;
+        let meta = self.get_json(&endpoint).await?;
+        let (all_videos, _) = self
+            .list_videos(&format!("{}/videos?sort=-publishedAt", endpoint), &target.origin)
+            .await?;
+        
+        Ok(ChannelInfo {
+            id: meta["name"].as_str().unwrap_or("").to_string(),
+            name: meta["displayName"].as_str().unwrap_or("PeerTube Channel").to_string(),
+            description: meta["description"].as_str().unwrap_or("").to_string(),
+            playlists: Vec::new(),
+            all_videos,
+            platform: "PeerTube".to_string(),
+            url: url.trim().to_string(),
+        })
+    }
+    
+    async fn download_video(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+    ) -> Result<()> {
+        self.download_video_impl(url, options, save_path, progress_callback, None).await
+    }
+    
+    async fn check_dependencies(&self) -> Result<Vec<Dependency>> {
+        Ok(vec![
+            Dependency {
+                name: "yt-dlp (bundled)".to_string(),
+                installed: self.backend.ytdlp_path().exists(),
+                version: None,
+                install_instructions: "yt-dlp is bundled with the application. If missing, please reinstall the application.".to_string(),
+            },
+        ])
+    }
+    
+    fn get_platform_settings(&self) -> Vec<PlatformSetting> {
+        Vec::new()
+    }
+    
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl Default for PeerTubeProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const UUID: &str = "9c9de5e8-0a1e-484a-b099-e80766180a6d";
+    const SHORT_UUID: &str = "kkGMgK9ZtnKfYAgnEtQxbv";
+    
+    #[test]
+    fn test_parse_peertube_urls() {
+        let provider = PeerTubeProvider::new();
+        
+        let target = provider.parse_url(&format!("https://tube.example.org/w/{}", SHORT_UUID)).unwrap();
+        assert_eq!(target.origin, "https://tube.example.org");
+        assert_eq!(target.resource, PeerTubeResource::Video(SHORT_UUID.to_string()));
+        
+        let target = provider.parse_url(&format!("https://Tube.Example.org:8443/videos/watch/{}", UUID)).unwrap();
+        assert_eq!(target.origin, "https://tube.example.org:8443");
+        assert_eq!(target.host, "tube.example.org");
+        assert_eq!(target.resource, PeerTubeResource::Video(UUID.to_string()));
+        
+        let target = provider.parse_url(&format!("https://tube.example.org/w/p/{}", SHORT_UUID)).unwrap();
+        assert_eq!(target.resource, PeerTubeResource::Playlist(SHORT_UUID.to_string()));
+        
+        let target = provider.parse_url("https://tube.example.org/c/cooking@other.example/videos").unwrap();
+        assert_eq!(target.resource, PeerTubeResource::Channel("cooking@other.example".to_string()));
+        
+        let target = provider.parse_url("https://tube.example.org/a/alice").unwrap();
+        assert_eq!(target.resource, PeerTubeResource::Account("alice".to_string()));
+        
+        assert!(provider.parse_url("https://example.com/w/not-an-id").is_none());
+        assert!(provider.parse_url("https://example.com/about").is_none());
+    }
+
+    #[test]
+    fn test_hosts_failing_the_probe_are_no_longer_matched() {
+        let provider = PeerTubeProvider::new();
+        let url = format!("https://blog.example.com/w/{}", SHORT_UUID);
+        assert!(provider.matches_url(&url));
+        
+        provider.instances.write().unwrap().insert("blog.example.com".to_string(), false);
+        assert!(!provider.matches_url(&url));
+        assert!(provider.matches_url(&format!("https://tube.example.org/w/{}", SHORT_UUID)));
+    }
+
+    #[test]
+    fn test_parse_video_from_api() {
+        let json: Value = serde_json::from_str(&format!(
+            r#"{{"uuid":"{}","shortUUID":"{}","name":"Sourdough","duration":754,"views":42,
+               "publishedAt":"2024-03-09T18:22:01.000Z","thumbnailPath":"/lazy-static/thumbnails/a.jpg",
+               "channel":{{"displayName":"Cooking"}},"files":[],
+               "streamingPlaylists":[{{"files":[{{"resolution":{{"id":720,"label":"720p"}},"size":1048576}}]}}]}}"#,
+            UUID, SHORT_UUID
+        )).unwrap();
+        
+        let info = PeerTubeProvider::parse_video(&json, "https://tube.example.org");
+        assert_eq!(info.id, UUID);
+        assert_eq!(info.title, "Sourdough");
+        assert_eq!(info.uploader, "Cooking");
+        assert_eq!(info.upload_date, "20240309");
+        assert_eq!(info.thumbnail, "https://tube.example.org/lazy-static/thumbnails/a.jpg");
+        assert_eq!(info.url, format!("https://tube.example.org/w/{}", SHORT_UUID));
+        assert_eq!(info.available_formats.len(), 1);
+        assert_eq!(info.available_formats[0].filesize, Some(1048576));
+    }
+
+    #[test]
+    fn test_running_live_is_rejected() {
+        let live: Value = serde_json::json!({"name": "Stream", "isLive": true, "state": {"id": 1}});
+        assert!(matches!(PeerTubeProvider::check_not_live(&live), Err(DownloadError::LiveStream(_))));
+        
+        // Ended lives with a replay are regular videos
+        let replay: Value = serde_json::json!({"name": "Stream", "isLive": false, "state": {"id": 1}});
+        assert!(PeerTubeProvider::check_not_live(&replay).is_ok());
+    }
+
+    #[test]
+    fn test_ytdlp_url_names_the_instance() {
+        assert_eq!(
+            PeerTubeProvider::ytdlp_url("tube.example.org", UUID),
+            format!("peertube:tube.example.org:{}", UUID)
+        );
+    }
+}
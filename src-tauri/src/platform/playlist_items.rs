@@ -0,0 +1,103 @@
+use crate::error::{DownloadError, Result};
+
+/// One entry of a playlist item selection: a single position or an inclusive range
+#[derive(Debug, Clone, PartialEq)]
+enum Selection {
+    Index(i64),
+    /// `None` bounds run to the start or end of the playlist
+    Range(Option<i64>, Option<i64>),
+}
+
+/// Subset of a playlist in yt-dlp's `--playlist-items` syntax, e.g. `1-10,15`
+///
+/// Positions are 1-based; negative positions count from the end (`-1` is the
+/// last video) and open ranges like `20-` run to the end of the playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistItems {
+    selections: Vec<Selection>,
+}
+
+impl PlaylistItems {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = |part: &str| DownloadError::InvalidOption(format!("Invalid playlist item selection: {}", part));
+        let number = |s: &str, part: &str| -> Result<Option<i64>> {
+            match s {
+                "" => Ok(None),
+                s => s.parse().map(Some).map_err(|_| invalid(part)),
+            }
+        };
+        
+        let mut selections = Vec::new();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            if let Ok(index) = part.parse::<i64>() {
+                selections.push(Selection::Index(index));
+                continue;
+            }
+            
+            // The range separator is the first '-' or ':' that isn't a leading minus sign
+            let split = part
+                .char_indices()
+                .find(|(i, c)| *c == ':' || (*c == '-' && *i > 0))
+                .map(|(i, _)| i)
+                .ok_or_else(|| invalid(part))?;
+            let start = number(&part[..split], part)?;
+            let end = number(&part[split + 1..], part)?;
+            selections.push(Selection::Range(start, end));
+        }
+        
+        if selections.is_empty() {
+            return Err(invalid(spec));
+        }
+        Ok(Self { selections })
+    }
+    
+    /// Whether the 1-based `position` in a playlist of `len` videos is selected
+    pub fn contains(&self, position: usize, len: usize) -> bool {
+        let resolve = |index: i64| if index < 0 { len as i64 + 1 + index } else { index };
+        let position = position as i64;
+        self.selections.iter().any(|selection| match *selection {
+            Selection::Index(index) => resolve(index) == position,
+            Selection::Range(start, end) => {
+                start.map_or(1, resolve) <= position && position <= end.map_or(len as i64, resolve)
+            }
+        })
+    }
+    
+    /// The selected entries of `items`, in playlist order
+    pub fn select<T: Clone>(&self, items: &[T]) -> Vec<T> {
+        items
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.contains(i + 1, items.len()))
+            .map(|(_, item)| item.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_select_ranges_and_single_items() {
+        let videos: Vec<usize> = (1..=20).collect();
+        
+        let items = PlaylistItems::parse("1-3, 15,7").unwrap();
+        assert_eq!(items.select(&videos), vec![1, 2, 3, 7, 15]);
+        
+        assert_eq!(PlaylistItems::parse("18-").unwrap().select(&videos), vec![18, 19, 20]);
+        assert_eq!(PlaylistItems::parse(":2").unwrap().select(&videos), vec![1, 2]);
+        assert_eq!(PlaylistItems::parse("-1").unwrap().select(&videos), vec![20]);
+        assert_eq!(PlaylistItems::parse("-3--2").unwrap().select(&videos), vec![18, 19]);
+        
+        // Positions past the end select nothing
+        assert!(PlaylistItems::parse("25-30").unwrap().select(&videos).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_selections() {
+        assert!(PlaylistItems::parse("").is_err());
+        assert!(PlaylistItems::parse("1-a").is_err());
+        assert!(PlaylistItems::parse("first").is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! Proof-of-origin (PO) token and visitor data for YouTube's bot checks
+//!
+//! YouTube increasingly answers yt-dlp with "Sign in to confirm you're not a
+//! bot". Besides cookies, yt-dlp can get past it with a PO token and the
+//! visitor data it was minted for, both taken from a browser session and
+//! passed as `youtube` extractor arguments.
+
+use serde::{Deserialize, Serialize};
+use crate::error::{DownloadError, Result};
+
+/// Client and context a bare token is assumed to be for
+const DEFAULT_TOKEN_CONTEXT: &str = "web.gvs";
+
+/// PO token and visitor data passed to yt-dlp's YouTube extractor
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PoTokenSettings {
+    /// `CLIENT.CONTEXT+TOKEN` (e.g. `web.gvs+MnQ...`), or a bare token for the web client
+    #[serde(default)]
+    pub po_token: Option<String>,
+    /// `visitorData` of the session the token was generated in
+    #[serde(default)]
+    pub visitor_data: Option<String>,
+}
+
+impl PoTokenSettings {
+    /// Check both values only use characters yt-dlp's argument syntax can carry
+    pub fn validate(&self) -> Result<()> {
+        if let Some(token) = self.po_token() {
+            let (context, value) = token.split_once('+').unwrap_or_default();
+            let context_ok = context
+                .split_once('.')
+                .is_some_and(|(client, kind)| is_word(client) && is_word(kind));
+            if !context_ok || !is_token(value) {
+                return Err(DownloadError::InvalidOption(
+                    "PO token must look like web.gvs+TOKEN (letters, digits, '-', '_' and '=')".to_string(),
+                ));
+            }
+        }
+        if let Some(visitor_data) = self.visitor_data() {
+            if !is_token(&visitor_data.replace('%', "")) {
+                return Err(DownloadError::InvalidOption(
+                    "Visitor data may only contain letters, digits, '-', '_', '=' and '%'".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+    
+    /// The token with its client and context, `web.gvs+` added to a bare token
+    pub fn po_token(&self) -> Option<String> {
+        let token = self.po_token.as_deref().map(str::trim).filter(|t| !t.is_empty())?;
+        Some(if token.contains('+') {
+            token.to_string()
+        } else {
+            format!("{}+{}", DEFAULT_TOKEN_CONTEXT, token)
+        })
+    }
+    
+    pub fn visitor_data(&self) -> Option<String> {
+        self.visitor_data.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+    }
+    
+    /// `youtube` extractor arguments for the configured values; empty when none are set
+    ///
+    /// Visitor data only applies when yt-dlp skips fetching its own from the
+    /// webpage and player configs.
+    pub fn extractor_args(&self) -> Vec<(&'static str, String)> {
+        let mut args = Vec::new();
+        if let Some(token) = self.po_token() {
+            args.push(("po_token", token));
+        }
+        if let Some(visitor_data) = self.visitor_data() {
+            args.push(("visitor_data", visitor_data));
+            args.push(("player_skip", "webpage,configs".to_string()));
+        }
+        args
+    }
+}
+
+fn is_word(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '='))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_extractor_args() {
+        assert!(PoTokenSettings::default().extractor_args().is_empty());
+        
+        let settings = PoTokenSettings {
+            po_token: Some(" MnQ-abc_123= ".to_string()),
+            visitor_data: Some("CgtBQnlVMnBiVFJPYyiQ%3D%3D".to_string()),
+        };
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.extractor_args(), vec![
+            ("po_token", "web.gvs+MnQ-abc_123=".to_string()),
+            ("visitor_data", "CgtBQnlVMnBiVFJPYyiQ%3D%3D".to_string()),
+            ("player_skip", "webpage,configs".to_string()),
+        ]);
+        
+        let mweb = PoTokenSettings { po_token: Some("mweb.gvs+abc".to_string()), visitor_data: None };
+        assert_eq!(mweb.extractor_args(), vec![("po_token", "mweb.gvs+abc".to_string())]);
+    }
+
+    #[test]
+    fn test_validate_rejects_argument_separators() {
+        for token in ["abc;player_client=ios", "web.gvs+", "web+abc", "a b"] {
+            let settings = PoTokenSettings { po_token: Some(token.to_string()), visitor_data: None };
+            assert!(settings.validate().is_err(), "{}", token);
+        }
+        let settings = PoTokenSettings { po_token: None, visitor_data: Some("abc,def".to_string()) };
+        assert!(settings.validate().is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// User limits on ffmpeg work during post-processing
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PostprocessLimits {
+    /// Share of CPU cores ffmpeg may use per job, 1-100 ("max 50% CPU")
+    pub cpu_percent: u8,
+    /// Transcoding jobs allowed at once; 0 means no limit
+    pub max_concurrent_transcodes: usize,
+}
+
+impl Default for PostprocessLimits {
+    fn default() -> Self {
+        Self {
+            cpu_percent: 100,
+            max_concurrent_transcodes: 0,
+        }
+    }
+}
+
+impl PostprocessLimits {
+    /// ffmpeg `-threads` value for `cores` CPU cores, `None` when uncapped
+    pub fn ffmpeg_threads(&self, cores: usize) -> Option<usize> {
+        if self.cpu_percent >= 100 {
+            return None;
+        }
+        Some((cores * self.cpu_percent as usize / 100).max(1))
+    }
+}
+
+/// Album and track number written into audio downloads
+///
+/// Set when an item comes from a playlist; artist, title and cover art come
+/// from the video itself.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct AudioTags {
+    /// Album tag, usually the playlist name
+    #[serde(default)]
+    pub album: Option<String>,
+    /// Position in the playlist, 1-based
+    #[serde(default)]
+    pub track_number: Option<u32>,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    limits: PostprocessLimits,
+    active: usize,
+}
+
+/// Shared scheduler for ffmpeg post-processing across all downloads
+///
+/// Jobs take a slot before transcoding and wait while `max_concurrent_transcodes`
+/// are already running. The thread cap applies to jobs started afterwards.
+#[derive(Debug, Default)]
+pub struct PostprocessPool {
+    state: Mutex<PoolState>,
+    released: Notify,
+}
+
+/// A running post-processing job; frees its slot when dropped
+#[derive(Debug)]
+pub struct TranscodeSlot {
+    pool: Arc<PostprocessPool>,
+}
+
+impl Drop for TranscodeSlot {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.pool.state.lock() {
+            state.active = state.active.saturating_sub(1);
+        }
+        self.pool.released.notify_waiters();
+    }
+}
+
+impl PostprocessPool {
+    pub fn new(limits: PostprocessLimits) -> Self {
+        Self {
+            state: Mutex::new(PoolState { limits, active: 0 }),
+            released: Notify::new(),
+        }
+    }
+    
+    /// Replace the limits; waiting jobs re-check against the new concurrency cap
+    pub fn set_limits(&self, limits: PostprocessLimits) {
+        if let Ok(mut state) = self.state.lock() {
+            state.limits = limits;
+        }
+        self.released.notify_waiters();
+    }
+    
+    pub fn limits(&self) -> PostprocessLimits {
+        self.state.lock().map(|s| s.limits).unwrap_or_default()
+    }
+    
+    /// ffmpeg `-threads` value for this machine under the current limits
+    pub fn ffmpeg_threads(&self) -> Option<usize> {
+        let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        self.limits().ffmpeg_threads(cores)
+    }
+    
+    /// Jobs currently holding a slot
+    pub fn active(&self) -> usize {
+        self.state.lock().map(|s| s.active).unwrap_or(0)
+    }
+    
+    /// Wait for a free transcoding slot
+    pub async fn acquire(self: &Arc<Self>) -> TranscodeSlot {
+        loop {
+            // Registered before checking, so a release in between is not missed
+            let released = self.released.notified();
+            {
+                let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                let max = state.limits.max_concurrent_transcodes;
+                if max == 0 || state.active < max {
+                    state.active += 1;
+                    return TranscodeSlot { pool: Arc::clone(self) };
+                }
+            }
+            released.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    
+    #[test]
+    fn test_cpu_percent_maps_to_thread_count() {
+        let half = PostprocessLimits { cpu_percent: 50, max_concurrent_transcodes: 0 };
+        assert_eq!(half.ffmpeg_threads(8), Some(4));
+        assert_eq!(half.ffmpeg_threads(1), Some(1));
+        assert_eq!(PostprocessLimits::default().ffmpeg_threads(8), None);
+    }
+
+    #[test]
+    fn test_only_reencoding_downloads_need_a_slot() {
+        let with_args = |args: &str| crate::platform::DownloadOptions {
+            postprocessor_args: Some(args.to_string()),
+            ..Default::default()
+        };
+        assert!(!crate::platform::DownloadOptions::default().transcodes());
+        assert!(!with_args("-c:v copy -c:a copy -movflags +faststart").transcodes());
+        assert!(with_args("-c:v libx264 -crf 20").transcodes());
+        assert!(crate::platform::DownloadOptions { audio_only: true, ..Default::default() }.transcodes());
+    }
+
+    #[tokio::test]
+    async fn test_pool_limits_concurrent_transcodes() {
+        let pool = Arc::new(PostprocessPool::new(PostprocessLimits { cpu_percent: 100, max_concurrent_transcodes: 1 }));
+        let first = pool.acquire().await;
+        
+        let waiting = tokio::spawn({
+            let pool = Arc::clone(&pool);
+            async move { pool.acquire().await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+        assert_eq!(pool.active(), 1);
+        
+        drop(first);
+        let second = tokio::time::timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap();
+        assert_eq!(pool.active(), 1);
+        drop(second);
+        assert_eq!(pool.active(), 0);
+    }
+}
@@ -1,8 +1,20 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::watch;
 use crate::error::Result;
+use crate::i18n::{translate, Language};
+use super::audio::AudioFormat;
+use crate::formatting::ProgressDisplay;
+use super::http_headers::RequestHeaders;
+use super::metadata_limit::MetadataLimit;
+use super::ytdlp_backend::MetadataNetwork;
+use super::command_runner::{ProcessPriority, ProcessSlot};
+use super::postprocess::{AudioTags, PostprocessPool, TranscodeSlot};
+use super::ytdlp_command::YtDlpRetries;
 
 /// Trait that all platform providers must implement
 #[async_trait]
@@ -13,9 +25,20 @@ pub trait PlatformProvider: Send + Sync {
     /// Check if URL belongs to this platform
     fn matches_url(&self, url: &str) -> bool;
     
+    /// Confirm a URL that `matches_url` accepted on its shape alone, e.g. by probing its host.
+    /// `PlatformRegistry::resolve_url` moves on to the next matching provider when this is false
+    async fn confirm_url(&self, _url: &str) -> bool {
+        true
+    }
+    
     /// Get supported URL patterns (for UI hints)
     fn supported_patterns(&self) -> Vec<String>;
     
+    /// Normalize a matched URL (e.g. strip tracking parameters)
+    fn normalize_url(&self, url: &str) -> String {
+        url.trim().to_string()
+    }
+    
     /// Get video information
     async fn get_video_info(&self, url: &str) -> Result<VideoInfo>;
     
@@ -40,6 +63,22 @@ pub trait PlatformProvider: Send + Sync {
     /// Get platform-specific settings
     fn get_platform_settings(&self) -> Vec<PlatformSetting>;
     
+    /// Cap on this provider's concurrent metadata operations, for providers that have one
+    fn metadata_limit(&self) -> Option<&MetadataLimit> {
+        None
+    }
+    
+    /// Request settings for this provider's metadata queries, for providers that run them through yt-dlp
+    fn metadata_network(&self) -> Option<&MetadataNetwork> {
+        None
+    }
+    
+    /// Whether a stopped download picks up from its partial file when started again.
+    /// yt-dlp continues `.part` files; providers that start over return false
+    fn supports_resume(&self) -> bool {
+        true
+    }
+    
     /// Enable downcasting to concrete types
     fn as_any(&self) -> &dyn Any;
 }
@@ -57,6 +96,29 @@ pub struct VideoInfo {
     pub available_formats: Vec<FormatInfo>,
     pub platform: String,
     pub url: String,
+    /// Streaming right now; `duration` is 0 and the download only ends with the stream
+    #[serde(default)]
+    pub is_live: bool,
+    /// RFC 3339 start time of an upcoming premiere or stream
+    #[serde(default)]
+    pub premiere_at: Option<String>,
+}
+
+impl VideoInfo {
+    /// Expected download size in bytes, from the sizes the formats report
+    ///
+    /// A `format_id` may join formats with `+` (`137+140`), whose sizes add up;
+    /// without one, the largest format stands in for yt-dlp's pick. `None` when
+    /// a size isn't known.
+    pub fn estimated_size(&self, format_id: Option<&str>) -> Option<u64> {
+        match format_id {
+            Some(format_id) => format_id
+                .split('+')
+                .map(|id| self.available_formats.iter().find(|f| f.format_id == id)?.filesize)
+                .sum(),
+            None => self.available_formats.iter().filter_map(|f| f.filesize).max(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -96,11 +158,182 @@ pub struct FormatInfo {
     pub filesize: Option<u64>,
 }
 
+/// Containers video downloads can be saved in; other requests get `mp4`
+pub(crate) const VIDEO_CONTAINERS: &[&str] = &["mp4", "mkv", "webm", "mov", "m4v"];
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DownloadOptions {
     pub quality: String,
     pub format: String,
     pub audio_only: bool,
+    /// Advanced ffmpeg output arguments, validated before use
+    #[serde(default)]
+    pub postprocessor_args: Option<String>,
+    /// Relocate the MP4 index for streaming-friendly output (the `mp4_faststart` setting)
+    #[serde(default)]
+    pub faststart: bool,
+    /// Write chapter markers into the file (the `embed_chapters` setting or item override)
+    #[serde(default)]
+    pub embed_chapters: bool,
+    /// Subtitle languages written next to the video (e.g. `["en", "ja"]`); empty for none
+    #[serde(default)]
+    pub subtitle_languages: Vec<String>,
+    /// Use auto-generated captions for languages without manual subtitles
+    #[serde(default)]
+    pub auto_subtitles: bool,
+    /// Scheduling priority for the yt-dlp/ffmpeg processes doing the download
+    #[serde(default)]
+    pub priority: ProcessPriority,
+    /// Saved provider settings for the item's platform, keyed as in `get_platform_settings`
+    #[serde(default)]
+    pub platform_settings: HashMap<String, serde_json::Value>,
+    /// HTTP(S) proxy for yt-dlp
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// User-agent and extra HTTP headers for yt-dlp
+    #[serde(default)]
+    pub headers: RequestHeaders,
+    /// yt-dlp download archive; videos listed there are skipped
+    #[serde(default)]
+    pub download_archive: Option<PathBuf>,
+    /// Record a live stream from its beginning rather than from the moment the download starts
+    #[serde(default)]
+    pub record_live: bool,
+    /// Wait for a premiere or scheduled stream to start instead of failing
+    #[serde(default)]
+    pub wait_for_video: bool,
+    /// Start of the clip to download, in seconds; the whole video when unset
+    #[serde(default)]
+    pub section_start: Option<f64>,
+    /// End of the clip in seconds; the end of the video when unset
+    #[serde(default)]
+    pub section_end: Option<f64>,
+    /// Bitrate for audio extraction (`--audio-quality`), e.g. `192K` or `0` for best VBR
+    #[serde(default)]
+    pub audio_quality: Option<String>,
+    /// Exact yt-dlp format (`-f`), from `available_formats`; `137+140` pairs a video and an audio stream.
+    /// Overrides `quality` when set
+    #[serde(default)]
+    pub format_id: Option<String>,
+    /// DASH/HLS fragments to fetch in parallel (`--concurrent-fragments`); one at a time when unset
+    #[serde(default)]
+    pub concurrent_fragments: Option<u32>,
+    /// Request and fragment retries within one yt-dlp run; yt-dlp's defaults when unset
+    #[serde(default)]
+    pub retries: Option<YtDlpRetries>,
+    /// Speed cap in bytes per second (`--limit-rate`), this download's share of the global limit
+    #[serde(default)]
+    pub rate_limit: Option<u64>,
+    /// Later changes to `rate_limit`, for providers that pace their own reads
+    #[serde(skip)]
+    pub rate_limit_updates: Option<watch::Receiver<Option<u64>>>,
+    /// Album and track number for audio downloads
+    #[serde(default)]
+    pub tags: AudioTags,
+    /// Shared ffmpeg thread cap and transcode scheduling; unlimited when unset
+    #[serde(skip)]
+    pub postprocess: Option<Arc<PostprocessPool>>,
+    /// Receives the PID of the helper process while it runs
+    #[serde(skip)]
+    pub process: Option<ProcessSlot>,
+}
+
+impl DownloadOptions {
+    /// Extension of the finished file, from what is actually written rather than the requested container
+    ///
+    /// Audio-only downloads get their audio format's extension even when a video
+    /// container like `mp4` was asked for.
+    pub fn output_extension(&self) -> &'static str {
+        if self.audio_only {
+            return AudioFormat::for_container(&self.format).as_str();
+        }
+        let format = self.format.to_lowercase();
+        VIDEO_CONTAINERS.iter().find(|c| **c == format).copied().unwrap_or("mp4")
+    }
+    
+    /// Audio quality to request, if any; lossless formats have no bitrate
+    pub fn audio_quality_arg(&self) -> Option<&str> {
+        let lossless = AudioFormat::parse(&self.format).is_some_and(|f| f.is_lossless());
+        self.audio_quality.as_deref().filter(|_| self.audio_only && !lossless)
+    }
+    
+    /// Whether faststart applies: enabled and producing an MP4-family video file
+    pub fn wants_faststart(&self) -> bool {
+        self.faststart && !self.audio_only && matches!(self.format.as_str(), "mp4" | "m4v" | "mov")
+    }
+    
+    /// Whether post-processing re-encodes (audio extraction or user encoder settings) rather than remuxing
+    pub fn transcodes(&self) -> bool {
+        if self.audio_only {
+            return true;
+        }
+        let args: Vec<&str> = self.postprocessor_args.as_deref().unwrap_or("").split_whitespace().collect();
+        args.iter().enumerate().any(|(i, arg)| match *arg {
+            "-movflags" | "-metadata" => false,
+            "-c:a" | "-c:v" => args.get(i + 1) != Some(&"copy"),
+            _ => arg.starts_with('-'),
+        })
+    }
+    
+    /// Clip bounds as (start, end) when only a section of the video is wanted
+    pub fn section(&self) -> Option<(f64, Option<f64>)> {
+        match (self.section_start, self.section_end) {
+            (None, None) => None,
+            (start, end) => Some((start.unwrap_or(0.0), end)),
+        }
+    }
+    
+    /// Length of the clip in seconds, when both ends are known
+    pub fn section_length(&self) -> Option<f64> {
+        let (start, end) = self.section()?;
+        end.map(|end| end - start).filter(|len| *len > 0.0)
+    }
+    
+    /// ffmpeg `-threads` cap from the post-processing limits
+    pub fn ffmpeg_threads(&self) -> Option<usize> {
+        self.postprocess.as_ref()?.ffmpeg_threads()
+    }
+    
+    /// Wait for a transcoding slot if this download re-encodes
+    pub async fn transcode_slot(&self) -> Option<TranscodeSlot> {
+        match &self.postprocess {
+            Some(pool) if self.transcodes() => Some(pool.acquire().await),
+            _ => None,
+        }
+    }
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            quality: "best".to_string(),
+            format: "mp4".to_string(),
+            audio_only: false,
+            postprocessor_args: None,
+            faststart: false,
+            embed_chapters: false,
+            subtitle_languages: Vec::new(),
+            auto_subtitles: false,
+            priority: ProcessPriority::Normal,
+            platform_settings: HashMap::new(),
+            proxy: None,
+            headers: RequestHeaders::default(),
+            download_archive: None,
+            record_live: false,
+            wait_for_video: false,
+            section_start: None,
+            section_end: None,
+            audio_quality: None,
+            format_id: None,
+            concurrent_fragments: None,
+            retries: None,
+            rate_limit: None,
+            rate_limit_updates: None,
+            tags: AudioTags::default(),
+            postprocess: None,
+            process: None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -110,6 +343,12 @@ pub struct DownloadProgress {
     pub total_bytes: u64,
     pub speed: f64,
     pub eta: u64,
+    /// Seconds recorded so far, for live streams where there is no percentage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed: Option<u64>,
+    /// Localized strings for the numbers above, filled in before the progress event is sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<ProgressDisplay>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -123,11 +362,33 @@ pub struct Dependency {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PlatformSetting {
     pub key: String,
+    /// Translation key of the label, e.g. `settings.youtube.max_resolution`
+    pub label_key: String,
+    /// Label in the display language, filled in by `localized`
+    #[serde(default)]
     pub label: String,
     pub setting_type: SettingType,
     pub default_value: serde_json::Value,
 }
 
+impl PlatformSetting {
+    pub fn new(key: &str, label_key: &str, setting_type: SettingType, default_value: serde_json::Value) -> Self {
+        Self {
+            key: key.to_string(),
+            label_key: label_key.to_string(),
+            label: String::new(),
+            setting_type,
+            default_value,
+        }
+    }
+    
+    /// This setting with its label translated into `language`
+    pub fn localized(mut self, language: Language) -> Self {
+        self.label = translate(&self.label_key, language);
+        self
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "type")]
 pub enum SettingType {
@@ -135,4 +396,6 @@ pub enum SettingType {
     String,
     Number,
     Select { options: Vec<String> },
+    /// A list of strings, saved as a JSON array
+    List,
 }
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+/// Per-provider rate limiter for metadata requests
+///
+/// Spaces out requests to the same provider by a minimum interval so that
+/// batch operations don't hammer a single platform with yt-dlp invocations.
+pub struct RateLimiter {
+    next_allowed: Mutex<HashMap<String, Instant>>,
+    min_interval: Duration,
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter with specified minimum interval between requests
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            next_allowed: Mutex::new(HashMap::new()),
+            min_interval,
+        }
+    }
+
+    /// Create a rate limiter with 500ms interval (recommended for metadata fetches)
+    pub fn with_default_interval() -> Self {
+        Self::new(Duration::from_millis(500))
+    }
+
+    /// Wait until a request for the given provider is allowed
+    pub async fn acquire(&self, provider: &str) {
+        // Reserve the next slot while holding the lock, then sleep without it
+        let wait = {
+            let mut next_allowed = self.next_allowed.lock().await;
+            let now = Instant::now();
+
+            let slot = match next_allowed.get(provider) {
+                Some(next) if *next > now => *next,
+                _ => now,
+            };
+
+            next_allowed.insert(provider.to_string(), slot + self.min_interval);
+            slot.duration_since(now)
+        };
+
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::with_default_interval()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_same_provider_is_spaced_out() {
+        let limiter = RateLimiter::new(Duration::from_millis(50));
+        let start = Instant::now();
+
+        limiter.acquire("YouTube").await;
+        limiter.acquire("YouTube").await;
+        limiter.acquire("YouTube").await;
+
+        // First request is immediate, the next two wait one interval each
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_different_providers_do_not_wait() {
+        let limiter = RateLimiter::new(Duration::from_millis(200));
+        let start = Instant::now();
+
+        limiter.acquire("YouTube").await;
+        limiter.acquire("Bilibili").await;
+
+        assert!(start.elapsed() < Duration::from_millis(200));
+    }
+}
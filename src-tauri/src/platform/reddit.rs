@@ -0,0 +1,616 @@
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+use super::command_runner::{CommandRequest, CommandRunner, OutputLine};
+use super::metadata_limit::MetadataLimit;
+use super::provider::*;
+use super::ytdlp_backend::{exit_error, network_options, MetadataNetwork, YtDlpBackend};
+use super::ytdlp_command::YtDlpCommandBuilder;
+use crate::error::{DownloadError, Result};
+use crate::storage::StorageService;
+use crate::log_info;
+
+/// Share of overall progress used by each stage (video, audio, merge)
+const VIDEO_STAGE: (f64, f64) = (0.0, 80.0);
+const AUDIO_STAGE: (f64, f64) = (80.0, 95.0);
+const MERGE_STAGE: (f64, f64) = (95.0, 100.0);
+
+/// Progress callback shared across the download stages
+type ProgressSink = Mutex<Box<dyn Fn(DownloadProgress) + Send>>;
+
+/// One stream of a post to fetch with yt-dlp
+struct StreamDownload<'a> {
+    url: &'a str,
+    format: &'a str,
+    output: &'a Path,
+    /// Share of overall progress this stream covers
+    stage: (f64, f64),
+    options: &'a DownloadOptions,
+}
+
+/// Reddit-hosted video provider (v.redd.it)
+///
+/// Reddit serves DASH video and audio as separate streams, so downloads fetch
+/// each stream with yt-dlp and then mux them with the bundled ffmpeg.
+pub struct RedditProvider {
+    backend: YtDlpBackend,
+    ffmpeg_path: PathBuf,
+    url_patterns: Vec<Regex>,
+}
+
+impl RedditProvider {
+    pub fn new() -> Self {
+        Self::with_executables(PathBuf::from("yt-dlp"), PathBuf::from("ffmpeg"))
+    }
+    
+    /// Create a new RedditProvider with custom executable paths
+    pub fn with_executables(ytdlp_path: PathBuf, ffmpeg_path: PathBuf) -> Self {
+        // Compile URL patterns for efficient matching
+        let url_patterns = vec![
+            // Direct video host URLs
+            Regex::new(r"^https?://v\.redd\.it/[\w-]+").unwrap(),
+            // Post URLs
+            Regex::new(r"^https?://(www\.|old\.|new\.)?reddit\.com/r/\w+/comments/\w+").unwrap(),
+            // Share links
+            Regex::new(r"^https?://(www\.)?reddit\.com/r/\w+/s/\w+").unwrap(),
+            // Short post URLs
+            Regex::new(r"^https?://redd\.it/\w+").unwrap(),
+        ];
+        
+        Self {
+            backend: YtDlpBackend::new("Reddit", ytdlp_path)
+                .unavailable_when(&["Video unavailable", "No media found", "404"]),
+            ffmpeg_path,
+            url_patterns,
+        }
+    }
+    
+    /// Replace the process runner (used by tests to replay canned output)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.backend = self.backend.with_command_runner(runner);
+        self
+    }
+    
+    /// Map an ffmpeg runner error to a download error
+    fn map_ffmpeg_error(e: std::io::Error) -> DownloadError {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => DownloadError::DependencyMissing("ffmpeg".to_string()),
+            std::io::ErrorKind::Interrupted => DownloadError::Cancelled,
+            _ => DownloadError::DownloadFailed(format!("Failed to run ffmpeg: {}", e)),
+        }
+    }
+    
+    /// Fetch the raw yt-dlp JSON for a post
+    async fn fetch_info_json(&self, url: &str) -> Result<Value> {
+        self.backend
+            .run_json(&YtDlpCommandBuilder::new().dump_json().no_playlist().url(url))
+            .await
+    }
+    
+    /// Parse video info from yt-dlp JSON output
+    fn parse_video_info(&self, json: &Value, url: &str) -> VideoInfo {
+        let formats = json["formats"]
+            .as_array()
+            .map(|formats| {
+                formats
+                    .iter()
+                    .filter_map(|format| {
+                        let format_id = format["format_id"].as_str()?;
+                        Some(FormatInfo {
+                            format_id: format_id.to_string(),
+                            ext: format["ext"].as_str().unwrap_or("mp4").to_string(),
+                            resolution: format["resolution"].as_str().map(|s| s.to_string()),
+                            filesize: format["filesize"]
+                                .as_u64()
+                                .or_else(|| format["filesize_approx"].as_u64()),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        
+        VideoInfo {
+            id: json["id"].as_str().unwrap_or("").to_string(),
+            title: json["title"].as_str().unwrap_or("Reddit Video").to_string(),
+            description: json["description"].as_str().unwrap_or("").to_string(),
+            thumbnail: json["thumbnail"].as_str().unwrap_or("").to_string(),
+            duration: json["duration"].as_f64().map(|d| d.round() as u64).unwrap_or(0),
+            uploader: json["uploader"].as_str().unwrap_or("Unknown").to_string(),
+            upload_date: json["upload_date"].as_str().unwrap_or("").to_string(),
+            view_count: json["view_count"].as_u64().unwrap_or(0),
+            available_formats: formats,
+            platform: "Reddit".to_string(),
+            url: url.to_string(),
+            is_live: false,
+            premiere_at: None,
+        }
+    }
+    
+    /// Check whether the post has an audio-only stream that needs merging
+    fn has_separate_audio(json: &Value) -> bool {
+        json["formats"]
+            .as_array()
+            .map(|formats| {
+                formats.iter().any(|f| {
+                    f["vcodec"].as_str() == Some("none")
+                        && f["acodec"].as_str().is_some_and(|a| a != "none")
+                })
+            })
+            .unwrap_or(false)
+    }
+    
+    /// Resolve the directory downloads should be written to
+    fn output_dir(save_path: &Path) -> PathBuf {
+        if save_path.is_dir() {
+            save_path.to_path_buf()
+        } else {
+            save_path
+                .parent()
+                .map(|p| p.to_path_buf())
+                .unwrap_or_else(|| PathBuf::from("."))
+        }
+    }
+    
+    /// Download a single stream with yt-dlp, scaling its progress into `stage`
+    async fn download_stream(
+        &self,
+        stream: StreamDownload<'_>,
+        progress_callback: &ProgressSink,
+        cancel_token: Option<&CancellationToken>,
+    ) -> Result<()> {
+        let StreamDownload { url, format, output, stage, options } = stream;
+        let output_str = output.to_str()
+            .ok_or_else(|| DownloadError::DownloadFailed(format!("Invalid output path: {:?}", output)))?;
+        
+        let command = YtDlpCommandBuilder::new()
+            .machine_progress()
+            .no_warnings()
+            .no_playlist()
+            .no_part()
+            .force_overwrites()
+            .output(output_str)
+            .format(format);
+        let command = network_options(command, options).url(url);
+        
+        log_info!("[Reddit] Downloading stream {} to {}", format, output.display());
+        
+        let request = self.backend.download_request(&command, output, options);
+        let report = |progress| Self::report(progress_callback, Self::scale_progress(progress, stage));
+        let output = self.backend.stream(&request, None, &report, cancel_token).await?;
+        
+        if !output.success() {
+            if output.stderr.contains("Requested format is not available") {
+                return Err(DownloadError::VideoUnavailable(
+                    "The requested stream is not available for this post".to_string()
+                ));
+            }
+            return Err(exit_error(&output));
+        }
+        
+        Ok(())
+    }
+    
+    /// Mux separate video and audio files into one container with ffmpeg
+    #[allow(clippy::too_many_arguments)]
+    async fn merge_streams(
+        &self,
+        video_path: &Path,
+        audio_path: &Path,
+        output_path: &Path,
+        duration_secs: u64,
+        options: &DownloadOptions,
+        progress_callback: &ProgressSink,
+        cancel_token: Option<&CancellationToken>,
+    ) -> Result<()> {
+        log_info!("[Reddit] Merging {} + {} -> {}", video_path.display(), audio_path.display(), output_path.display());
+        
+        let mut args: Vec<String> = ["-y", "-loglevel", "error", "-nostats"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        args.push("-i".to_string());
+        args.push(video_path.to_string_lossy().to_string());
+        args.push("-i".to_string());
+        args.push(audio_path.to_string_lossy().to_string());
+        args.extend(
+            ["-map", "0:v:0", "-map", "1:a:0", "-c", "copy", "-movflags", "+faststart", "-progress", "pipe:1"]
+                .iter()
+                .map(|s| s.to_string()),
+        );
+        if let Some(threads) = options.ffmpeg_threads() {
+            args.push("-threads".to_string());
+            args.push(threads.to_string());
+        }
+        args.push(output_path.to_string_lossy().to_string());
+        
+        let request = CommandRequest::new(&self.ffmpeg_path, args)
+            .priority(options.priority)
+            .track_process(options.process.clone());
+        
+        // Merging is post-processing work, so it waits for a slot like a transcode
+        let _transcode_slot = match &options.postprocess {
+            Some(pool) => Some(pool.acquire().await),
+            None => None,
+        };
+        
+        let mut on_line = |line: OutputLine| {
+            if let OutputLine::Stdout(line) = line {
+                if let Some(percentage) = Self::parse_ffmpeg_progress_line(&line, duration_secs) {
+                    Self::report(progress_callback, Self::scale_progress(
+                        DownloadProgress {
+                            percentage,
+                            downloaded_bytes: 0,
+                            total_bytes: 0,
+                            speed: 0.0,
+                            eta: 0,
+                            elapsed: None,
+                            display: None,
+                        },
+                        MERGE_STAGE,
+                    ));
+                }
+            }
+        };
+        
+        let output = self.backend.runner().stream(&request, &mut on_line, cancel_token)
+            .await
+            .map_err(Self::map_ffmpeg_error)?;
+        
+        if !output.success() {
+            return Err(DownloadError::DownloadFailed(format!(
+                "ffmpeg merge failed with code {:?}: {}",
+                output.exit_code,
+                output.stderr.trim()
+            )));
+        }
+        
+        Ok(())
+    }
+    
+    /// Internal download implementation with cancellation support
+    async fn download_video_impl(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<()> {
+        // Muxing requires ffmpeg, so fail early if the bundled binary is missing
+        if !self.ffmpeg_path.exists() {
+            return Err(DownloadError::DependencyMissing(
+                format!("ffmpeg not found at: {:?}", self.ffmpeg_path)
+            ));
+        }
+        
+        let progress_callback: ProgressSink = Mutex::new(progress_callback);
+        let json = self.fetch_info_json(url).await?;
+        let info = self.parse_video_info(&json, url);
+        let needs_merge = !options.audio_only && Self::has_separate_audio(&json);
+        
+        let output_dir = Self::output_dir(save_path);
+        tokio::fs::create_dir_all(&output_dir).await?;
+        
+        let base_name = format!(
+            "{} [{}]",
+            StorageService::sanitize_filename(&info.title),
+            StorageService::sanitize_filename(&info.id)
+        );
+        
+        let stream = |format, output, stage| StreamDownload { url, format, output, stage, options: &options };
+        if options.audio_only {
+            let output_path = output_dir.join(format!("{}.m4a", base_name));
+            self.download_stream(stream("bestaudio", &output_path, (0.0, 100.0)), &progress_callback, cancel_token.as_ref()).await?;
+        } else if !needs_merge {
+            // Video-only posts (e.g. converted GIFs) have nothing to merge
+            let output_path = output_dir.join(format!("{}.mp4", base_name));
+            self.download_stream(stream("bestvideo/best", &output_path, (0.0, 100.0)), &progress_callback, cancel_token.as_ref()).await?;
+        } else {
+            let video_path = output_dir.join(format!("{}.video.tmp", base_name));
+            let audio_path = output_dir.join(format!("{}.audio.tmp", base_name));
+            let output_path = output_dir.join(format!("{}.mp4", base_name));
+            
+            let result = async {
+                self.download_stream(stream("bestvideo", &video_path, VIDEO_STAGE), &progress_callback, cancel_token.as_ref()).await?;
+                self.download_stream(stream("bestaudio", &audio_path, AUDIO_STAGE), &progress_callback, cancel_token.as_ref()).await?;
+                self.merge_streams(&video_path, &audio_path, &output_path, info.duration, &options, &progress_callback, cancel_token.as_ref()).await
+            }
+            .await;
+            
+            // Intermediate streams are only useful for the merge
+            let _ = tokio::fs::remove_file(&video_path).await;
+            let _ = tokio::fs::remove_file(&audio_path).await;
+            
+            if result.is_err() {
+                let _ = tokio::fs::remove_file(&output_path).await;
+            }
+            result?;
+        }
+        
+        Self::report(&progress_callback, DownloadProgress {
+            percentage: 100.0,
+            downloaded_bytes: 0,
+            total_bytes: 0,
+            speed: 0.0,
+            eta: 0,
+            elapsed: None,
+            display: None,
+        });
+        
+        log_info!("[Reddit] ✓ Download completed: {}", base_name);
+        Ok(())
+    }
+    
+    /// Download video with cancellation support (public method for download manager)
+    pub async fn download_with_cancellation(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: CancellationToken,
+    ) -> Result<()> {
+        self.download_video_impl(url, options, save_path, progress_callback, Some(cancel_token)).await
+    }
+    
+    /// Parse an ffmpeg `-progress` line into a merge percentage
+    fn parse_ffmpeg_progress_line(line: &str, duration_secs: u64) -> Option<f64> {
+        let line = line.trim();
+        
+        if line == "progress=end" {
+            return Some(100.0);
+        }
+        
+        // out_time_ms is (despite the name) in microseconds, same as out_time_us
+        let micros = line
+            .strip_prefix("out_time_us=")
+            .or_else(|| line.strip_prefix("out_time_ms="))?
+            .parse::<u64>()
+            .ok()?;
+        
+        if duration_secs == 0 {
+            return None;
+        }
+        
+        let elapsed_secs = micros as f64 / 1_000_000.0;
+        Some((elapsed_secs / duration_secs as f64 * 100.0).min(100.0))
+    }
+    
+    /// Forward a progress update to the caller's callback
+    fn report(progress_callback: &ProgressSink, progress: DownloadProgress) {
+        if let Ok(callback) = progress_callback.lock() {
+            callback(progress);
+        }
+    }
+    
+    /// Map a stage-local progress value into the overall progress range
+    fn scale_progress(progress: DownloadProgress, stage: (f64, f64)) -> DownloadProgress {
+        let (start, end) = stage;
+        DownloadProgress {
+            percentage: start + (end - start) * progress.percentage / 100.0,
+            ..progress
+        }
+    }
+}
+
+#[async_trait]
+impl PlatformProvider for RedditProvider {
+    fn name(&self) -> &str {
+        "Reddit"
+    }
+    
+    fn matches_url(&self, url: &str) -> bool {
+        let url = url.trim();
+        self.url_patterns.iter().any(|pattern| pattern.is_match(url))
+    }
+    
+    fn supported_patterns(&self) -> Vec<String> {
+        vec![
+            "https://v.redd.it/VIDEO_ID".to_string(),
+            "https://www.reddit.com/r/SUBREDDIT/comments/POST_ID/...".to_string(),
+            "https://www.reddit.com/r/SUBREDDIT/s/SHARE_ID".to_string(),
+            "https://redd.it/POST_ID".to_string(),
+        ]
+    }
+    
+    async fn get_video_info(&self, url: &str) -> Result<VideoInfo> {
+        let json = self.fetch_info_json(url).await?;
+        Ok(self.parse_video_info(&json, url))
+    }
+    
+    async fn get_playlist_info(&self, url: &str) -> Result<PlaylistInfo> {
+        Err(DownloadError::InvalidUrl(format!("Reddit posts are not playlists: {}", url)))
+    }
+    
+    async fn get_channel_info(&self, url: &str) -> Result<ChannelInfo> {
+        Err(DownloadError::InvalidUrl(format!("Reddit posts are not channels: {}", url)))
+    }
+    
+    async fn download_video(
+        &self,
+        url: &str,
+        options: DownloadOptions,
+        save_path: &Path,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+    ) -> Result<()> {
+        self.download_video_impl(url, options, save_path, progress_callback, None).await
+    }
+    
+    async fn check_dependencies(&self) -> Result<Vec<Dependency>> {
+        Ok(vec![
+            Dependency {
+                name: "yt-dlp (bundled)".to_string(),
+                installed: self.backend.ytdlp_path().exists(),
+                version: None,
+                install_instructions: "yt-dlp is bundled with the application. If missing, please reinstall the application.".to_string(),
+            },
+            Dependency {
+                name: "ffmpeg (bundled)".to_string(),
+                installed: self.ffmpeg_path.exists(),
+                version: None,
+                install_instructions: "ffmpeg is required to merge Reddit video and audio streams. If missing, please reinstall the application.".to_string(),
+            },
+        ])
+    }
+    
+    fn get_platform_settings(&self) -> Vec<PlatformSetting> {
+        Vec::new()
+    }
+    
+    fn metadata_limit(&self) -> Option<&MetadataLimit> {
+        Some(self.backend.metadata_limit())
+    }
+    
+    fn metadata_network(&self) -> Option<&MetadataNetwork> {
+        Some(self.backend.metadata_network())
+    }
+    
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl Default for RedditProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::command_runner::{MockCommandRunner, MockResponse};
+    
+    #[test]
+    fn test_matches_reddit_urls() {
+        let provider = RedditProvider::new();
+        assert!(provider.matches_url("https://v.redd.it/abc123xyz"));
+        assert!(provider.matches_url("https://www.reddit.com/r/videos/comments/1abcde/some_title/"));
+        assert!(provider.matches_url("https://old.reddit.com/r/videos/comments/1abcde/"));
+        assert!(provider.matches_url("https://www.reddit.com/r/videos/s/AbCdEf123"));
+        assert!(provider.matches_url("https://redd.it/1abcde"));
+    }
+
+    #[test]
+    fn test_does_not_match_other_urls() {
+        let provider = RedditProvider::new();
+        assert!(!provider.matches_url("https://www.reddit.com/r/videos/"));
+        assert!(!provider.matches_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+        assert!(!provider.matches_url(""));
+    }
+
+    #[test]
+    fn test_parse_ffmpeg_progress_line() {
+        assert_eq!(RedditProvider::parse_ffmpeg_progress_line("out_time_us=5000000", 10), Some(50.0));
+        assert_eq!(RedditProvider::parse_ffmpeg_progress_line("out_time_ms=10000000", 10), Some(100.0));
+        assert_eq!(RedditProvider::parse_ffmpeg_progress_line("progress=end", 10), Some(100.0));
+        assert_eq!(RedditProvider::parse_ffmpeg_progress_line("out_time_us=5000000", 0), None);
+        assert_eq!(RedditProvider::parse_ffmpeg_progress_line("bitrate=1000kbits/s", 10), None);
+    }
+
+    #[test]
+    fn test_scale_progress_into_stage() {
+        let progress = DownloadProgress {
+            percentage: 50.0,
+            downloaded_bytes: 10,
+            total_bytes: 20,
+            speed: 1.0,
+            eta: 5,
+            elapsed: None,
+            display: None,
+        };
+        let scaled = RedditProvider::scale_progress(progress, AUDIO_STAGE);
+        assert_eq!(scaled.percentage, 87.5);
+        assert_eq!(scaled.total_bytes, 20);
+    }
+
+    #[test]
+    fn test_has_separate_audio() {
+        let with_audio = serde_json::json!({
+            "formats": [
+                { "format_id": "dash-720", "vcodec": "avc1", "acodec": "none" },
+                { "format_id": "dash-audio", "vcodec": "none", "acodec": "mp4a.40.2" }
+            ]
+        });
+        let video_only = serde_json::json!({
+            "formats": [ { "format_id": "dash-720", "vcodec": "avc1", "acodec": "none" } ]
+        });
+        assert!(RedditProvider::has_separate_audio(&with_audio));
+        assert!(!RedditProvider::has_separate_audio(&video_only));
+    }
+
+    #[tokio::test]
+    async fn test_download_merges_streams_with_mock_runner() {
+        let dir = tempfile::tempdir().unwrap();
+        let ffmpeg = tempfile::NamedTempFile::new().unwrap();
+        let info = r#"{"id":"abc123","title":"Cat video","duration":10,"formats":[
+            {"format_id":"dash-720","vcodec":"avc1","acodec":"none"},
+            {"format_id":"dash-audio","vcodec":"none","acodec":"mp4a.40.2"}]}"#.replace('\n', "");
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![
+            MockResponse::success(&info),
+            MockResponse::success("[vortex-progress] 50 100 10 5\n[vortex-progress] 100 100 10 0"),
+            MockResponse::success("[vortex-progress] 100 100 10 0"),
+            MockResponse::success("out_time_us=5000000\nprogress=end"),
+        ]));
+        let provider = RedditProvider::with_executables(PathBuf::from("yt-dlp"), ffmpeg.path().to_path_buf())
+            .with_command_runner(runner.clone());
+        
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let sink = updates.clone();
+        let options = DownloadOptions {
+            quality: "best".to_string(),
+            format: "mp4".to_string(),
+            audio_only: false,
+            ..Default::default()
+        };
+        provider
+            .download_video(
+                "https://v.redd.it/abc123",
+                options,
+                dir.path(),
+                Box::new(move |p| sink.lock().unwrap().push(p.percentage)),
+            )
+            .await
+            .unwrap();
+        
+        assert_eq!(*updates.lock().unwrap(), vec![40.0, 80.0, 95.0, 97.5, 100.0, 100.0]);
+        
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 4);
+        assert!(calls[1].args.contains(&"bestvideo".to_string()));
+        assert!(calls[2].args.contains(&"bestaudio".to_string()));
+        assert_eq!(calls[3].program, ffmpeg.path());
+        assert!(calls[3].args.last().unwrap().ends_with("Cat video [abc123].mp4"));
+    }
+
+    #[tokio::test]
+    async fn test_merge_failure_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let ffmpeg = tempfile::NamedTempFile::new().unwrap();
+        let info = r#"{"id":"abc123","title":"Cat video","duration":10,"formats":[{"format_id":"dash-audio","vcodec":"none","acodec":"mp4a.40.2"}]}"#;
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![
+            MockResponse::success(info),
+            MockResponse::success(""),
+            MockResponse::success(""),
+            MockResponse::failure(1, "Invalid data found when processing input"),
+        ]));
+        let provider = RedditProvider::with_executables(PathBuf::from("yt-dlp"), ffmpeg.path().to_path_buf())
+            .with_command_runner(runner);
+        
+        let options = DownloadOptions {
+            quality: "best".to_string(),
+            format: "mp4".to_string(),
+            audio_only: false,
+            ..Default::default()
+        };
+        let result = provider.download_video("https://v.redd.it/abc123", options, dir.path(), Box::new(|_| {})).await;
+        
+        match result {
+            Err(DownloadError::DownloadFailed(msg)) => assert!(msg.contains("Invalid data")),
+            other => panic!("expected DownloadFailed, got {:?}", other),
+        }
+    }
+}
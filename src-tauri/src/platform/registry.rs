@@ -1,28 +1,40 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use super::http_headers::HttpHeaderSettings;
+use super::metadata_limit::DEFAULT_METADATA_CONCURRENCY;
 use super::provider::PlatformProvider;
+use super::proxy::ProxySettings;
+use crate::error::{DownloadError, Result};
+use crate::error_handler::UrlValidator;
 
 /// Registry for managing platform providers
+///
+/// Providers are kept in registration order, which is also their detection
+/// priority: specific providers must be registered before catch-all ones.
 pub struct PlatformRegistry {
-    providers: HashMap<String, Arc<dyn PlatformProvider>>,
+    providers: Vec<Arc<dyn PlatformProvider>>,
 }
 
 impl PlatformRegistry {
     pub fn new() -> Self {
         Self {
-            providers: HashMap::new(),
+            providers: Vec::new(),
         }
     }
     
     /// Register a new platform provider
+    /// Re-registering a name replaces the provider but keeps its original priority
     pub fn register(&mut self, provider: Arc<dyn PlatformProvider>) {
-        let name = provider.name().to_string();
-        self.providers.insert(name, provider);
+        match self.providers.iter().position(|p| p.name() == provider.name()) {
+            Some(index) => self.providers[index] = provider,
+            None => self.providers.push(provider),
+        }
     }
     
     /// Detect provider based on URL
+    /// The first registered provider that matches wins
     pub fn detect_provider(&self, url: &str) -> Option<Arc<dyn PlatformProvider>> {
-        for provider in self.providers.values() {
+        for provider in &self.providers {
             if provider.matches_url(url) {
                 return Some(Arc::clone(provider));
             }
@@ -30,14 +42,48 @@ impl PlatformRegistry {
         None
     }
     
-    /// Get all registered providers
+    /// Validate a URL and resolve the provider that handles it
+    /// Returns the provider together with the provider-normalized URL.
+    /// A provider that matches but can't confirm the URL passes it to the next one
+    pub async fn resolve_url(&self, url: &str) -> Result<(Arc<dyn PlatformProvider>, String)> {
+        let validated = UrlValidator::validate_generic_url(url)?;
+        
+        for provider in self.providers.iter().filter(|p| p.matches_url(&validated)) {
+            if provider.confirm_url(&validated).await {
+                let normalized = provider.normalize_url(&validated);
+                return Ok((Arc::clone(provider), normalized));
+            }
+        }
+        Err(DownloadError::PlatformNotSupported(validated))
+    }
+    
+    /// Get all registered providers in priority order
     pub fn get_all_providers(&self) -> Vec<Arc<dyn PlatformProvider>> {
-        self.providers.values().map(Arc::clone).collect()
+        self.providers.iter().map(Arc::clone).collect()
+    }
+    
+    /// Apply the `metadata_concurrency` setting; providers it doesn't name get the default
+    pub fn apply_metadata_concurrency(&self, limits: &HashMap<String, usize>) {
+        for provider in &self.providers {
+            if let Some(limit) = provider.metadata_limit() {
+                limit.set_limit(limits.get(provider.name()).copied().unwrap_or(DEFAULT_METADATA_CONCURRENCY));
+            }
+        }
+    }
+    
+    /// Apply the `proxy` and `http_headers` settings to each provider's metadata queries
+    pub fn apply_network_settings(&self, proxy: &ProxySettings, headers: &HttpHeaderSettings) {
+        for provider in &self.providers {
+            if let Some(network) = provider.metadata_network() {
+                network.set_proxy(proxy.for_platform(provider.name()));
+                network.set_headers(headers.for_platform(provider.name()));
+            }
+        }
     }
     
     /// Get provider by name
     pub fn get_provider(&self, name: &str) -> Option<Arc<dyn PlatformProvider>> {
-        self.providers.get(name).map(Arc::clone)
+        self.providers.iter().find(|p| p.name() == name).map(Arc::clone)
     }
 }
 
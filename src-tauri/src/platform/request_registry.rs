@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// Tracks in-flight metadata requests so the frontend can cancel them
+///
+/// Each request is identified by a caller-chosen ID. Cancelling a request
+/// cancels its token, which aborts pending retries and kills the yt-dlp
+/// process backing the current attempt.
+pub struct InfoRequestRegistry {
+    /// Token of each request ID, with the generation it was registered at
+    requests: Mutex<HashMap<String, (u64, CancellationToken)>>,
+    next_generation: AtomicU64,
+}
+
+/// A registered request, handed back to `finish` once it completes
+pub struct InfoRequest {
+    pub token: CancellationToken,
+    id: Option<String>,
+    generation: u64,
+}
+
+impl InfoRequestRegistry {
+    pub fn new() -> Self {
+        Self {
+            requests: Mutex::new(HashMap::new()),
+            next_generation: AtomicU64::new(0),
+        }
+    }
+    
+    /// Register a request and return it with its cancellation token
+    /// Requests without an ID get an untracked token that is never cancelled
+    pub async fn register(&self, request_id: Option<&str>) -> InfoRequest {
+        let token = CancellationToken::new();
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        
+        if let Some(id) = request_id {
+            let mut requests = self.requests.lock().await;
+            // Re-using an ID supersedes the previous request
+            if let Some((_, previous)) = requests.insert(id.to_string(), (generation, token.clone())) {
+                previous.cancel();
+            }
+        }
+        
+        InfoRequest {
+            token,
+            id: request_id.map(str::to_string),
+            generation,
+        }
+    }
+    
+    /// Cancel a request by ID
+    /// Returns true if the request was still in flight
+    pub async fn cancel(&self, request_id: &str) -> bool {
+        let mut requests = self.requests.lock().await;
+        match requests.remove(request_id) {
+            Some((_, token)) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Stop tracking a finished request
+    /// A request superseded under the same ID leaves its replacement tracked
+    pub async fn finish(&self, request: &InfoRequest) {
+        if let Some(id) = &request.id {
+            let mut requests = self.requests.lock().await;
+            if requests.get(id).is_some_and(|(generation, _)| *generation == request.generation) {
+                requests.remove(id);
+            }
+        }
+    }
+    
+    /// Number of requests currently in flight
+    pub async fn active_count(&self) -> usize {
+        self.requests.lock().await.len()
+    }
+}
+
+impl Default for InfoRequestRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_cancel_registered_request() {
+        let registry = InfoRequestRegistry::new();
+        let request = registry.register(Some("req-1")).await;
+        
+        assert_eq!(registry.active_count().await, 1);
+        assert!(registry.cancel("req-1").await);
+        assert!(request.token.is_cancelled());
+        assert_eq!(registry.active_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_reusing_id_cancels_previous_request() {
+        let registry = InfoRequestRegistry::new();
+        let first = registry.register(Some("req-1")).await;
+        let second = registry.register(Some("req-1")).await;
+        
+        assert!(first.token.is_cancelled());
+        assert!(!second.token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_superseded_request_finishing_late_keeps_replacement() {
+        let registry = InfoRequestRegistry::new();
+        let first = registry.register(Some("req-1")).await;
+        let second = registry.register(Some("req-1")).await;
+        
+        // The superseded request winds down after its replacement has started
+        registry.finish(&first).await;
+        assert_eq!(registry.active_count().await, 1);
+        assert!(registry.cancel("req-1").await);
+        assert!(second.token.is_cancelled());
+        
+        let third = registry.register(Some("req-2")).await;
+        registry.finish(&third).await;
+        assert_eq!(registry.active_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_untracked_request() {
+        let registry = InfoRequestRegistry::new();
+        let request = registry.register(None).await;
+        
+        assert_eq!(registry.active_count().await, 0);
+        assert!(!registry.cancel("missing").await);
+        assert!(!request.token.is_cancelled());
+    }
+}
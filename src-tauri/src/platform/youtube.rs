@@ -1,21 +1,91 @@
 use async_trait::async_trait;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::path::{Path, PathBuf};
-use std::process::Stdio;
-use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
-use tokio::sync::Mutex;
+use std::sync::{Arc, Mutex, RwLock};
 use tokio_util::sync::CancellationToken;
+use super::command_runner::{CommandRequest, CommandRunner, OutputLine};
+use super::metadata_limit::MetadataLimit;
 use super::provider::*;
+use super::ytdlp_backend::{exit_error, is_bot_check, network_options, MetadataNetwork, YtDlpBackend};
+use super::audio::AudioFormat;
+use super::po_token::PoTokenSettings;
+use super::browsers::is_supported_browser;
+use super::registry::PlatformRegistry;
+use super::ytdlp_command::{parse_section_progress, supports_embedded_thumbnail, validate_metadata_rule, validate_postprocessor_args, AuthMethod, SponsorBlockOptions, SubtitleOptions, YtDlpCommandBuilder};
 use crate::error::{DownloadError, Result};
+use crate::error_handler::UrlValidator;
+use crate::storage::PrivateCopy;
+use crate::{log_debug, log_error, log_info, log_warn};
+
+/// Extra container tags beyond yt-dlp's defaults (title, date, artist, description):
+/// the full description also goes into `comment`, which players show most widely
+const METADATA_RULES: &[&str] = &["description:(?s)(?P<meta_comment>.+)"];
+
+/// yt-dlp errors for videos that are removed, private or otherwise not viewable
+const UNAVAILABLE_MARKERS: &[&str] = &["Video unavailable", "Private video"];
+
+/// SponsorBlock segment categories users can choose to cut
+const SPONSORBLOCK_CATEGORIES: &[&str] = &["sponsor", "intro", "outro", "selfpromo"];
+
+/// Subtitle language choice meaning every language the video has
+const ALL_SUBTITLES: &str = "all";
+
+/// How often yt-dlp rechecks a premiere whose start time it doesn't know
+const PREMIERE_RETRY_INTERVAL_SECS: u64 = 60;
+
+/// Channel page tabs that list one kind of upload
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelTab {
+    Videos,
+    Shorts,
+    Live,
+}
+
+impl ChannelTab {
+    /// Tab name in the channel URL path
+    pub fn path(&self) -> &'static str {
+        match self {
+            Self::Videos => "videos",
+            Self::Shorts => "shorts",
+            Self::Live => "streams",
+        }
+    }
+    
+    /// `channel_url` pointed at this tab, replacing any tab it already has
+    pub fn apply(&self, channel_url: &str) -> String {
+        format!("{}/{}", Self::channel_root(channel_url), self.path())
+    }
+    
+    /// `channel_url` without its tab (`/videos`, `/shorts`, ...), query or fragment
+    pub fn channel_root(channel_url: &str) -> String {
+        let url = channel_url.trim().split(['?', '#']).next().unwrap_or_default();
+        let (scheme, rest) = url.split_once("://").unwrap_or(("https", url));
+        let mut segments = rest.split('/').filter(|s| !s.is_empty());
+        let host = segments.next().unwrap_or_default();
+        
+        // `@handle` is one path segment; `channel/ID`, `c/NAME` and `user/NAME` are two
+        let mut channel: Vec<&str> = segments.by_ref().take(1).collect();
+        if channel.first().is_some_and(|s| !s.starts_with('@')) {
+            channel.extend(segments.take(1));
+        }
+        format!("{}://{}/{}", scheme, host, channel.join("/"))
+    }
+}
 
 /// YouTube platform provider using yt-dlp
 pub struct YouTubeProvider {
-    ytdlp_path: PathBuf,
+    backend: YtDlpBackend,
     ffmpeg_path: PathBuf,
     url_patterns: Vec<Regex>,
+    /// Netscape cookies file for age-restricted and members-only videos
+    cookies_file: RwLock<Option<PathBuf>>,
+    /// Browser to read cookies from when no cookies file is set
+    cookies_browser: RwLock<Option<String>>,
+    /// PO token and visitor data for getting past bot checks
+    po_token: RwLock<PoTokenSettings>,
 }
 
 impl YouTubeProvider {
@@ -26,6 +96,10 @@ impl YouTubeProvider {
             Regex::new(r"^https?://(www\.)?youtube\.com/watch\?v=[\w-]+").unwrap(),
             // Short URLs
             Regex::new(r"^https?://youtu\.be/[\w-]+").unwrap(),
+            // Shorts and live stream URLs
+            Regex::new(r"^https?://(www\.|m\.)?youtube\.com/(shorts|live)/[\w-]+").unwrap(),
+            // YouTube Music tracks, albums and playlists
+            Regex::new(r"^https?://music\.youtube\.com/(watch\?v=|playlist\?list=|browse/)[\w-]+").unwrap(),
             // Playlist URLs
             Regex::new(r"^https?://(www\.)?youtube\.com/playlist\?list=[\w-]+").unwrap(),
             // Channel URLs (new format with @)
@@ -39,9 +113,13 @@ impl YouTubeProvider {
         ];
         
         Self {
-            ytdlp_path: PathBuf::from("yt-dlp"),
+            backend: YtDlpBackend::new("YouTube", PathBuf::from("yt-dlp"))
+                .unavailable_when(UNAVAILABLE_MARKERS),
             ffmpeg_path: PathBuf::from("ffmpeg"),
             url_patterns,
+            cookies_file: RwLock::new(None),
+            cookies_browser: RwLock::new(None),
+            po_token: RwLock::new(PoTokenSettings::default()),
         }
     }
     
@@ -53,6 +131,10 @@ impl YouTubeProvider {
             Regex::new(r"^https?://(www\.)?youtube\.com/watch\?v=[\w-]+").unwrap(),
             // Short URLs
             Regex::new(r"^https?://youtu\.be/[\w-]+").unwrap(),
+            // Shorts and live stream URLs
+            Regex::new(r"^https?://(www\.|m\.)?youtube\.com/(shorts|live)/[\w-]+").unwrap(),
+            // YouTube Music tracks, albums and playlists
+            Regex::new(r"^https?://music\.youtube\.com/(watch\?v=|playlist\?list=|browse/)[\w-]+").unwrap(),
             // Playlist URLs
             Regex::new(r"^https?://(www\.)?youtube\.com/playlist\?list=[\w-]+").unwrap(),
             // Channel URLs (new format with @)
@@ -66,73 +148,132 @@ impl YouTubeProvider {
         ];
         
         Self {
-            ytdlp_path,
+            backend: YtDlpBackend::new("YouTube", ytdlp_path).unavailable_when(UNAVAILABLE_MARKERS),
             ffmpeg_path,
             url_patterns,
+            cookies_file: RwLock::new(None),
+            cookies_browser: RwLock::new(None),
+            po_token: RwLock::new(PoTokenSettings::default()),
+        }
+    }
+    
+    /// Whether `url` points at YouTube Music; those are downloaded as tagged audio by default
+    pub fn is_music_url(url: &str) -> bool {
+        let url = url.trim();
+        url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"))
+            .is_some_and(|rest| rest.starts_with("music.youtube.com/"))
+    }
+    
+    /// Replace the process runner (used by tests to replay canned yt-dlp output)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.backend = self.backend.with_command_runner(runner);
+        self
+    }
+    
+    /// Use a cookies file for all metadata requests and downloads (`None` to stop)
+    pub fn set_cookies_file(&self, path: Option<PathBuf>) {
+        if let Ok(mut cookies_file) = self.cookies_file.write() {
+            *cookies_file = path;
+        }
+    }
+    
+    /// Read cookies from an installed browser (e.g. `chrome`) when no cookies file is set
+    pub fn set_cookies_browser(&self, browser: Option<String>) {
+        if let Ok(mut cookies_browser) = self.cookies_browser.write() {
+            *cookies_browser = browser;
+        }
+    }
+    
+    /// Apply the `cookies_file_path` and `cookies_from_browser` settings to the provider in `registry`
+    pub fn apply_cookies_setting(registry: &PlatformRegistry, cookies_file_path: Option<&str>, cookies_from_browser: Option<&str>) {
+        if let Some(provider) = registry.get_provider("YouTube") {
+            if let Some(youtube) = provider.as_any().downcast_ref::<YouTubeProvider>() {
+                youtube.set_cookies_file(cookies_file_path.filter(|p| !p.is_empty()).map(PathBuf::from));
+                
+                let browser = cookies_from_browser.filter(|b| !b.is_empty());
+                if let Some(unsupported) = browser.filter(|b| !is_supported_browser(b)) {
+                    log_info!("[YouTube] Ignoring unsupported cookies browser: {}", unsupported);
+                }
+                youtube.set_cookies_browser(browser.filter(|b| is_supported_browser(b)).map(str::to_string));
+            }
+        }
+    }
+    
+    /// Pass a PO token and visitor data to every request (empty settings to stop)
+    pub fn set_po_token(&self, settings: PoTokenSettings) {
+        if let Ok(mut po_token) = self.po_token.write() {
+            *po_token = settings;
+        }
+    }
+    
+    /// Apply the `youtube_po_token` setting to the provider in `registry`
+    pub fn apply_po_token_setting(registry: &PlatformRegistry, settings: &PoTokenSettings) {
+        if let Some(provider) = registry.get_provider("YouTube") {
+            if let Some(youtube) = provider.as_any().downcast_ref::<YouTubeProvider>() {
+                youtube.set_po_token(settings.clone());
+            }
+        }
+    }
+    
+    /// Add the configured cookies and PO token to a yt-dlp command
+    ///
+    /// A cookies file takes precedence over browser cookies. A file that has
+    /// since been moved or deleted is skipped rather than failing every request.
+    /// yt-dlp saves cookies back on exit, so each process gets a private copy of
+    /// the file; keep the returned copy alive until the process has finished.
+    fn with_auth(&self, mut command: YtDlpCommandBuilder) -> (YtDlpCommandBuilder, Option<PrivateCopy>) {
+        let token_args = self.po_token.read().map(|t| t.extractor_args()).unwrap_or_default();
+        if !token_args.is_empty() {
+            command = command.extractor_args("youtube", &token_args);
+        }
+        
+        let cookies_file = self.cookies_file.read().ok().and_then(|c| c.clone());
+        match cookies_file {
+            Some(path) if path.is_file() => match PrivateCopy::create(&path, "cookies") {
+                Ok(copy) => {
+                    let command = command.auth(AuthMethod::CookiesFile(copy.path().to_path_buf()));
+                    return (command, Some(copy));
+                }
+                Err(e) => log_warn!("[YouTube] Could not copy cookies file, continuing without it: {}", e),
+            },
+            Some(path) => log_info!("[YouTube] Cookies file not found, continuing without it: {}", path.display()),
+            None => {}
+        }
+        
+        match self.cookies_browser.read().ok().and_then(|b| b.clone()) {
+            Some(browser) => (command.auth(AuthMethod::CookiesFromBrowser(browser)), None),
+            None => (command, None),
         }
     }
     
     /// Check if yt-dlp is installed
     pub async fn check_installation(&self) -> bool {
-        match Command::new(&self.ytdlp_path)
-            .arg("--version")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .await
-        {
-            Ok(status) => status.success(),
+        let request = CommandRequest::new(self.backend.ytdlp_path(), YtDlpCommandBuilder::new().version().build());
+        match self.backend.runner().output(&request).await {
+            Ok(output) => output.success(),
             Err(_) => false,
         }
     }
     
     /// Update yt-dlp to latest version
     pub async fn update_ytdlp(&self) -> Result<()> {
-        let output = Command::new(&self.ytdlp_path)
-            .arg("-U")
-            .output()
+        let request = CommandRequest::new(self.backend.ytdlp_path(), vec!["-U".to_string()]);
+        let output = self.backend.runner().output(&request)
             .await
             .map_err(|e| DownloadError::DownloadFailed(format!("Failed to update yt-dlp: {}", e)))?;
         
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(DownloadError::DownloadFailed(format!("yt-dlp update failed: {}", error)));
+        if !output.success() {
+            return Err(DownloadError::DownloadFailed(format!("yt-dlp update failed: {}", output.stderr)));
         }
         
         Ok(())
     }
     
     /// Execute yt-dlp command and return stdout
-    async fn execute_ytdlp(&self, args: &[&str]) -> Result<String> {
-        let output = Command::new(&self.ytdlp_path)
-            .args(args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    DownloadError::YtdlpNotFound
-                } else {
-                    DownloadError::DownloadFailed(format!("Failed to execute yt-dlp: {}", e))
-                }
-            })?;
-        
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            
-            // Parse common error messages
-            if error.contains("Video unavailable") || error.contains("Private video") {
-                return Err(DownloadError::VideoUnavailable(error.to_string()));
-            } else if error.contains("network") || error.contains("timeout") {
-                return Err(DownloadError::Network(error.to_string()));
-            } else {
-                return Err(DownloadError::DownloadFailed(error.to_string()));
-            }
-        }
-        
-        String::from_utf8(output.stdout)
-            .map_err(|e| DownloadError::DownloadFailed(format!("Invalid UTF-8 output: {}", e)))
+    async fn execute_ytdlp(&self, command: &YtDlpCommandBuilder) -> Result<String> {
+        // The runner kills the process if this future is dropped (cancelled request)
+        let (command, _cookies) = self.with_auth(command.clone());
+        self.backend.run(&command).await
     }
     
     /// Parse video info from yt-dlp JSON output
@@ -172,6 +313,15 @@ impl YouTubeProvider {
             available_formats: self.parse_formats(json),
             platform: "YouTube".to_string(),
             url: url.to_string(),
+            is_live: json["is_live"].as_bool().unwrap_or(false)
+                || json["live_status"].as_str() == Some("is_live"),
+            premiere_at: match json["live_status"].as_str() {
+                Some("is_upcoming") => json["release_timestamp"]
+                    .as_i64()
+                    .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                    .map(|at| at.to_rfc3339()),
+                _ => None,
+            },
         })
     }
     
@@ -232,6 +382,15 @@ impl YouTubeProvider {
                 format!("Invalid save path: {:?}", save_path)
             ))?;
         
+        // Several audio tracks only fit in MKV, so a video file target is renamed to match
+        let all_audio_tracks = self.all_audio_tracks(&options);
+        let mkv_target = save_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .filter(|ext| all_audio_tracks && ext != "mkv" && VIDEO_CONTAINERS.contains(&ext.as_str()))
+            .map(|_| save_path.with_extension("mkv").to_string_lossy().to_string());
+        let output = mkv_target.as_deref().unwrap_or(save_path_str);
+        
         // Validate ffmpeg path exists before starting download
         if !self.ffmpeg_path.exists() {
             return Err(DownloadError::DownloadFailed(
@@ -239,182 +398,380 @@ impl YouTubeProvider {
             ));
         }
         
-        // Get ffmpeg location and handle paths with spaces
-        let ffmpeg_location = self.ffmpeg_path.to_str()
-            .ok_or_else(|| DownloadError::DownloadFailed(
-                format!("Invalid ffmpeg path: {:?}", self.ffmpeg_path)
-            ))?;
-        
         // Build yt-dlp command arguments
-        let mut args = vec![
-            "--newline",      // Output progress on new lines for easier parsing
-            "--no-color",     // Prevent ANSI color codes
-            "--progress",     // Force progress output
-            "--no-warnings",  // Reduce noise in output
-            "--no-playlist",  // Don't download playlists
-            "-o", save_path_str,  // Output template (yt-dlp handles special characters)
-        ];
+        let format_arg = self.build_format_string(&options);
+        let mut command = YtDlpCommandBuilder::new()
+            .progress()      // Line-based progress output for parsing
+            .no_warnings()   // Reduce noise in output
+            .no_playlist()   // Don't download playlists
+            .output(output)  // Output template (yt-dlp handles special characters)
+            .ffmpeg_location(&self.ffmpeg_path)
+            .format(&format_arg);
+        
+        if all_audio_tracks {
+            command = command.audio_multistreams().merge_output_format("mkv");
+        }
         
-        // Specify ffmpeg location (yt-dlp handles quoting internally)
-        args.push("--ffmpeg-location");
-        args.push(ffmpeg_location);
+        // Add audio extraction if needed
+        if options.audio_only {
+            command = command.extract_audio(&options.format);
+            if let Some(quality) = options.audio_quality_arg() {
+                command = command.audio_quality(quality);
+            }
+        }
         
-        // Add format selection based on options
-        let format_arg = self.build_format_string(&options);
-        args.push("-f");
-        args.push(&format_arg);
+        // Title, description and upload date into the container metadata
+        let embed_metadata = self.setting_value(&options, "youtube_embed_metadata")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if embed_metadata {
+            command = command.embed_metadata();
+            for rule in METADATA_RULES {
+                command = command.parse_metadata(rule);
+            }
+        }
         
-        // Add audio-only flag if needed
+        // Audio always gets artist, album and track tags and its cover art
         if options.audio_only {
-            args.push("-x");  // Extract audio
-            args.push("--audio-format");
-            args.push(&options.format);
+            command = command.tag_audio(&options.format, &options.tags);
         }
         
-        // Add URL
-        args.push(url);
+        // User-defined parse rules run last so they can override the built-in tags
+        let parse_rules = self.metadata_parse_rules(&options)?;
+        if !parse_rules.is_empty() {
+            command = command.embed_metadata();
+            for rule in &parse_rules {
+                command = command.parse_metadata(rule);
+            }
+        }
         
-        // Log the complete command before execution
-        println!("[yt-dlp] Executing command: {:?} {:?}", self.ytdlp_path, args);
-        println!("[yt-dlp] URL: {}", url);
-        println!("[yt-dlp] Save path: {}", save_path.display());
-        println!("[yt-dlp] Format: {}", format_arg);
-        println!("[yt-dlp] Audio only: {}", options.audio_only);
-        
-        // Spawn yt-dlp process with piped stdout for progress
-        let mut child = Command::new(&self.ytdlp_path)
-            .args(&args)
-            .env("PYTHONIOENCODING", "utf-8")  // Force UTF-8 encoding
-            .env("LANG", "en_US.UTF-8")        // Set English locale
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    println!("[yt-dlp] ERROR: yt-dlp executable not found at {:?}", self.ytdlp_path);
-                    DownloadError::YtdlpNotFound
-                } else {
-                    println!("[yt-dlp] ERROR: Failed to spawn yt-dlp: {}", e);
-                    DownloadError::DownloadFailed(format!("Failed to spawn yt-dlp: {}", e))
-                }
-            })?;
+        // Cover art for video, when the container can hold it
+        let embed_thumbnail = self.setting_value(&options, "youtube_embed_thumbnail")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if embed_thumbnail && supports_embedded_thumbnail(&options.format) {
+            command = command.embed_thumbnail();
+        }
+        if self.setting_value(&options, "youtube_write_thumbnail").and_then(|v| v.as_bool()).unwrap_or(false) {
+            command = command.write_thumbnail();
+        }
         
-        // Get stdout for progress monitoring (yt-dlp outputs progress to stdout with --newline)
-        let stdout = child.stdout.take().ok_or_else(|| {
-            println!("[yt-dlp] ERROR: Failed to capture yt-dlp stdout");
-            DownloadError::DownloadFailed("Failed to capture yt-dlp stdout".to_string())
-        })?;
-        
-        // Also capture stderr for error messages
-        let stderr = child.stderr.take().ok_or_else(|| {
-            println!("[yt-dlp] WARNING: Failed to capture yt-dlp stderr");
-            DownloadError::DownloadFailed("Failed to capture yt-dlp stderr".to_string())
-        })?;
-        
-        let stdout_reader = BufReader::new(stdout);
-        let mut stdout_lines = stdout_reader.lines();
-        
-        let stderr_reader = BufReader::new(stderr);
-        let mut stderr_lines = stderr_reader.lines();
-        
-        // Wrap child in Arc<Mutex> for shared access
-        let child = Arc::new(Mutex::new(child));
-        let child_clone = child.clone();
-        
-        // Spawn task to monitor cancellation
-        if let Some(token) = cancel_token {
-            let child_for_cancel = child_clone.clone();
-            tokio::spawn(async move {
-                token.cancelled().await;
-                println!("[yt-dlp] Cancellation requested, killing process");
-                // Kill the process when cancelled
-                if let Ok(mut child) = child_for_cancel.try_lock() {
-                    let _ = child.kill().await;
-                }
+        // Cut sponsor segments out (downloads the segment list from SponsorBlock)
+        if let Some(sponsorblock) = self.sponsorblock_options(&options) {
+            command = command.sponsorblock(sponsorblock);
+        }
+        
+        // Subtitles are written as side files next to the video. With the fallback
+        // enabled yt-dlp still prefers manual subtitles and only takes auto-generated
+        // captions for languages that have none; those arrive as VTT, so convert to SRT.
+        // "all" fetches every manual track (`video.<lang>.srt`) but no auto captions,
+        // which YouTube machine-translates into over a hundred languages.
+        if !options.audio_only && !options.subtitle_languages.is_empty() {
+            let all = options.subtitle_languages.iter().any(|l| l == ALL_SUBTITLES);
+            command = command.subtitles(SubtitleOptions {
+                languages: if all { Vec::new() } else { options.subtitle_languages.clone() },
+                auto_generated: options.auto_subtitles && !all,
+                embed: false,
+                convert_to: (options.auto_subtitles || all).then(|| "srt".to_string()),
             });
         }
         
-        // Spawn task to read and log stderr in real-time
-        tokio::spawn(async move {
-            while let Ok(Some(line)) = stderr_lines.next_line().await {
-                println!("[yt-dlp stderr] {}", line);
+        // Chat replay of streams and premieres, kept as JSON next to the video
+        if self.setting_value(&options, "youtube_live_chat").and_then(|v| v.as_bool()).unwrap_or(false) {
+            command = command.live_chat();
+        }
+        
+        if options.embed_chapters {
+            command = command.embed_chapters();
+        }
+        
+        if options.wants_faststart() && !all_audio_tracks {
+            command = command.faststart();
+        }
+        
+        if let Some(ppa) = options.postprocessor_args.as_deref() {
+            command = command.postprocessor_args(&validate_postprocessor_args(ppa)?);
+        }
+        
+        if let Some(threads) = options.ffmpeg_threads() {
+            command = command.ffmpeg_threads(threads);
+        }
+        
+        command = network_options(command, &options);
+        
+        if let Some(archive) = &options.download_archive {
+            command = command.download_archive(archive);
+        }
+        
+        if options.record_live {
+            command = command.live_from_start();
+        }
+        
+        if options.wait_for_video {
+            command = command.wait_for_video(PREMIERE_RETRY_INTERVAL_SECS);
+        }
+        
+        if let Some((start, end)) = options.section() {
+            command = command.download_sections(start, end);
+        }
+        
+        let (command, _cookies) = self.with_auth(command);
+        let command = command.url(url);
+        let args = command.build();
+        
+        // Log the complete command before execution
+        log_info!("[yt-dlp] Executing command: {:?} {}", self.backend.ytdlp_path(), command.to_log_string());
+        log_info!("[yt-dlp] URL: {}", url);
+        log_info!("[yt-dlp] Save path: {}", save_path.display());
+        log_info!("[yt-dlp] Format: {}", format_arg);
+        log_info!("[yt-dlp] Audio only: {}", options.audio_only);
+        
+        let request = CommandRequest::new(self.backend.ytdlp_path(), args)
+            .env("PYTHONIOENCODING", "utf-8")  // Force UTF-8 encoding
+            .env("LANG", "en_US.UTF-8")        // Set English locale
+            .confine_to(save_path)             // Run in the download directory
+            .priority(options.priority)        // Background priority setting
+            .track_process(options.process.clone());
+        
+        // The callback is shared with the line handler, which must be Send
+        let progress_callback = Mutex::new(progress_callback);
+        let report = |progress: DownloadProgress| {
+            if let Ok(callback) = progress_callback.lock() {
+                callback(progress);
             }
-        });
+        };
         
-        // Parse progress from stdout
-        println!("[yt-dlp] Starting to monitor download progress...");
-        while let Ok(Some(line)) = stdout_lines.next_line().await {
-            // Log all stdout output in real-time
-            println!("[yt-dlp stdout] {}", line);
-            
-            // Attempt to parse progress from the line
-            if let Some(progress) = self.parse_progress_line(&line) {
-                println!("[yt-dlp] ✓ Parsed progress: {:.1}% (downloaded: {} bytes, total: {} bytes, speed: {:.2} MB/s, ETA: {}s)", 
-                         progress.percentage, 
-                         progress.downloaded_bytes,
-                         progress.total_bytes,
-                         progress.speed / (1024.0 * 1024.0), 
-                         progress.eta);
-                progress_callback(progress);
-            } else if line.contains("[download]") {
-                // Log when we encounter a download line that we couldn't parse
-                println!("[yt-dlp] ✗ Could not parse progress from download line: {}", line);
+        // Parse progress from stdout (yt-dlp outputs progress to stdout with --newline)
+        log_info!("[yt-dlp] Starting to monitor download progress...");
+        let section_length = options.section_length();
+        let mut on_line = |line: OutputLine| match line {
+            OutputLine::Stdout(line) => {
+                // Log all stdout output in real-time
+                log_debug!("[yt-dlp stdout] {}", line);
+                
+                // Attempt to parse progress from the line
+                if let Some(progress) = self.parse_progress_line(&line) {
+                    log_debug!("[yt-dlp] ✓ Parsed progress: {:.1}% (downloaded: {} bytes, total: {} bytes, speed: {:.2} MB/s, ETA: {}s)", 
+                             progress.percentage, 
+                             progress.downloaded_bytes,
+                             progress.total_bytes,
+                             progress.speed / (1024.0 * 1024.0), 
+                             progress.eta);
+                    report(progress);
+                } else if line.contains("[download]") {
+                    // Log when we encounter a download line that we couldn't parse
+                    log_debug!("[yt-dlp] ✗ Could not parse progress from download line: {}", line);
+                }
             }
-        }
+            OutputLine::Stderr(line) => {
+                // Cutting a section: ffmpeg reports its position instead of yt-dlp reporting bytes
+                match parse_section_progress(&line, section_length) {
+                    Some(progress) => report(progress),
+                    None => log_debug!("[yt-dlp stderr] {}", line),
+                }
+            }
+        };
         
-        // Wait for process to complete
-        println!("[yt-dlp] Waiting for process to complete...");
-        let status = child.lock().await.wait().await
-            .map_err(|e| {
-                println!("[yt-dlp] ERROR: Failed to wait for yt-dlp process: {}", e);
-                DownloadError::DownloadFailed(format!("Failed to wait for yt-dlp: {}", e))
+        // Post-processing runs inside the yt-dlp process, so a transcode slot covers the whole run
+        let _transcode_slot = options.transcode_slot().await;
+        
+        // Run to completion; cancellation kills the process
+        let output = self.backend.runner().stream(&request, &mut on_line, cancel_token.as_ref())
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    log_error!("[yt-dlp] ERROR: yt-dlp executable not found at {:?}", self.backend.ytdlp_path());
+                    DownloadError::YtdlpNotFound
+                }
+                std::io::ErrorKind::Interrupted => {
+                    log_info!("[yt-dlp] Cancellation requested, process killed");
+                    DownloadError::Cancelled
+                }
+                _ => {
+                    log_error!("[yt-dlp] ERROR: Failed to run yt-dlp: {}", e);
+                    DownloadError::DownloadFailed(format!("Failed to run yt-dlp: {}", e))
+                }
             })?;
         
-        if !status.success() {
-            println!("[yt-dlp] ✗ Download FAILED with exit status: {}", status);
-            let error_msg = format!("yt-dlp exited with status: {} (check stderr output above for details)", status);
-            return Err(DownloadError::DownloadFailed(error_msg));
+        if !output.success() {
+            log_error!("[yt-dlp] ✗ Download FAILED with exit code: {:?}", output.exit_code);
+            return Err(exit_error(&output));
         }
         
-        println!("[yt-dlp] ✓ Download completed successfully");
+        log_info!("[yt-dlp] ✓ Download completed successfully");
         
         // Always send 100% progress when yt-dlp exits successfully
         // This ensures completion is reported even if progress updates were not received
-        println!("[yt-dlp] Sending final 100% completion progress");
-        progress_callback(DownloadProgress {
+        log_info!("[yt-dlp] Sending final 100% completion progress");
+        report(DownloadProgress {
             percentage: 100.0,
             downloaded_bytes: 0,
             total_bytes: 0,
             speed: 0.0,
             eta: 0,
+            elapsed: None,
+            display: None,
         });
         
-        println!("[yt-dlp] Final status: SUCCESS");
-        println!("[yt-dlp] Output file: {}", save_path.display());
+        log_info!("[yt-dlp] Final status: SUCCESS");
+        log_info!("[yt-dlp] Output file: {}", save_path.display());
         
         Ok(())
     }
     
+    /// Value of a platform setting: the saved one, or the default from `get_platform_settings`
+    fn setting_value(&self, options: &DownloadOptions, key: &str) -> Option<Value> {
+        options.platform_settings.get(key).cloned().or_else(|| {
+            self.get_platform_settings()
+                .into_iter()
+                .find(|s| s.key == key)
+                .map(|s| s.default_value)
+        })
+    }
+    
+    /// User `--parse-metadata` rules from `youtube_parse_metadata_rules`, blank entries skipped
+    fn metadata_parse_rules(&self, options: &DownloadOptions) -> Result<Vec<String>> {
+        let rules: Vec<String> = match self.setting_value(options, "youtube_parse_metadata_rules") {
+            Some(Value::Array(list)) => list.iter().filter_map(|r| r.as_str()).map(str::to_string).collect(),
+            Some(Value::String(list)) => list.lines().map(str::to_string).collect(),
+            _ => Vec::new(),
+        };
+        rules
+            .into_iter()
+            .map(|rule| rule.trim().to_string())
+            .filter(|rule| !rule.is_empty())
+            .map(|rule| validate_metadata_rule(&rule).map(|_| rule))
+            .collect()
+    }
+    
+    /// SponsorBlock handling from the `youtube_skip_ads` and category settings
+    ///
+    /// Unknown category names are ignored; `None` when disabled or nothing is left.
+    fn sponsorblock_options(&self, options: &DownloadOptions) -> Option<SponsorBlockOptions> {
+        let enabled = self.setting_value(options, "youtube_skip_ads")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        
+        let categories: Vec<String> = match self.setting_value(options, "youtube_sponsorblock_categories") {
+            Some(Value::String(list)) => list.split(',').map(|c| c.trim().to_string()).collect(),
+            Some(Value::Array(list)) => list.iter().filter_map(|c| c.as_str()).map(|c| c.trim().to_string()).collect(),
+            _ => Vec::new(),
+        };
+        let categories: Vec<String> = categories
+            .into_iter()
+            .filter(|c| SPONSORBLOCK_CATEGORIES.contains(&c.as_str()))
+            .collect();
+        
+        (!categories.is_empty()).then_some(SponsorBlockOptions::Remove(categories))
+    }
+    
     /// Build format string for yt-dlp based on download options
     fn build_format_string(&self, options: &DownloadOptions) -> String {
+        if let Some(format_id) = &options.format_id {
+            return format_id.clone();
+        }
+        let language = self.audio_language(options);
         if options.audio_only {
-            // Best audio quality
-            return "bestaudio".to_string();
+            // Best audio, preferring a stream already in the target codec
+            let selector = AudioFormat::parse(&options.format).map_or("bestaudio", |f| f.source_selector());
+            return match language {
+                Some(language) => format!("bestaudio[language^={}]/{}", language, selector),
+                None => selector.to_string(),
+            };
         }
         
         // Parse quality preference
-        let quality = &options.quality;
         let format = &options.format;
+        let height = match options.quality.as_str() {
+            "2160p" | "4k" => Some(2160),
+            "1440p" => Some(1440),
+            "1080p" => Some(1080),
+            "720p" => Some(720),
+            "480p" => Some(480),
+            "360p" => Some(360),
+            _ => None,
+        };
+        let height = height.map(|h| format!("[height<={}]", h));
+        let combined = height.clone().unwrap_or_else(|| format!("[ext={}]", format));
+        let height = height.unwrap_or_default();
+        
+        // Avoided HDR/high frame rates filter every video choice
+        let (preferred, avoided) = self.dynamic_range_and_fps_filters(options);
+        let by_quality = format!(
+            "bestvideo{}[ext={}]{}+bestaudio/best{}{}/best",
+            height, format, avoided, combined, avoided
+        );
+        
+        // Try the preferred codec, HDR and frame rate first, falling back to any at the same height
+        let vcodec = self.preferred_vcodec(options).map(|c| format!("[vcodec^={}]", c)).unwrap_or_default();
+        let (video, selector) = if vcodec.is_empty() && preferred.is_empty() {
+            (format!("bestvideo{}[ext={}]{}", height, format, avoided), by_quality)
+        } else {
+            let video = format!("bestvideo{}{}{}{}", height, vcodec, preferred, avoided);
+            let selector = format!("{}+bestaudio/{}", video, by_quality);
+            (video, selector)
+        };
         
-        match quality.as_str() {
-            "best" => format!("bestvideo[ext={}]+bestaudio/best[ext={}]/best", format, format),
-            "2160p" | "4k" => format!("bestvideo[height<=2160][ext={}]+bestaudio/best[height<=2160]/best", format),
-            "1440p" => format!("bestvideo[height<=1440][ext={}]+bestaudio/best[height<=1440]/best", format),
-            "1080p" => format!("bestvideo[height<=1080][ext={}]+bestaudio/best[height<=1080]/best", format),
-            "720p" => format!("bestvideo[height<=720][ext={}]+bestaudio/best[height<=720]/best", format),
-            "480p" => format!("bestvideo[height<=480][ext={}]+bestaudio/best[height<=480]/best", format),
-            "360p" => format!("bestvideo[height<=360][ext={}]+bestaudio/best[height<=360]/best", format),
-            _ => format!("bestvideo[ext={}]+bestaudio/best[ext={}]/best", format, format),
+        // Every audio track, or the one in the preferred language, before the default track
+        if self.all_audio_tracks(options) {
+            format!("{}+mergeall[vcodec=none]/{}", video, selector)
+        } else if let Some(language) = language {
+            format!("{}+bestaudio[language^={}]/{}", video, language, selector)
+        } else {
+            selector
+        }
+    }
+    
+    /// Audio language code from `youtube_audio_language`, if set to something a selector can hold
+    fn audio_language(&self, options: &DownloadOptions) -> Option<String> {
+        let language = self.setting_value(options, "youtube_audio_language")?;
+        let language = language.as_str()?.trim();
+        let valid = !language.is_empty() && language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        valid.then(|| language.to_string())
+    }
+    
+    /// Whether `youtube_all_audio_tracks` asks for every audio track of a video download
+    fn all_audio_tracks(&self, options: &DownloadOptions) -> bool {
+        !options.audio_only
+            && options.format_id.is_none()
+            && self.setting_value(options, "youtube_all_audio_tracks").and_then(|v| v.as_bool()).unwrap_or(false)
+    }
+    
+    /// Format filters from `youtube_hdr` and `youtube_high_fps`: (preferred, avoided)
+    ///
+    /// Formats that don't report a dynamic range or frame rate still pass the avoid filters.
+    fn dynamic_range_and_fps_filters(&self, options: &DownloadOptions) -> (String, String) {
+        let mut preferred = String::new();
+        let mut avoided = String::new();
+        let choice = |key: &str| self.setting_value(options, key).and_then(|v| v.as_str().map(str::to_string));
+        
+        match choice("youtube_hdr").as_deref() {
+            Some("prefer") => preferred.push_str("[dynamic_range!=SDR]"),
+            Some("avoid") => avoided.push_str("[dynamic_range=?SDR]"),
+            _ => {}
+        }
+        match choice("youtube_high_fps").as_deref() {
+            Some("prefer") => preferred.push_str("[fps>30]"),
+            Some("avoid") => avoided.push_str("[fps<=?30]"),
+            _ => {}
+        }
+        (preferred, avoided)
+    }
+    
+    /// yt-dlp `vcodec` prefix from `youtube_video_codec`; `auto` defers to `youtube_prefer_av1`
+    fn preferred_vcodec(&self, options: &DownloadOptions) -> Option<&'static str> {
+        let codec = self.setting_value(options, "youtube_video_codec");
+        match codec.as_ref().and_then(|v| v.as_str()) {
+            Some("av1") => Some("av01"),
+            Some("vp9") => Some("vp9"),
+            Some("h264") => Some("avc1"),
+            _ => self.setting_value(options, "youtube_prefer_av1")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+                .then_some("av01"),
         }
     }
     
@@ -433,6 +790,8 @@ impl YouTubeProvider {
                 total_bytes: 0,
                 speed: 0.0,
                 eta: 0,
+                elapsed: None,
+                display: None,
             });
         }
         
@@ -444,6 +803,8 @@ impl YouTubeProvider {
                 total_bytes: 0,
                 speed: 0.0,
                 eta: 0,
+                elapsed: None,
+                display: None,
             });
         }
         
@@ -455,12 +816,22 @@ impl YouTubeProvider {
                 total_bytes: 0,
                 speed: 0.0,
                 eta: 0,
+                elapsed: None,
+                display: None,
             });
         }
         
+        // Live recordings have no total, so yt-dlp reports size and elapsed time instead:
+        // [download]  25.30MiB at  2.10MiB/s (00:01:23) (frag 15/?)
+        if !line.contains('%') {
+            if let Some(progress) = self.parse_live_progress(line) {
+                return Some(progress);
+            }
+        }
+        
         // Pattern 4: Standard format - [download]  45.8% of 123.45MiB at 1.23MiB/s ETA 00:42
         // Try to extract percentage first - if this fails, the line is unparseable
-        println!("[yt-dlp] Parsing progress line: {}", line);
+        log_debug!("[yt-dlp] Parsing progress line: {}", line);
         match self.extract_percentage(line) {
             Some(percentage) => {
                 // Extract downloaded and total bytes
@@ -478,16 +849,50 @@ impl YouTubeProvider {
                     total_bytes,
                     speed,
                     eta,
+                    elapsed: None,
+                    display: None,
                 })
             }
             None => {
                 // Log unparseable lines that contain "[download]" for debugging
-                println!("[yt-dlp] ⚠ Unparseable download line: {}", line);
+                log_debug!("[yt-dlp] ⚠ Unparseable download line: {}", line);
                 None
             }
         }
     }
     
+    /// Parse a live recording progress line into bytes so far, speed and elapsed time
+    fn parse_live_progress(&self, line: &str) -> Option<DownloadProgress> {
+        let re = Regex::new(r"\[download\]\s+~?\s*(\d+\.?\d*)(KiB|MiB|GiB|B)\s+at\s+.*\((\d+):(\d+)(?::(\d+))?\)").ok()?;
+        let caps = re.captures(line)?;
+        
+        let value: f64 = caps[1].parse().ok()?;
+        let multiplier = match &caps[2] {
+            "KiB" => 1024.0,
+            "MiB" => 1024.0 * 1024.0,
+            "GiB" => 1024.0 * 1024.0 * 1024.0,
+            _ => 1.0,
+        };
+        
+        // (MM:SS) or (HH:MM:SS)
+        let parts: Vec<u64> = [caps.get(3), caps.get(4), caps.get(5)]
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m.as_str().parse().ok())
+            .collect();
+        let elapsed = parts.iter().fold(0, |total, part| total * 60 + part);
+        
+        Some(DownloadProgress {
+            percentage: 0.0,
+            downloaded_bytes: (value * multiplier) as u64,
+            total_bytes: 0,
+            speed: self.extract_speed(line).unwrap_or(0.0),
+            eta: 0,
+            elapsed: Some(elapsed),
+            display: None,
+        })
+    }
+    
     /// Extract percentage from progress line
     fn extract_percentage(&self, line: &str) -> Option<f64> {
         // Wrap regex operations in error handling
@@ -674,30 +1079,26 @@ impl YouTubeProvider {
     /// Test yt-dlp installation by fetching video title
     /// This is a lightweight test that verifies yt-dlp can communicate with YouTube
     pub async fn test_download(&self, url: &str) -> Result<String> {
-        println!("[yt-dlp test] Testing yt-dlp with URL: {}", url);
-        println!("[yt-dlp test] yt-dlp path: {:?}", self.ytdlp_path);
+        log_info!("[yt-dlp test] Testing yt-dlp with URL: {}", url);
+        log_info!("[yt-dlp test] yt-dlp path: {:?}", self.backend.ytdlp_path());
         
         // Check if yt-dlp executable exists
-        if !self.ytdlp_path.exists() {
-            let error_msg = format!("yt-dlp executable not found at: {:?}", self.ytdlp_path);
-            println!("[yt-dlp test] ERROR: {}", error_msg);
+        if !self.backend.ytdlp_path().exists() {
+            let error_msg = format!("yt-dlp executable not found at: {:?}", self.backend.ytdlp_path());
+            log_error!("[yt-dlp test] ERROR: {}", error_msg);
             return Err(DownloadError::YtdlpNotFound);
         }
         
         // Try to fetch video title using yt-dlp
-        let output = Command::new(&self.ytdlp_path)
-            .args(&[
-                "--no-warnings",
-                "--print", "title",
-                url,
-            ])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
+        let request = CommandRequest::new(
+            self.backend.ytdlp_path(),
+            YtDlpCommandBuilder::new().no_warnings().print_field("title").url(url).build(),
+        );
+        let output = self.backend.runner().output(&request)
             .await
             .map_err(|e| {
                 let error_msg = format!("Failed to execute yt-dlp: {}", e);
-                println!("[yt-dlp test] ERROR: {}", error_msg);
+                log_error!("[yt-dlp test] ERROR: {}", error_msg);
                 if e.kind() == std::io::ErrorKind::NotFound {
                     DownloadError::YtdlpNotFound
                 } else {
@@ -705,16 +1106,20 @@ impl YouTubeProvider {
                 }
             })?;
         
-        if output.status.success() {
-            let title = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            println!("[yt-dlp test] ✓ SUCCESS: Retrieved video title: {}", title);
+        if output.success() {
+            let title = output.stdout.trim().to_string();
+            log_info!("[yt-dlp test] ✓ SUCCESS: Retrieved video title: {}", title);
             Ok(title)
         } else {
-            let error = String::from_utf8_lossy(&output.stderr).to_string();
-            println!("[yt-dlp test] ✗ FAILED: {}", error);
+            let error = output.stderr.clone();
+            log_error!("[yt-dlp test] ✗ FAILED: {}", error);
             
             // Provide clear error messages based on common issues
-            if error.contains("Video unavailable") || error.contains("Private video") {
+            if is_bot_check(&error) {
+                Err(DownloadError::BotCheck(
+                    "Test failed: YouTube is asking to confirm you're not a bot.".to_string()
+                ))
+            } else if error.contains("Video unavailable") || error.contains("Private video") {
                 Err(DownloadError::VideoUnavailable(
                     "Test failed: Video is unavailable or private. Try a different URL.".to_string()
                 ))
@@ -737,15 +1142,12 @@ impl YouTubeProvider {
     
     /// Get yt-dlp version
     pub async fn get_ytdlp_version(&self) -> Result<String> {
-        if !self.ytdlp_path.exists() {
+        if !self.backend.ytdlp_path().exists() {
             return Err(DownloadError::YtdlpNotFound);
         }
         
-        let output = Command::new(&self.ytdlp_path)
-            .arg("--version")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
+        let request = CommandRequest::new(self.backend.ytdlp_path(), YtDlpCommandBuilder::new().version().build());
+        let output = self.backend.runner().output(&request)
             .await
             .map_err(|e| {
                 if e.kind() == std::io::ErrorKind::NotFound {
@@ -755,12 +1157,11 @@ impl YouTubeProvider {
                 }
             })?;
         
-        if output.status.success() {
-            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.success() {
+            let version = output.stdout.trim().to_string();
             Ok(version)
         } else {
-            let error = String::from_utf8_lossy(&output.stderr);
-            Err(DownloadError::DownloadFailed(format!("Failed to get yt-dlp version: {}", error)))
+            Err(DownloadError::DownloadFailed(format!("Failed to get yt-dlp version: {}", output.stderr)))
         }
     }
     
@@ -772,18 +1173,15 @@ impl YouTubeProvider {
             ));
         }
         
-        let output = Command::new(&self.ffmpeg_path)
-            .arg("-version")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
+        let request = CommandRequest::new(&self.ffmpeg_path, vec!["-version".to_string()]);
+        let output = self.backend.runner().output(&request)
             .await
             .map_err(|e| {
                 DownloadError::DownloadFailed(format!("Failed to get ffmpeg version: {}", e))
             })?;
         
-        if output.status.success() {
-            let version_output = String::from_utf8_lossy(&output.stdout);
+        if output.success() {
+            let version_output = &output.stdout;
             // Extract version from first line: "ffmpeg version X.X.X ..."
             let version = version_output
                 .lines()
@@ -801,23 +1199,22 @@ impl YouTubeProvider {
                 .unwrap_or_else(|| "unknown".to_string());
             Ok(version)
         } else {
-            let error = String::from_utf8_lossy(&output.stderr);
-            Err(DownloadError::DownloadFailed(format!("Failed to get ffmpeg version: {}", error)))
+            Err(DownloadError::DownloadFailed(format!("Failed to get ffmpeg version: {}", output.stderr)))
         }
     }
     
     /// Log versions of yt-dlp and ffmpeg at startup
     pub async fn log_versions(&self) {
-        println!("[YouTubeProvider] Logging dependency versions...");
+        log_info!("[YouTubeProvider] Logging dependency versions...");
         
         match self.get_ytdlp_version().await {
-            Ok(version) => println!("[YouTubeProvider] yt-dlp version: {}", version),
-            Err(e) => println!("[YouTubeProvider] Failed to get yt-dlp version: {:?}", e),
+            Ok(version) => log_info!("[YouTubeProvider] yt-dlp version: {}", version),
+            Err(e) => log_warn!("[YouTubeProvider] Failed to get yt-dlp version: {:?}", e),
         }
         
         match self.get_ffmpeg_version().await {
-            Ok(version) => println!("[YouTubeProvider] ffmpeg version: {}", version),
-            Err(e) => println!("[YouTubeProvider] Failed to get ffmpeg version: {:?}", e),
+            Ok(version) => log_info!("[YouTubeProvider] ffmpeg version: {}", version),
+            Err(e) => log_warn!("[YouTubeProvider] Failed to get ffmpeg version: {:?}", e),
         }
     }
 }
@@ -836,10 +1233,18 @@ impl PlatformProvider for YouTubeProvider {
         self.url_patterns.iter().any(|pattern| pattern.is_match(url))
     }
     
+    fn normalize_url(&self, url: &str) -> String {
+        UrlValidator::remove_tracking_params(&UrlValidator::normalize_video_path(url.trim()))
+    }
+    
     fn supported_patterns(&self) -> Vec<String> {
         vec![
             "https://www.youtube.com/watch?v=VIDEO_ID".to_string(),
             "https://youtu.be/VIDEO_ID".to_string(),
+            "https://www.youtube.com/shorts/VIDEO_ID".to_string(),
+            "https://www.youtube.com/live/VIDEO_ID".to_string(),
+            "https://music.youtube.com/watch?v=VIDEO_ID".to_string(),
+            "https://music.youtube.com/playlist?list=PLAYLIST_ID".to_string(),
             "https://www.youtube.com/playlist?list=PLAYLIST_ID".to_string(),
             "https://www.youtube.com/@CHANNEL_NAME".to_string(),
             "https://www.youtube.com/channel/CHANNEL_ID".to_string(),
@@ -850,12 +1255,9 @@ impl PlatformProvider for YouTubeProvider {
     
     async fn get_video_info(&self, url: &str) -> Result<VideoInfo> {
         // Use yt-dlp to extract video information in JSON format
-        let json_output = self.execute_ytdlp(&[
-            "--dump-json",
-            "--no-playlist",
-            "--skip-download",
-            url,
-        ]).await?;
+        let json_output = self.execute_ytdlp(
+            &YtDlpCommandBuilder::new().dump_json().no_playlist().url(url)
+        ).await?;
         
         let json: Value = serde_json::from_str(&json_output)
             .map_err(|e| DownloadError::DownloadFailed(format!("Failed to parse video info: {}", e)))?;
@@ -865,12 +1267,9 @@ impl PlatformProvider for YouTubeProvider {
     
     async fn get_playlist_info(&self, url: &str) -> Result<PlaylistInfo> {
         // First, get playlist metadata
-        let json_output = self.execute_ytdlp(&[
-            "--dump-json",
-            "--flat-playlist",
-            "--skip-download",
-            url,
-        ]).await?;
+        let json_output = self.execute_ytdlp(
+            &YtDlpCommandBuilder::new().dump_flat_playlist().url(url)
+        ).await?;
         
         // Parse each line as a separate JSON object (one per video)
         let mut videos = Vec::new();
@@ -878,6 +1277,12 @@ impl PlatformProvider for YouTubeProvider {
         let mut playlist_id = String::new();
         let mut playlist_description = String::new();
         let mut uploader = String::new();
+        // Keep YouTube Music entries on music.youtube.com so they default to audio
+        let watch_base = if Self::is_music_url(url) {
+            "https://music.youtube.com/watch?v="
+        } else {
+            "https://www.youtube.com/watch?v="
+        };
         
         for line in json_output.lines() {
             if line.trim().is_empty() {
@@ -915,7 +1320,7 @@ impl PlatformProvider for YouTubeProvider {
             
             // Parse video entry
             if let Some(video_id) = json["id"].as_str() {
-                let video_url = format!("https://www.youtube.com/watch?v={}", video_id);
+                let video_url = format!("{}{}", watch_base, video_id);
                 videos.push(VideoInfo {
                     id: video_id.to_string(),
                     title: json["title"]
@@ -951,6 +1356,8 @@ impl PlatformProvider for YouTubeProvider {
                     available_formats: Vec::new(), // Formats not available in flat playlist
                     platform: "YouTube".to_string(),
                     url: video_url,
+                    is_live: false,
+                    premiere_at: None,
                 });
             }
         }
@@ -971,13 +1378,22 @@ impl PlatformProvider for YouTubeProvider {
     }
     
     async fn get_channel_info(&self, url: &str) -> Result<ChannelInfo> {
-        // First, get channel metadata
-        let json_output = self.execute_ytdlp(&[
-            "--dump-json",
-            "--flat-playlist",
-            "--skip-download",
-            url,
-        ]).await?;
+        // Attempt to get playlists tab (this may not always work); a requested
+        // tab like `/videos` is replaced, as playlists are a tab of their own
+        let playlists_url = if url.contains("/@") || url.contains("/channel/") {
+            format!("{}/playlists", ChannelTab::channel_root(url))
+        } else {
+            url.to_string()
+        };
+        
+        // Channel videos and playlists are fetched in parallel, up to the metadata limit
+        let videos_command = YtDlpCommandBuilder::new().dump_flat_playlist().url(url);
+        let playlists_command = YtDlpCommandBuilder::new().dump_flat_playlist().url(&playlists_url);
+        let (json_output, playlists_output) = tokio::join!(
+            self.execute_ytdlp(&videos_command),
+            self.execute_ytdlp(&playlists_command),
+        );
+        let json_output = json_output?;
         
         let mut channel_name = String::new();
         let mut channel_id = String::new();
@@ -1046,6 +1462,8 @@ impl PlatformProvider for YouTubeProvider {
                     available_formats: Vec::new(),
                     platform: "YouTube".to_string(),
                     url: video_url,
+                    is_live: false,
+                    premiere_at: None,
                 });
             }
         }
@@ -1053,22 +1471,8 @@ impl PlatformProvider for YouTubeProvider {
         // Try to get channel playlists
         let mut playlists = Vec::new();
         
-        // Attempt to get playlists tab (this may not always work)
-        let playlists_url = if url.contains("/@") {
-            format!("{}/playlists", url.trim_end_matches('/'))
-        } else if url.contains("/channel/") {
-            format!("{}/playlists", url.trim_end_matches('/'))
-        } else {
-            url.to_string()
-        };
-        
-        // Try to fetch playlists (may fail if channel has no playlists tab)
-        if let Ok(playlists_output) = self.execute_ytdlp(&[
-            "--dump-json",
-            "--flat-playlist",
-            "--skip-download",
-            &playlists_url,
-        ]).await {
+        // The playlists fetch may fail if the channel has no playlists tab
+        if let Ok(playlists_output) = playlists_output {
             let mut current_playlist: Option<PlaylistInfo> = None;
             let mut playlist_videos: Vec<VideoInfo> = Vec::new();
             
@@ -1135,6 +1539,8 @@ impl PlatformProvider for YouTubeProvider {
                             available_formats: Vec::new(),
                             platform: "YouTube".to_string(),
                             url: video_url,
+                            is_live: false,
+                            premiere_at: None,
                         });
                     }
                 }
@@ -1174,9 +1580,9 @@ impl PlatformProvider for YouTubeProvider {
         let mut dependencies = Vec::new();
         
         // Check bundled yt-dlp
-        let ytdlp_installed = self.ytdlp_path.exists();
+        let ytdlp_installed = self.backend.ytdlp_path().exists();
         let ytdlp_version = if ytdlp_installed {
-            match self.execute_ytdlp(&["--version"]).await {
+            match self.execute_ytdlp(&YtDlpCommandBuilder::new().version()).await {
                 Ok(version) => Some(version.trim().to_string()),
                 Err(_) => None,
             }
@@ -1194,16 +1600,10 @@ impl PlatformProvider for YouTubeProvider {
         // Check bundled ffmpeg
         let ffmpeg_installed = self.ffmpeg_path.exists();
         let ffmpeg_version = if ffmpeg_installed {
-            match Command::new(&self.ffmpeg_path)
-                .arg("-version")
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .output()
-                .await
-            {
-                Ok(output) if output.status.success() => {
-                    let version_output = String::from_utf8_lossy(&output.stdout);
-                    version_output
+            let request = CommandRequest::new(&self.ffmpeg_path, vec!["-version".to_string()]);
+            match self.backend.runner().output(&request).await {
+                Ok(output) if output.success() => {
+                    output.stdout
                         .lines()
                         .next()
                         .and_then(|line| line.split_whitespace().nth(2))
@@ -1227,24 +1627,60 @@ impl PlatformProvider for YouTubeProvider {
     
     fn get_platform_settings(&self) -> Vec<PlatformSetting> {
         vec![
-            PlatformSetting {
-                key: "youtube_prefer_av1".to_string(),
-                label: "优先使用 AV1 编码".to_string(),
-                setting_type: SettingType::Boolean,
-                default_value: serde_json::json!(false),
-            },
-            PlatformSetting {
-                key: "youtube_skip_ads".to_string(),
-                label: "跳过赞助片段 (SponsorBlock)".to_string(),
-                setting_type: SettingType::Boolean,
-                default_value: serde_json::json!(true),
-            },
-            PlatformSetting {
-                key: "youtube_subtitle_language".to_string(),
-                label: "字幕语言".to_string(),
-                setting_type: SettingType::Select {
+            PlatformSetting::new(
+                "youtube_prefer_av1",
+                "settings.youtube.prefer_av1",
+                SettingType::Boolean,
+                serde_json::json!(false),
+            ),
+            PlatformSetting::new(
+                "youtube_video_codec",
+                "settings.youtube.video_codec",
+                SettingType::Select {
+                    options: vec![
+                        "auto".to_string(),
+                        "av1".to_string(),
+                        "vp9".to_string(),
+                        "h264".to_string(),
+                    ],
+                },
+                serde_json::json!("auto"),
+            ),
+            PlatformSetting::new(
+                "youtube_hdr",
+                "settings.youtube.hdr",
+                SettingType::Select {
+                    options: vec!["auto".to_string(), "prefer".to_string(), "avoid".to_string()],
+                },
+                serde_json::json!("auto"),
+            ),
+            PlatformSetting::new(
+                "youtube_high_fps",
+                "settings.youtube.high_fps",
+                SettingType::Select {
+                    options: vec!["auto".to_string(), "prefer".to_string(), "avoid".to_string()],
+                },
+                serde_json::json!("auto"),
+            ),
+            PlatformSetting::new(
+                "youtube_skip_ads",
+                "settings.youtube.skip_ads",
+                SettingType::Boolean,
+                serde_json::json!(true),
+            ),
+            PlatformSetting::new(
+                "youtube_sponsorblock_categories",
+                "settings.youtube.sponsorblock_categories",
+                SettingType::String,
+                serde_json::json!("sponsor"),
+            ),
+            PlatformSetting::new(
+                "youtube_subtitle_language",
+                "settings.youtube.subtitle_language",
+                SettingType::Select {
                     options: vec![
                         "none".to_string(),
+                        ALL_SUBTITLES.to_string(),
                         "zh-CN".to_string(),
                         "zh-TW".to_string(),
                         "en".to_string(),
@@ -1252,24 +1688,42 @@ impl PlatformProvider for YouTubeProvider {
                         "ko".to_string(),
                     ],
                 },
-                default_value: serde_json::json!("none"),
-            },
-            PlatformSetting {
-                key: "youtube_embed_thumbnail".to_string(),
-                label: "嵌入缩略图到视频文件".to_string(),
-                setting_type: SettingType::Boolean,
-                default_value: serde_json::json!(true),
-            },
-            PlatformSetting {
-                key: "youtube_embed_metadata".to_string(),
-                label: "嵌入元数据 (标题、描述等)".to_string(),
-                setting_type: SettingType::Boolean,
-                default_value: serde_json::json!(true),
-            },
-            PlatformSetting {
-                key: "youtube_max_resolution".to_string(),
-                label: "最大分辨率".to_string(),
-                setting_type: SettingType::Select {
+                serde_json::json!("none"),
+            ),
+            PlatformSetting::new(
+                "youtube_auto_subtitles",
+                "settings.youtube.auto_subtitles",
+                SettingType::Boolean,
+                serde_json::json!(false),
+            ),
+            PlatformSetting::new(
+                "youtube_live_chat",
+                "settings.youtube.live_chat",
+                SettingType::Boolean,
+                serde_json::json!(false),
+            ),
+            PlatformSetting::new(
+                "youtube_embed_thumbnail",
+                "settings.youtube.embed_thumbnail",
+                SettingType::Boolean,
+                serde_json::json!(true),
+            ),
+            PlatformSetting::new(
+                "youtube_write_thumbnail",
+                "settings.youtube.write_thumbnail",
+                SettingType::Boolean,
+                serde_json::json!(false),
+            ),
+            PlatformSetting::new(
+                "youtube_embed_metadata",
+                "settings.youtube.embed_metadata",
+                SettingType::Boolean,
+                serde_json::json!(true),
+            ),
+            PlatformSetting::new(
+                "youtube_max_resolution",
+                "settings.youtube.max_resolution",
+                SettingType::Select {
                     options: vec![
                         "best".to_string(),
                         "2160p".to_string(),
@@ -1279,11 +1733,37 @@ impl PlatformProvider for YouTubeProvider {
                         "480p".to_string(),
                     ],
                 },
-                default_value: serde_json::json!("1080p"),
-            },
+                serde_json::json!("1080p"),
+            ),
+            PlatformSetting::new(
+                "youtube_parse_metadata_rules",
+                "settings.youtube.parse_metadata_rules",
+                SettingType::List,
+                serde_json::json!([]),
+            ),
+            PlatformSetting::new(
+                "youtube_audio_language",
+                "settings.youtube.audio_language",
+                SettingType::String,
+                serde_json::json!(""),
+            ),
+            PlatformSetting::new(
+                "youtube_all_audio_tracks",
+                "settings.youtube.all_audio_tracks",
+                SettingType::Boolean,
+                serde_json::json!(false),
+            ),
         ]
     }
     
+    fn metadata_limit(&self) -> Option<&MetadataLimit> {
+        Some(self.backend.metadata_limit())
+    }
+    
+    fn metadata_network(&self) -> Option<&MetadataNetwork> {
+        Some(self.backend.metadata_network())
+    }
+    
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -1298,6 +1778,8 @@ impl Default for YouTubeProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::platform::command_runner::{MockCommandRunner, MockResponse};
+    use crate::i18n::Language;
 
     #[test]
     fn test_matches_standard_video_url() {
@@ -1312,6 +1794,37 @@ mod tests {
         assert!(provider.matches_url("https://youtu.be/dQw4w9WgXcQ"));
     }
 
+    #[test]
+    fn test_matches_shorts_and_live_urls() {
+        let provider = YouTubeProvider::new();
+        assert!(provider.matches_url("https://www.youtube.com/shorts/aqz-KE-bpKQ"));
+        assert!(provider.matches_url("https://m.youtube.com/shorts/aqz-KE-bpKQ?feature=share"));
+        assert!(provider.matches_url("https://youtube.com/live/jfKfPfyJRdk"));
+    }
+
+    #[test]
+    fn test_matches_music_urls() {
+        let provider = YouTubeProvider::new();
+        assert!(provider.matches_url("https://music.youtube.com/watch?v=lYBUbBu4W08"));
+        assert!(provider.matches_url("https://music.youtube.com/playlist?list=OLAK5uy_kRVRaMzkBOuRpYYUfFtOkLbQK5HR1vXFQ"));
+        assert!(provider.matches_url("https://music.youtube.com/browse/MPREb_4pL8gzRtw1p"));
+        assert!(YouTubeProvider::is_music_url("https://music.youtube.com/watch?v=lYBUbBu4W08"));
+        assert!(!YouTubeProvider::is_music_url("https://www.youtube.com/watch?v=lYBUbBu4W08"));
+    }
+
+    #[test]
+    fn test_channel_tab_urls() {
+        assert_eq!(ChannelTab::Videos.apply("https://www.youtube.com/@LinusTechTips"), "https://www.youtube.com/@LinusTechTips/videos");
+        assert_eq!(ChannelTab::Live.apply("https://www.youtube.com/@LinusTechTips/featured/"), "https://www.youtube.com/@LinusTechTips/streams");
+        assert_eq!(
+            ChannelTab::Shorts.apply("https://www.youtube.com/channel/UCXuqSBlHAE6Xw-yeJA0Tunw/videos?view=0"),
+            "https://www.youtube.com/channel/UCXuqSBlHAE6Xw-yeJA0Tunw/shorts"
+        );
+        // A channel literally named like a tab is not mistaken for one
+        assert_eq!(ChannelTab::Videos.apply("https://www.youtube.com/c/about"), "https://www.youtube.com/c/about/videos");
+        assert_eq!(ChannelTab::channel_root("https://www.youtube.com/@LinusTechTips/shorts/"), "https://www.youtube.com/@LinusTechTips");
+    }
+
     #[test]
     fn test_matches_playlist_url() {
         let provider = YouTubeProvider::new();
@@ -1357,6 +1870,19 @@ mod tests {
         assert!(provider.matches_url("  https://www.youtube.com/watch?v=dQw4w9WgXcQ  "));
     }
 
+    #[test]
+    fn test_normalize_url_strips_tracking_params() {
+        let provider = YouTubeProvider::new();
+        assert_eq!(
+            provider.normalize_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ&feature=share"),
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ"
+        );
+        assert_eq!(
+            provider.normalize_url("https://youtube.com/shorts/aqz-KE-bpKQ?si=abc123"),
+            "https://www.youtube.com/watch?v=aqz-KE-bpKQ"
+        );
+    }
+
     #[test]
     fn test_provider_name() {
         let provider = YouTubeProvider::new();
@@ -1367,7 +1893,7 @@ mod tests {
     fn test_supported_patterns() {
         let provider = YouTubeProvider::new();
         let patterns = provider.supported_patterns();
-        assert_eq!(patterns.len(), 7);
+        assert_eq!(patterns.len(), 11);
         assert!(patterns.contains(&"https://www.youtube.com/watch?v=VIDEO_ID".to_string()));
     }
 
@@ -1375,13 +1901,31 @@ mod tests {
     fn test_platform_settings() {
         let provider = YouTubeProvider::new();
         let settings = provider.get_platform_settings();
-        assert_eq!(settings.len(), 6);
+        assert_eq!(settings.len(), 16);
         
         // Check that key settings exist
         assert!(settings.iter().any(|s| s.key == "youtube_prefer_av1"));
+        assert!(settings.iter().any(|s| s.key == "youtube_video_codec"));
+        assert!(settings.iter().any(|s| s.key == "youtube_hdr"));
+        assert!(settings.iter().any(|s| s.key == "youtube_high_fps"));
         assert!(settings.iter().any(|s| s.key == "youtube_skip_ads"));
+        assert!(settings.iter().any(|s| s.key == "youtube_sponsorblock_categories"));
         assert!(settings.iter().any(|s| s.key == "youtube_subtitle_language"));
+        assert!(settings.iter().any(|s| s.key == "youtube_auto_subtitles"));
+        assert!(settings.iter().any(|s| s.key == "youtube_write_thumbnail"));
+        assert!(settings.iter().any(|s| s.key == "youtube_live_chat"));
         assert!(settings.iter().any(|s| s.key == "youtube_max_resolution"));
+        assert!(settings.iter().any(|s| s.key == "youtube_parse_metadata_rules"));
+        assert!(settings.iter().any(|s| s.key == "youtube_audio_language"));
+        assert!(settings.iter().any(|s| s.key == "youtube_all_audio_tracks"));
+        
+        // Every label is translated rather than falling back to its key
+        for setting in settings {
+            for language in [Language::English, Language::Chinese] {
+                let localized = setting.clone().localized(language);
+                assert_ne!(localized.label, localized.label_key);
+            }
+        }
     }
 
     #[test]
@@ -1391,6 +1935,7 @@ mod tests {
             quality: "best".to_string(),
             format: "mp4".to_string(),
             audio_only: false,
+            ..Default::default()
         };
         let format = provider.build_format_string(&options);
         assert!(format.contains("bestvideo"));
@@ -1404,6 +1949,7 @@ mod tests {
             quality: "1080p".to_string(),
             format: "mp4".to_string(),
             audio_only: false,
+            ..Default::default()
         };
         let format = provider.build_format_string(&options);
         assert!(format.contains("height<=1080"));
@@ -1416,11 +1962,117 @@ mod tests {
             quality: "best".to_string(),
             format: "mp3".to_string(),
             audio_only: true,
+            ..Default::default()
         };
         let format = provider.build_format_string(&options);
         assert_eq!(format, "bestaudio");
     }
 
+    #[test]
+    fn test_build_format_string_codec_preference() {
+        let provider = YouTubeProvider::new();
+        let mut options = DownloadOptions {
+            quality: "1080p".to_string(),
+            format: "mp4".to_string(),
+            ..Default::default()
+        };
+        options.platform_settings.insert("youtube_video_codec".to_string(), serde_json::json!("vp9"));
+        assert_eq!(
+            provider.build_format_string(&options),
+            "bestvideo[height<=1080][vcodec^=vp9]+bestaudio/bestvideo[height<=1080][ext=mp4]+bestaudio/best[height<=1080]/best"
+        );
+        
+        // The older AV1 toggle applies while the codec is left on auto
+        options.quality = "best".to_string();
+        options.platform_settings.insert("youtube_video_codec".to_string(), serde_json::json!("auto"));
+        options.platform_settings.insert("youtube_prefer_av1".to_string(), serde_json::json!(true));
+        assert_eq!(
+            provider.build_format_string(&options),
+            "bestvideo[vcodec^=av01]+bestaudio/bestvideo[ext=mp4]+bestaudio/best[ext=mp4]/best"
+        );
+    }
+
+    #[test]
+    fn test_build_format_string_audio_language() {
+        let provider = YouTubeProvider::new();
+        let mut options = DownloadOptions {
+            quality: "720p".to_string(),
+            format: "mp4".to_string(),
+            ..Default::default()
+        };
+        options.platform_settings.insert("youtube_audio_language".to_string(), serde_json::json!("ja"));
+        assert_eq!(
+            provider.build_format_string(&options),
+            "bestvideo[height<=720][ext=mp4]+bestaudio[language^=ja]/bestvideo[height<=720][ext=mp4]+bestaudio/best[height<=720]/best"
+        );
+        
+        options.platform_settings.insert("youtube_all_audio_tracks".to_string(), serde_json::json!(true));
+        assert!(provider.build_format_string(&options).starts_with("bestvideo[height<=720][ext=mp4]+mergeall[vcodec=none]/"));
+        
+        let audio = DownloadOptions { audio_only: true, format: "mp3".to_string(), ..options.clone() };
+        assert_eq!(provider.build_format_string(&audio), "bestaudio[language^=ja]/bestaudio");
+        
+        // Anything that isn't a language code is ignored rather than spliced into the selector
+        options.platform_settings.insert("youtube_all_audio_tracks".to_string(), serde_json::json!(false));
+        options.platform_settings.insert("youtube_audio_language".to_string(), serde_json::json!("ja]/worst"));
+        assert!(!provider.build_format_string(&options).contains("language"));
+    }
+
+    #[test]
+    fn test_build_format_string_hdr_and_fps() {
+        let provider = YouTubeProvider::new();
+        let mut options = DownloadOptions {
+            quality: "2160p".to_string(),
+            format: "mp4".to_string(),
+            ..Default::default()
+        };
+        options.platform_settings.insert("youtube_hdr".to_string(), serde_json::json!("avoid"));
+        options.platform_settings.insert("youtube_high_fps".to_string(), serde_json::json!("prefer"));
+        assert_eq!(
+            provider.build_format_string(&options),
+            "bestvideo[height<=2160][fps>30][dynamic_range=?SDR]+bestaudio/bestvideo[height<=2160][ext=mp4][dynamic_range=?SDR]+bestaudio/best[height<=2160][dynamic_range=?SDR]/best"
+        );
+        
+        options.platform_settings.insert("youtube_hdr".to_string(), serde_json::json!("prefer"));
+        options.platform_settings.insert("youtube_high_fps".to_string(), serde_json::json!("avoid"));
+        assert_eq!(
+            provider.build_format_string(&options),
+            "bestvideo[height<=2160][dynamic_range!=SDR][fps<=?30]+bestaudio/bestvideo[height<=2160][ext=mp4][fps<=?30]+bestaudio/best[height<=2160][fps<=?30]/best"
+        );
+    }
+
+    #[test]
+    fn test_build_format_string_uses_format_id() {
+        let provider = YouTubeProvider::new();
+        let options = DownloadOptions {
+            quality: "720p".to_string(),
+            format_id: Some("137+140".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(provider.build_format_string(&options), "137+140");
+    }
+
+    #[test]
+    fn test_audio_format_choices() {
+        let provider = YouTubeProvider::new();
+        let mut options = DownloadOptions {
+            format: "opus".to_string(),
+            audio_only: true,
+            audio_quality: Some("160K".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(provider.build_format_string(&options), "bestaudio[acodec=opus]/bestaudio");
+        assert_eq!(options.audio_quality_arg(), Some("160K"));
+        
+        options.format = "m4a".to_string();
+        assert_eq!(provider.build_format_string(&options), "bestaudio[ext=m4a]/bestaudio");
+        
+        // Lossless output has no bitrate to pick
+        options.format = "flac".to_string();
+        assert_eq!(provider.build_format_string(&options), "bestaudio");
+        assert_eq!(options.audio_quality_arg(), None);
+    }
+
     #[test]
     fn test_extract_percentage() {
         let provider = YouTubeProvider::new();
@@ -1508,6 +2160,39 @@ mod tests {
         assert_eq!(progress.eta, 42);
     }
 
+    #[test]
+    fn test_parse_live_progress_line() {
+        let provider = YouTubeProvider::new();
+        
+        let line = "[download]  25.50MiB at    2.00MiB/s (01:02:03) (frag 15/?)";
+        let progress = provider.parse_progress_line(line).unwrap();
+        assert_eq!(progress.elapsed, Some(3723));
+        assert_eq!(progress.downloaded_bytes, (25.5 * 1024.0 * 1024.0) as u64);
+        assert_eq!(progress.speed, 2.0 * 1024.0 * 1024.0);
+        assert_eq!(progress.percentage, 0.0);
+        
+        let short = provider.parse_progress_line("[download]   512.00KiB at  Unknown B/s (00:05)").unwrap();
+        assert_eq!(short.elapsed, Some(5));
+        
+        let vod = provider.parse_progress_line("[download]  45.8% of 123.45MiB at 1.23MiB/s ETA 00:42").unwrap();
+        assert_eq!(vod.elapsed, None);
+    }
+
+    #[test]
+    fn test_parse_video_info_detects_live() {
+        let provider = YouTubeProvider::new();
+        let live = serde_json::json!({"id": "abc", "title": "Stream", "live_status": "is_live"});
+        assert!(provider.parse_video_info(&live, "https://www.youtube.com/watch?v=abc").unwrap().is_live);
+        
+        let replay = serde_json::json!({"id": "abc", "title": "Stream", "live_status": "was_live", "duration": 3600});
+        assert!(!provider.parse_video_info(&replay, "https://www.youtube.com/watch?v=abc").unwrap().is_live);
+        
+        let premiere = serde_json::json!({"id": "abc", "title": "Premiere", "live_status": "is_upcoming", "release_timestamp": 1767225600});
+        let info = provider.parse_video_info(&premiere, "https://www.youtube.com/watch?v=abc").unwrap();
+        assert!(!info.is_live);
+        assert_eq!(info.premiere_at.as_deref(), Some("2026-01-01T00:00:00+00:00"));
+    }
+
     #[test]
     fn test_parse_progress_line_no_match() {
         let provider = YouTubeProvider::new();
@@ -1518,4 +2203,475 @@ mod tests {
         let line2 = "Some random output";
         assert!(provider.parse_progress_line(line2).is_none());
     }
+    
+    fn mock_provider(responses: Vec<MockResponse>) -> (YouTubeProvider, Arc<MockCommandRunner>, tempfile::NamedTempFile) {
+        // download_video_impl checks the ffmpeg path exists before running yt-dlp
+        let ffmpeg = tempfile::NamedTempFile::new().unwrap();
+        let runner = Arc::new(MockCommandRunner::with_responses(responses));
+        let provider = YouTubeProvider::with_executables(PathBuf::from("yt-dlp"), ffmpeg.path().to_path_buf())
+            .with_command_runner(runner.clone());
+        (provider, runner, ffmpeg)
+    }
+    
+    fn default_options() -> DownloadOptions {
+        DownloadOptions {
+            quality: "best".to_string(),
+            format: "mp4".to_string(),
+            audio_only: false,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_video_info_with_mock_runner() {
+        let json = r#"{"id":"dQw4w9WgXcQ","title":"Test Video","duration":212,"uploader":"Tester","view_count":42,"formats":[]}"#;
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success(json)]);
+        
+        let info = provider.get_video_info("https://www.youtube.com/watch?v=dQw4w9WgXcQ").await.unwrap();
+        assert_eq!(info.id, "dQw4w9WgXcQ");
+        assert_eq!(info.title, "Test Video");
+        assert_eq!(info.duration, 212);
+        
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 1);
+        assert!(calls[0].args.contains(&"--dump-json".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_get_video_info_maps_unavailable_error() {
+        let (provider, _runner, _ffmpeg) = mock_provider(vec![
+            MockResponse::failure(1, "ERROR: [youtube] abc: Video unavailable"),
+        ]);
+        
+        let result = provider.get_video_info("https://www.youtube.com/watch?v=abc").await;
+        assert!(matches!(result, Err(DownloadError::VideoUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_download_reports_progress_from_mock_output() {
+        let stdout = "[youtube] Extracting URL\n\
+                      [download]  25.0% of 10.00MiB at 1.00MiB/s ETA 00:07\n\
+                      [download]  75.0% of 10.00MiB at 1.00MiB/s ETA 00:02";
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success(stdout)]);
+        
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let sink = updates.clone();
+        provider
+            .download_video(
+                "https://www.youtube.com/watch?v=abc",
+                default_options(),
+                Path::new("/tmp/out.mp4"),
+                Box::new(move |p| sink.lock().unwrap().push(p.percentage)),
+            )
+            .await
+            .unwrap();
+        
+        assert_eq!(*updates.lock().unwrap(), vec![25.0, 75.0, 100.0]);
+        
+        let args = &runner.calls()[0].args;
+        assert!(args.contains(&"/tmp/out.mp4".to_string()));
+        assert_eq!(args.last().unwrap(), "https://www.youtube.com/watch?v=abc");
+    }
+
+    #[tokio::test]
+    async fn test_download_requests_selected_subtitles() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success("")]);
+        let options = DownloadOptions {
+            subtitle_languages: vec!["en".to_string(), "ja".to_string()],
+            ..default_options()
+        };
+        
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let args = &runner.calls()[0].args;
+        let pos = args.iter().position(|a| a == "--write-subs").expect("subtitles requested");
+        assert_eq!(args[pos + 1..pos + 3], ["--sub-langs".to_string(), "en,ja".to_string()]);
+        assert!(!args.contains(&"--write-auto-subs".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_download_all_subtitles_as_srt_without_auto_captions() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success("")]);
+        let options = DownloadOptions {
+            subtitle_languages: vec![ALL_SUBTITLES.to_string()],
+            auto_subtitles: true,
+            ..default_options()
+        };
+        
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let args = &runner.calls()[0].args;
+        assert!(args.windows(2).any(|w| w[0] == "--sub-langs" && w[1] == "all"));
+        assert!(args.windows(2).any(|w| w[0] == "--convert-subs" && w[1] == "srt"));
+        assert!(!args.contains(&"--write-auto-subs".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_download_falls_back_to_auto_captions_as_srt() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success("")]);
+        let options = DownloadOptions {
+            subtitle_languages: vec!["en".to_string()],
+            auto_subtitles: true,
+            ..default_options()
+        };
+        
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let args = &runner.calls()[0].args;
+        assert!(args.contains(&"--write-auto-subs".to_string()));
+        let pos = args.iter().position(|a| a == "--convert-subs").expect("conversion requested");
+        assert_eq!(args[pos + 1], "srt");
+    }
+
+    #[tokio::test]
+    async fn test_download_embeds_chapters_when_requested() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success(""), MockResponse::success("")]);
+        
+        for embed_chapters in [true, false] {
+            let options = DownloadOptions { embed_chapters, ..default_options() };
+            provider
+                .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+                .await
+                .unwrap();
+        }
+        
+        let calls = runner.calls();
+        assert!(calls[0].args.contains(&"--embed-chapters".to_string()));
+        assert!(!calls[1].args.contains(&"--embed-chapters".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_download_embeds_thumbnail_including_audio_only() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![
+            MockResponse::success(""),
+            MockResponse::success(""),
+            MockResponse::success(""),
+        ]);
+        
+        // Enabled by default, for audio-only as well
+        let audio = DownloadOptions { audio_only: true, format: "mp3".to_string(), ..default_options() };
+        let wav = DownloadOptions { audio_only: true, format: "wav".to_string(), ..default_options() };
+        let mut disabled = default_options();
+        disabled.platform_settings.insert("youtube_embed_thumbnail".to_string(), serde_json::json!(false));
+        
+        for options in [audio, wav, disabled] {
+            provider
+                .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out"), Box::new(|_| {}))
+                .await
+                .unwrap();
+        }
+        
+        let calls = runner.calls();
+        assert!(calls[0].args.contains(&"--embed-thumbnail".to_string()));
+        assert!(calls[0].args.contains(&"--convert-thumbnails".to_string()));
+        // WAV cannot carry cover art
+        assert!(!calls[1].args.contains(&"--embed-thumbnail".to_string()));
+        assert!(!calls[2].args.contains(&"--embed-thumbnail".to_string()));
+        assert!(!calls[0].args.contains(&"--write-thumbnail".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_download_writes_thumbnail_file_when_enabled() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success("")]);
+        
+        // WAV can't embed cover art, but the side file still works
+        let mut options = DownloadOptions { audio_only: true, format: "wav".to_string(), ..default_options() };
+        options.platform_settings.insert("youtube_write_thumbnail".to_string(), serde_json::json!(true));
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let args = &runner.calls()[0].args;
+        assert!(args.contains(&"--write-thumbnail".to_string()));
+        assert!(!args.contains(&"--embed-thumbnail".to_string()));
+        let pos = args.iter().position(|a| a == "--convert-thumbnails").expect("thumbnail conversion");
+        assert_eq!(args[pos + 1], "jpg");
+    }
+
+    #[tokio::test]
+    async fn test_download_honors_embed_metadata_setting() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success(""), MockResponse::success("")]);
+        
+        let mut disabled = default_options();
+        disabled.platform_settings.insert("youtube_embed_metadata".to_string(), serde_json::json!(false));
+        for options in [default_options(), disabled] {
+            provider
+                .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+                .await
+                .unwrap();
+        }
+        
+        let calls = runner.calls();
+        assert!(calls[0].args.contains(&"--embed-metadata".to_string()));
+        let pos = calls[0].args.iter().position(|a| a == "--parse-metadata").expect("metadata rule");
+        assert!(calls[0].args[pos + 1].contains("meta_comment"));
+        assert!(!calls[1].args.contains(&"--embed-metadata".to_string()));
+        assert!(!calls[1].args.contains(&"--parse-metadata".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_music_audio_gets_album_metadata() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success(""), MockResponse::success("")]);
+        
+        let mut options = default_options();
+        options.platform_settings.insert("youtube_embed_metadata".to_string(), serde_json::json!(false));
+        let audio = DownloadOptions { audio_only: true, format: "mp3".to_string(), ..options.clone() };
+        provider
+            .download_video("https://music.youtube.com/watch?v=abc", audio, Path::new("/tmp/out"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let calls = runner.calls();
+        assert!(calls[0].args.contains(&"--embed-metadata".to_string()));
+        assert!(calls[0].args.contains(&"--embed-thumbnail".to_string()));
+        assert!(calls[0].args.iter().any(|a| a.ends_with("%(meta_album)s")));
+        assert!(!calls[1].args.iter().any(|a| a.ends_with("%(meta_album)s")));
+    }
+
+    #[tokio::test]
+    async fn test_user_parse_rules_run_after_audio_tags() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success("")]);
+        
+        let mut options = DownloadOptions { audio_only: true, format: "mp3".to_string(), ..default_options() };
+        options.platform_settings.insert(
+            "youtube_parse_metadata_rules".to_string(),
+            serde_json::json!(["title:%(meta_artist)s - %(meta_title)s", " "]),
+        );
+        provider
+            .download_video("https://music.youtube.com/watch?v=abc", options.clone(), Path::new("/tmp/out"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let args = &runner.calls()[0].args;
+        let rules: Vec<&String> = args
+            .iter()
+            .zip(args.iter().skip(1))
+            .filter(|(flag, _)| *flag == "--parse-metadata")
+            .map(|(_, rule)| rule)
+            .collect();
+        assert_eq!(rules.last().unwrap().as_str(), "title:%(meta_artist)s - %(meta_title)s");
+        assert!(rules.len() > 1);
+        
+        options.platform_settings.insert("youtube_parse_metadata_rules".to_string(), serde_json::json!(["title"]));
+        let result = provider
+            .download_video("https://music.youtube.com/watch?v=abc", options, Path::new("/tmp/out"), Box::new(|_| {}))
+            .await;
+        assert!(matches!(result, Err(DownloadError::InvalidOption(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cookies_file_is_passed_when_present() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success(""), MockResponse::success("")]);
+        let cookies = tempfile::NamedTempFile::new().unwrap();
+        
+        provider.set_cookies_file(Some(cookies.path().to_path_buf()));
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", default_options(), Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        // A cookies file that disappeared is skipped
+        provider.set_cookies_file(Some(PathBuf::from("/nonexistent/cookies.txt")));
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", default_options(), Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let calls = runner.calls();
+        let pos = calls[0].args.iter().position(|a| a == "--cookies").expect("cookies passed");
+        // yt-dlp gets a private copy of the file, removed once it has finished
+        let copy = PathBuf::from(&calls[0].args[pos + 1]);
+        assert_ne!(copy, cookies.path());
+        assert!(copy.to_string_lossy().contains("vortex-cookies-"));
+        assert!(!copy.exists());
+        assert!(!calls[1].args.contains(&"--cookies".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_download_uses_proxy() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success("")]);
+        let options = DownloadOptions {
+            proxy: Some("http://127.0.0.1:8080".to_string()),
+            ..default_options()
+        };
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let args = &runner.calls()[0].args;
+        let pos = args.iter().position(|a| a == "--proxy").expect("proxy passed");
+        assert_eq!(args[pos + 1], "http://127.0.0.1:8080");
+    }
+
+    #[tokio::test]
+    async fn test_section_download_reports_progress_within_clip() {
+        let response = MockResponse {
+            stderr_lines: vec!["out_time_us=30000000".to_string(), "progress=continue".to_string()],
+            ..MockResponse::success("")
+        };
+        let (provider, runner, _ffmpeg) = mock_provider(vec![response]);
+        let options = DownloadOptions {
+            section_start: Some(600.0),
+            section_end: Some(720.0),
+            ..default_options()
+        };
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reported);
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out.mp4"), Box::new(move |p| {
+                sink.lock().unwrap().push(p.percentage);
+            }))
+            .await
+            .unwrap();
+        
+        let args = &runner.calls()[0].args;
+        let pos = args.iter().position(|a| a == "--download-sections").expect("sections passed");
+        assert_eq!(args[pos + 1], "*600-720");
+        // 30s into a 2 minute clip, then the final 100%
+        assert_eq!(*reported.lock().unwrap(), [25.0, 100.0]);
+    }
+
+    #[tokio::test]
+    async fn test_browser_cookies_apply_without_cookies_file() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success(""), MockResponse::success("")]);
+        let cookies = tempfile::NamedTempFile::new().unwrap();
+        
+        provider.set_cookies_browser(Some("firefox".to_string()));
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", default_options(), Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        // An explicit cookies file wins
+        provider.set_cookies_file(Some(cookies.path().to_path_buf()));
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", default_options(), Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let calls = runner.calls();
+        let pos = calls[0].args.iter().position(|a| a == "--cookies-from-browser").expect("browser cookies");
+        assert_eq!(calls[0].args[pos + 1], "firefox");
+        assert!(calls[1].args.contains(&"--cookies".to_string()));
+        assert!(!calls[1].args.contains(&"--cookies-from-browser".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_all_audio_tracks_are_merged_into_mkv() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success("")]);
+        let mut options = default_options();
+        options.platform_settings.insert("youtube_all_audio_tracks".to_string(), serde_json::json!(true));
+        
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let args = &runner.calls()[0].args;
+        assert!(args.contains(&"--audio-multistreams".to_string()));
+        assert!(args.windows(2).any(|w| w[0] == "--merge-output-format" && w[1] == "mkv"));
+        assert!(args.windows(2).any(|w| w[0] == "-o" && w[1] == "/tmp/out.mkv"));
+    }
+
+    #[tokio::test]
+    async fn test_po_token_is_passed_and_bot_check_is_reported() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![
+            MockResponse::failure(1, "ERROR: [youtube] abc: Sign in to confirm you're not a bot. Use --cookies-from-browser or --cookies"),
+        ]);
+        provider.set_po_token(PoTokenSettings {
+            po_token: Some("web.gvs+TOKEN".to_string()),
+            visitor_data: None,
+        });
+        
+        let result = provider
+            .download_video("https://www.youtube.com/watch?v=abc", default_options(), Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await;
+        assert!(matches!(result, Err(DownloadError::BotCheck(_))));
+        
+        let args = &runner.calls()[0].args;
+        let pos = args.iter().position(|a| a == "--extractor-args").expect("extractor args");
+        assert_eq!(args[pos + 1], "youtube:po_token=web.gvs+TOKEN");
+    }
+
+    #[tokio::test]
+    async fn test_download_honors_sponsorblock_settings() {
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success(""), MockResponse::success("")]);
+        
+        let mut options = default_options();
+        options.platform_settings.insert("youtube_skip_ads".to_string(), serde_json::json!(true));
+        options.platform_settings.insert(
+            "youtube_sponsorblock_categories".to_string(),
+            serde_json::json!("sponsor, outro,filler"),
+        );
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", options.clone(), Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        options.platform_settings.insert("youtube_skip_ads".to_string(), serde_json::json!(false));
+        provider
+            .download_video("https://www.youtube.com/watch?v=abc", options, Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await
+            .unwrap();
+        
+        let calls = runner.calls();
+        let pos = calls[0].args.iter().position(|a| a == "--sponsorblock-remove").expect("segments removed");
+        assert_eq!(calls[0].args[pos + 1], "sponsor,outro");
+        assert!(!calls[1].args.contains(&"--sponsorblock-remove".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_download_failure_exit_code() {
+        let (provider, _runner, _ffmpeg) = mock_provider(vec![
+            MockResponse::failure(1, "ERROR: unable to download video data: HTTP Error 403"),
+        ]);
+        
+        let result = provider
+            .download_video("https://www.youtube.com/watch?v=abc", default_options(), Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await;
+        
+        match result {
+            Err(DownloadError::DownloadFailed(msg)) => assert!(msg.contains("HTTP Error 403")),
+            other => panic!("expected DownloadFailed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_ytdlp_not_found() {
+        let (provider, _runner, _ffmpeg) = mock_provider(vec![MockResponse::not_found()]);
+        
+        let result = provider
+            .download_video("https://www.youtube.com/watch?v=abc", default_options(), Path::new("/tmp/out.mp4"), Box::new(|_| {}))
+            .await;
+        assert!(matches!(result, Err(DownloadError::YtdlpNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_channel_playlists_fetched_from_channel_root() {
+        let entry = r#"{"id": "abc", "title": "Clip", "channel": "Name"}"#;
+        let (provider, runner, _ffmpeg) = mock_provider(vec![MockResponse::success(entry), MockResponse::success(entry)]);
+        
+        provider.get_channel_info("https://www.youtube.com/@Name/shorts").await.unwrap();
+        
+        // Both fetches run at once, so the calls may come in either order
+        let urls: Vec<String> = runner.calls().iter().filter_map(|c| c.args.last().cloned()).collect();
+        assert_eq!(urls.len(), 2);
+        assert!(urls.contains(&"https://www.youtube.com/@Name/shorts".to_string()));
+        assert!(urls.contains(&"https://www.youtube.com/@Name/playlists".to_string()));
+    }
 }
@@ -0,0 +1,376 @@
+//! Shared plumbing for yt-dlp-backed providers
+//!
+//! A provider keeps its URL patterns and JSON parsing and composes a
+//! `YtDlpBackend` for the rest: running metadata queries under the provider's
+//! metadata limit, turning yt-dlp failures into `DownloadError`s, and streaming
+//! downloads with progress. New sites (Bilibili, Vimeo, Twitch, ...) only need
+//! the site-specific parts.
+
+use serde_json::Value;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use tokio_util::sync::CancellationToken;
+use super::command_runner::{CommandOutput, CommandRequest, CommandRunner, OutputLine, SystemCommandRunner};
+use super::http_headers::RequestHeaders;
+use super::metadata_limit::MetadataLimit;
+use super::provider::{DownloadOptions, DownloadProgress};
+use super::ytdlp_command::{parse_machine_progress, parse_section_progress, YtDlpCommandBuilder};
+use crate::error::{DownloadError, Result};
+use crate::log_info;
+
+/// yt-dlp executable, process runner and metadata limit of one provider
+pub struct YtDlpBackend {
+    /// Provider name, used to tag log lines
+    name: &'static str,
+    ytdlp_path: PathBuf,
+    runner: Arc<dyn CommandRunner>,
+    /// Caps concurrent metadata yt-dlp calls (the `metadata_concurrency` setting)
+    metadata_limit: MetadataLimit,
+    /// stderr fragments meaning the media doesn't exist or can't be accessed
+    unavailable_markers: &'static [&'static str],
+    /// Request settings for metadata queries (the `http_headers` setting)
+    network: MetadataNetwork,
+}
+
+/// Request settings a provider's metadata queries are sent with
+///
+/// Downloads take theirs from `DownloadOptions`; info, playlist and channel
+/// lookups have no options, so the settings are applied to the provider instead.
+#[derive(Debug, Default)]
+pub struct MetadataNetwork {
+    proxy: RwLock<Option<String>>,
+    headers: RwLock<RequestHeaders>,
+}
+
+impl MetadataNetwork {
+    pub fn set_proxy(&self, proxy: Option<&str>) {
+        if let Ok(mut current) = self.proxy.write() {
+            *current = proxy.map(str::to_string);
+        }
+    }
+    
+    pub fn set_headers(&self, headers: RequestHeaders) {
+        if let Ok(mut current) = self.headers.write() {
+            *current = headers;
+        }
+    }
+    
+    /// `command` with the current settings applied
+    pub fn apply(&self, mut command: YtDlpCommandBuilder) -> YtDlpCommandBuilder {
+        if let Ok(proxy) = self.proxy.read() {
+            if let Some(proxy) = proxy.as_deref() {
+                command = command.proxy(proxy);
+            }
+        }
+        match self.headers.read() {
+            Ok(headers) => command.request_headers(&headers),
+            Err(_) => command,
+        }
+    }
+}
+
+impl YtDlpBackend {
+    pub fn new(name: &'static str, ytdlp_path: PathBuf) -> Self {
+        Self {
+            name,
+            ytdlp_path,
+            runner: Arc::new(SystemCommandRunner::new()),
+            metadata_limit: MetadataLimit::default(),
+            unavailable_markers: &["Video unavailable", "HTTP Error 404"],
+            network: MetadataNetwork::default(),
+        }
+    }
+    
+    /// Replace the process runner (used by tests to replay canned output)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.runner = runner;
+        self
+    }
+    
+    /// stderr fragments that map a failed query to `DownloadError::VideoUnavailable`
+    pub fn unavailable_when(mut self, markers: &'static [&'static str]) -> Self {
+        self.unavailable_markers = markers;
+        self
+    }
+    
+    pub fn ytdlp_path(&self) -> &Path {
+        &self.ytdlp_path
+    }
+    
+    pub fn runner(&self) -> &Arc<dyn CommandRunner> {
+        &self.runner
+    }
+    
+    pub fn metadata_limit(&self) -> &MetadataLimit {
+        &self.metadata_limit
+    }
+    
+    pub fn metadata_network(&self) -> &MetadataNetwork {
+        &self.network
+    }
+    
+    /// Run a metadata query and return its stdout
+    ///
+    /// Sent with the provider's `MetadataNetwork` settings. Waits for a slot
+    /// under the metadata limit; a failed run is classified with `classify_failure`.
+    pub async fn run(&self, command: &YtDlpCommandBuilder) -> Result<String> {
+        let command = self.network.apply(command.clone());
+        let request = CommandRequest::new(&self.ytdlp_path, command.build());
+        let _permit = self.metadata_limit.acquire().await;
+        let output = self.runner.output(&request)
+            .await
+            .map_err(map_run_error)?;
+        
+        if !output.success() {
+            return Err(self.classify_failure(&output.stderr));
+        }
+        
+        Ok(output.stdout)
+    }
+    
+    /// Run a `--dump-json` style query and parse its output
+    pub async fn run_json(&self, command: &YtDlpCommandBuilder) -> Result<Value> {
+        let output = self.run(command).await?;
+        serde_json::from_str(&output)
+            .map_err(|e| DownloadError::DownloadFailed(format!("Failed to parse video info: {}", e)))
+    }
+    
+    /// Error for a failed yt-dlp run from its stderr
+    pub fn classify_failure(&self, stderr: &str) -> DownloadError {
+        if is_bot_check(stderr) {
+            DownloadError::BotCheck(stderr.to_string())
+        } else if stderr.contains("Unsupported URL") {
+            DownloadError::PlatformNotSupported(stderr.to_string())
+        } else if self.unavailable_markers.iter().any(|marker| stderr.contains(marker)) {
+            DownloadError::VideoUnavailable(stderr.to_string())
+        } else if stderr.contains("network") || stderr.contains("timeout") {
+            DownloadError::Network(stderr.to_string())
+        } else {
+            DownloadError::DownloadFailed(stderr.to_string())
+        }
+    }
+    
+    /// Request for a download: UTF-8 output, run in the download directory at the item's priority
+    pub fn download_request(&self, command: &YtDlpCommandBuilder, save_path: &Path, options: &DownloadOptions) -> CommandRequest {
+        CommandRequest::new(&self.ytdlp_path, command.build())
+            .env("PYTHONIOENCODING", "utf-8")
+            .confine_to(save_path)
+            .priority(options.priority)
+            .track_process(options.process.clone())
+    }
+    
+    /// Stream a download request, passing each progress update to `report`
+    ///
+    /// Expects `--progress-template` machine progress on stdout; section cuts
+    /// report ffmpeg's position on stderr instead. Other stderr lines are
+    /// logged. A non-zero exit comes back as `Ok` so the provider can map its
+    /// own failures first, then fall back to `exit_error`.
+    pub async fn stream(
+        &self,
+        request: &CommandRequest,
+        section_length: Option<f64>,
+        report: &(dyn Fn(DownloadProgress) + Sync),
+        cancel_token: Option<&CancellationToken>,
+    ) -> Result<CommandOutput> {
+        let mut on_line = |line: OutputLine| match line {
+            OutputLine::Stdout(line) => {
+                if let Some(progress) = parse_machine_progress(&line) {
+                    report(progress);
+                }
+            }
+            OutputLine::Stderr(line) => match parse_section_progress(&line, section_length) {
+                Some(progress) => report(progress),
+                None => log_info!("[{} yt-dlp stderr] {}", self.name, line),
+            },
+        };
+        
+        self.runner.stream(request, &mut on_line, cancel_token)
+            .await
+            .map_err(map_run_error)
+    }
+    
+    /// Download `command` to `save_path`, holding a transcode slot for the whole run
+    ///
+    /// Post-processing runs inside the yt-dlp process, so the slot covers it too.
+    /// A successful run always ends with a 100% update, even if yt-dlp never
+    /// printed one. As with `stream`, a non-zero exit is returned for the provider to map.
+    pub async fn download(
+        &self,
+        command: &YtDlpCommandBuilder,
+        save_path: &Path,
+        options: &DownloadOptions,
+        progress_callback: Box<dyn Fn(DownloadProgress) + Send>,
+        cancel_token: Option<&CancellationToken>,
+    ) -> Result<CommandOutput> {
+        log_info!("[{}] Executing command: {:?} {}", self.name, self.ytdlp_path, command.to_log_string());
+        let request = self.download_request(command, save_path, options);
+        
+        // The callback is shared with the line handler, which must be Send
+        let progress_callback = Mutex::new(progress_callback);
+        let completed = AtomicBool::new(false);
+        let report = |progress: DownloadProgress| {
+            if progress.percentage >= 100.0 {
+                completed.store(true, Ordering::Relaxed);
+            }
+            if let Ok(callback) = progress_callback.lock() {
+                callback(progress);
+            }
+        };
+        
+        let _transcode_slot = options.transcode_slot().await;
+        let output = self.stream(&request, options.section_length(), &report, cancel_token).await?;
+        if output.success() && !completed.load(Ordering::Relaxed) {
+            report(DownloadProgress {
+                percentage: 100.0,
+                downloaded_bytes: 0,
+                total_bytes: 0,
+                speed: 0.0,
+                eta: 0,
+                elapsed: None,
+                display: None,
+            });
+        }
+        Ok(output)
+    }
+}
+
+/// Map a runner error to a download error
+pub fn map_run_error(e: io::Error) -> DownloadError {
+    match e.kind() {
+        io::ErrorKind::NotFound => DownloadError::YtdlpNotFound,
+        io::ErrorKind::Interrupted => DownloadError::Cancelled,
+        _ => DownloadError::DownloadFailed(format!("Failed to run yt-dlp: {}", e)),
+    }
+}
+
+/// Whether yt-dlp failed because the site asked it to prove it isn't a bot
+pub fn is_bot_check(stderr: &str) -> bool {
+    stderr.contains("Sign in to confirm you") && stderr.contains("not a bot")
+}
+
+/// Error for a download that exited unsuccessfully
+pub fn exit_error(output: &CommandOutput) -> DownloadError {
+    if is_bot_check(&output.stderr) {
+        return DownloadError::BotCheck(output.stderr.trim().to_string());
+    }
+    DownloadError::DownloadFailed(format!(
+        "yt-dlp exited with code {:?}: {}",
+        output.exit_code,
+        output.stderr.trim()
+    ))
+}
+
+/// Proxy, HTTP headers, fragment concurrency, retries and speed cap from the download options
+pub fn network_options(mut command: YtDlpCommandBuilder, options: &DownloadOptions) -> YtDlpCommandBuilder {
+    if let Some(proxy) = &options.proxy {
+        command = command.proxy(proxy);
+    }
+    command = command.request_headers(&options.headers);
+    if let Some(fragments) = options.concurrent_fragments {
+        command = command.concurrent_fragments(fragments);
+    }
+    if let Some(retries) = options.retries {
+        command = command.retry_counts(retries);
+    }
+    if let Some(rate) = options.rate_limit {
+        command = command.rate_limit(rate);
+    }
+    command
+}
+
+/// Format selector capping the video height at the requested quality (`1080p`, `4k`, ...)
+///
+/// A chosen `format_id` wins; audio-only takes the best audio; unknown qualities take the best.
+pub fn height_capped_format(options: &DownloadOptions) -> String {
+    if let Some(format_id) = &options.format_id {
+        return format_id.clone();
+    }
+    if options.audio_only {
+        return "bestaudio/best".to_string();
+    }
+    
+    let height = match options.quality.as_str() {
+        "4k" => Ok(2160),
+        quality => quality.trim_end_matches('p').parse::<u32>(),
+    };
+    match height {
+        Ok(h) => format!("bestvideo[height<={h}]+bestaudio/best[height<={h}]/best", h = h),
+        Err(_) => "bestvideo+bestaudio/best".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::command_runner::{MockCommandRunner, MockResponse};
+    
+    #[tokio::test]
+    async fn test_run_classifies_failures() {
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![
+            MockResponse::failure(1, "ERROR: [example] abc: Video unavailable"),
+            MockResponse::failure(1, "ERROR: Unsupported URL: https://example.com/"),
+            MockResponse::failure(1, "ERROR: Removed by uploader"),
+            MockResponse::failure(1, "ERROR: [youtube] abc: Sign in to confirm you’re not a bot. Use --cookies-from-browser"),
+            MockResponse::success("{\"id\": \"abc\"}"),
+        ]));
+        let backend = YtDlpBackend::new("Example", PathBuf::from("yt-dlp"))
+            .unavailable_when(&["Video unavailable"])
+            .with_command_runner(runner.clone());
+        let command = YtDlpCommandBuilder::new().dump_json().url("https://example.com/abc");
+        
+        assert!(matches!(backend.run(&command).await, Err(DownloadError::VideoUnavailable(_))));
+        assert!(matches!(backend.run(&command).await, Err(DownloadError::PlatformNotSupported(_))));
+        assert!(matches!(backend.run(&command).await, Err(DownloadError::DownloadFailed(_))));
+        assert!(matches!(backend.run(&command).await, Err(DownloadError::BotCheck(_))));
+        assert_eq!(backend.run_json(&command).await.unwrap()["id"], "abc");
+        assert_eq!(runner.calls().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_run_sends_metadata_headers() {
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![MockResponse::success("{}")]));
+        let backend = YtDlpBackend::new("Example", PathBuf::from("yt-dlp")).with_command_runner(runner.clone());
+        backend.metadata_network().set_headers(RequestHeaders {
+            user_agent: None,
+            headers: [("Referer".to_string(), "https://example.com/".to_string())].into(),
+        });
+        
+        backend.run(&YtDlpCommandBuilder::new().dump_json().url("https://example.com/abc")).await.unwrap();
+        let args = &runner.calls()[0].args;
+        assert!(args.windows(2).any(|w| w[0] == "--add-header" && w[1] == "Referer:https://example.com/"));
+        assert_eq!(args.last().unwrap(), "https://example.com/abc");
+    }
+
+    #[tokio::test]
+    async fn test_run_sends_metadata_proxy() {
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![
+            MockResponse::success("{}"),
+            MockResponse::success("{}"),
+        ]));
+        let backend = YtDlpBackend::new("Example", PathBuf::from("yt-dlp")).with_command_runner(runner.clone());
+        let command = YtDlpCommandBuilder::new().dump_json().url("https://example.com/abc");
+        backend.metadata_network().set_proxy(Some("socks5://127.0.0.1:1080"));
+        backend.run(&command).await.unwrap();
+        backend.metadata_network().set_proxy(None);
+        backend.run(&command).await.unwrap();
+        
+        let calls = runner.calls();
+        assert!(calls[0].args.windows(2).any(|w| w[0] == "--proxy" && w[1] == "socks5://127.0.0.1:1080"));
+        assert!(!calls[1].args.contains(&"--proxy".to_string()));
+    }
+
+    #[test]
+    fn test_height_capped_format() {
+        let options = |quality: &str| DownloadOptions { quality: quality.to_string(), ..Default::default() };
+        assert_eq!(height_capped_format(&options("720p")), "bestvideo[height<=720]+bestaudio/best[height<=720]/best");
+        assert_eq!(height_capped_format(&options("4k")), "bestvideo[height<=2160]+bestaudio/best[height<=2160]/best");
+        assert_eq!(height_capped_format(&options("best")), "bestvideo+bestaudio/best");
+        
+        let audio = DownloadOptions { audio_only: true, ..options("720p") };
+        assert_eq!(height_capped_format(&audio), "bestaudio/best");
+        let chosen = DownloadOptions { format_id: Some("137+140".to_string()), ..audio };
+        assert_eq!(height_capped_format(&chosen), "137+140");
+    }
+}
@@ -0,0 +1,206 @@
+//! Low-resolution preview frames of a video before it is queued
+//!
+//! yt-dlp resolves the direct stream URL of a small video format and ffmpeg
+//! seeks into that stream for each requested timestamp, so only a few seconds
+//! around each frame are fetched instead of the whole video. Frames come back
+//! as JPEG data URLs the UI can show directly.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::error::{DownloadError, Result};
+use crate::platform::command_runner::{CommandRequest, CommandRunner, SystemCommandRunner};
+use crate::platform::ytdlp_backend::map_run_error;
+use crate::platform::ytdlp_command::YtDlpCommandBuilder;
+use crate::log_info;
+
+/// Most frames extracted per request
+pub const MAX_PREVIEW_FRAMES: usize = 8;
+
+/// Height of the extracted frames in pixels
+const FRAME_HEIGHT: u32 = 180;
+
+/// A small video-only stream is enough for frames and the quickest to seek
+const PREVIEW_FORMAT: &str = "bestvideo[height<=360]/best[height<=360]/worstvideo/worst";
+
+/// One extracted frame
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PreviewFrame {
+    /// Seconds into the video
+    pub timestamp: f64,
+    /// `data:image/jpeg;base64,...`
+    pub image: String,
+}
+
+/// Check the requested timestamps before any process is started
+pub fn validate_timestamps(timestamps: &[f64]) -> Result<()> {
+    if timestamps.is_empty() {
+        return Err(DownloadError::InvalidOption("At least one timestamp is required".to_string()));
+    }
+    if timestamps.len() > MAX_PREVIEW_FRAMES {
+        return Err(DownloadError::InvalidOption(format!(
+            "At most {} preview frames can be requested at once",
+            MAX_PREVIEW_FRAMES
+        )));
+    }
+    if timestamps.iter().any(|t| !t.is_finite() || *t < 0.0) {
+        return Err(DownloadError::InvalidOption("Timestamps must be non-negative seconds".to_string()));
+    }
+    Ok(())
+}
+
+/// Grabs preview frames from a video's stream with yt-dlp and ffmpeg
+pub struct PreviewService {
+    ytdlp_path: PathBuf,
+    ffmpeg_path: PathBuf,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl PreviewService {
+    pub fn new(ytdlp_path: PathBuf, ffmpeg_path: PathBuf) -> Self {
+        Self {
+            ytdlp_path,
+            ffmpeg_path,
+            runner: Arc::new(SystemCommandRunner),
+        }
+    }
+    
+    /// Replace the process runner (used by tests)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.runner = runner;
+        self
+    }
+    
+    /// Direct URL of the preview-sized video stream of `url`
+    pub async fn stream_url(&self, url: &str) -> Result<String> {
+        let command = YtDlpCommandBuilder::new()
+            .no_playlist()
+            .format(PREVIEW_FORMAT)
+            .print_field("urls")
+            .url(url);
+        let output = self
+            .runner
+            .output(&CommandRequest::new(&self.ytdlp_path, command.build()))
+            .await
+            .map_err(map_run_error)?;
+        
+        if !output.success() {
+            return Err(DownloadError::DownloadFailed(format!(
+                "yt-dlp could not resolve a stream for {}: {}",
+                url,
+                output.stderr.trim()
+            )));
+        }
+        
+        output
+            .stdout
+            .lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("http"))
+            .map(str::to_string)
+            .ok_or_else(|| DownloadError::DownloadFailed(format!("yt-dlp returned no stream URL for {}", url)))
+    }
+    
+    /// Frames at each of `timestamps`, in order
+    ///
+    /// Timestamps ffmpeg can't decode a frame at (e.g. past the end of the
+    /// video) are left out rather than failing the whole request.
+    pub async fn frames(&self, url: &str, timestamps: &[f64]) -> Result<Vec<PreviewFrame>> {
+        validate_timestamps(timestamps)?;
+        let stream = self.stream_url(url).await?;
+        
+        let dir = std::env::temp_dir().join(format!("vortex-preview-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir)?;
+        let frames = self.extract(&stream, timestamps, &dir).await;
+        let _ = std::fs::remove_dir_all(&dir);
+        frames
+    }
+    
+    async fn extract(&self, stream: &str, timestamps: &[f64], dir: &Path) -> Result<Vec<PreviewFrame>> {
+        let mut frames = Vec::new();
+        for (index, &timestamp) in timestamps.iter().enumerate() {
+            let path = dir.join(format!("frame-{}.jpg", index));
+            // -ss before -i seeks the stream by keyframe, then decodes up to the exact time
+            let args = vec![
+                "-hide_banner".to_string(),
+                "-loglevel".to_string(),
+                "error".to_string(),
+                "-ss".to_string(),
+                format!("{:.3}", timestamp),
+                "-i".to_string(),
+                stream.to_string(),
+                "-frames:v".to_string(),
+                "1".to_string(),
+                "-vf".to_string(),
+                format!("scale=-2:{}", FRAME_HEIGHT),
+                "-q:v".to_string(),
+                "5".to_string(),
+                "-y".to_string(),
+                path.to_string_lossy().to_string(),
+            ];
+            
+            let output = self
+                .runner
+                .output(&CommandRequest::new(&self.ffmpeg_path, args))
+                .await
+                .map_err(|e| match e.kind() {
+                    std::io::ErrorKind::NotFound => DownloadError::DependencyMissing("ffmpeg".to_string()),
+                    _ => DownloadError::Io(e),
+                })?;
+            
+            match std::fs::read(&path) {
+                Ok(bytes) if output.success() && !bytes.is_empty() => frames.push(PreviewFrame {
+                    timestamp,
+                    image: format!("data:image/jpeg;base64,{}", base64::engine::general_purpose::STANDARD.encode(bytes)),
+                }),
+                _ => log_info!(
+                    "[Preview] No frame at {:.3}s: {}",
+                    timestamp,
+                    output.stderr.lines().last().unwrap_or("no output")
+                ),
+            }
+        }
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::command_runner::{MockCommandRunner, MockResponse};
+    
+    #[test]
+    fn test_validate_timestamps() {
+        assert!(validate_timestamps(&[0.0, 12.5]).is_ok());
+        assert!(validate_timestamps(&[]).is_err());
+        assert!(validate_timestamps(&[-1.0]).is_err());
+        assert!(validate_timestamps(&[f64::NAN]).is_err());
+        assert!(validate_timestamps(&[1.0; MAX_PREVIEW_FRAMES + 1]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_frames_skip_timestamps_without_a_frame() {
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![
+            MockResponse::success("https://rr1.example.com/videoplayback?itag=134\n"),
+            MockResponse::success(""),
+            MockResponse::failure(1, "Output file is empty, nothing was encoded"),
+        ]));
+        let service = PreviewService::new(PathBuf::from("yt-dlp"), PathBuf::from("ffmpeg"))
+            .with_command_runner(runner.clone());
+        
+        let stream = service.stream_url("https://www.youtube.com/watch?v=abc").await.unwrap();
+        assert_eq!(stream, "https://rr1.example.com/videoplayback?itag=134");
+        
+        // The mock runner writes nothing, so stand in for ffmpeg's first frame
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("frame-0.jpg"), [0xff, 0xd8, 0xff]).unwrap();
+        let frames = service.extract(&stream, &[5.0, 9999.0], dir.path()).await.unwrap();
+        
+        assert_eq!(frames, vec![PreviewFrame { timestamp: 5.0, image: "data:image/jpeg;base64,/9j/".to_string() }]);
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 3);
+        assert!(calls[0].args.contains(&"urls".to_string()));
+        assert!(calls[1].args.windows(2).any(|w| w[0] == "-ss" && w[1] == "5.000"));
+    }
+}
@@ -0,0 +1,221 @@
+//! Completion and failure sounds
+//!
+//! `SoundEventSink` listens to download events and plays a short sound when a
+//! download completes or fails, so long sessions don't depend on a
+//! notification banner that's easy to miss. Sounds are played with the
+//! platform's command-line player (`afplay`, `paplay`, PowerShell).
+
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+use crate::error::{DownloadError, Result};
+use crate::events::EventSink;
+use crate::log_warn;
+
+/// Daily period without sounds, e.g. `22:00` to `07:00`; may wrap past midnight
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct QuietHours {
+    /// Local `HH:MM`
+    pub start: String,
+    /// Local `HH:MM`
+    pub end: String,
+}
+
+impl QuietHours {
+    fn bounds(&self) -> Result<(NaiveTime, NaiveTime)> {
+        let parse = |time: &str| {
+            NaiveTime::parse_from_str(time, "%H:%M")
+                .map_err(|_| DownloadError::InvalidOption(format!("Invalid quiet hours time '{}': use HH:MM", time)))
+        };
+        Ok((parse(&self.start)?, parse(&self.end)?))
+    }
+    
+    pub fn validate(&self) -> Result<()> {
+        self.bounds().map(|_| ())
+    }
+    
+    /// Whether `time` falls within the quiet period
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        match self.bounds() {
+            Ok((start, end)) if start <= end => start <= time && time < end,
+            Ok((start, end)) => time >= start || time < end,
+            Err(_) => false,
+        }
+    }
+}
+
+/// Sound settings; custom sounds are paths to audio files the system player can handle
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SoundSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Played when a download completes; the system's default sound when unset
+    #[serde(default)]
+    pub completion_sound: Option<String>,
+    /// Played when a download fails; the system's default sound when unset
+    #[serde(default)]
+    pub failure_sound: Option<String>,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl SoundSettings {
+    pub fn validate(&self) -> Result<()> {
+        match &self.quiet_hours {
+            Some(quiet_hours) => quiet_hours.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cue {
+    Completion,
+    Failure,
+}
+
+#[cfg(target_os = "macos")]
+fn default_sound(cue: Cue) -> &'static str {
+    match cue {
+        Cue::Completion => "/System/Library/Sounds/Glass.aiff",
+        Cue::Failure => "/System/Library/Sounds/Basso.aiff",
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn default_sound(cue: Cue) -> &'static str {
+    match cue {
+        Cue::Completion => r"C:\Windows\Media\tada.wav",
+        Cue::Failure => r"C:\Windows\Media\Windows Critical Stop.wav",
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn default_sound(cue: Cue) -> &'static str {
+    match cue {
+        Cue::Completion => "/usr/share/sounds/freedesktop/stereo/complete.oga",
+        Cue::Failure => "/usr/share/sounds/freedesktop/stereo/dialog-error.oga",
+    }
+}
+
+/// Start playing `path` without waiting for it to finish
+fn play(path: PathBuf) {
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = std::process::Command::new("afplay");
+        command.arg(&path);
+        command
+    };
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("powershell");
+        command.args(["-NoProfile", "-Command"]).arg(format!(
+            "(New-Object Media.SoundPlayer '{}').PlaySync()",
+            path.display().to_string().replace('\'', "''")
+        ));
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = {
+        let mut command = std::process::Command::new("paplay");
+        command.arg(&path);
+        command
+    };
+    
+    match command.spawn() {
+        // Reap the player once it's done
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => log_warn!("[Sounds] Failed to play {}: {}", path.display(), e),
+    }
+}
+
+/// Plays the configured sounds for `download:complete` and `download:error` events
+#[derive(Default)]
+pub struct SoundEventSink {
+    settings: RwLock<SoundSettings>,
+}
+
+impl SoundEventSink {
+    pub fn new(settings: SoundSettings) -> Self {
+        Self { settings: RwLock::new(settings) }
+    }
+    
+    /// Apply the `sounds` setting
+    pub fn set_settings(&self, settings: SoundSettings) {
+        if let Ok(mut current) = self.settings.write() {
+            *current = settings;
+        }
+    }
+    
+    /// Sound to play for `event` at local time `now`, if any
+    fn sound_for(&self, event: &str, now: NaiveTime) -> Option<PathBuf> {
+        let cue = match event {
+            "download:complete" => Cue::Completion,
+            "download:error" => Cue::Failure,
+            _ => return None,
+        };
+        let settings = self.settings.read().ok()?;
+        if !settings.enabled || settings.quiet_hours.as_ref().is_some_and(|q| q.contains(now)) {
+            return None;
+        }
+        
+        let custom = match cue {
+            Cue::Completion => settings.completion_sound.as_deref(),
+            Cue::Failure => settings.failure_sound.as_deref(),
+        };
+        let sound = custom.filter(|s| !s.is_empty()).unwrap_or(default_sound(cue));
+        Some(PathBuf::from(sound))
+    }
+}
+
+impl EventSink for SoundEventSink {
+    fn emit(&self, event: &str, _payload: serde_json::Value) {
+        if let Some(sound) = self.sound_for(event, Local::now().time()) {
+            play(sound);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn at(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_past_midnight() {
+        let night = QuietHours { start: "22:00".to_string(), end: "07:00".to_string() };
+        assert!(night.contains(at("23:30")));
+        assert!(night.contains(at("06:59")));
+        assert!(!night.contains(at("07:00")));
+        assert!(!night.contains(at("12:00")));
+        
+        let lunch = QuietHours { start: "12:00".to_string(), end: "13:00".to_string() };
+        assert!(lunch.contains(at("12:30")));
+        assert!(!lunch.contains(at("13:30")));
+        
+        assert!(QuietHours { start: "25:00".to_string(), end: "07:00".to_string() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_sound_selection() {
+        let sink = SoundEventSink::default();
+        assert_eq!(sink.sound_for("download:complete", at("12:00")), None);
+        
+        sink.set_settings(SoundSettings {
+            enabled: true,
+            failure_sound: Some("/sounds/oops.wav".to_string()),
+            quiet_hours: Some(QuietHours { start: "22:00".to_string(), end: "07:00".to_string() }),
+            ..Default::default()
+        });
+        assert_eq!(sink.sound_for("download:error", at("12:00")), Some(PathBuf::from("/sounds/oops.wav")));
+        assert_eq!(sink.sound_for("download:complete", at("12:00")), Some(PathBuf::from(default_sound(Cue::Completion))));
+        assert_eq!(sink.sound_for("download:complete", at("23:00")), None);
+        assert_eq!(sink.sound_for("download:progress", at("12:00")), None);
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use super::shared_files::PrivateCopy;
+use crate::error::{DownloadError, Result};
+
+/// File name of the app-maintained yt-dlp download archive, in the app data directory
+pub const ARCHIVE_FILE_NAME: &str = "download_archive.txt";
+
+/// Held while the app reads or writes the shared archive; yt-dlp only sees private copies
+static ARCHIVE_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_archive() -> MutexGuard<'static, ()> {
+    ARCHIVE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Copy of the archive at `path` for one yt-dlp process; merge it back with `merge_archive_copy`
+pub fn private_archive_copy(path: &Path) -> Result<PrivateCopy> {
+    let _lock = lock_archive();
+    PrivateCopy::create(path, "archive")
+}
+
+/// Append entries recorded in `copy` that the archive at `path` doesn't have yet,
+/// returning how many were added
+pub fn merge_archive_copy(copy: &PrivateCopy, path: &Path) -> Result<usize> {
+    let _lock = lock_archive();
+    let existing = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let known: HashSet<&str> = existing.lines().map(str::trim).collect();
+    
+    let recorded = std::fs::read_to_string(copy.path())?;
+    let new: Vec<&str> = recorded.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !known.contains(l))
+        .collect();
+    if new.is_empty() {
+        return Ok(0);
+    }
+    
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        file.write_all(b"\n")?;
+    }
+    for line in &new {
+        writeln!(file, "{}", line)?;
+    }
+    Ok(new.len())
+}
+
+/// Number of videos recorded in the archive at `path`; a missing archive is empty
+///
+/// Each line is `<extractor> <video id>`, as written by `--download-archive`.
+pub fn archive_entry_count(path: &Path) -> Result<usize> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(contents.lines().filter(|l| !l.trim().is_empty()).count()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Forget every recorded video, returning how many entries were removed
+pub fn reset_archive(path: &Path) -> Result<usize> {
+    let _lock = lock_archive();
+    let removed = archive_entry_count(path)?;
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(removed)
+}
+
+/// Copy the archive to `destination`, returning the number of entries exported
+pub fn export_archive(path: &Path, destination: &Path) -> Result<usize> {
+    let _lock = lock_archive();
+    if !path.exists() {
+        return Err(DownloadError::InvalidOption("The download archive is empty".to_string()));
+    }
+    std::fs::copy(path, destination).map_err(|e| {
+        DownloadError::PermissionDenied(format!("Cannot write {}: {}", destination.display(), e))
+    })?;
+    archive_entry_count(destination)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_reset_and_export_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join(ARCHIVE_FILE_NAME);
+        assert_eq!(archive_entry_count(&archive).unwrap(), 0);
+        assert!(export_archive(&archive, &dir.path().join("out.txt")).is_err());
+        
+        std::fs::write(&archive, "youtube dQw4w9WgXcQ\nyoutube jNQXAC9IVRw\n\n").unwrap();
+        assert_eq!(export_archive(&archive, &dir.path().join("out.txt")).unwrap(), 2);
+        
+        assert_eq!(reset_archive(&archive).unwrap(), 2);
+        assert!(!archive.exists());
+        assert_eq!(reset_archive(&archive).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_concurrent_copies_merge_without_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join(ARCHIVE_FILE_NAME);
+        std::fs::write(&archive, "youtube aaa").unwrap();
+        
+        let first = private_archive_copy(&archive).unwrap();
+        let second = private_archive_copy(&archive).unwrap();
+        std::fs::write(first.path(), "youtube aaa\nyoutube bbb\n").unwrap();
+        std::fs::write(second.path(), "youtube aaa\nyoutube bbb\nyoutube ccc\n").unwrap();
+        
+        assert_eq!(merge_archive_copy(&first, &archive).unwrap(), 1);
+        assert_eq!(merge_archive_copy(&second, &archive).unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(&archive).unwrap(), "youtube aaa\nyoutube bbb\nyoutube ccc\n");
+    }
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use crate::error::{DownloadError, Result};
+
+/// Data written by a default benchmark run
+pub const DEFAULT_BENCHMARK_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Write size per call, similar to what yt-dlp and ffmpeg issue
+const CHUNK_BYTES: usize = 1024 * 1024;
+
+/// Below this sustained rate downloads are likely to be limited by the destination
+pub const SLOW_DESTINATION_BYTES_PER_SECOND: f64 = 20.0 * 1024.0 * 1024.0;
+
+/// Sequential write throughput measured at a download destination
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WriteBenchmark {
+    pub path: String,
+    pub bytes_written: u64,
+    pub seconds: f64,
+    pub bytes_per_second: f64,
+    /// Slow enough (e.g. a NAS over Wi-Fi) that downloading to a local folder first would help
+    pub recommend_staging: bool,
+}
+
+/// Measure sequential write speed in `dir` by writing and removing a temporary file
+///
+/// The file is flushed to the device before the clock stops, so the result is
+/// not inflated by the page cache.
+pub fn benchmark_destination(dir: &Path, bytes: u64) -> Result<WriteBenchmark> {
+    if !dir.is_dir() {
+        return Err(DownloadError::InvalidOption(format!("Not a directory: {}", dir.display())));
+    }
+    
+    let file_path: PathBuf = dir.join(format!(".vortex-benchmark-{}.tmp", std::process::id()));
+    let result = write_test_file(&file_path, bytes);
+    let _ = std::fs::remove_file(&file_path);
+    let seconds = result?;
+    
+    let bytes_per_second = bytes as f64 / seconds.max(f64::EPSILON);
+    Ok(WriteBenchmark {
+        path: dir.to_string_lossy().to_string(),
+        bytes_written: bytes,
+        seconds,
+        bytes_per_second,
+        recommend_staging: bytes_per_second < SLOW_DESTINATION_BYTES_PER_SECOND,
+    })
+}
+
+/// Write `bytes` of incompressible data to `path`, returning the elapsed seconds
+fn write_test_file(path: &Path, bytes: u64) -> Result<f64> {
+    // Pseudo-random so compressing or deduplicating filesystems can't shortcut it
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let chunk: Vec<u8> = (0..CHUNK_BYTES)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect();
+    
+    let started = Instant::now();
+    let mut file = std::fs::File::create(path).map_err(|e| {
+        DownloadError::PermissionDenied(format!("Cannot write to {}: {}", path.display(), e))
+    })?;
+    
+    let mut remaining = bytes;
+    while remaining > 0 {
+        let len = remaining.min(CHUNK_BYTES as u64) as usize;
+        file.write_all(&chunk[..len])?;
+        remaining -= len as u64;
+    }
+    file.sync_all()?;
+    
+    Ok(started.elapsed().as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_benchmark_writes_and_cleans_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = benchmark_destination(dir.path(), 4 * 1024 * 1024 + 10).unwrap();
+        
+        assert_eq!(result.bytes_written, 4 * 1024 * 1024 + 10);
+        assert!(result.bytes_per_second > 0.0);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_benchmark_rejects_missing_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(benchmark_destination(&dir.path().join("missing"), 1024).is_err());
+    }
+}
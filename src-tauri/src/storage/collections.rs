@@ -0,0 +1,227 @@
+//! Starred downloads and smart collections over download history
+//!
+//! A smart collection is a saved filter ("audio-only from channel X this
+//! year"), not a list of downloads: its contents are recomputed from history
+//! each time it is opened, so new downloads show up without being added.
+
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use super::settings::CompletedDownload;
+use crate::error::{DownloadError, Result};
+
+/// Calendar period a download was completed in, relative to today
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Period {
+    Today,
+    /// Since Monday
+    ThisWeek,
+    ThisMonth,
+    ThisYear,
+}
+
+impl Period {
+    /// Local midnight the period starts at
+    fn start(self, now: DateTime<Local>) -> DateTime<Local> {
+        let today = now.date_naive();
+        let first_day = match self {
+            Period::Today => today,
+            Period::ThisWeek => today - Duration::days(today.weekday().num_days_from_monday() as i64),
+            Period::ThisMonth => today.with_day(1).unwrap_or(today),
+            Period::ThisYear => today.with_ordinal(1).unwrap_or(today),
+        };
+        let midnight = first_day.and_hms_opt(0, 0, 0).unwrap_or_default();
+        Local.from_local_datetime(&midnight).earliest().unwrap_or(now)
+    }
+}
+
+/// Conditions a download must meet to be in a collection; unset ones match everything
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct CollectionFilter {
+    /// Only starred downloads
+    #[serde(default)]
+    pub starred: bool,
+    /// Platform name, e.g. `YouTube`
+    #[serde(default)]
+    pub platform: Option<String>,
+    /// Channel or account, compared case-insensitively
+    #[serde(default)]
+    pub uploader: Option<String>,
+    /// Only audio-only (`true`) or only video (`false`) downloads
+    #[serde(default)]
+    pub audio_only: Option<bool>,
+    #[serde(default)]
+    pub completed_in: Option<Period>,
+    /// Text the title must contain, case-insensitively
+    #[serde(default)]
+    pub title_contains: Option<String>,
+}
+
+impl CollectionFilter {
+    /// Whether `download` belongs in the collection at `now`
+    pub fn matches(&self, download: &CompletedDownload, now: DateTime<Local>) -> bool {
+        if self.starred && !download.starred {
+            return false;
+        }
+        if self.platform.as_ref().is_some_and(|p| !p.eq_ignore_ascii_case(&download.platform)) {
+            return false;
+        }
+        if let Some(uploader) = &self.uploader {
+            if !download.uploader.as_ref().is_some_and(|u| u.eq_ignore_ascii_case(uploader)) {
+                return false;
+            }
+        }
+        if self.audio_only.is_some_and(|audio_only| audio_only != download.audio_only) {
+            return false;
+        }
+        if let Some(period) = self.completed_in {
+            let completed = DateTime::parse_from_rfc3339(&download.completed_at).map(|t| t.with_timezone(&Local));
+            if !completed.is_ok_and(|t| t >= period.start(now)) {
+                return false;
+            }
+        }
+        if let Some(text) = &self.title_contains {
+            if !download.title.to_lowercase().contains(&text.to_lowercase()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A named, saved filter over download history
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SmartCollection {
+    /// Assigned when the collection is first saved
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub filter: CollectionFilter,
+}
+
+impl SmartCollection {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(DownloadError::InvalidOption("Collection name cannot be empty".to_string()));
+        }
+        Ok(())
+    }
+    
+    /// Downloads in the collection, most recent first
+    pub fn select(&self, downloads: &[CompletedDownload], now: DateTime<Local>) -> Vec<CompletedDownload> {
+        let mut selected: Vec<CompletedDownload> = downloads
+            .iter()
+            .filter(|d| self.filter.matches(d, now))
+            .cloned()
+            .collect();
+        selected.sort_by(|a, b| b.completed_at.cmp(&a.completed_at));
+        selected
+    }
+}
+
+/// Saved smart collections, in the order they were created
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Collections {
+    pub collections: Vec<SmartCollection>,
+}
+
+impl Collections {
+    /// Add `collection`, or replace the one with its ID; a new one gets an ID
+    pub fn upsert(&mut self, mut collection: SmartCollection) -> SmartCollection {
+        match self.collections.iter_mut().find(|c| !collection.id.is_empty() && c.id == collection.id) {
+            Some(existing) => *existing = collection.clone(),
+            None => {
+                if collection.id.is_empty() {
+                    collection.id = uuid::Uuid::new_v4().to_string();
+                }
+                self.collections.push(collection.clone());
+            }
+        }
+        collection
+    }
+    
+    pub fn get(&self, id: &str) -> Option<&SmartCollection> {
+        self.collections.iter().find(|c| c.id == id)
+    }
+    
+    /// Remove a collection, returning whether it existed
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.collections.len();
+        self.collections.retain(|c| c.id != id);
+        self.collections.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn download(title: &str, uploader: &str, audio_only: bool, completed_at: DateTime<Local>) -> CompletedDownload {
+        CompletedDownload {
+            id: title.to_string(),
+            video_id: title.to_string(),
+            title: title.to_string(),
+            completed_at: completed_at.to_rfc3339(),
+            save_path: format!("/music/{}.m4a", title),
+            file_size: 1,
+            platform: "YouTube".to_string(),
+            tombstoned_at: None,
+            review: None,
+            library_path: None,
+            uploader: Some(uploader.to_string()),
+            audio_only,
+            starred: false,
+            subtitles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_smart_collection_selects_matching_downloads() {
+        let now = Local.with_ymd_and_hms(2026, 3, 12, 15, 0, 0).unwrap();
+        let mut starred = download("Live at Home", "Band", true, Local.with_ymd_and_hms(2026, 1, 1, 0, 30, 0).unwrap());
+        starred.starred = true;
+        let history = vec![
+            download("Single", "band", true, Local.with_ymd_and_hms(2026, 3, 10, 9, 0, 0).unwrap()),
+            download("Music Video", "Band", false, Local.with_ymd_and_hms(2026, 3, 11, 9, 0, 0).unwrap()),
+            download("Old Album", "Band", true, Local.with_ymd_and_hms(2025, 12, 31, 23, 0, 0).unwrap()),
+            download("Podcast", "Someone Else", true, Local.with_ymd_and_hms(2026, 2, 1, 9, 0, 0).unwrap()),
+            starred,
+        ];
+        
+        let collection = SmartCollection {
+            id: "1".to_string(),
+            name: "Band audio this year".to_string(),
+            filter: CollectionFilter {
+                uploader: Some("BAND".to_string()),
+                audio_only: Some(true),
+                completed_in: Some(Period::ThisYear),
+                ..Default::default()
+            },
+        };
+        let titles: Vec<String> = collection.select(&history, now).into_iter().map(|d| d.title).collect();
+        assert_eq!(titles, vec!["Single", "Live at Home"]);
+        
+        let starred = CollectionFilter { starred: true, ..Default::default() };
+        assert_eq!(history.iter().filter(|d| starred.matches(d, now)).count(), 1);
+        let this_week = CollectionFilter { completed_in: Some(Period::ThisWeek), ..Default::default() };
+        assert_eq!(history.iter().filter(|d| this_week.matches(d, now)).count(), 2);
+    }
+
+    #[test]
+    fn test_upsert_assigns_ids_and_replaces_existing() {
+        let mut collections = Collections::default();
+        let saved = collections.upsert(SmartCollection {
+            id: String::new(),
+            name: "Starred".to_string(),
+            filter: CollectionFilter { starred: true, ..Default::default() },
+        });
+        assert!(!saved.id.is_empty());
+        
+        collections.upsert(SmartCollection { name: "Favorites".to_string(), ..saved.clone() });
+        assert_eq!(collections.collections.len(), 1);
+        assert_eq!(collections.get(&saved.id).unwrap().name, "Favorites");
+        assert!(collections.remove(&saved.id));
+        assert!(!collections.remove(&saved.id));
+    }
+}
@@ -0,0 +1,180 @@
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use crate::error::{DownloadError, Result};
+
+/// Failures kept in the log; older ones are dropped first
+const MAX_ERROR_RECORDS: usize = 5000;
+
+/// One failed download
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorRecord {
+    /// Local time with offset, so the hour of day is the user's
+    pub at: String,
+    /// `HTTP 403` for HTTP failures, otherwise the error type (`NetworkError`, ...)
+    pub code: String,
+    pub platform: String,
+}
+
+impl ErrorRecord {
+    pub fn new(error: &DownloadError, platform: &str) -> Self {
+        Self {
+            at: chrono::Local::now().to_rfc3339(),
+            code: error_code(error),
+            platform: platform.to_string(),
+        }
+    }
+}
+
+/// Short code grouping similar failures, preferring the HTTP status when the message has one
+pub fn error_code(error: &DownloadError) -> String {
+    let message = error.to_string();
+    if let Some(pos) = message.find("HTTP Error ") {
+        let status: String = message[pos + "HTTP Error ".len()..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        if !status.is_empty() {
+            return format!("HTTP {}", status);
+        }
+    }
+    format!("{:?}", error.error_type())
+}
+
+/// Failure counts by code, platform and local hour of day
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorStats {
+    pub total: usize,
+    /// Start of the period covered, when limited
+    pub since: Option<String>,
+    pub by_code: BTreeMap<String, usize>,
+    pub by_platform: BTreeMap<String, usize>,
+    /// Failures per hour of day, index 0-23
+    pub by_hour: Vec<usize>,
+    /// Per-code hourly counts, for patterns like "403s spike every evening"
+    pub by_code_and_hour: BTreeMap<String, Vec<usize>>,
+}
+
+/// Persistent log of download failures
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ErrorLog {
+    pub records: Vec<ErrorRecord>,
+}
+
+impl ErrorLog {
+    /// Load the log from `path`; a missing or unreadable file starts a new log
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+    
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+    
+    pub fn record(&mut self, record: ErrorRecord) {
+        self.records.push(record);
+        if self.records.len() > MAX_ERROR_RECORDS {
+            self.records.drain(..self.records.len() - MAX_ERROR_RECORDS);
+        }
+    }
+    
+    /// Aggregate failures from the last `days` days, or all of them
+    pub fn stats(&self, days: Option<u32>) -> ErrorStats {
+        let since = days.map(|d| Utc::now() - Duration::days(d as i64));
+        let mut stats = ErrorStats {
+            since: since.map(|s| s.to_rfc3339()),
+            by_hour: vec![0; 24],
+            ..Default::default()
+        };
+        
+        for record in &self.records {
+            let Ok(at) = DateTime::parse_from_rfc3339(&record.at) else {
+                continue;
+            };
+            if since.is_some_and(|since| at < since) {
+                continue;
+            }
+            
+            let hour = at.hour() as usize;
+            stats.total += 1;
+            *stats.by_code.entry(record.code.clone()).or_default() += 1;
+            *stats.by_platform.entry(record.platform.clone()).or_default() += 1;
+            stats.by_hour[hour] += 1;
+            stats.by_code_and_hour.entry(record.code.clone()).or_insert_with(|| vec![0; 24])[hour] += 1;
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn record(at: &str, code: &str, platform: &str) -> ErrorRecord {
+        ErrorRecord {
+            at: at.to_string(),
+            code: code.to_string(),
+            platform: platform.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_error_code_prefers_http_status() {
+        let forbidden = DownloadError::DownloadFailed("ERROR: unable to download video data: HTTP Error 403: Forbidden".to_string());
+        assert_eq!(error_code(&forbidden), "HTTP 403");
+        assert_eq!(error_code(&DownloadError::Network("reset".to_string())), "NetworkError");
+        assert_eq!(error_code(&DownloadError::Timeout), "Timeout");
+    }
+
+    #[test]
+    fn test_stats_group_by_code_platform_and_local_hour() {
+        let mut log = ErrorLog::default();
+        log.record(record("2024-05-01T20:15:00+02:00", "HTTP 403", "YouTube"));
+        log.record(record("2024-05-02T20:40:00+02:00", "HTTP 403", "YouTube"));
+        log.record(record("2024-05-02T09:05:00+02:00", "NetworkError", "Kick"));
+        
+        let stats = log.stats(None);
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.by_code["HTTP 403"], 2);
+        assert_eq!(stats.by_platform["Kick"], 1);
+        assert_eq!(stats.by_hour[20], 2);
+        assert_eq!(stats.by_code_and_hour["HTTP 403"][20], 2);
+        assert_eq!(stats.by_code_and_hour["NetworkError"][9], 1);
+    }
+
+    #[test]
+    fn test_stats_limited_to_recent_days() {
+        let mut log = ErrorLog::default();
+        log.record(record("2020-01-01T12:00:00+00:00", "Timeout", "YouTube"));
+        log.record(ErrorRecord::new(&DownloadError::Timeout, "YouTube"));
+        
+        assert_eq!(log.stats(Some(7)).total, 1);
+        assert_eq!(log.stats(None).total, 2);
+    }
+
+    #[test]
+    fn test_log_round_trips_and_stays_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("errors.json");
+        
+        let mut log = ErrorLog::default();
+        for _ in 0..MAX_ERROR_RECORDS + 5 {
+            log.record(record("2024-05-01T12:00:00+00:00", "Timeout", "YouTube"));
+        }
+        assert_eq!(log.records.len(), MAX_ERROR_RECORDS);
+        
+        log.save(&path).unwrap();
+        assert_eq!(ErrorLog::load(&path).records.len(), MAX_ERROR_RECORDS);
+        assert!(ErrorLog::load(&dir.path().join("missing.json")).records.is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+//! Review-before-move inbox for completed downloads
+//!
+//! With the inbox on, a finished file and its sidecars (subtitles, thumbnail,
+//! ...) are moved to a review folder and recorded in history as unreviewed.
+//! Approving moves them on to the folder they were downloaded to; rejecting
+//! deletes them.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use super::settings::CompletedDownload;
+use crate::error::{DownloadError, Result};
+
+/// Where completed downloads wait for review
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct InboxSettings {
+    /// Move completed downloads to `folder` instead of leaving them at their destination
+    #[serde(default)]
+    pub enabled: bool,
+    /// Review folder; must be an absolute path when the inbox is on
+    #[serde(default)]
+    pub folder: String,
+}
+
+impl InboxSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.enabled && !Path::new(&self.folder).is_absolute() {
+            return Err(DownloadError::InvalidOption(format!(
+                "Inbox folder must be an absolute path, got '{}'",
+                self.folder
+            )));
+        }
+        Ok(())
+    }
+    
+    /// Review folder, when the inbox is on
+    pub fn folder(&self) -> Option<PathBuf> {
+        self.enabled.then(|| PathBuf::from(&self.folder))
+    }
+}
+
+/// Review state of a download that went through the inbox
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewStatus {
+    Unreviewed,
+    /// Moved to its library folder
+    Approved,
+    /// Deleted
+    Rejected,
+}
+
+/// `file` and the files next to it named after it (`video.en.srt`, `video.jpg`, ...)
+pub fn with_sidecars(file: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) = (file.parent(), file.file_stem()) else {
+        return vec![file.to_path_buf()];
+    };
+    let prefix = format!("{}.", stem.to_string_lossy());
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    name.starts_with(&prefix) && !name.ends_with(".part") && entry.path().is_file()
+                })
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    if !files.iter().any(|f| f == file) {
+        files.push(file.to_path_buf());
+    }
+    files.sort();
+    files
+}
+
+/// Move `file` and its sidecars into `dir`, returning the file's new path
+///
+/// Names already taken in `dir` get a ` (2)`, ` (3)`, ... suffix, the same one
+/// for the file and its sidecars so they stay paired.
+pub fn move_with_sidecars(file: &Path, dir: &Path) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let stem = file.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let files = with_sidecars(file);
+    let names: Vec<String> = files
+        .iter()
+        .filter_map(|f| f.file_name().map(|n| n.to_string_lossy().to_string()))
+        .collect();
+    
+    let renamed = |suffix: &str| -> Vec<PathBuf> {
+        names
+            .iter()
+            .map(|name| dir.join(format!("{}{}{}", stem, suffix, &name[stem.len()..])))
+            .collect()
+    };
+    let targets = (1..)
+        .map(|n| renamed(&if n == 1 { String::new() } else { format!(" ({})", n) }))
+        .find(|targets| targets.iter().all(|t| !t.exists()))
+        .unwrap_or_default();
+    
+    let mut moved = file.to_path_buf();
+    for (from, to) in files.iter().zip(&targets) {
+        move_file(from, to)?;
+        if from == file {
+            moved = to.clone();
+        }
+    }
+    Ok(moved)
+}
+
+/// Delete `file` and its sidecars
+pub fn delete_with_sidecars(file: &Path) -> Result<()> {
+    for path in with_sidecars(file) {
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Rename, falling back to copy and delete across file systems
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)?;
+    Ok(())
+}
+
+/// Move an unreviewed download to its library folder
+pub fn approve(download: &mut CompletedDownload) -> Result<()> {
+    let library = download
+        .library_path
+        .clone()
+        .filter(|_| download.review == Some(ReviewStatus::Unreviewed))
+        .ok_or_else(|| DownloadError::InvalidOption(format!("{} is not waiting for review", download.title)))?;
+    let moved = move_with_sidecars(Path::new(&download.save_path), Path::new(&library))?;
+    download.save_path = moved.to_string_lossy().to_string();
+    download.review = Some(ReviewStatus::Approved);
+    Ok(())
+}
+
+/// Delete an unreviewed download and its sidecars
+pub fn reject(download: &mut CompletedDownload) -> Result<()> {
+    if download.review != Some(ReviewStatus::Unreviewed) {
+        return Err(DownloadError::InvalidOption(format!("{} is not waiting for review", download.title)));
+    }
+    delete_with_sidecars(Path::new(&download.save_path))?;
+    download.review = Some(ReviewStatus::Rejected);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn unreviewed(file: &Path, library: &Path) -> CompletedDownload {
+        CompletedDownload {
+            id: "1".to_string(),
+            video_id: "abc".to_string(),
+            title: "Talk".to_string(),
+            completed_at: "2026-01-01T00:00:00Z".to_string(),
+            save_path: file.to_string_lossy().to_string(),
+            file_size: 5,
+            platform: "YouTube".to_string(),
+            tombstoned_at: None,
+            review: Some(ReviewStatus::Unreviewed),
+            library_path: Some(library.to_string_lossy().to_string()),
+            uploader: None,
+            audio_only: false,
+            starred: false,
+            subtitles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_approve_moves_file_and_sidecars_to_library() {
+        let dir = tempfile::tempdir().unwrap();
+        let inbox = dir.path().join("inbox");
+        let library = dir.path().join("library");
+        std::fs::create_dir_all(&inbox).unwrap();
+        std::fs::create_dir_all(&library).unwrap();
+        std::fs::write(inbox.join("Talk.mp4"), b"video").unwrap();
+        std::fs::write(inbox.join("Talk.en.srt"), b"subs").unwrap();
+        std::fs::write(inbox.join("Talking Heads.mp4"), b"other").unwrap();
+        // A file of the same name is already in the library
+        std::fs::write(library.join("Talk.mp4"), b"older").unwrap();
+        
+        let mut download = unreviewed(&inbox.join("Talk.mp4"), &library);
+        approve(&mut download).unwrap();
+        
+        assert_eq!(download.review, Some(ReviewStatus::Approved));
+        assert_eq!(PathBuf::from(&download.save_path), library.join("Talk (2).mp4"));
+        assert!(library.join("Talk (2).en.srt").exists());
+        assert!(inbox.join("Talking Heads.mp4").exists());
+        assert!(approve(&mut download).is_err());
+    }
+
+    #[test]
+    fn test_reject_deletes_file_and_sidecars() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Talk.mp4"), b"video").unwrap();
+        std::fs::write(dir.path().join("Talk.jpg"), b"thumbnail").unwrap();
+        
+        let mut download = unreviewed(&dir.path().join("Talk.mp4"), dir.path());
+        reject(&mut download).unwrap();
+        
+        assert_eq!(download.review, Some(ReviewStatus::Rejected));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_validate_inbox_folder() {
+        assert!(InboxSettings::default().validate().is_ok());
+        assert!(InboxSettings { enabled: true, folder: String::new() }.validate().is_err());
+        assert!(InboxSettings { enabled: true, folder: "inbox".to_string() }.validate().is_err());
+        let folder = std::env::temp_dir().to_string_lossy().to_string();
+        assert!(InboxSettings { enabled: true, folder }.validate().is_ok());
+    }
+}
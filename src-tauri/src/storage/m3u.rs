@@ -0,0 +1,62 @@
+use std::path::Path;
+
+/// A local file to include in an exported playlist
+#[derive(Clone, Debug)]
+pub struct M3uEntry {
+    pub title: String,
+    pub path: String,
+    /// Duration in seconds, if known
+    pub duration: Option<u64>,
+}
+
+/// Render entries as an extended M3U8 playlist written to `playlist_path`
+///
+/// Files under the playlist's own directory are written as relative paths so the
+/// playlist keeps working when the whole folder is moved.
+pub fn render_m3u(entries: &[M3uEntry], playlist_path: &Path) -> String {
+    let base = playlist_path.parent();
+    let mut out = String::from("#EXTM3U\n");
+    
+    for entry in entries {
+        let duration = entry.duration.map(|d| d as i64).unwrap_or(-1);
+        // Newlines in a title would break the line-oriented format
+        let title = entry.title.replace(['\r', '\n'], " ");
+        let path = Path::new(&entry.path);
+        let location = base
+            .and_then(|b| path.strip_prefix(b).ok())
+            .unwrap_or(path);
+        
+        out.push_str(&format!("#EXTINF:{},{}\n", duration, title));
+        out.push_str(&location.to_string_lossy());
+        out.push('\n');
+    }
+    
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_render_m3u_uses_relative_paths_in_order() {
+        let entries = vec![
+            M3uEntry {
+                title: "First".to_string(),
+                path: "/music/list/01 First.mp3".to_string(),
+                duration: Some(215),
+            },
+            M3uEntry {
+                title: "Second\nLine".to_string(),
+                path: "/other/Second.mp4".to_string(),
+                duration: None,
+            },
+        ];
+        
+        let rendered = render_m3u(&entries, Path::new("/music/list/playlist.m3u8"));
+        assert_eq!(
+            rendered,
+            "#EXTM3U\n#EXTINF:215,First\n01 First.mp3\n#EXTINF:-1,Second Line\n/other/Second.mp4\n"
+        );
+    }
+}
@@ -1,5 +1,21 @@
 pub mod service;
 pub mod settings;
+pub mod playlist_sync;
+pub mod m3u;
+pub mod archive;
+pub mod benchmark;
+pub mod error_stats;
+pub mod platform_stats;
+pub mod shared_files;
+pub mod inbox;
+pub mod collections;
 
 pub use service::StorageService;
-pub use settings::AppSettings;
+pub use settings::{AppSettings, CompletedDownload, HistoryStore};
+pub use inbox::{InboxSettings, ReviewStatus};
+pub use collections::{CollectionFilter, Period, SmartCollection};
+pub use benchmark::WriteBenchmark;
+pub use error_stats::{ErrorLog, ErrorRecord, ErrorStats};
+pub use platform_stats::{PlatformStats, PlatformStatsLog};
+pub use playlist_sync::{PlaylistDiff, PlaylistEntry, PlaylistSnapshot};
+pub use shared_files::PrivateCopy;
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use crate::error::Result;
+
+/// Lifetime download totals for one platform
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformStats {
+    pub platform: String,
+    pub completed: usize,
+    pub failed: usize,
+    /// Bytes downloaded by completed downloads
+    pub bytes: u64,
+    /// Share of finished downloads that completed, 0.0-1.0; `None` before the first one
+    pub success_rate: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+struct PlatformTotals {
+    completed: usize,
+    failed: usize,
+    bytes: u64,
+}
+
+/// Persistent per-platform download totals
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PlatformStatsLog {
+    platforms: BTreeMap<String, PlatformTotals>,
+}
+
+impl PlatformStatsLog {
+    /// Load the totals from `path`; a missing or unreadable file starts from zero
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+    
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+    
+    pub fn record_completed(&mut self, platform: &str, bytes: u64) {
+        let totals = self.platforms.entry(platform.to_string()).or_default();
+        totals.completed += 1;
+        totals.bytes += bytes;
+    }
+    
+    pub fn record_failed(&mut self, platform: &str) {
+        self.platforms.entry(platform.to_string()).or_default().failed += 1;
+    }
+    
+    /// Totals for `platform`, zero if it has no downloads yet
+    pub fn stats(&self, platform: &str) -> PlatformStats {
+        let totals = self.platforms.get(platform).cloned().unwrap_or_default();
+        let finished = totals.completed + totals.failed;
+        PlatformStats {
+            platform: platform.to_string(),
+            completed: totals.completed,
+            failed: totals.failed,
+            bytes: totals.bytes,
+            success_rate: (finished > 0).then(|| totals.completed as f64 / finished as f64),
+        }
+    }
+    
+    /// Totals for every platform with downloads, by name
+    pub fn all(&self) -> Vec<PlatformStats> {
+        self.platforms.keys().map(|platform| self.stats(platform)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_totals_and_success_rate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("platform_stats.json");
+        
+        let mut log = PlatformStatsLog::default();
+        for _ in 0..49 {
+            log.record_completed("YouTube", 1024);
+        }
+        log.record_failed("YouTube");
+        log.record_failed("Kick");
+        log.save(&path).unwrap();
+        
+        let log = PlatformStatsLog::load(&path);
+        let youtube = log.stats("YouTube");
+        assert_eq!((youtube.completed, youtube.failed, youtube.bytes), (49, 1, 49 * 1024));
+        assert_eq!(youtube.success_rate, Some(0.98));
+        assert_eq!(log.stats("Kick").success_rate, Some(0.0));
+        assert_eq!(log.stats("Reddit").success_rate, None);
+        assert_eq!(log.all().iter().map(|s| s.platform.as_str()).collect::<Vec<_>>(), ["Kick", "YouTube"]);
+    }
+}
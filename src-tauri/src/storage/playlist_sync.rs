@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use crate::platform::{ChannelInfo, PlaylistInfo, VideoInfo};
+
+/// A video as it appeared in a playlist snapshot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlaylistEntry {
+    pub video_id: String,
+    pub title: String,
+}
+
+/// Playlist contents recorded the last time it was diffed
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlaylistSnapshot {
+    pub playlist_id: String,
+    pub platform: String,
+    pub url: String,
+    pub entries: Vec<PlaylistEntry>,
+    pub taken_at: String,
+}
+
+impl PlaylistSnapshot {
+    /// Capture the current contents of a playlist
+    pub fn from_playlist(playlist: &PlaylistInfo) -> Self {
+        Self {
+            playlist_id: playlist.id.clone(),
+            platform: playlist.platform.clone(),
+            url: playlist.url.clone(),
+            entries: playlist
+                .videos
+                .iter()
+                .map(|v| PlaylistEntry {
+                    video_id: v.id.clone(),
+                    title: v.title.clone(),
+                })
+                .collect(),
+            taken_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Treat a channel's uploads as a playlist so it can be diffed the same way
+pub fn listing_from_channel(channel: &ChannelInfo) -> PlaylistInfo {
+    PlaylistInfo {
+        id: channel.id.clone(),
+        title: channel.name.clone(),
+        description: channel.description.clone(),
+        uploader: channel.name.clone(),
+        video_count: channel.all_videos.len(),
+        videos: channel.all_videos.clone(),
+        platform: channel.platform.clone(),
+        url: channel.url.clone(),
+        has_more: false,
+        page: 0,
+        page_size: 0,
+    }
+}
+
+/// Result of comparing a playlist against the local download history
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlaylistDiff {
+    pub playlist_id: String,
+    pub title: String,
+    pub platform: String,
+    /// Videos in the playlist that have not been downloaded yet
+    pub new_videos: Vec<VideoInfo>,
+    /// Videos in the playlist that are already downloaded
+    pub already_downloaded: Vec<VideoInfo>,
+    /// Videos seen in the previous snapshot that are no longer in the playlist
+    pub removed: Vec<PlaylistEntry>,
+    /// Removed videos that were downloaded, so the local copy is now the only one
+    pub tombstoned: Vec<PlaylistEntry>,
+    /// When the previous snapshot was taken, if this playlist was diffed before
+    pub previous_snapshot_at: Option<String>,
+}
+
+/// Compare a playlist's current contents against downloaded video IDs and the previous snapshot
+pub fn diff_playlist(
+    playlist: &PlaylistInfo,
+    downloaded_ids: &HashSet<String>,
+    previous: Option<&PlaylistSnapshot>,
+) -> PlaylistDiff {
+    let (already_downloaded, new_videos): (Vec<VideoInfo>, Vec<VideoInfo>) = playlist
+        .videos
+        .iter()
+        .cloned()
+        .partition(|v| downloaded_ids.contains(&v.id));
+    
+    let current_ids: HashSet<&str> = playlist.videos.iter().map(|v| v.id.as_str()).collect();
+    let removed: Vec<PlaylistEntry> = previous
+        .map(|snapshot| {
+            snapshot
+                .entries
+                .iter()
+                .filter(|e| !current_ids.contains(e.video_id.as_str()))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    let tombstoned = removed
+        .iter()
+        .filter(|e| downloaded_ids.contains(&e.video_id))
+        .cloned()
+        .collect();
+    
+    PlaylistDiff {
+        playlist_id: playlist.id.clone(),
+        title: playlist.title.clone(),
+        platform: playlist.platform.clone(),
+        new_videos,
+        already_downloaded,
+        removed,
+        tombstoned,
+        previous_snapshot_at: previous.map(|s| s.taken_at.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn video(id: &str) -> VideoInfo {
+        VideoInfo {
+            id: id.to_string(),
+            title: format!("Video {}", id),
+            description: String::new(),
+            thumbnail: String::new(),
+            duration: 60,
+            uploader: "Uploader".to_string(),
+            upload_date: "20240101".to_string(),
+            view_count: 0,
+            available_formats: Vec::new(),
+            platform: "YouTube".to_string(),
+            url: format!("https://www.youtube.com/watch?v={}", id),
+            is_live: false,
+            premiere_at: None,
+        }
+    }
+    
+    fn playlist(ids: &[&str]) -> PlaylistInfo {
+        PlaylistInfo {
+            id: "PL1".to_string(),
+            title: "Playlist".to_string(),
+            description: String::new(),
+            uploader: "Uploader".to_string(),
+            video_count: ids.len(),
+            videos: ids.iter().map(|id| video(id)).collect(),
+            platform: "YouTube".to_string(),
+            url: "https://www.youtube.com/playlist?list=PL1".to_string(),
+            has_more: false,
+            page: 0,
+            page_size: 0,
+        }
+    }
+    
+    fn ids(list: &[VideoInfo]) -> Vec<&str> {
+        list.iter().map(|v| v.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_first_diff_splits_new_and_downloaded() {
+        let downloaded: HashSet<String> = ["b".to_string()].into_iter().collect();
+        let diff = diff_playlist(&playlist(&["a", "b", "c"]), &downloaded, None);
+        
+        assert_eq!(ids(&diff.new_videos), vec!["a", "c"]);
+        assert_eq!(ids(&diff.already_downloaded), vec!["b"]);
+        assert!(diff.removed.is_empty());
+        assert!(diff.tombstoned.is_empty());
+        assert!(diff.previous_snapshot_at.is_none());
+    }
+
+    #[test]
+    fn test_diff_reports_removed_videos_from_snapshot() {
+        let previous = PlaylistSnapshot::from_playlist(&playlist(&["a", "b", "c"]));
+        let downloaded: HashSet<String> = ["a".to_string(), "b".to_string()].into_iter().collect();
+        let diff = diff_playlist(&playlist(&["a", "d"]), &downloaded, Some(&previous));
+        
+        assert_eq!(ids(&diff.new_videos), vec!["d"]);
+        assert_eq!(ids(&diff.already_downloaded), vec!["a"]);
+        let removed: Vec<&str> = diff.removed.iter().map(|e| e.video_id.as_str()).collect();
+        assert_eq!(removed, vec!["b", "c"]);
+        let tombstoned: Vec<&str> = diff.tombstoned.iter().map(|e| e.video_id.as_str()).collect();
+        assert_eq!(tombstoned, vec!["b"]);
+        assert_eq!(diff.previous_snapshot_at, Some(previous.taken_at.clone()));
+    }
+}
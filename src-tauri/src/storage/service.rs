@@ -3,8 +3,12 @@ use std::sync::{Arc, Mutex};
 use tauri::AppHandle;
 use tauri_plugin_store::{Store, StoreBuilder};
 use tauri::Wry;
-use super::settings::{AppSettings, DownloadHistory, QueueState};
+use super::settings::{AppSettings, CompletedDownload, DownloadHistory, HistoryStore, QueueState};
+use super::collections::{Collections, SmartCollection};
+use super::inbox;
+use super::playlist_sync::PlaylistSnapshot;
 use crate::error::{DownloadError, Result};
+use crate::{log_debug, log_warn};
 
 /// Storage service for file system operations and configuration
 pub struct StorageService {
@@ -112,6 +116,24 @@ impl StorageService {
         }
     }
     
+    /// Free bytes available to this user on the volume holding `path`
+    ///
+    /// `path` need not exist yet; the nearest existing ancestor is checked.
+    pub fn available_space(path: &Path) -> Result<u64> {
+        let existing = path
+            .ancestors()
+            .find(|p| p.exists())
+            .ok_or_else(|| DownloadError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No existing directory for {}", path.display())
+            )))?;
+        
+        let stat = nix::sys::statvfs::statvfs(existing)
+            .map_err(|e| DownloadError::Io(std::io::Error::other(format!("Failed to get disk space: {}", e))))?;
+        
+        Ok(stat.blocks_available() as u64 * stat.fragment_size() as u64)
+    }
+    
     /// Validate that a path is safe to use
     /// Prevents path traversal attacks and ensures path is absolute
     pub fn validate_path(&self, path: &Path) -> Result<()> {
@@ -180,6 +202,7 @@ impl StorageService {
             std::io::ErrorKind::Other,
             format!("Failed to persist settings: {}", e)
         )))?;
+        log_debug!("[Storage] Settings saved");
         
         Ok(())
     }
@@ -295,7 +318,7 @@ impl StorageService {
     }
     
     /// Add a completed download to history
-    pub fn add_to_history(&self, download: crate::storage::settings::CompletedDownload) -> Result<()> {
+    pub fn add_to_history(&self, download: CompletedDownload) -> Result<()> {
         let mut history = self.load_download_history()?;
         history.downloads.push(download);
         
@@ -307,6 +330,156 @@ impl StorageService {
         self.save_download_history(&history)
     }
     
+    /// Approve or reject inbox downloads by history ID, returning the updated entries
+    ///
+    /// Entries that fail (e.g. the file was moved by hand) are skipped and logged.
+    pub fn review_downloads(&self, ids: &[String], approve: bool) -> Result<Vec<CompletedDownload>> {
+        let mut history = self.load_download_history()?;
+        let mut reviewed = Vec::new();
+        for download in history.downloads.iter_mut().filter(|d| ids.contains(&d.id)) {
+            let result = if approve { inbox::approve(download) } else { inbox::reject(download) };
+            match result {
+                Ok(()) => reviewed.push(download.clone()),
+                Err(e) => log_warn!("[Inbox] Could not review {}: {}", download.title, e),
+            }
+        }
+        
+        if !reviewed.is_empty() {
+            self.save_download_history(&history)?;
+        }
+        Ok(reviewed)
+    }
+    
+    /// Mark downloaded videos as removed upstream, returning how many entries were newly tombstoned
+    pub fn mark_tombstoned(&self, platform: &str, video_ids: &[String]) -> Result<usize> {
+        if video_ids.is_empty() {
+            return Ok(0);
+        }
+        
+        let mut history = self.load_download_history()?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut marked = 0;
+        
+        for download in history.downloads.iter_mut() {
+            if download.platform == platform
+                && download.tombstoned_at.is_none()
+                && video_ids.contains(&download.video_id)
+            {
+                download.tombstoned_at = Some(now.clone());
+                marked += 1;
+            }
+        }
+        
+        if marked > 0 {
+            self.save_download_history(&history)?;
+        }
+        
+        Ok(marked)
+    }
+    
+    /// Star or unstar a history entry, returning the updated entry
+    pub fn set_starred(&self, id: &str, starred: bool) -> Result<CompletedDownload> {
+        let mut history = self.load_download_history()?;
+        let download = history
+            .downloads
+            .iter_mut()
+            .find(|d| d.id == id)
+            .ok_or_else(|| DownloadError::InvalidOption(format!("No download with ID {} in history", id)))?;
+        download.starred = starred;
+        let updated = download.clone();
+        
+        self.save_download_history(&history)?;
+        Ok(updated)
+    }
+    
+    /// Save smart collections
+    pub fn save_collections(&self, collections: &Collections) -> Result<()> {
+        let mut store = self.store.lock().map_err(|e| DownloadError::Io(std::io::Error::other(format!("Failed to lock store: {}", e))))?;
+        
+        store.insert(
+            "smart_collections".to_string(),
+            serde_json::to_value(collections).map_err(DownloadError::Serialization)?
+        ).map_err(|e| DownloadError::Io(std::io::Error::other(format!("Failed to save collections: {}", e))))?;
+        
+        store.save().map_err(|e| DownloadError::Io(std::io::Error::other(format!("Failed to persist collections: {}", e))))?;
+        
+        Ok(())
+    }
+    
+    /// Load smart collections
+    pub fn load_collections(&self) -> Result<Collections> {
+        let store = self.store.lock().map_err(|e| DownloadError::Io(std::io::Error::other(format!("Failed to lock store: {}", e))))?;
+        
+        match store.get("smart_collections") {
+            Some(value) => {
+                serde_json::from_value(value.clone())
+                    .map_err(DownloadError::Serialization)
+            }
+            None => Ok(Collections::default())
+        }
+    }
+    
+    /// Create or update a smart collection, returning it with its ID
+    pub fn save_collection(&self, collection: SmartCollection) -> Result<SmartCollection> {
+        collection.validate()?;
+        let mut collections = self.load_collections()?;
+        let saved = collections.upsert(collection);
+        self.save_collections(&collections)?;
+        Ok(saved)
+    }
+    
+    /// Delete a smart collection; its downloads are left alone
+    pub fn delete_collection(&self, id: &str) -> Result<()> {
+        let mut collections = self.load_collections()?;
+        if collections.remove(id) {
+            self.save_collections(&collections)?;
+        }
+        Ok(())
+    }
+    
+    /// Current contents of a smart collection, most recent first
+    pub fn collection_downloads(&self, id: &str) -> Result<Vec<CompletedDownload>> {
+        let collections = self.load_collections()?;
+        let collection = collections
+            .get(id)
+            .ok_or_else(|| DownloadError::InvalidOption(format!("No collection with ID {}", id)))?;
+        let history = self.load_download_history()?;
+        Ok(collection.select(&history.downloads, chrono::Local::now()))
+    }
+    
+    /// Save the last known contents of a playlist
+    pub fn save_playlist_snapshot(&self, snapshot: &PlaylistSnapshot) -> Result<()> {
+        let mut store = self.store.lock()
+            .map_err(|e| DownloadError::Io(std::io::Error::other(format!("Failed to lock store: {}", e))))?;
+        
+        let key = format!("playlist_snapshot_{}_{}", snapshot.platform, snapshot.playlist_id);
+        store.insert(
+            key,
+            serde_json::to_value(snapshot).map_err(|e| DownloadError::Serialization(e))?
+        ).map_err(|e| DownloadError::Io(std::io::Error::other(format!("Failed to save playlist snapshot: {}", e))))?;
+        
+        store.save()
+            .map_err(|e| DownloadError::Io(std::io::Error::other(format!("Failed to persist playlist snapshot: {}", e))))?;
+        
+        Ok(())
+    }
+    
+    /// Load the last known contents of a playlist, if it was snapshotted before
+    pub fn load_playlist_snapshot(&self, platform: &str, playlist_id: &str) -> Result<Option<PlaylistSnapshot>> {
+        let store = self.store.lock()
+            .map_err(|e| DownloadError::Io(std::io::Error::other(format!("Failed to lock store: {}", e))))?;
+        
+        let key = format!("playlist_snapshot_{}_{}", platform, playlist_id);
+        match store.get(&key) {
+            Some(value) => {
+                serde_json::from_value(value.clone())
+                    .map(Some)
+                    .map_err(|e| DownloadError::Serialization(e))
+            }
+            None => Ok(None)
+        }
+    }
+    
     /// Save queue state
     pub fn save_queue_state(&self, queue: &QueueState) -> Result<()> {
         let mut store = self.store.lock().map_err(|e| DownloadError::Io(std::io::Error::new(
@@ -326,6 +499,7 @@ impl StorageService {
             std::io::ErrorKind::Other,
             format!("Failed to persist queue state: {}", e)
         )))?;
+        log_debug!("[Storage] Queue state saved ({} items)", queue.items.len());
         
         Ok(())
     }
@@ -362,6 +536,7 @@ impl StorageService {
             std::io::ErrorKind::Other,
             format!("Failed to persist changes: {}", e)
         )))?;
+        log_debug!("[Storage] Queue state cleared");
         
         Ok(())
     }
@@ -407,3 +582,9 @@ impl StorageService {
         }
     }
 }
+
+impl HistoryStore for StorageService {
+    fn add_to_history(&self, download: CompletedDownload) -> Result<()> {
+        StorageService::add_to_history(self, download)
+    }
+}
@@ -1,5 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::download::{MixingPolicy, QuickWins};
+use crate::error::{DownloadError, Result};
+use crate::logging::LogSettings;
+use crate::sounds::SoundSettings;
+use crate::transcription::TranscriptionSettings;
+use super::inbox::{InboxSettings, ReviewStatus};
+use crate::platform::{AudioSettings, DohSettings, HttpHeaderSettings, MAX_METADATA_CONCURRENCY, PoTokenSettings, PostprocessLimits, ProcessPriority, ProxySettings, YtDlpRetries};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AppSettings {
@@ -13,6 +20,160 @@ pub struct AppSettings {
     pub enabled_platforms: Vec<String>,
     #[serde(default)]
     pub first_launch_completed: bool,
+    /// Opt-in: let the generic provider handle any site yt-dlp supports
+    #[serde(default)]
+    pub allow_generic_sites: bool,
+    /// Rewrite MP4 output with the index at the front so it streams to TVs and phones
+    #[serde(default = "default_mp4_faststart")]
+    pub mp4_faststart: bool,
+    /// DASH/HLS fragments yt-dlp downloads at once, 1 to `MAX_CONCURRENT_FRAGMENTS`
+    #[serde(default = "default_concurrent_fragments")]
+    pub concurrent_fragments: u32,
+    /// Retries yt-dlp makes within one run, before the app-level retry restarts the download
+    #[serde(default)]
+    pub ytdlp_retries: YtDlpRetries,
+    /// Metadata lookups each provider runs at once, by provider name; `DEFAULT_METADATA_CONCURRENCY` when unset
+    #[serde(default)]
+    pub metadata_concurrency: HashMap<String, usize>,
+    /// Pause downloads when free space at the destination drops below this many MB (0 disables)
+    #[serde(default = "default_min_free_space_mb")]
+    pub min_free_space_mb: u64,
+    /// Total download speed in KiB/s, split evenly between running downloads (0 for no limit)
+    #[serde(default)]
+    pub global_speed_limit: u64,
+    /// Netscape cookies file passed to yt-dlp for age-restricted and members-only videos
+    #[serde(default)]
+    pub cookies_file_path: Option<String>,
+    /// Browser to read cookies from (`safari`, `chrome`, `firefox`, ...) when no cookies file is set
+    #[serde(default)]
+    pub cookies_from_browser: Option<String>,
+    /// PO token and visitor data for YouTube's "confirm you're not a bot" checks
+    #[serde(default)]
+    pub youtube_po_token: PoTokenSettings,
+    /// How queued items interleave: strict FIFO, by playlist, or fair share between platforms
+    #[serde(default)]
+    pub queue_mixing_policy: MixingPolicy,
+    /// Start very small downloads in extra slots when the concurrency limit is reached
+    #[serde(default)]
+    pub queue_quick_wins: QuickWins,
+    /// Keep chapter markers in downloaded files; items can override it
+    #[serde(default)]
+    pub embed_chapters: bool,
+    /// Record downloaded videos in a yt-dlp archive and skip them next time
+    #[serde(default)]
+    pub use_download_archive: bool,
+    /// Run yt-dlp and ffmpeg at background priority during downloads
+    #[serde(default)]
+    pub helper_process_priority: ProcessPriority,
+    /// ffmpeg CPU share and concurrent transcode cap for post-processing
+    #[serde(default)]
+    pub postprocess_limits: PostprocessLimits,
+    /// HTTP(S) proxy for yt-dlp and update checks, with per-platform overrides
+    #[serde(default)]
+    pub proxy: ProxySettings,
+    /// User-agent and extra HTTP headers for yt-dlp, with per-platform overrides
+    #[serde(default)]
+    pub http_headers: HttpHeaderSettings,
+    /// Resolve hosts for update checks and direct downloads over DNS-over-HTTPS
+    #[serde(default)]
+    pub dns_over_https: DohSettings,
+    /// Format and bitrate of audio-only downloads
+    #[serde(default)]
+    pub audio: AudioSettings,
+    /// Subtitles generated with whisper.cpp for completed videos without any
+    #[serde(default)]
+    pub transcription: TranscriptionSettings,
+    /// Review folder completed downloads wait in before moving to their destination
+    #[serde(default)]
+    pub inbox: InboxSettings,
+    /// Locale for sizes, speeds, durations and setting labels shown in the UI (e.g. `de-DE`); the system locale when unset
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Sounds played when downloads complete or fail
+    #[serde(default)]
+    pub sounds: SoundSettings,
+    /// Log verbosity for the in-app console and the log file
+    #[serde(default)]
+    pub logging: LogSettings,
+}
+
+fn default_mp4_faststart() -> bool {
+    true
+}
+
+fn default_min_free_space_mb() -> u64 {
+    1024
+}
+
+/// Lowest non-zero `global_speed_limit` in KiB/s; less stalls downloads outright
+pub const MIN_GLOBAL_SPEED_LIMIT: u64 = 64;
+
+/// Upper bound for `concurrent_fragments`; more mostly gets throttled by the server
+pub const MAX_CONCURRENT_FRAGMENTS: u32 = 16;
+
+fn default_concurrent_fragments() -> u32 {
+    1
+}
+
+impl AppSettings {
+    /// Check values the settings UI can't constrain on its own
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=MAX_CONCURRENT_FRAGMENTS).contains(&self.concurrent_fragments) {
+            return Err(DownloadError::InvalidOption(format!(
+                "Concurrent fragments must be between 1 and {}, got {}",
+                MAX_CONCURRENT_FRAGMENTS, self.concurrent_fragments
+            )));
+        }
+        self.ytdlp_retries.validate()?;
+        if self.global_speed_limit != 0 && self.global_speed_limit < MIN_GLOBAL_SPEED_LIMIT {
+            return Err(DownloadError::InvalidOption(format!(
+                "Global speed limit must be 0 (no limit) or at least {} KiB/s, got {}",
+                MIN_GLOBAL_SPEED_LIMIT, self.global_speed_limit
+            )));
+        }
+        for (provider, limit) in &self.metadata_concurrency {
+            if !(1..=MAX_METADATA_CONCURRENCY).contains(limit) {
+                return Err(DownloadError::InvalidOption(format!(
+                    "Metadata concurrency for {} must be between 1 and {}, got {}",
+                    provider, MAX_METADATA_CONCURRENCY, limit
+                )));
+            }
+        }
+        self.proxy.validate()?;
+        self.youtube_po_token.validate()?;
+        self.http_headers.validate()?;
+        self.dns_over_https.validate()?;
+        self.audio.validate()?;
+        self.transcription.validate()?;
+        self.inbox.validate()?;
+        self.queue_quick_wins.validate()?;
+        self.sounds.validate()
+    }
+    
+    /// `global_speed_limit` in bytes per second, if set
+    pub fn global_speed_limit_bytes(&self) -> Option<u64> {
+        Some(self.global_speed_limit * 1024).filter(|limit| *limit > 0)
+    }
+    
+    /// Stored value of a provider setting, if the user has set it
+    pub fn platform_setting(&self, platform: &str, key: &str) -> Option<&serde_json::Value> {
+        self.platform_settings.get(platform)?.get(key)
+    }
+    
+    /// Subtitle languages from the YouTube `youtube_subtitle_language` setting ("none" means none)
+    pub fn youtube_subtitle_languages(&self) -> Vec<String> {
+        match self.platform_setting("YouTube", "youtube_subtitle_language").and_then(|v| v.as_str()) {
+            Some(lang) if !lang.is_empty() && lang != "none" => vec![lang.to_string()],
+            _ => Vec::new(),
+        }
+    }
+    
+    /// Whether the YouTube `youtube_auto_subtitles` caption fallback is enabled
+    pub fn youtube_auto_subtitles(&self) -> bool {
+        self.platform_setting("YouTube", "youtube_auto_subtitles")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
 }
 
 impl Default for AppSettings {
@@ -27,6 +188,31 @@ impl Default for AppSettings {
             platform_settings: HashMap::new(),
             enabled_platforms: vec!["YouTube".to_string()],
             first_launch_completed: false,
+            allow_generic_sites: false,
+            mp4_faststart: true,
+            concurrent_fragments: default_concurrent_fragments(),
+            ytdlp_retries: YtDlpRetries::default(),
+            metadata_concurrency: HashMap::new(),
+            min_free_space_mb: default_min_free_space_mb(),
+            global_speed_limit: 0,
+            cookies_file_path: None,
+            cookies_from_browser: None,
+            youtube_po_token: PoTokenSettings::default(),
+            queue_mixing_policy: MixingPolicy::default(),
+            queue_quick_wins: QuickWins::default(),
+            embed_chapters: false,
+            use_download_archive: false,
+            helper_process_priority: ProcessPriority::Normal,
+            postprocess_limits: PostprocessLimits::default(),
+            proxy: ProxySettings::default(),
+            http_headers: HttpHeaderSettings::default(),
+            dns_over_https: DohSettings::default(),
+            audio: AudioSettings::default(),
+            transcription: TranscriptionSettings::default(),
+            inbox: InboxSettings::default(),
+            locale: None,
+            sounds: SoundSettings::default(),
+            logging: LogSettings::default(),
         }
     }
 }
@@ -68,4 +254,29 @@ pub struct CompletedDownload {
     pub save_path: String,
     pub file_size: u64,
     pub platform: String,
+    /// Set when the video disappeared upstream, leaving the local copy as the only one
+    #[serde(default)]
+    pub tombstoned_at: Option<String>,
+    /// Set for downloads that went through the inbox
+    #[serde(default)]
+    pub review: Option<ReviewStatus>,
+    /// Folder an inbox download moves to when approved
+    #[serde(default)]
+    pub library_path: Option<String>,
+    /// Channel or account that published the download
+    #[serde(default)]
+    pub uploader: Option<String>,
+    #[serde(default)]
+    pub audio_only: bool,
+    /// Starred by the user
+    #[serde(default)]
+    pub starred: bool,
+    /// Subtitle files saved next to the download, one per language
+    #[serde(default)]
+    pub subtitles: Vec<String>,
+}
+
+/// Where completed downloads are recorded; `StorageService` in the app
+pub trait HistoryStore: Send + Sync {
+    fn add_to_history(&self, download: CompletedDownload) -> Result<()>;
 }
@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+use crate::error::Result;
+
+/// Private copy of a file that concurrent yt-dlp processes would otherwise share
+///
+/// yt-dlp rewrites its cookies file and appends to its archive while it runs,
+/// so two processes pointed at the same file can interleave writes and corrupt
+/// it. Each process gets its own copy instead; the copy is deleted on drop.
+#[derive(Debug)]
+pub struct PrivateCopy {
+    path: PathBuf,
+}
+
+impl PrivateCopy {
+    /// Copy `source` into the temp directory; a missing source gives an empty copy
+    ///
+    /// The copy keeps the source's permissions, so private files stay private.
+    pub fn create(source: &Path, prefix: &str) -> Result<Self> {
+        let extension = source.extension().and_then(|e| e.to_str()).unwrap_or("txt");
+        let path = std::env::temp_dir().join(format!("vortex-{}-{}.{}", prefix, uuid::Uuid::new_v4(), extension));
+        if source.exists() {
+            std::fs::copy(source, &path)?;
+        } else {
+            std::fs::write(&path, "")?;
+        }
+        Ok(Self { path })
+    }
+    
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PrivateCopy {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_copy_is_independent_and_removed_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("cookies.txt");
+        std::fs::write(&source, "# Netscape HTTP Cookie File\n").unwrap();
+        
+        let copy = PrivateCopy::create(&source, "cookies").unwrap();
+        assert_ne!(copy.path(), source);
+        std::fs::write(copy.path(), "rewritten by yt-dlp").unwrap();
+        assert_eq!(std::fs::read_to_string(&source).unwrap(), "# Netscape HTTP Cookie File\n");
+        
+        let path = copy.path().to_path_buf();
+        drop(copy);
+        assert!(!path.exists());
+        
+        let empty = PrivateCopy::create(&dir.path().join("missing.txt"), "archive").unwrap();
+        assert_eq!(std::fs::read_to_string(empty.path()).unwrap(), "");
+    }
+}
@@ -0,0 +1,190 @@
+//! Deterministic harness for queue tests
+//!
+//! Runs a real `DownloadManager` against the simulated `MockProvider`, with
+//! events recorded in memory and app data in a throwaway directory. Combined
+//! with `#[tokio::test(start_paused = true)]`, simulated downloads advance on
+//! tokio's virtual clock, so whole queues finish in milliseconds and always in
+//! the same order. Only compiled with the `test-harness` feature.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use crate::download::{DownloadItem, DownloadManager, DownloadStatus};
+use crate::events::EventSink;
+use crate::paths::FixedPathProvider;
+use crate::platform::{MockProvider, MockProviderConfig, PlatformProvider, PlatformRegistry};
+
+/// Records every event for later assertions
+#[derive(Default)]
+pub struct RecordingEventSink {
+    events: Mutex<Vec<(String, serde_json::Value)>>,
+}
+
+impl RecordingEventSink {
+    /// All events so far, oldest first
+    pub fn events(&self) -> Vec<(String, serde_json::Value)> {
+        self.events.lock().map(|e| e.clone()).unwrap_or_default()
+    }
+    
+    /// Payloads of the events named `event`
+    pub fn payloads(&self, event: &str) -> Vec<serde_json::Value> {
+        self.events()
+            .into_iter()
+            .filter(|(name, _)| name == event)
+            .map(|(_, payload)| payload)
+            .collect()
+    }
+}
+
+impl EventSink for RecordingEventSink {
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        if let Ok(mut events) = self.events.lock() {
+            events.push((event.to_string(), payload));
+        }
+    }
+}
+
+/// A download manager wired to the mock provider
+pub struct QueueHarness {
+    pub manager: Arc<DownloadManager>,
+    pub events: Arc<RecordingEventSink>,
+    provider: Arc<MockProvider>,
+    data_dir: PathBuf,
+}
+
+impl QueueHarness {
+    /// Harness where each simulated download takes 4 seconds of virtual time
+    pub fn new() -> Self {
+        Self::with_config(MockProviderConfig {
+            bytes_per_second: 1024 * 1024,
+            file_size: 4 * 1024 * 1024,
+            tick_interval: Duration::from_millis(500),
+        })
+    }
+    
+    pub fn with_config(config: MockProviderConfig) -> Self {
+        let data_dir = std::env::temp_dir().join(format!(
+            "vortex-harness-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let _ = std::fs::create_dir_all(&data_dir);
+        
+        let provider = Arc::new(MockProvider::new(config));
+        let mut registry = PlatformRegistry::new();
+        registry.register(provider.clone());
+        let events = Arc::new(RecordingEventSink::default());
+        let manager = Arc::new(DownloadManager::new(
+            Arc::new(FixedPathProvider::new(&data_dir)),
+            events.clone(),
+            Arc::new(registry),
+        ));
+        
+        Self { manager, events, provider, data_dir }
+    }
+    
+    /// App data directory used by the manager (saved queue, error log, ...)
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+    
+    /// A queued mock item; `query` is appended to the URL, e.g. `fail_at=50`
+    pub fn item(&self, id: &str, query: Option<&str>) -> DownloadItem {
+        let mut url = format!("https://mock.vortex.test/watch/{}", id);
+        if let Some(query) = query {
+            url.push('?');
+            url.push_str(query);
+        }
+        
+        DownloadItem {
+            id: id.to_string(),
+            video_id: id.to_string(),
+            title: format!("Mock Video {}", id),
+            thumbnail: String::new(),
+            status: DownloadStatus::Queued,
+            progress: 0.0,
+            speed: 0.0,
+            eta: 0,
+            save_path: self.data_dir.to_string_lossy().to_string(),
+            error: None,
+            url,
+            platform: "Mock".to_string(),
+            postprocessor_args: None,
+            subtitle_languages: None,
+            auto_subtitles: None,
+            embed_chapters: None,
+            scheduled_at: None,
+            playlist_id: None,
+            record_live: false,
+            premiere_at: None,
+            section_start: None,
+            section_end: None,
+            audio_only: false,
+            format_id: None,
+            album: None,
+            track_number: None,
+            uploader: None,
+            quality: None,
+            variant_group: None,
+            estimated_size: None,
+            request_id: None,
+            liveness: None,
+        }
+    }
+    
+    /// A queued mock item at `format_id`, sized from its video info as the app queues it
+    pub async fn item_from_info(&self, id: &str, format_id: Option<&str>) -> DownloadItem {
+        let mut item = self.item(id, None);
+        if let Ok(info) = self.provider.get_video_info(&item.url).await {
+            item.estimated_size = info.estimated_size(format_id);
+        }
+        item.format_id = format_id.map(str::to_string);
+        item
+    }
+    
+    /// Current status of item `id`
+    pub async fn status(&self, id: &str) -> Option<DownloadStatus> {
+        self.manager
+            .get_queue_status()
+            .await
+            .into_iter()
+            .find(|i| i.id == id)
+            .map(|i| i.status)
+    }
+    
+    /// Poll the queue until `done` holds, giving up after `timeout` of (virtual) time
+    pub async fn wait_for(&self, timeout: Duration, done: impl Fn(&[DownloadItem]) -> bool) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if done(&self.manager.get_queue_status().await) {
+                return true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+    
+    /// Wait until no item is queued or downloading
+    pub async fn wait_until_settled(&self, timeout: Duration) -> bool {
+        self.wait_for(timeout, |queue| {
+            queue
+                .iter()
+                .all(|i| !matches!(i.status, DownloadStatus::Queued | DownloadStatus::Waiting | DownloadStatus::Downloading))
+        })
+        .await
+    }
+}
+
+impl Default for QueueHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for QueueHarness {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.data_dir);
+    }
+}
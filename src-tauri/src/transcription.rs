@@ -0,0 +1,269 @@
+//! Subtitles for finished downloads that have none, generated by a local whisper.cpp
+//!
+//! ffmpeg extracts 16 kHz mono audio from the video, then whisper.cpp writes
+//! `<video name>.srt` next to it. Nothing leaves the machine; the binary and
+//! its model are optional resources installed alongside yt-dlp and ffmpeg.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::error::{DownloadError, Result};
+use crate::platform::{CommandRequest, CommandRunner, ProcessPriority, SystemCommandRunner};
+use crate::storage::inbox::with_sidecars;
+
+/// Sidecar subtitle extensions; a video with any of these is left alone
+const SUBTITLE_EXTENSIONS: &[&str] = &["srt", "vtt", "ass", "ssa"];
+
+/// Automatic transcription of completed videos
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TranscriptionSettings {
+    /// Generate subtitles for completed videos without any
+    #[serde(default)]
+    pub enabled: bool,
+    /// Spoken language as an ISO 639-1 code (`en`, `de`, ...), or `auto` to detect it
+    #[serde(default = "default_language")]
+    pub language: String,
+}
+
+fn default_language() -> String {
+    "auto".to_string()
+}
+
+impl Default for TranscriptionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            language: default_language(),
+        }
+    }
+}
+
+impl TranscriptionSettings {
+    pub fn validate(&self) -> Result<()> {
+        let is_code = (2..=3).contains(&self.language.len())
+            && self.language.chars().all(|c| c.is_ascii_lowercase());
+        if self.language != "auto" && !is_code {
+            return Err(DownloadError::InvalidOption(format!(
+                "Transcription language must be a language code like 'en' or 'auto', got '{}'",
+                self.language
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Runs whisper.cpp over downloaded videos
+pub struct TranscriptionService {
+    whisper_path: PathBuf,
+    model_path: PathBuf,
+    ffmpeg_path: PathBuf,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl TranscriptionService {
+    pub fn new(whisper_path: PathBuf, model_path: PathBuf, ffmpeg_path: PathBuf) -> Self {
+        Self {
+            whisper_path,
+            model_path,
+            ffmpeg_path,
+            runner: Arc::new(SystemCommandRunner),
+        }
+    }
+    
+    /// Replace the process runner (used by tests)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.runner = runner;
+        self
+    }
+    
+    /// Whether the whisper binary and its model are installed
+    pub fn is_available(&self) -> bool {
+        self.whisper_path.is_file() && self.model_path.is_file()
+    }
+    
+    /// Whether `media` already has subtitles, as a sidecar file or an embedded stream
+    pub async fn has_subtitles(&self, media: &Path) -> bool {
+        if !subtitle_files(media).is_empty() {
+            return true;
+        }
+        
+        // ffmpeg with no output lists the streams on stderr and exits non-zero
+        let args = vec!["-hide_banner".to_string(), "-i".to_string(), media.to_string_lossy().to_string()];
+        let request = CommandRequest::new(&self.ffmpeg_path, args).confine_to(media);
+        match self.runner.output(&request).await {
+            Ok(output) => output.stderr.lines().any(|l| l.trim_start().starts_with("Stream #") && l.contains("Subtitle:")),
+            Err(_) => false,
+        }
+    }
+    
+    /// Transcribe `media` into an SRT file next to it, returning the file's path
+    ///
+    /// `threads` caps both ffmpeg and whisper, like other post-processing.
+    pub async fn transcribe(
+        &self,
+        media: &Path,
+        settings: &TranscriptionSettings,
+        priority: ProcessPriority,
+        threads: Option<usize>,
+    ) -> Result<PathBuf> {
+        let stem = media.with_extension("");
+        let audio = PathBuf::from(format!("{}.whisper.wav", stem.display()));
+        
+        let result = async {
+            let mut args: Vec<String> = ["-y", "-loglevel", "error", "-nostats", "-i"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+            args.push(media.to_string_lossy().to_string());
+            args.extend(["-vn", "-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"].iter().map(|s| s.to_string()));
+            if let Some(threads) = threads {
+                args.push("-threads".to_string());
+                args.push(threads.to_string());
+            }
+            args.push(audio.to_string_lossy().to_string());
+            self.run(CommandRequest::new(&self.ffmpeg_path, args), "ffmpeg", media, priority).await?;
+            
+            let mut args = vec![
+                "-m".to_string(),
+                self.model_path.to_string_lossy().to_string(),
+                "-f".to_string(),
+                audio.to_string_lossy().to_string(),
+                "-l".to_string(),
+                settings.language.clone(),
+                "-osrt".to_string(),
+                "-np".to_string(),
+                "-of".to_string(),
+                stem.to_string_lossy().to_string(),
+            ];
+            if let Some(threads) = threads {
+                args.push("-t".to_string());
+                args.push(threads.to_string());
+            }
+            self.run(CommandRequest::new(&self.whisper_path, args), "whisper", media, priority).await
+        }
+        .await;
+        
+        let _ = tokio::fs::remove_file(&audio).await;
+        result?;
+        
+        let subtitles = stem.with_extension("srt");
+        if !subtitles.is_file() {
+            return Err(DownloadError::DownloadFailed(format!(
+                "whisper finished without writing {}",
+                subtitles.display()
+            )));
+        }
+        Ok(subtitles)
+    }
+    
+    /// Run one step next to `media`, mapping failures to download errors
+    async fn run(&self, request: CommandRequest, name: &str, media: &Path, priority: ProcessPriority) -> Result<()> {
+        let request = request.confine_to(media).priority(priority);
+        let output = self.runner.output(&request).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => DownloadError::DependencyMissing(name.to_string()),
+            _ => DownloadError::DownloadFailed(format!("Failed to run {}: {}", name, e)),
+        })?;
+        if !output.success() {
+            return Err(DownloadError::DownloadFailed(format!(
+                "{} failed with code {:?}: {}",
+                name,
+                output.exit_code,
+                output.stderr.trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Subtitle files named after `media` (`video.srt`, `video.en.vtt`, ...) next to it
+pub fn subtitle_files(media: &Path) -> Vec<PathBuf> {
+    with_sidecars(media)
+        .into_iter()
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| SUBTITLE_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{MockCommandRunner, MockResponse};
+    
+    #[test]
+    fn test_validate_language() {
+        assert!(TranscriptionSettings::default().validate().is_ok());
+        for language in ["en", "zh", "haw"] {
+            let settings = TranscriptionSettings { enabled: true, language: language.to_string() };
+            assert!(settings.validate().is_ok(), "{}", language);
+        }
+        for language in ["", "EN", "english", "en; rm"] {
+            let settings = TranscriptionSettings { enabled: true, language: language.to_string() };
+            assert!(settings.validate().is_err(), "{}", language);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subtitles_are_detected_beside_or_inside_the_video() {
+        let dir = tempfile::tempdir().unwrap();
+        let video = dir.path().join("talk.mp4");
+        std::fs::write(&video, b"video").unwrap();
+        std::fs::write(dir.path().join("talk.jpg"), b"thumbnail").unwrap();
+        
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![
+            MockResponse::failure(1, "  Stream #0:0: Video: h264\n  Stream #0:1: Audio: aac"),
+            MockResponse::failure(1, "  Stream #0:0: Video: h264\n  Stream #0:2(eng): Subtitle: mov_text"),
+        ]));
+        let service = TranscriptionService::new(PathBuf::from("whisper-cli"), PathBuf::from("model.bin"), PathBuf::from("ffmpeg"))
+            .with_command_runner(runner.clone());
+        
+        assert!(!service.has_subtitles(&video).await);
+        assert!(service.has_subtitles(&video).await);
+        
+        std::fs::write(dir.path().join("talk.en.vtt"), b"WEBVTT").unwrap();
+        assert!(service.has_subtitles(&video).await);
+        assert_eq!(runner.calls().len(), 2);
+    }
+
+    #[test]
+    fn test_subtitle_files_lists_every_language() {
+        let dir = tempfile::tempdir().unwrap();
+        let video = dir.path().join("talk.mp4");
+        for name in ["talk.mp4", "talk.en.srt", "talk.pt-BR.srt", "talk.jpg", "talks.en.srt"] {
+            std::fs::write(dir.path().join(name), b"x").unwrap();
+        }
+        
+        assert_eq!(subtitle_files(&video), vec![dir.path().join("talk.en.srt"), dir.path().join("talk.pt-BR.srt")]);
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_extracts_audio_then_writes_srt() {
+        let dir = tempfile::tempdir().unwrap();
+        let video = dir.path().join("talk.mp4");
+        std::fs::write(&video, b"video").unwrap();
+        // The mock doesn't run whisper, so the output it would write is already there
+        std::fs::write(dir.path().join("talk.srt"), b"1\n00:00:00,000 --> 00:00:01,000\nHi\n").unwrap();
+        
+        let runner = Arc::new(MockCommandRunner::with_responses(vec![
+            MockResponse::success(""),
+            MockResponse::success(""),
+        ]));
+        let service = TranscriptionService::new(PathBuf::from("whisper-cli"), PathBuf::from("model.bin"), PathBuf::from("ffmpeg"))
+            .with_command_runner(runner.clone());
+        let settings = TranscriptionSettings { enabled: true, language: "en".to_string() };
+        
+        let srt = service.transcribe(&video, &settings, ProcessPriority::Background, Some(2)).await.unwrap();
+        assert_eq!(srt, dir.path().join("talk.srt"));
+        
+        let calls = runner.calls();
+        let wav = dir.path().join("talk.whisper.wav").to_string_lossy().to_string();
+        assert_eq!(calls[0].program, PathBuf::from("ffmpeg"));
+        assert!(calls[0].args.ends_with(&["-threads".to_string(), "2".to_string(), wav.clone()]));
+        assert_eq!(calls[1].program, PathBuf::from("whisper-cli"));
+        assert!(calls[1].args.windows(2).any(|w| w[0] == "-f" && w[1] == wav));
+        assert!(calls[1].args.windows(2).any(|w| w[0] == "-l" && w[1] == "en"));
+        assert!(calls[1].args.windows(2).any(|w| w[0] == "-of" && w[1] == dir.path().join("talk").to_string_lossy()));
+        assert_eq!(calls[1].priority, ProcessPriority::Background);
+    }
+}
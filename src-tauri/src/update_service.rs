@@ -3,14 +3,112 @@ use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use tokio::process::Command;
 use tokio::io::AsyncWriteExt;
+use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
 use crate::error::{DownloadError, Result};
+use crate::checksum_manifest::ChecksumManifest;
 use crate::executable_manager::Architecture;
+use crate::platform::{sandboxed_command, CommandRequest, DohSettings, YouTubeProvider};
+use crate::{log_info, log_warn};
+
+/// Extended attribute macOS puts on downloaded files; Gatekeeper checks files carrying it
+const QUARANTINE_XATTR: &str = "com.apple.quarantine";
+
+/// Shown when macOS refuses to run the new yt-dlp even after the quarantine flag is removed
+const GATEKEEPER_BLOCKED: &str = "macOS blocked the updated yt-dlp (Gatekeeper). \
+Allow it under System Settings > Privacy & Security, or reinstall the app. The previous version is still in place";
+
+/// SIGKILL, which is how macOS stops a binary that fails its code-signing check
+const SIGKILL: i32 = 9;
+
+/// Whether a failed `--version` run looks like macOS refusing to execute the binary
+pub fn blocked_by_gatekeeper(spawn_error: Option<&std::io::Error>, status: Option<std::process::ExitStatus>) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    
+    if !cfg!(target_os = "macos") {
+        return false;
+    }
+    match (spawn_error, status) {
+        (Some(e), _) => e.kind() == std::io::ErrorKind::PermissionDenied,
+        (None, Some(status)) => status.signal() == Some(SIGKILL),
+        (None, None) => false,
+    }
+}
+
+/// Long-lived public video used to check that a new yt-dlp release still works
+pub const REFERENCE_TEST_URL: &str = "https://www.youtube.com/watch?v=jNQXAC9IVRw";
+
+/// Result of checking a freshly installed yt-dlp against the reference video
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum UpdateVerification {
+    /// The new release fetched the reference video
+    Passed,
+    /// The new release failed where the previous one still works; rolling back is recommended
+    Failed { error: String, rollback_available: bool },
+    /// Both releases failed (offline, or the reference video is unreachable), so the update is kept
+    Inconclusive { error: String },
+}
+
+impl UpdateVerification {
+    /// Combine the new release's test with the previous release's, if it was tested
+    pub fn from_results(new_release: &Result<String>, previous_release: Option<&Result<String>>) -> Self {
+        match (new_release, previous_release) {
+            (Ok(_), _) => UpdateVerification::Passed,
+            (Err(e), Some(Ok(_))) => UpdateVerification::Failed {
+                error: e.to_string(),
+                rollback_available: true,
+            },
+            (Err(e), Some(Err(_))) => UpdateVerification::Inconclusive { error: e.to_string() },
+            (Err(e), None) => UpdateVerification::Failed {
+                error: e.to_string(),
+                rollback_available: false,
+            },
+        }
+    }
+}
+
+/// A yt-dlp GitHub release
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReleaseInfo {
+    pub version: String,
+    /// Markdown release notes
+    pub notes: Option<String>,
+    pub url: Option<String>,
+}
+
+impl ReleaseInfo {
+    /// Parse a GitHub `releases/latest` API response
+    pub fn from_github_json(json: &serde_json::Value) -> Result<Self> {
+        let version = json["tag_name"]
+            .as_str()
+            .ok_or_else(|| DownloadError::DownloadFailed("No tag_name in GitHub API response".to_string()))?
+            .to_string();
+        
+        Ok(Self {
+            version,
+            notes: json["body"].as_str().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+            url: json["html_url"].as_str().map(|s| s.to_string()),
+        })
+    }
+}
+
+/// Outcome of `UpdateService::update_verified`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UpdateOutcome {
+    pub message: String,
+    /// Absent when no update was installed
+    pub verification: Option<UpdateVerification>,
+}
 
 /// Service for managing yt-dlp updates
 pub struct UpdateService {
     ytdlp_path: PathBuf,
     arch: Architecture,
+    /// HTTP(S) proxy for GitHub requests
+    proxy: Option<String>,
+    /// DNS-over-HTTPS for GitHub hosts
+    doh: DohSettings,
 }
 
 impl UpdateService {
@@ -19,13 +117,43 @@ impl UpdateService {
         Self {
             ytdlp_path,
             arch,
+            proxy: None,
+            doh: DohSettings::default(),
+        }
+    }
+    
+    /// Route GitHub requests through `proxy`
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Self {
+        self.proxy = proxy.filter(|p| !p.is_empty());
+        self
+    }
+    
+    /// Resolve GitHub hosts with DNS-over-HTTPS when `doh` is enabled
+    pub fn with_dns_over_https(mut self, doh: DohSettings) -> Self {
+        self.doh = doh;
+        self
+    }
+    
+    /// HTTP client for GitHub, using the configured proxy
+    fn http_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder().user_agent("YouTube-Downloader-GUI");
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| DownloadError::InvalidOption(format!("Invalid proxy {}: {}", proxy, e)))?;
+            builder = builder.proxy(proxy);
         }
+        if let Some(resolver) = self.doh.resolver() {
+            builder = builder.dns_resolver(resolver);
+        }
+        
+        builder
+            .build()
+            .map_err(|e| DownloadError::Network(format!("Failed to create HTTP client: {}", e)))
     }
     
     /// Get the current version of yt-dlp
     pub async fn get_current_version(&self) -> Result<String> {
-        let output = Command::new(&self.ytdlp_path)
-            .arg("--version")
+        let output = sandboxed_command(&CommandRequest::new(&self.ytdlp_path, vec!["--version".to_string()]))
             .output()
             .await
             .map_err(|e| DownloadError::DownloadFailed(format!("Failed to get yt-dlp version: {}", e)))?;
@@ -40,11 +168,13 @@ impl UpdateService {
     
     /// Get the latest version available from GitHub
     pub async fn get_latest_version(&self) -> Result<String> {
+        Ok(self.get_latest_release().await?.version)
+    }
+    
+    /// Get the latest release from GitHub, including its release notes
+    pub async fn get_latest_release(&self) -> Result<ReleaseInfo> {
         // Use GitHub API to get the latest release
-        let client = reqwest::Client::builder()
-            .user_agent("YouTube-Downloader-GUI")
-            .build()
-            .map_err(|e| DownloadError::Network(format!("Failed to create HTTP client: {}", e)))?;
+        let client = self.http_client()?;
         
         let response = client
             .get("https://api.github.com/repos/yt-dlp/yt-dlp/releases/latest")
@@ -61,20 +191,20 @@ impl UpdateService {
             .await
             .map_err(|e| DownloadError::Network(format!("Failed to parse GitHub API response: {}", e)))?;
         
-        let version = json["tag_name"]
-            .as_str()
-            .ok_or_else(|| DownloadError::DownloadFailed("No tag_name in GitHub API response".to_string()))?
-            .to_string();
-        
-        Ok(version)
+        ReleaseInfo::from_github_json(&json)
     }
     
     /// Check if an update is available
     pub async fn check_for_update(&self) -> Result<Option<String>> {
+        Ok(self.check_for_update_release().await?.map(|release| release.version))
+    }
+    
+    /// Check if an update is available, returning the release with its notes
+    pub async fn check_for_update_release(&self) -> Result<Option<ReleaseInfo>> {
         let current = self.get_current_version().await?;
-        let latest = self.get_latest_version().await?;
+        let latest = self.get_latest_release().await?;
         
-        if current != latest {
+        if current != latest.version {
             Ok(Some(latest))
         } else {
             Ok(None)
@@ -85,10 +215,7 @@ impl UpdateService {
     async fn download_latest(&self, temp_path: &Path) -> Result<()> {
         let download_url = "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp_macos";
         
-        let client = reqwest::Client::builder()
-            .user_agent("YouTube-Downloader-GUI")
-            .build()
-            .map_err(|e| DownloadError::Network(format!("Failed to create HTTP client: {}", e)))?;
+        let client = self.http_client()?;
         
         let response = client
             .get(download_url)
@@ -147,16 +274,72 @@ impl UpdateService {
         Ok(())
     }
     
+    /// Path of the previous release kept after an update
+    pub fn backup_path(&self) -> PathBuf {
+        self.ytdlp_path.with_extension("backup")
+    }
+    
     /// Update yt-dlp to the latest version
     pub async fn update(&self) -> Result<String> {
-        // Check if update is available
-        let new_version = match self.check_for_update().await? {
+        match self.check_for_update().await? {
+            Some(version) => {
+                self.install_latest().await?;
+                Ok(format!("Updated to version {}", version))
+            }
+            None => Ok("Already up to date".to_string()),
+        }
+    }
+    
+    /// Update yt-dlp, then check the new release against `REFERENCE_TEST_URL`
+    ///
+    /// First pass tests the new release. If it fails, the second pass tests the
+    /// backed-up previous release the same way: only when that one still works
+    /// is the new release blamed and a rollback offered. The backup is removed
+    /// once verification passes and kept otherwise.
+    pub async fn update_verified(&self, ffmpeg_path: &Path) -> Result<UpdateOutcome> {
+        let version = match self.check_for_update().await? {
             Some(version) => version,
-            None => return Ok("Already up to date".to_string()),
+            None => {
+                return Ok(UpdateOutcome {
+                    message: "Already up to date".to_string(),
+                    verification: None,
+                })
+            }
+        };
+        
+        self.install_latest().await?;
+        log_info!("[UpdateService] Installed yt-dlp {}, verifying with {}", version, REFERENCE_TEST_URL);
+        
+        let new_release = YouTubeProvider::with_executables(self.ytdlp_path.clone(), ffmpeg_path.to_path_buf())
+            .test_download(REFERENCE_TEST_URL)
+            .await;
+        let backup_path = self.backup_path();
+        let previous_release = if new_release.is_err() && backup_path.exists() {
+            Some(
+                YouTubeProvider::with_executables(backup_path.clone(), ffmpeg_path.to_path_buf())
+                    .test_download(REFERENCE_TEST_URL)
+                    .await,
+            )
+        } else {
+            None
         };
         
+        let verification = UpdateVerification::from_results(&new_release, previous_release.as_ref());
+        log_info!("[UpdateService] Verification: {:?}", verification);
+        if verification == UpdateVerification::Passed {
+            let _ = fs::remove_file(&backup_path);
+        }
+        
+        Ok(UpdateOutcome {
+            message: format!("Updated to version {}", version),
+            verification: Some(verification),
+        })
+    }
+    
+    /// Download, verify and swap in the latest release, keeping the current one as a backup
+    async fn install_latest(&self) -> Result<()> {
         // Create backup path
-        let backup_path = self.ytdlp_path.with_extension("backup");
+        let backup_path = self.backup_path();
         
         // Create temp path for download
         let temp_path = self.ytdlp_path.with_extension("tmp");
@@ -167,20 +350,28 @@ impl UpdateService {
         // Set executable permissions on temp file
         self.set_executable(&temp_path)?;
         
+        // Let Gatekeeper run the file we just downloaded
+        clear_quarantine(&temp_path).await;
+        
         // Verify the downloaded file works
-        let test_output = Command::new(&temp_path)
-            .arg("--version")
+        let test_output = sandboxed_command(&CommandRequest::new(&temp_path, vec!["--version".to_string()]))
             .output()
             .await
             .map_err(|e| {
                 // Clean up temp file on error
                 let _ = fs::remove_file(&temp_path);
+                if blocked_by_gatekeeper(Some(&e), None) {
+                    return DownloadError::DownloadFailed(GATEKEEPER_BLOCKED.to_string());
+                }
                 DownloadError::DownloadFailed(format!("Downloaded yt-dlp failed verification: {}", e))
             })?;
         
         if !test_output.status.success() {
             // Clean up temp file
             let _ = fs::remove_file(&temp_path);
+            if blocked_by_gatekeeper(None, Some(test_output.status)) {
+                return Err(DownloadError::DownloadFailed(GATEKEEPER_BLOCKED.to_string()));
+            }
             return Err(DownloadError::DownloadFailed("Downloaded yt-dlp failed to run".to_string()));
         }
         
@@ -206,59 +397,32 @@ impl UpdateService {
                 DownloadError::DownloadFailed(format!("Failed to replace yt-dlp: {}", e))
             })?;
         
-        // Remove backup on success
-        if backup_path.exists() {
-            let _ = fs::remove_file(&backup_path);
-        }
-        
         // Update checksums file
-        self.update_checksums_file().await?;
+        self.update_checksums_file()?;
         
-        Ok(format!("Updated to version {}", new_version))
+        Ok(())
     }
     
-    /// Update the CHECKSUMS.txt file with the new yt-dlp checksum
-    async fn update_checksums_file(&self) -> Result<()> {
+    /// Record the installed yt-dlp's checksum in the user manifest
+    ///
+    /// The signed manifest bundled with the app is left alone; startup
+    /// verification accepts yt-dlp matching either one.
+    fn update_checksums_file(&self) -> Result<()> {
         // Get the parent directory (resources/bin)
         let bin_dir = self.ytdlp_path
             .parent()
             .and_then(|p| p.parent())
             .ok_or_else(|| DownloadError::DownloadFailed("Invalid yt-dlp path".to_string()))?;
         
-        let checksums_path = bin_dir.join("CHECKSUMS.txt");
-        
-        // Calculate new checksum
         let new_checksum = self.calculate_checksum(&self.ytdlp_path)?;
-        
-        // Read existing checksums
-        let checksums_content = fs::read_to_string(&checksums_path)
-            .map_err(|e| DownloadError::DownloadFailed(format!("Failed to read checksums file: {}", e)))?;
-        
-        // Update the appropriate line
-        let arch_dir = self.arch.dir_name();
-        let ytdlp_key = format!("{}/yt-dlp", arch_dir);
-        
-        let mut new_content = String::new();
-        for line in checksums_content.lines() {
-            if line.contains(&ytdlp_key) {
-                // Replace with new checksum
-                new_content.push_str(&format!("{}  {}\n", new_checksum, ytdlp_key));
-            } else {
-                new_content.push_str(line);
-                new_content.push('\n');
-            }
-        }
-        
-        // Write updated checksums
-        fs::write(&checksums_path, new_content)
-            .map_err(|e| DownloadError::DownloadFailed(format!("Failed to write checksums file: {}", e)))?;
-        
-        Ok(())
+        let mut manifest = ChecksumManifest::load_user(bin_dir)?;
+        manifest.set(&format!("{}/yt-dlp", self.arch.dir_name()), &new_checksum);
+        manifest.save_user(bin_dir)
     }
     
     /// Rollback to backup version if available
     pub fn rollback(&self) -> Result<()> {
-        let backup_path = self.ytdlp_path.with_extension("backup");
+        let backup_path = self.backup_path();
         
         if !backup_path.exists() {
             return Err(DownloadError::DownloadFailed("No backup available".to_string()));
@@ -267,17 +431,99 @@ impl UpdateService {
         fs::rename(&backup_path, &self.ytdlp_path)
             .map_err(|e| DownloadError::DownloadFailed(format!("Failed to rollback: {}", e)))?;
         
+        // Keep the recorded checksum in line with the restored binary
+        self.update_checksums_file()?;
+        
         Ok(())
     }
 }
 
+/// Remove the quarantine flag from a verified download; a no-op outside macOS
+async fn clear_quarantine(path: &Path) {
+    if !cfg!(target_os = "macos") {
+        return;
+    }
+    match Command::new("xattr").arg("-d").arg(QUARANTINE_XATTR).arg(path).output().await {
+        Ok(output) if !output.status.success() => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // Files fetched by reqwest usually don't carry the flag at all
+            if !stderr.contains("No such xattr") {
+                log_warn!("[Update] Failed to clear quarantine flag on {:?}: {}", path, stderr.trim());
+            }
+        }
+        Ok(_) => log_info!("[Update] Cleared quarantine flag on {:?}", path),
+        Err(e) => log_warn!("[Update] Failed to run xattr: {}", e),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_blocked_by_gatekeeper() {
+        use std::os::unix::process::ExitStatusExt;
+        
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        let not_found = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let killed = std::process::ExitStatus::from_raw(SIGKILL);
+        let failed = std::process::ExitStatus::from_raw(1 << 8);
+        
+        let on_macos = cfg!(target_os = "macos");
+        assert_eq!(blocked_by_gatekeeper(Some(&denied), None), on_macos);
+        assert_eq!(blocked_by_gatekeeper(None, Some(killed)), on_macos);
+        assert!(!blocked_by_gatekeeper(Some(&not_found), None));
+        assert!(!blocked_by_gatekeeper(None, Some(failed)));
+    }
+    
     #[test]
     fn test_architecture_dir_name() {
         assert_eq!(Architecture::X86_64.dir_name(), "x86_64");
         assert_eq!(Architecture::Aarch64.dir_name(), "aarch64");
     }
+
+    #[test]
+    fn test_http_client_rejects_invalid_proxy() {
+        let service = |proxy: &str| UpdateService::new(PathBuf::from("/tmp/yt-dlp"), Architecture::Aarch64)
+            .with_proxy(Some(proxy.to_string()));
+        assert!(service("http://127.0.0.1:8080").http_client().is_ok());
+        assert!(service("not a proxy").http_client().is_err());
+    }
+
+    #[test]
+    fn test_release_info_from_github_json() {
+        let json = serde_json::json!({
+            "tag_name": "2024.08.06",
+            "html_url": "https://github.com/yt-dlp/yt-dlp/releases/tag/2024.08.06",
+            "body": "#### Extractor changes\n- **youtube**: Fix signature extraction\n"
+        });
+        let release = ReleaseInfo::from_github_json(&json).unwrap();
+        assert_eq!(release.version, "2024.08.06");
+        assert_eq!(release.notes.as_deref(), Some("#### Extractor changes\n- **youtube**: Fix signature extraction"));
+        
+        let release = ReleaseInfo::from_github_json(&serde_json::json!({"tag_name": "2024.08.06", "body": "  "})).unwrap();
+        assert_eq!(release.notes, None);
+        assert!(ReleaseInfo::from_github_json(&serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn test_verification_blames_new_release_only_when_previous_works() {
+        let broken: Result<String> = Err(DownloadError::DownloadFailed("extractor error".to_string()));
+        let working: Result<String> = Ok("Me at the zoo".to_string());
+        let offline: Result<String> = Err(DownloadError::Network("offline".to_string()));
+        
+        assert_eq!(UpdateVerification::from_results(&working, None), UpdateVerification::Passed);
+        assert!(matches!(
+            UpdateVerification::from_results(&broken, Some(&working)),
+            UpdateVerification::Failed { rollback_available: true, .. }
+        ));
+        assert!(matches!(
+            UpdateVerification::from_results(&broken, Some(&offline)),
+            UpdateVerification::Inconclusive { .. }
+        ));
+        assert!(matches!(
+            UpdateVerification::from_results(&broken, None),
+            UpdateVerification::Failed { rollback_available: false, .. }
+        ));
+    }
 }
@@ -1,7 +1,11 @@
 // Integration tests for download queue persistence and management
 // These tests verify queue state management without requiring full Tauri runtime
 
-use youtube_downloader_gui::download::{DownloadItem, DownloadStatus};
+use std::sync::Arc;
+use youtube_downloader_gui::download::{DownloadItem, DownloadManager, DownloadStatus};
+use youtube_downloader_gui::events::NullEventSink;
+use youtube_downloader_gui::paths::FixedPathProvider;
+use youtube_downloader_gui::platform::PlatformRegistry;
 use youtube_downloader_gui::storage::settings::QueueState;
 
 #[test]
@@ -29,6 +33,26 @@ fn test_queue_state_add_items() {
         error: None,
         url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
         platform: "YouTube".to_string(),
+        postprocessor_args: None,
+        subtitle_languages: None,
+        auto_subtitles: None,
+        embed_chapters: None,
+        scheduled_at: None,
+        playlist_id: None,
+        record_live: false,
+        premiere_at: None,
+        section_start: None,
+        section_end: None,
+        audio_only: false,
+        format_id: None,
+        album: None,
+        track_number: None,
+        uploader: None,
+        quality: None,
+        variant_group: None,
+        estimated_size: None,
+        request_id: None,
+        liveness: None,
     };
     
     queue.items.push(item);
@@ -55,6 +79,26 @@ fn test_queue_state_serialization() {
         error: None,
         url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
         platform: "YouTube".to_string(),
+        postprocessor_args: None,
+        subtitle_languages: None,
+        auto_subtitles: None,
+        embed_chapters: None,
+        scheduled_at: None,
+        playlist_id: None,
+        record_live: false,
+        premiere_at: None,
+        section_start: None,
+        section_end: None,
+        audio_only: false,
+        format_id: None,
+        album: None,
+        track_number: None,
+        uploader: None,
+        quality: None,
+        variant_group: None,
+        estimated_size: None,
+        request_id: None,
+        liveness: None,
     };
     
     queue.items.push(item);
@@ -87,6 +131,26 @@ fn test_download_item_status_transitions() {
         error: None,
         url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
         platform: "YouTube".to_string(),
+        postprocessor_args: None,
+        subtitle_languages: None,
+        auto_subtitles: None,
+        embed_chapters: None,
+        scheduled_at: None,
+        playlist_id: None,
+        record_live: false,
+        premiere_at: None,
+        section_start: None,
+        section_end: None,
+        audio_only: false,
+        format_id: None,
+        album: None,
+        track_number: None,
+        uploader: None,
+        quality: None,
+        variant_group: None,
+        estimated_size: None,
+        request_id: None,
+        liveness: None,
     };
     
     // Queued -> Downloading
@@ -123,6 +187,26 @@ fn test_download_item_with_error() {
         error: Some("Network error: Connection timeout".to_string()),
         url: "https://www.youtube.com/watch?v=invalid".to_string(),
         platform: "YouTube".to_string(),
+        postprocessor_args: None,
+        subtitle_languages: None,
+        auto_subtitles: None,
+        embed_chapters: None,
+        scheduled_at: None,
+        playlist_id: None,
+        record_live: false,
+        premiere_at: None,
+        section_start: None,
+        section_end: None,
+        audio_only: false,
+        format_id: None,
+        album: None,
+        track_number: None,
+        uploader: None,
+        quality: None,
+        variant_group: None,
+        estimated_size: None,
+        request_id: None,
+        liveness: None,
     };
     
     assert_eq!(item.status, DownloadStatus::Failed);
@@ -157,6 +241,26 @@ fn test_queue_state_multiple_items() {
             error: if i == 4 { Some("Test error".to_string()) } else { None },
             url: format!("https://www.youtube.com/watch?v=video-{}", i),
             platform: "YouTube".to_string(),
+            postprocessor_args: None,
+            subtitle_languages: None,
+            auto_subtitles: None,
+            embed_chapters: None,
+            scheduled_at: None,
+            playlist_id: None,
+            record_live: false,
+            premiere_at: None,
+            section_start: None,
+            section_end: None,
+            audio_only: false,
+            format_id: None,
+            album: None,
+            track_number: None,
+            uploader: None,
+            quality: None,
+            variant_group: None,
+            estimated_size: None,
+            request_id: None,
+            liveness: None,
         };
         
         queue.items.push(item);
@@ -196,6 +300,26 @@ fn test_queue_state_restore_after_crash() {
         error: None,
         url: "https://www.youtube.com/watch?v=video-1".to_string(),
         platform: "YouTube".to_string(),
+        postprocessor_args: None,
+        subtitle_languages: None,
+        auto_subtitles: None,
+        embed_chapters: None,
+        scheduled_at: None,
+        playlist_id: None,
+        record_live: false,
+        premiere_at: None,
+        section_start: None,
+        section_end: None,
+        audio_only: false,
+        format_id: None,
+        album: None,
+        track_number: None,
+        uploader: None,
+        quality: None,
+        variant_group: None,
+        estimated_size: None,
+        request_id: None,
+        liveness: None,
     };
     
     let item2 = DownloadItem {
@@ -211,6 +335,26 @@ fn test_queue_state_restore_after_crash() {
         error: None,
         url: "https://www.youtube.com/watch?v=video-2".to_string(),
         platform: "YouTube".to_string(),
+        postprocessor_args: None,
+        subtitle_languages: None,
+        auto_subtitles: None,
+        embed_chapters: None,
+        scheduled_at: None,
+        playlist_id: None,
+        record_live: false,
+        premiere_at: None,
+        section_start: None,
+        section_end: None,
+        audio_only: false,
+        format_id: None,
+        album: None,
+        track_number: None,
+        uploader: None,
+        quality: None,
+        variant_group: None,
+        estimated_size: None,
+        request_id: None,
+        liveness: None,
     };
     
     queue.items.push(item1);
@@ -244,3 +388,111 @@ fn test_download_status_equality() {
     assert_ne!(DownloadStatus::Queued, DownloadStatus::Downloading);
     assert_ne!(DownloadStatus::Completed, DownloadStatus::Failed);
 }
+
+#[test]
+fn test_scheduled_item_is_due_only_after_start_time() {
+    let mut item = DownloadItem {
+        id: "test-1".to_string(),
+        video_id: "dQw4w9WgXcQ".to_string(),
+        title: "Scheduled Video".to_string(),
+        thumbnail: String::new(),
+        status: DownloadStatus::Queued,
+        progress: 0.0,
+        speed: 0.0,
+        eta: 0,
+        save_path: "/Users/test/Downloads".to_string(),
+        error: None,
+        url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
+        platform: "YouTube".to_string(),
+        postprocessor_args: None,
+        subtitle_languages: None,
+        auto_subtitles: None,
+        embed_chapters: None,
+        scheduled_at: None,
+        playlist_id: None,
+        record_live: false,
+        premiere_at: None,
+        section_start: None,
+        section_end: None,
+        audio_only: false,
+        format_id: None,
+        album: None,
+        track_number: None,
+        uploader: None,
+        quality: None,
+        variant_group: None,
+        estimated_size: None,
+        request_id: None,
+        liveness: None,
+    };
+    let now = chrono::Utc::now();
+    assert!(item.is_due(now));
+    
+    item.scheduled_at = Some((now + chrono::Duration::hours(2)).to_rfc3339());
+    assert!(!item.is_due(now));
+    assert!(item.is_due(now + chrono::Duration::hours(3)));
+    
+    // The start time survives a queue save/restore round trip
+    let json = serde_json::to_string(&item).unwrap();
+    assert!(json.contains("scheduledAt"));
+    let restored: DownloadItem = serde_json::from_str(&json).unwrap();
+    assert!(!restored.is_due(now));
+}
+
+#[tokio::test]
+async fn test_manager_saves_and_restores_queue_without_tauri() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let manager = DownloadManager::new(
+        Arc::new(FixedPathProvider::new(data_dir.path())),
+        Arc::new(NullEventSink),
+        Arc::new(PlatformRegistry::new()),
+    );
+    
+    let item = DownloadItem {
+        id: "paused-1".to_string(),
+        video_id: "dQw4w9WgXcQ".to_string(),
+        title: "Paused Video".to_string(),
+        thumbnail: String::new(),
+        status: DownloadStatus::Paused,
+        progress: 42.0,
+        speed: 0.0,
+        eta: 0,
+        save_path: data_dir.path().to_string_lossy().to_string(),
+        error: None,
+        url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
+        platform: "YouTube".to_string(),
+        postprocessor_args: None,
+        subtitle_languages: None,
+        auto_subtitles: None,
+        embed_chapters: None,
+        scheduled_at: None,
+        playlist_id: None,
+        record_live: false,
+        premiere_at: None,
+        section_start: None,
+        section_end: None,
+        audio_only: false,
+        format_id: None,
+        album: None,
+        track_number: None,
+        uploader: None,
+        quality: None,
+        variant_group: None,
+        estimated_size: None,
+        request_id: None,
+        liveness: None,
+    };
+    let queue_file = data_dir.path().join("queue.json");
+    std::fs::write(&queue_file, serde_json::to_string(&vec![item]).unwrap()).unwrap();
+    
+    manager.restore_queue_state().await.unwrap();
+    let queue = manager.get_queue_status().await;
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0].status, DownloadStatus::Paused);
+    
+    std::fs::remove_file(&queue_file).unwrap();
+    manager.save_queue_state().await.unwrap();
+    let saved: Vec<DownloadItem> = serde_json::from_str(&std::fs::read_to_string(&queue_file).unwrap()).unwrap();
+    assert_eq!(saved[0].id, "paused-1");
+    assert_eq!(saved[0].progress, 42.0);
+}
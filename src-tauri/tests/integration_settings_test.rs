@@ -2,6 +2,7 @@
 // These tests verify that settings can be saved and loaded correctly
 
 use youtube_downloader_gui::storage::settings::{AppSettings, CompletedDownload, DownloadHistory};
+use youtube_downloader_gui::platform::PostprocessLimits;
 use std::collections::HashMap;
 
 #[test]
@@ -15,6 +16,25 @@ fn test_app_settings_default() {
     assert_eq!(settings.max_retry_attempts, 3);
     assert_eq!(settings.enabled_platforms, vec!["YouTube".to_string()]);
     assert!(!settings.first_launch_completed);
+    assert!(settings.mp4_faststart);
+}
+
+#[test]
+fn test_app_settings_faststart_defaults_on_for_existing_settings() {
+    let json = r#"{
+        "default_save_path": "/Users/test/Downloads",
+        "default_quality": "best",
+        "default_format": "mp4",
+        "max_concurrent_downloads": 3,
+        "auto_retry_on_failure": true,
+        "max_retry_attempts": 3,
+        "platform_settings": {},
+        "enabled_platforms": ["YouTube"]
+    }"#;
+    
+    let settings: AppSettings = serde_json::from_str(json).unwrap();
+    assert!(settings.mp4_faststart);
+    assert_eq!(settings.postprocess_limits, PostprocessLimits::default());
 }
 
 #[test]
@@ -52,6 +72,23 @@ fn test_app_settings_platform_settings() {
     assert_eq!(yt_settings.get("max_resolution").unwrap(), &serde_json::json!("1080p"));
 }
 
+#[test]
+fn test_youtube_subtitle_language_setting() {
+    let mut settings = AppSettings::default();
+    assert!(settings.youtube_subtitle_languages().is_empty());
+    
+    let mut youtube_settings = HashMap::new();
+    youtube_settings.insert("youtube_subtitle_language".to_string(), serde_json::json!("none"));
+    settings.platform_settings.insert("YouTube".to_string(), youtube_settings.clone());
+    assert!(settings.youtube_subtitle_languages().is_empty());
+    
+    youtube_settings.insert("youtube_subtitle_language".to_string(), serde_json::json!("zh-CN"));
+    youtube_settings.insert("youtube_auto_subtitles".to_string(), serde_json::json!(true));
+    settings.platform_settings.insert("YouTube".to_string(), youtube_settings);
+    assert_eq!(settings.youtube_subtitle_languages(), vec!["zh-CN".to_string()]);
+    assert!(settings.youtube_auto_subtitles());
+}
+
 #[test]
 fn test_download_history_default() {
     let history = DownloadHistory::default();
@@ -70,6 +107,13 @@ fn test_download_history_add_download() {
         save_path: "/Users/test/Downloads/video.mp4".to_string(),
         file_size: 1024 * 1024 * 50, // 50 MB
         platform: "YouTube".to_string(),
+        tombstoned_at: None,
+        review: None,
+        library_path: None,
+        uploader: None,
+        audio_only: false,
+        starred: false,
+        subtitles: Vec::new(),
     };
     
     history.downloads.push(download.clone());
@@ -91,6 +135,13 @@ fn test_download_history_serialization() {
         save_path: "/Users/test/Downloads/video.mp4".to_string(),
         file_size: 1024 * 1024 * 50,
         platform: "YouTube".to_string(),
+        tombstoned_at: None,
+        review: None,
+        library_path: None,
+        uploader: None,
+        audio_only: false,
+        starred: false,
+        subtitles: Vec::new(),
     };
     
     history.downloads.push(download);
@@ -116,6 +167,13 @@ fn test_completed_download_serialization() {
         save_path: "/Users/test/Downloads/video.mp4".to_string(),
         file_size: 52428800, // 50 MB
         platform: "YouTube".to_string(),
+        tombstoned_at: None,
+        review: None,
+        library_path: None,
+        uploader: None,
+        audio_only: false,
+        starred: false,
+        subtitles: Vec::new(),
     };
     
     let json = serde_json::to_string(&download).unwrap();
@@ -153,3 +211,36 @@ fn test_app_settings_validation() {
     settings.max_concurrent_downloads = 10;
     assert_eq!(settings.max_concurrent_downloads, 10); // Should be clamped by manager
 }
+
+#[test]
+fn test_concurrent_fragments_validation() {
+    let mut settings = AppSettings::default();
+    assert_eq!(settings.concurrent_fragments, 1);
+    assert!(settings.validate().is_ok());
+    
+    settings.concurrent_fragments = 16;
+    assert!(settings.validate().is_ok());
+    settings.concurrent_fragments = 0;
+    assert!(settings.validate().is_err());
+    settings.concurrent_fragments = 17;
+    assert!(settings.validate().is_err());
+}
+
+#[test]
+fn test_metadata_concurrency_validation() {
+    let mut settings = AppSettings::default();
+    settings.metadata_concurrency.insert("YouTube".to_string(), 4);
+    assert!(settings.validate().is_ok());
+    
+    settings.metadata_concurrency.insert("Kick".to_string(), 0);
+    assert!(settings.validate().is_err());
+}
+
+#[test]
+fn test_completed_download_without_tombstone_field() {
+    // History written before tombstone tracking must still load
+    let json = r#"{"id":"test-id","video_id":"dQw4w9WgXcQ","title":"Test Video","completed_at":"2024-01-01T00:00:00Z","save_path":"/tmp/video.mp4","file_size":1,"platform":"YouTube"}"#;
+    let deserialized: CompletedDownload = serde_json::from_str(json).unwrap();
+    
+    assert!(deserialized.tombstoned_at.is_none());
+}
@@ -139,6 +139,7 @@ async fn test_youtube_download_with_progress() {
         quality: "best".to_string(),
         format: "mp4".to_string(),
         audio_only: false,
+        ..Default::default()
     };
     let result = provider.download_video(
         url,
@@ -203,6 +204,7 @@ async fn test_youtube_download_invalid_url() {
         quality: "best".to_string(),
         format: "mp4".to_string(),
         audio_only: false,
+        ..Default::default()
     };
     let result = provider.download_video(
         url,
@@ -242,6 +244,7 @@ async fn test_youtube_download_unavailable_video() {
         quality: "best".to_string(),
         format: "mp4".to_string(),
         audio_only: false,
+        ..Default::default()
     };
     let result = provider.download_video(
         url,
@@ -327,6 +330,7 @@ async fn test_youtube_download_progress_tracking() {
         quality: "best".to_string(),
         format: "mp4".to_string(),
         audio_only: false,
+        ..Default::default()
     };
     let result = provider.download_video(
         url,
@@ -1,5 +1,6 @@
 use std::sync::Arc;
-use youtube_downloader_gui::platform::{PlatformProvider, PlatformRegistry, YouTubeProvider};
+use youtube_downloader_gui::error::ErrorType;
+use youtube_downloader_gui::platform::{GenericProvider, PeerTubeProvider, PlatformProvider, PlatformRegistry, YouTubeProvider};
 
 #[test]
 fn test_registry_new() {
@@ -100,3 +101,68 @@ fn test_registry_default() {
     let registry = PlatformRegistry::default();
     assert_eq!(registry.get_all_providers().len(), 0);
 }
+
+#[tokio::test]
+async fn test_registry_resolve_url_normalizes() {
+    let mut registry = PlatformRegistry::new();
+    registry.register(Arc::new(YouTubeProvider::new()));
+    
+    let (provider, url) = registry
+        .resolve_url("  https://www.youtube.com/watch?v=dQw4w9WgXcQ&feature=share ")
+        .await
+        .unwrap();
+    assert_eq!(provider.name(), "YouTube");
+    assert_eq!(url, "https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+}
+
+#[tokio::test]
+async fn test_registry_resolve_url_errors() {
+    let mut registry = PlatformRegistry::new();
+    registry.register(Arc::new(YouTubeProvider::new()));
+    
+    // Malformed URLs fail generic validation
+    let err = registry.resolve_url("not a url").await.err().unwrap();
+    assert_eq!(err.error_type(), ErrorType::InvalidUrl);
+    
+    // Well-formed URLs without a provider are reported as unsupported
+    let err = registry.resolve_url("https://vimeo.com/123456").await.err().unwrap();
+    assert_eq!(err.error_type(), ErrorType::PlatformNotSupported);
+}
+
+#[tokio::test]
+async fn test_registry_resolve_url_falls_back_from_unconfirmed_peertube_host() {
+    let mut registry = PlatformRegistry::new();
+    let generic = Arc::new(GenericProvider::new());
+    generic.set_enabled(true);
+    registry.register(Arc::new(PeerTubeProvider::new()));
+    registry.register(generic);
+    
+    // The path looks like a PeerTube channel, but nothing answers the instance probe
+    let url = "http://localhost:1/c/some_channel";
+    assert_eq!(registry.detect_provider(url).unwrap().name(), "PeerTube");
+    let (provider, _) = registry.resolve_url(url).await.unwrap();
+    assert_eq!(provider.name(), "Generic");
+    
+    // The failed probe is remembered, so detection skips PeerTube from then on
+    assert_eq!(registry.detect_provider(url).unwrap().name(), "Generic");
+}
+
+#[test]
+fn test_registry_detection_respects_registration_order() {
+    let mut registry = PlatformRegistry::new();
+    let generic = Arc::new(GenericProvider::new());
+    generic.set_enabled(true);
+    
+    registry.register(Arc::new(YouTubeProvider::new()));
+    registry.register(generic);
+    
+    // The specific provider wins even though the generic one also matches
+    let detected = registry.detect_provider("https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+    assert_eq!(detected.unwrap().name(), "YouTube");
+    
+    let detected = registry.detect_provider("https://vimeo.com/123456");
+    assert_eq!(detected.unwrap().name(), "Generic");
+    
+    let names: Vec<String> = registry.get_all_providers().iter().map(|p| p.name().to_string()).collect();
+    assert_eq!(names, vec!["YouTube", "Generic"]);
+}
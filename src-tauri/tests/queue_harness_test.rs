@@ -0,0 +1,401 @@
+// Deterministic queue tests on the mock provider and tokio's virtual clock
+// Run with: cargo test --features test-harness --test queue_harness_test
+#![cfg(feature = "test-harness")]
+
+use std::time::Duration;
+use youtube_downloader_gui::download::{DownloadStatus, QuickWins};
+use youtube_downloader_gui::testing::QueueHarness;
+
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn all_completed(queue: &[youtube_downloader_gui::download::DownloadItem]) -> bool {
+    queue.iter().all(|i| i.status == DownloadStatus::Completed)
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_concurrency_limit_is_respected() {
+    let harness = QueueHarness::new();
+    harness.manager.set_max_concurrent(2).await;
+    
+    let items = (1..=4).map(|i| harness.item(&format!("video{}", i), None)).collect();
+    harness.manager.add_to_queue(items).await.unwrap();
+    
+    let mut peak = 0;
+    let deadline = tokio::time::Instant::now() + SETTLE_TIMEOUT;
+    while !all_completed(&harness.manager.get_queue_status().await) {
+        assert!(tokio::time::Instant::now() < deadline, "queue did not finish");
+        let downloading = harness.manager.get_queue_status().await
+            .iter()
+            .filter(|i| i.status == DownloadStatus::Downloading)
+            .count();
+        peak = peak.max(downloading);
+        assert!(harness.manager.active_download_count().await <= 2);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    
+    assert_eq!(peak, 2);
+    assert_eq!(harness.events.payloads("download:complete").len(), 4);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_pause_and_resume() {
+    let harness = QueueHarness::new();
+    harness.manager.add_to_queue(vec![harness.item("video1", None)]).await.unwrap();
+    
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |q| q[0].status == DownloadStatus::Downloading && q[0].progress > 0.0).await);
+    harness.manager.pause_download("video1").await.unwrap();
+    
+    // The stopped download must not overwrite the paused status when it winds down
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(harness.status("video1").await, Some(DownloadStatus::Paused));
+    assert_eq!(harness.manager.active_download_count().await, 0);
+    
+    harness.manager.resume_download("video1").await.unwrap();
+    assert!(harness.wait_for(SETTLE_TIMEOUT, all_completed).await);
+    
+    let liveness = harness.manager.get_queue_status().await[0].liveness.clone().unwrap();
+    assert_eq!(liveness.restart_count, 1);
+    assert!(liveness.last_progress_at.is_some());
+    assert_eq!(liveness.pid, None);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_cancel_stops_download() {
+    let harness = QueueHarness::new();
+    harness.manager.add_to_queue(vec![harness.item("video1", None)]).await.unwrap();
+    
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |q| q[0].status == DownloadStatus::Downloading).await);
+    harness.manager.cancel_download("video1").await.unwrap();
+    
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(harness.status("video1").await, Some(DownloadStatus::Cancelled));
+    assert!(harness.events.payloads("download:complete").is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_remove_cancels_and_deletes_partial_files() {
+    let harness = QueueHarness::new();
+    harness.manager.add_to_queue(vec![harness.item("video1", None), harness.item("video2", None)]).await.unwrap();
+    
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |q| q[0].status == DownloadStatus::Downloading).await);
+    let part = harness.data_dir().join("Mock Video video1 [video1].mp4.part");
+    std::fs::write(&part, b"partial").unwrap();
+    
+    harness.manager.remove_from_queue("video1", true).await.unwrap();
+    assert!(!part.exists());
+    assert!(harness.manager.remove_from_queue("video1", true).await.is_err());
+    
+    // The removed download must not come back when it winds down
+    assert!(harness.wait_for(SETTLE_TIMEOUT, all_completed).await);
+    let queue = harness.manager.get_queue_status().await;
+    assert_eq!(queue.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(), vec!["video2"]);
+    assert_eq!(harness.events.payloads("download:complete").len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_clear_completed_failed_and_all() {
+    let harness = QueueHarness::new();
+    harness.manager.set_max_concurrent(3).await;
+    let items = vec![
+        harness.item("video1", None),
+        harness.item("video2", Some("fail_at=50")),
+        harness.item("video3", None),
+    ];
+    harness.manager.add_to_queue(items).await.unwrap();
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+    
+    assert_eq!(harness.manager.clear_completed().await, 2);
+    let queue = harness.manager.get_queue_status().await;
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0].status, DownloadStatus::Failed);
+    assert_eq!(harness.manager.clear_failed().await, 1);
+    
+    harness.manager.add_to_queue(vec![harness.item("video4", None)]).await.unwrap();
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |q| q[0].status == DownloadStatus::Downloading).await);
+    assert_eq!(harness.manager.clear_queue().await, 1);
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert!(harness.manager.get_queue_status().await.is_empty());
+    assert_eq!(harness.manager.active_download_count().await, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_network_failures_requeue_and_hold_queue() {
+    let harness = QueueHarness::new();
+    harness.manager.set_max_concurrent(4).await;
+    
+    let items = (1..=4).map(|i| harness.item(&format!("video{}", i), Some("fail_at=50"))).collect();
+    harness.manager.add_to_queue(items).await.unwrap();
+    
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |_| !harness.events.payloads("queue:outage").is_empty()).await);
+    
+    // Every failed item goes back to the queue instead of failing, and nothing restarts
+    let queue = harness.manager.get_queue_status().await;
+    assert!(queue.iter().all(|i| i.status == DownloadStatus::Queued), "{:?}", queue.iter().map(|i| &i.status).collect::<Vec<_>>());
+    assert!(harness.manager.dashboard_snapshot().await.held);
+    
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(harness.manager.active_download_count().await, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_single_failure_is_reported() {
+    let harness = QueueHarness::new();
+    harness.manager.add_to_queue(vec![
+        harness.item("broken", Some("fail_at=50")),
+        harness.item("fine", None),
+    ]).await.unwrap();
+    
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+    assert_eq!(harness.status("broken").await, Some(DownloadStatus::Failed));
+    assert_eq!(harness.status("fine").await, Some(DownloadStatus::Completed));
+    assert_eq!(harness.manager.error_stats(None).await.total, 1);
+    
+    let stats = harness.manager.platform_stats("Mock").await;
+    assert_eq!((stats.completed, stats.failed), (1, 1));
+    assert_eq!(stats.bytes, 4 * 1024 * 1024);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_crashed_download_is_failed_and_frees_its_slot() {
+    let harness = QueueHarness::new();
+    harness.manager.set_max_concurrent(1).await;
+    harness.manager.add_to_queue(vec![
+        harness.item("crash", Some("panic_at=50")),
+        harness.item("next", None),
+    ]).await.unwrap();
+    
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+    assert_eq!(harness.status("crash").await, Some(DownloadStatus::Failed));
+    assert_eq!(harness.status("next").await, Some(DownloadStatus::Completed));
+    let errors = harness.events.payloads("download:error");
+    assert!(errors[0]["error"].as_str().unwrap().contains("stopped unexpectedly"));
+    assert_eq!(harness.manager.active_download_count().await, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_hung_download_is_failed_by_watchdog() {
+    let harness = QueueHarness::new();
+    harness.manager.set_max_concurrent(1).await;
+    harness.manager.add_to_queue(vec![
+        harness.item("hang", Some("hang_at=50")),
+        harness.item("next", None),
+    ]).await.unwrap();
+    
+    // The task keeps running but reports nothing, well within the download timeout
+    assert!(harness.wait_for(Duration::from_secs(15 * 60), |q| q[0].status == DownloadStatus::Failed).await);
+    let errors = harness.events.payloads("download:error");
+    assert!(errors[0]["error"].as_str().unwrap().contains("no progress"));
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+    assert_eq!(harness.status("next").await, Some(DownloadStatus::Completed));
+    assert_eq!(harness.manager.active_download_count().await, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_scheduled_item_waits_for_start_time() {
+    let harness = QueueHarness::new();
+    let mut later = harness.item("later", None);
+    later.scheduled_at = Some((chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339());
+    harness.manager.add_to_queue(vec![later, harness.item("now", None)]).await.unwrap();
+    
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |q| q.iter().any(|i| i.id == "now" && i.status == DownloadStatus::Completed)).await);
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert_eq!(harness.status("later").await, Some(DownloadStatus::Queued));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_missing_ids_are_generated_and_duplicates_rejected() {
+    let harness = QueueHarness::new();
+    let ids = harness.manager.add_to_queue(vec![
+        harness.item("", None),
+        harness.item("video1", None),
+        harness.item("  ", None),
+    ]).await.unwrap();
+    
+    assert_eq!(ids.len(), 3);
+    assert_eq!(ids[1], "video1");
+    assert_ne!(ids[0], ids[2]);
+    assert!(ids.iter().all(|id| !id.trim().is_empty()));
+    
+    // Already queued, or repeated within the batch
+    assert!(harness.manager.add_to_queue(vec![harness.item("video1", None)]).await.is_err());
+    assert!(harness.manager.add_to_queue(vec![harness.item("video2", None), harness.item("video2", None)]).await.is_err());
+    assert_eq!(harness.manager.get_queue_status().await.len(), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_premiere_waits_with_countdown() {
+    let harness = QueueHarness::new();
+    let mut premiere = harness.item("premiere", None);
+    premiere.premiere_at = Some((chrono::Utc::now() + chrono::Duration::minutes(10)).to_rfc3339());
+    harness.manager.add_to_queue(vec![premiere]).await.unwrap();
+    
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |_| !harness.events.payloads("download:waiting").is_empty()).await);
+    let countdown = &harness.events.payloads("download:waiting")[0];
+    assert_eq!(countdown["id"], "premiere");
+    assert!(countdown["secondsRemaining"].as_i64().unwrap() > 0);
+    
+    // The mock provider sends data right away, which ends the wait
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+    let statuses: Vec<_> = harness.events.payloads("download:status_change")
+        .into_iter()
+        .map(|p| p["status"].as_str().unwrap_or_default().to_string())
+        .collect();
+    assert_eq!(statuses, ["waiting", "downloading", "completed"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_repeated_add_request_is_ignored() {
+    let harness = QueueHarness::new();
+    let first = harness.manager.add_to_queue_once("click-1", vec![harness.item("", None)]).await.unwrap();
+    let repeat = harness.manager.add_to_queue_once("click-1", vec![harness.item("", None)]).await.unwrap();
+    assert_eq!(first, repeat);
+    assert_eq!(harness.manager.get_queue_status().await.len(), 1);
+    
+    harness.manager.add_to_queue_once("click-2", vec![harness.item("", None)]).await.unwrap();
+    assert_eq!(harness.manager.get_queue_status().await.len(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_variant_group_reports_combined_progress() {
+    let harness = QueueHarness::new();
+    let mut video = harness.item("video", None);
+    video.quality = Some("1080p".to_string());
+    let mut audio = harness.item("audio", None);
+    audio.audio_only = true;
+    for item in [&mut video, &mut audio] {
+        item.variant_group = Some("group".to_string());
+    }
+    harness.manager.add_to_queue(vec![video, audio, harness.item("other", None)]).await.unwrap();
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+    
+    let updates = harness.events.payloads("download:group_progress");
+    assert!(!updates.is_empty());
+    assert!(updates.iter().all(|u| u["group"] == "group" && u["itemIds"].as_array().unwrap().len() == 2));
+    let last = updates.last().unwrap();
+    assert_eq!(last["completed"], 2);
+    assert_eq!(last["finished"], true);
+    
+    let progress = harness.manager.variant_group_progress("group").await.unwrap();
+    assert_eq!(progress.progress, 100.0);
+    
+    let mut invalid = harness.item("invalid", None);
+    invalid.quality = Some("hd".to_string());
+    assert!(harness.manager.add_to_queue(vec![invalid]).await.is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_retry_failed_download() {
+    let harness = QueueHarness::new();
+    harness.manager.add_to_queue(vec![
+        harness.item("broken", Some("fail_at=50")),
+        harness.item("fine", None),
+    ]).await.unwrap();
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+    
+    assert!(harness.manager.retry_download("fine").await.is_err());
+    assert!(harness.manager.retry_download("missing").await.is_err());
+    harness.manager.retry_download("broken").await.unwrap();
+    let broken = harness.manager.get_queue_status().await.into_iter().find(|i| i.id == "broken").unwrap();
+    assert_eq!(broken.status, DownloadStatus::Queued);
+    assert!(broken.error.is_none());
+    
+    // The mock fails it again, so it can be retried in bulk too
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+    assert_eq!(harness.status("broken").await, Some(DownloadStatus::Failed));
+    assert_eq!(harness.manager.error_stats(None).await.total, 2);
+    assert_eq!(harness.manager.retry_failed().await, 1);
+    assert_eq!(harness.manager.retry_failed().await, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_pause_resume_and_cancel_all() {
+    let harness = QueueHarness::new();
+    harness.manager.set_max_concurrent(2).await;
+    let items = (1..=4).map(|i| harness.item(&format!("video{}", i), None)).collect();
+    harness.manager.add_to_queue(items).await.unwrap();
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |q| q.iter().any(|i| i.status == DownloadStatus::Downloading)).await);
+    
+    // One queue update for the whole batch, no per-item status changes
+    let updates = harness.events.payloads("queue:update").len();
+    let status_changes = harness.events.payloads("download:status_change").len();
+    assert_eq!(harness.manager.pause_all().await, 4);
+    assert_eq!(harness.events.payloads("queue:update").len(), updates + 1);
+    assert_eq!(harness.events.payloads("download:status_change").len(), status_changes);
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(harness.manager.active_download_count().await, 0);
+    assert!(harness.manager.get_queue_status().await.iter().all(|i| i.status == DownloadStatus::Paused));
+    
+    assert_eq!(harness.manager.resume_all().await, 4);
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+    assert!(harness.manager.get_queue_status().await.iter().all(|i| i.status == DownloadStatus::Completed));
+    
+    harness.manager.add_to_queue(vec![harness.item("video5", None), harness.item("video6", None)]).await.unwrap();
+    assert_eq!(harness.manager.cancel_all().await, 2);
+    assert_eq!(harness.manager.cancel_all().await, 0);
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(harness.status("video5").await, Some(DownloadStatus::Cancelled));
+    assert_eq!(harness.manager.active_download_count().await, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_quick_wins_start_small_items_past_the_limit() {
+    let harness = QueueHarness::new();
+    harness.manager.set_max_concurrent(1).await;
+    harness.manager.set_quick_wins(QuickWins { enabled: true, ..Default::default() }).await;
+    let mut small = harness.item("small", None);
+    small.estimated_size = Some(4 * 1024 * 1024);
+    harness.manager.add_to_queue(vec![
+        harness.item("large1", None),
+        harness.item("large2", None),
+        small,
+    ]).await.unwrap();
+    
+    // The small item overtakes large2, which still waits for the regular slot
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |q| q.iter().filter(|i| i.status == DownloadStatus::Downloading).count() == 2).await);
+    assert_eq!(harness.status("small").await, Some(DownloadStatus::Downloading));
+    assert_eq!(harness.status("large2").await, Some(DownloadStatus::Queued));
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_quick_wins_go_by_the_size_of_the_chosen_format() {
+    let harness = QueueHarness::new();
+    harness.manager.set_max_concurrent(1).await;
+    harness.manager.set_quick_wins(QuickWins { enabled: true, max_size_mb: 1, ..Default::default() }).await;
+    // Mock videos are 4 MiB, their audio format a tenth of that
+    harness.manager.add_to_queue(vec![
+        harness.item_from_info("large1", None).await,
+        harness.item_from_info("large2", None).await,
+        harness.item_from_info("small", Some("mock-audio")).await,
+    ]).await.unwrap();
+    
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |q| q.iter().filter(|i| i.status == DownloadStatus::Downloading).count() == 2).await);
+    assert_eq!(harness.status("small").await, Some(DownloadStatus::Downloading));
+    assert_eq!(harness.status("large2").await, Some(DownloadStatus::Queued));
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_global_speed_limit_is_shared_by_active_downloads() {
+    let harness = QueueHarness::new();
+    harness.manager.set_max_concurrent(2).await;
+    harness.manager.set_global_speed_limit(Some(1024 * 1024)).await;
+    let shares = |id: &str| -> Vec<u64> {
+        harness.events.payloads("download:rate_limit")
+            .into_iter()
+            .filter(|p| p["id"] == id)
+            .filter_map(|p| p["bytesPerSecond"].as_u64())
+            .collect()
+    };
+    
+    harness.manager.add_to_queue(vec![harness.item("first", None)]).await.unwrap();
+    assert!(harness.wait_for(SETTLE_TIMEOUT, |q| q[0].status == DownloadStatus::Downloading).await);
+    assert_eq!(shares("first"), [1024 * 1024]);
+    
+    // A second download halves the first one's share, and it gets the rest back once alone
+    harness.manager.add_to_queue(vec![harness.item("second", Some("speed=4194304"))]).await.unwrap();
+    assert!(harness.wait_until_settled(SETTLE_TIMEOUT).await);
+    assert_eq!(shares("second")[0], 512 * 1024);
+    assert_eq!(&shares("first")[..2], [1024 * 1024, 512 * 1024]);
+    assert!(harness.manager.get_queue_status().await.iter().all(|i| i.status == DownloadStatus::Completed));
+}
@@ -111,3 +111,12 @@ fn test_restricted_paths_macos() {
         );
     }
 }
+
+#[test]
+fn test_available_space_for_missing_destination() {
+    // A destination that doesn't exist yet reports its nearest existing ancestor
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("not/created/yet");
+    
+    assert!(StorageService::available_space(&missing).unwrap() > 0);
+}
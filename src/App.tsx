@@ -9,6 +9,7 @@ import ErrorDetailsModal from './components/ErrorDetailsModal';
 import SuccessFeedback from './components/SuccessFeedback';
 import { useToast } from './hooks/useToast';
 import { executeBatchOperation, formatBatchResultMessage, categorizeErrors, type ErrorCategory } from './utils/batchOperations';
+import { estimatedSize } from './utils/estimatedSize';
 import {
   getVideoInfo,
   getPlaylistInfo,
@@ -183,7 +184,8 @@ function App() {
         eta: 0,
         savePath: settings.defaultSavePath,
         url: video.url,
-        platform: video.platform
+        platform: video.platform,
+        estimatedSize: estimatedSize(video)
       }));
 
       // Optimistically update local state first
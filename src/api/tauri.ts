@@ -5,10 +5,12 @@ import type {
   VideoInfo,
   PlaylistInfo,
   ChannelInfo,
+  ChannelTab,
   DownloadItem,
   DownloadProgress,
   AppSettings,
   PlatformInfo,
+  PlatformSetting,
   Dependency
 } from '../types';
 
@@ -26,17 +28,21 @@ export async function getVideoInfo(url: string): Promise<VideoInfo> {
   return invoke('get_video_info', { url });
 }
 
-export async function getPlaylistInfo(url: string): Promise<PlaylistInfo> {
-  return invoke('get_playlist_info', { url });
+// playlistItems limits the videos returned, e.g. "1-10,15" (1-based, negative counts from the end)
+export async function getPlaylistInfo(url: string, playlistItems?: string): Promise<PlaylistInfo> {
+  return invoke('get_playlist_info', { url, playlistItems });
 }
 
-export async function getChannelInfo(url: string): Promise<ChannelInfo> {
-  return invoke('get_channel_info', { url });
+// tab lists only a YouTube channel's videos, shorts or live streams
+export async function getChannelInfo(url: string, tab?: ChannelTab): Promise<ChannelInfo> {
+  return invoke('get_channel_info', { url, tab });
 }
 
 // Download management
-export async function addToDownloadQueue(items: DownloadItem[]): Promise<void> {
-  return invoke('add_to_download_queue', { items });
+// Resolves to the item IDs, generated by the backend for items sent without one.
+// Calls repeating a requestToken return the first call's IDs without queueing again.
+export async function addToDownloadQueue(items: DownloadItem[], requestToken?: string): Promise<string[]> {
+  return invoke('add_to_download_queue', { items, requestToken });
 }
 
 export async function pauseDownload(id: string): Promise<void> {
@@ -64,6 +70,11 @@ export async function saveSettings(settings: AppSettings): Promise<void> {
   return invoke('save_settings', { settings });
 }
 
+// Setting definitions of a platform, labelled in the display language
+export async function getPlatformSettingDefinitions(platform: string): Promise<PlatformSetting[]> {
+  return invoke('get_platform_setting_definitions', { platform });
+}
+
 export async function selectDirectory(): Promise<string | null> {
   return invoke('select_directory');
 }
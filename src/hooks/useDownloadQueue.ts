@@ -13,6 +13,7 @@ import {
   listenQueueUpdate
 } from '../api/tauri';
 import type { DownloadItem, VideoInfo } from '../types';
+import { estimatedSize } from '../utils/estimatedSize';
 
 export function useDownloadQueue() {
   const {
@@ -70,7 +71,8 @@ export function useDownloadQueue() {
         eta: 0,
         savePath,
         url: video.url,
-        platform: video.platform || 'youtube'
+        platform: video.platform || 'youtube',
+        estimatedSize: estimatedSize(video)
       }));
 
       console.log('[useDownloadQueue] Created items:', items);
@@ -19,7 +19,14 @@ export interface DownloadOptions {
   audioOnly: boolean;
 }
 
-export type DownloadStatus = 'queued' | 'downloading' | 'paused' | 'completed' | 'failed' | 'cancelled';
+export type DownloadStatus = 'queued' | 'waiting' | 'downloading' | 'paused' | 'completed' | 'failed' | 'cancelled';
+
+export interface FormatInfo {
+  format_id: string;
+  ext: string;
+  resolution?: string;
+  filesize?: number;
+}
 
 export interface VideoInfo {
   id: string;
@@ -32,6 +39,8 @@ export interface VideoInfo {
   view_count: number;
   platform: string;
   url: string;
+  // Empty for videos listed in playlists and channels
+  available_formats?: FormatInfo[];
 }
 
 export interface PlaylistInfo {
@@ -61,6 +70,21 @@ export interface DownloadProgress {
   total_bytes: number;
   speed: number;
   eta: number;
+  display?: {
+    downloaded: string;
+    total: string;
+    speed: string;
+    eta: string;
+  };
+}
+
+export type LogLevel = 'debug' | 'info' | 'warn' | 'error';
+
+export interface LogRecord {
+  level: LogLevel;
+  module: string;
+  message: string;
+  timestamp: string;
 }
 
 export interface DownloadItem {
@@ -76,6 +100,32 @@ export interface DownloadItem {
   url: string;
   platform: string;
   error?: string;
+  // Album and track number tagged on audio downloads, e.g. from the playlist
+  album?: string;
+  trackNumber?: number;
+  // Request ID in the backend log records for this download
+  requestId?: string;
+  // Expected size in bytes, used by the quick-wins queue mode
+  estimatedSize?: number;
+  liveness?: {
+    lastProgressAt?: string;
+    pid?: number;
+    restartCount: number;
+  };
+}
+
+// A provider setting; label is in the display language (from the locale setting)
+export interface PlatformSetting {
+  key: string;
+  label_key: string;
+  label: string;
+  setting_type:
+    | { type: 'Boolean' }
+    | { type: 'String' }
+    | { type: 'Number' }
+    | { type: 'Select'; options: string[] }
+    | { type: 'List' };
+  default_value: unknown;
 }
 
 export interface PlatformInfo {
@@ -104,6 +154,8 @@ export interface Dependency {
 
 export type ContentType = 'video' | 'playlist' | 'channel';
 
+export type ChannelTab = 'videos' | 'shorts' | 'live';
+
 export interface VideoContent {
   type: 'video';
   data: VideoInfo;
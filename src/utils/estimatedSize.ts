@@ -0,0 +1,23 @@
+// Expected download size, as VideoInfo::estimated_size works it out in the backend
+
+import type { VideoInfo } from '../types';
+
+// Bytes the chosen format reports, summed over `+`-joined formats; without a
+// format, the largest one stands in for yt-dlp's pick. Undefined when unknown.
+export function estimatedSize(video: VideoInfo, formatId?: string): number | undefined {
+  const formats = video.available_formats ?? [];
+  if (formatId) {
+    let total = 0;
+    for (const id of formatId.split('+')) {
+      const size = formats.find((f) => f.format_id === id)?.filesize;
+      if (size == null) {
+        return undefined;
+      }
+      total += size;
+    }
+    return total;
+  }
+
+  const sizes = formats.map((f) => f.filesize).filter((size): size is number => size != null);
+  return sizes.length > 0 ? Math.max(...sizes) : undefined;
+}
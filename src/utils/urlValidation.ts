@@ -23,10 +23,13 @@ export const PLATFORM_PATTERNS: PlatformPattern[] = [
       /^https?:\/\/(www\.)?youtube\.com\/channel\/[\w-]+/,
       /^https?:\/\/(www\.)?youtube\.com\/c\/[\w-]+/,
       /^https?:\/\/youtu\.be\/[\w-]+/,
+      /^https?:\/\/(www\.|m\.)?youtube\.com\/(shorts|live)\/[\w-]+/,
+      /^https?:\/\/music\.youtube\.com\/(watch\?v=|playlist\?list=|browse\/)[\w-]+/,
     ],
     examples: [
       'https://www.youtube.com/watch?v=dQw4w9WgXcQ',
       'https://youtu.be/dQw4w9WgXcQ',
+      'https://www.youtube.com/shorts/aqz-KE-bpKQ',
       'https://www.youtube.com/playlist?list=PLrAXtmErZgOeiKm4sgNOknGvNjby9efdf',
       'https://www.youtube.com/@channelname',
       'https://www.youtube.com/channel/UCxxxxxx',